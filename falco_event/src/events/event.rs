@@ -3,6 +3,7 @@ use crate::events::{AnyEventPayload, EventMetadata, PayloadToBytes};
 use crate::events::{FromRawEvent, PayloadFromBytesError, RawEvent};
 use std::fmt::{Debug, Formatter};
 use std::io::Write;
+use std::time::SystemTime;
 
 /// A Falco event.
 ///
@@ -18,6 +19,24 @@ pub struct Event<T> {
     pub params: T,
 }
 
+impl<T> Event<T> {
+    /// Set the timestamp of the event, returning the updated event.
+    ///
+    /// See [`EventMetadata::set_timestamp`] for details.
+    #[inline]
+    pub fn with_timestamp(mut self, timestamp: SystemTime) -> Self {
+        self.metadata.set_timestamp(timestamp);
+        self
+    }
+
+    /// Set the thread ID of the event, returning the updated event.
+    #[inline]
+    pub fn with_tid(mut self, tid: i64) -> Self {
+        self.metadata.tid = tid;
+        self
+    }
+}
+
 impl<T: Debug> Debug for Event<T> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?} {:?}", self.metadata, self.params)
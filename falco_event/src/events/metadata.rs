@@ -26,6 +26,21 @@ impl EventMetadata {
         }
     }
 
+    /// Set the timestamp of the event.
+    ///
+    /// Timestamps before the UNIX epoch are clamped to the epoch. Use [`EventMetadata::default`]
+    /// to get metadata without a timestamp.
+    #[inline]
+    pub fn set_timestamp(&mut self, timestamp: SystemTime) {
+        let nanos = timestamp
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+
+        // u64::MAX means "no timestamp", so never produce it by accident
+        self.ts = nanos.min(u64::MAX as u128 - 1) as u64;
+    }
+
     /// Write event header
     ///
     /// To form a valid event, after calling this method, the caller must write out the payload
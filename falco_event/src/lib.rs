@@ -119,11 +119,19 @@ pub use falco_event_derive::AnyEvent;
 ///   notable ones (like PPME_ASYNCEVENT_E and PPME_PLUGINEVENT_E) support larger parameter values
 ///   and so their length type is `u32`
 ///
+/// Additionally, you can pass the `out_of_line` flag to mark the generated decoding function
+/// as `#[inline(never)]` instead of `#[inline]`. This trades a function call per event
+/// for smaller code, when the decoding function would otherwise get inlined in many places
+/// (e.g. in a large enum derived via [`AnyEvent`]).
+///
 /// This macro can be used only on structs, not enums. Each field of the struct must implement
 /// [`fields::FromBytes`] and [`fields::FromBytes`]. Due to the requirements of FieldMeta-based
 /// deserialization, the whole struct must also implement [`Default`] and may have at most one
 /// lifetime generic parameter.
 ///
+/// Fields holding sizes or time intervals can use the unit wrappers from [`types`]
+/// ([`types::Bytes`], [`types::Pages`] and [`types::Nanoseconds`]). They are encoded as `u64`
+/// but provide conversions to other units and human-readable formatting.
 ///
 /// See above for an example use.
 ///
@@ -25,6 +25,23 @@ impl ToBytes for &[u8] {
     }
 }
 
+impl ToBytes for Vec<u8> {
+    #[inline]
+    fn binary_size(&self) -> usize {
+        self.len()
+    }
+
+    #[inline]
+    fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.as_slice().write(writer)
+    }
+
+    #[inline]
+    fn default_repr() -> impl ToBytes {
+        &[] as &[u8]
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::fields::{FromBytes, ToBytes};
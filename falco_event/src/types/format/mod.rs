@@ -1,7 +1,9 @@
 mod bytebuf;
 mod cstr;
 mod option;
+mod table;
 
 pub use bytebuf::ByteBufFormatter;
 pub use cstr::CStrFormatter;
 pub use option::OptionFormatter;
+pub use table::{format_event_fields, DisplayAsDebug, HexAsDebug, HexDebug, OctAsDebug};
@@ -0,0 +1,65 @@
+use crate::events::{event_direction, EventDirection};
+use std::fmt::{Debug, Display, Formatter, LowerHex, Octal, Write};
+
+/// Use the [`Display`] implementation of the inner type as its [`Debug`] representation
+pub struct DisplayAsDebug<T>(pub T);
+
+impl<T: Display> Debug for DisplayAsDebug<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Use the [`LowerHex`] implementation of the inner type as its [`Debug`] representation
+pub struct HexAsDebug<T>(pub T);
+
+impl<T: LowerHex> Debug for HexAsDebug<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        LowerHex::fmt(&self.0, f)
+    }
+}
+
+/// Use the [`Octal`] implementation of the inner type as its [`Debug`] representation
+pub struct OctAsDebug<T>(pub T);
+
+impl<T: Octal> Debug for OctAsDebug<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Octal::fmt(&self.0, f)
+    }
+}
+
+/// Format the inner type as hex [`Debug`] (`{:x?}`)
+pub struct HexDebug<T>(pub T);
+
+impl<T: Debug> Debug for HexDebug<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:x?}", self.0)
+    }
+}
+
+/// Format an event from a table of field names and values
+///
+/// The output matches the [`Debug`] implementation of the generated event types
+/// (`> name field1=value1 field2=value2`), but all the formatting logic lives in a single
+/// non-generic function instead of being unrolled for every event type.
+#[inline(never)]
+pub fn format_event_fields(
+    f: &mut Formatter<'_>,
+    event_type_id: u16,
+    name: &str,
+    fields: &[(&str, &dyn Debug)],
+) -> std::fmt::Result {
+    match event_direction(event_type_id) {
+        EventDirection::Entry => f.write_str("> ")?,
+        EventDirection::Exit => f.write_str("< ")?,
+    }
+    f.write_str(name)?;
+    for (field_name, value) in fields {
+        f.write_char(' ')?;
+        f.write_str(field_name)?;
+        f.write_char('=')?;
+        value.fmt(f)?;
+    }
+
+    Ok(())
+}
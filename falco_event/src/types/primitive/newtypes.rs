@@ -1,5 +1,5 @@
 use crate::fields::{FromBytes, FromBytesError, ToBytes};
-use std::fmt::{Debug, Formatter, LowerHex};
+use std::fmt::{Debug, Display, Formatter, LowerHex};
 
 macro_rules! default_debug {
     ($name:ident) => {
@@ -349,3 +349,255 @@ mod bool_tests {
         assert_eq!(format!("{:?}", Bool(10)), "true(10)");
     }
 }
+
+newtype!(
+    /// A size in bytes
+    ///
+    /// The schema mostly uses plain integers for sizes, often in other units (e.g. `vm_size`
+    /// is in KiB). Wrap them in this type to convert them to other units, or format them
+    /// for humans with `{}` (the `Debug` representation is the raw number, like in sinsp).
+    ///
+    /// ```
+    /// use falco_event::types::Bytes;
+    ///
+    /// let vm_size = Bytes::from_kib(2048);
+    /// assert_eq!(vm_size.as_mib(), 2);
+    /// assert_eq!(vm_size.to_string(), "2.00MiB");
+    /// ```
+    Bytes(u64)
+);
+default_debug!(Bytes);
+
+impl Bytes {
+    /// Create a size from a number of KiB
+    #[inline]
+    pub const fn from_kib(kib: u64) -> Self {
+        Self(kib << 10)
+    }
+
+    /// Create a size from a number of MiB
+    #[inline]
+    pub const fn from_mib(mib: u64) -> Self {
+        Self(mib << 20)
+    }
+
+    /// Return the size in bytes
+    #[inline]
+    pub const fn as_bytes(&self) -> u64 {
+        self.0
+    }
+
+    /// Return the size in KiB, rounded down
+    #[inline]
+    pub const fn as_kib(&self) -> u64 {
+        self.0 >> 10
+    }
+
+    /// Return the size in MiB, rounded down
+    #[inline]
+    pub const fn as_mib(&self) -> u64 {
+        self.0 >> 20
+    }
+}
+
+impl From<u64> for Bytes {
+    fn from(bytes: u64) -> Self {
+        Self(bytes)
+    }
+}
+
+impl From<u32> for Bytes {
+    fn from(bytes: u32) -> Self {
+        Self(bytes as u64)
+    }
+}
+
+impl Display for Bytes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+
+        if self.0 < 1024 {
+            return write!(f, "{}B", self.0);
+        }
+
+        let mut value = self.0 as f64 / 1024.0;
+        let mut unit = 0;
+        while value >= 1024.0 && unit < UNITS.len() - 1 {
+            value /= 1024.0;
+            unit += 1;
+        }
+
+        write!(f, "{value:.2}{}", UNITS[unit])
+    }
+}
+
+newtype!(
+    /// A number of memory pages
+    ///
+    /// Used e.g. for page fault counters. Convert to [`Bytes`] with [`Pages::to_bytes`],
+    /// passing the page size of the system that generated the event.
+    Pages(u64)
+);
+default_debug!(Pages);
+
+impl Pages {
+    /// Return the number of pages
+    #[inline]
+    pub const fn as_pages(&self) -> u64 {
+        self.0
+    }
+
+    /// Return the size of the pages in bytes, given the size of a single page
+    #[inline]
+    pub const fn to_bytes(&self, page_size: u64) -> Bytes {
+        Bytes(self.0 * page_size)
+    }
+}
+
+impl From<u64> for Pages {
+    fn from(pages: u64) -> Self {
+        Self(pages)
+    }
+}
+
+impl From<u32> for Pages {
+    fn from(pages: u32) -> Self {
+        Self(pages as u64)
+    }
+}
+
+impl Display for Pages {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} pages", self.0)
+    }
+}
+
+newtype!(
+    /// A time interval in nanoseconds
+    ///
+    /// Relative times (`PT_RELTIME`) are already decoded as [`std::time::Duration`], but some
+    /// parameters carry nanoseconds as plain integers. The `{}` representation matches
+    /// the human-readable latency format in sinsp (e.g. `1.50ms`).
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use falco_event::types::Nanoseconds;
+    ///
+    /// let latency = Nanoseconds(1_500_000);
+    /// assert_eq!(latency.to_duration(), Duration::from_micros(1500));
+    /// assert_eq!(latency.to_string(), "1.50ms");
+    /// ```
+    Nanoseconds(u64)
+);
+default_debug!(Nanoseconds);
+
+impl Nanoseconds {
+    /// Return the interval in nanoseconds
+    #[inline]
+    pub const fn as_nanos(&self) -> u64 {
+        self.0
+    }
+
+    /// Return the interval in microseconds, rounded down
+    #[inline]
+    pub const fn as_micros(&self) -> u64 {
+        self.0 / 1_000
+    }
+
+    /// Return the interval in milliseconds, rounded down
+    #[inline]
+    pub const fn as_millis(&self) -> u64 {
+        self.0 / 1_000_000
+    }
+
+    /// Return the interval in (fractional) seconds
+    #[inline]
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / 1_000_000_000.0
+    }
+
+    /// Convert the interval to a [`std::time::Duration`]
+    #[inline]
+    pub const fn to_duration(&self) -> std::time::Duration {
+        std::time::Duration::from_nanos(self.0)
+    }
+}
+
+impl From<u64> for Nanoseconds {
+    fn from(nanos: u64) -> Self {
+        Self(nanos)
+    }
+}
+
+impl From<std::time::Duration> for Nanoseconds {
+    fn from(duration: std::time::Duration) -> Self {
+        Self(duration.as_nanos() as u64)
+    }
+}
+
+impl From<Nanoseconds> for std::time::Duration {
+    fn from(nanos: Nanoseconds) -> Self {
+        nanos.to_duration()
+    }
+}
+
+impl Display for Nanoseconds {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let nanos = self.0 as f64;
+        if self.0 >= 1_000_000_000 {
+            write!(f, "{:.2}s", nanos / 1_000_000_000.0)
+        } else if self.0 >= 1_000_000 {
+            write!(f, "{:.2}ms", nanos / 1_000_000.0)
+        } else if self.0 >= 1_000 {
+            write!(f, "{:.2}us", nanos / 1_000.0)
+        } else {
+            write!(f, "{}ns", self.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod unit_tests {
+    use crate::types::{Bytes, Nanoseconds, Pages};
+    use std::time::Duration;
+
+    #[test]
+    fn test_bytes() {
+        assert_eq!(Bytes::from_kib(4), Bytes(4096));
+        assert_eq!(Bytes::from_mib(1).as_kib(), 1024);
+        assert_eq!(Bytes(1536).as_kib(), 1);
+
+        assert_eq!(format!("{:?}", Bytes(1536)), "1536");
+        assert_eq!(Bytes(0).to_string(), "0B");
+        assert_eq!(Bytes(1023).to_string(), "1023B");
+        assert_eq!(Bytes(1536).to_string(), "1.50KiB");
+        assert_eq!(Bytes::from_mib(3 << 10).to_string(), "3.00GiB");
+        assert_eq!(Bytes(u64::MAX).to_string(), "16777216.00TiB");
+    }
+
+    #[test]
+    fn test_pages() {
+        assert_eq!(Pages(3).to_bytes(4096), Bytes::from_kib(12));
+        assert_eq!(format!("{:?}", Pages(3)), "3");
+        assert_eq!(Pages(3).to_string(), "3 pages");
+    }
+
+    #[test]
+    fn test_nanoseconds() {
+        assert_eq!(Nanoseconds(1_500).as_micros(), 1);
+        assert_eq!(
+            Duration::from(Nanoseconds(2_000_000)),
+            Duration::from_millis(2)
+        );
+        assert_eq!(
+            Nanoseconds::from(Duration::from_secs(1)),
+            Nanoseconds(1_000_000_000)
+        );
+
+        assert_eq!(format!("{:?}", Nanoseconds(1_500)), "1500");
+        assert_eq!(Nanoseconds(999).to_string(), "999ns");
+        assert_eq!(Nanoseconds(1_500).to_string(), "1.50us");
+        assert_eq!(Nanoseconds(2_250_000).to_string(), "2.25ms");
+        assert_eq!(Nanoseconds(61_000_000_000).to_string(), "61.00s");
+    }
+}
@@ -8,42 +8,85 @@ use std::io::Write;
 ///
 /// This type represents an array of C-style strings, where each string is null-terminated.
 /// To get an iterator over the strings, use the `iter` method.
+///
+/// The strings are not copied out of the event: the array is just a view into the raw
+/// buffer, with the number of strings counted once, when the array is parsed.
 #[derive(Copy, Clone)]
-pub struct CStrArray<'a>(&'a [u8]);
+pub struct CStrArray<'a> {
+    buf: &'a [u8],
+    len: usize,
+}
 
 /// This is an iterator for CStrArray that allows iterating over the contained C-style strings.
-pub struct CStrArrayIter<'a>(&'a [u8]);
+pub struct CStrArrayIter<'a> {
+    buf: &'a [u8],
+    remaining: usize,
+}
 
 impl<'a> Iterator for CStrArrayIter<'a> {
     type Item = &'a CStr;
 
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
-        if self.0.is_empty() {
+        if self.buf.is_empty() {
             None
         } else {
-            <&'a CStr>::from_bytes(&mut self.0).ok()
+            let s = <&'a CStr>::from_bytes(&mut self.buf).ok()?;
+            self.remaining -= 1;
+            Some(s)
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
 }
 
+impl ExactSizeIterator for CStrArrayIter<'_> {}
+
 impl<'a> CStrArray<'a> {
     /// Return an iterator over the C-style strings in this array
     #[inline]
     pub fn iter(&self) -> CStrArrayIter<'a> {
-        CStrArrayIter(self.0)
+        CStrArrayIter {
+            buf: self.buf,
+            remaining: self.len,
+        }
+    }
+
+    /// Return the number of strings in this array
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Check whether this array is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl<'a> IntoIterator for CStrArray<'a> {
+    type Item = &'a CStr;
+    type IntoIter = CStrArrayIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
     }
 }
 
 impl ToBytes for CStrArray<'_> {
     #[inline]
     fn binary_size(&self) -> usize {
-        self.0.binary_size()
+        self.buf.binary_size()
     }
 
     #[inline]
     fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
-        self.0.write(writer)
+        self.buf.write(writer)
     }
 
     #[inline]
@@ -56,7 +99,11 @@ impl<'a> FromBytes<'a> for CStrArray<'a> {
     #[inline]
     fn from_bytes(buf: &mut &'a [u8]) -> Result<Self, FromBytesError> {
         match buf.last() {
-            Some(&0) | None => Ok(CStrArray(std::mem::take(buf))),
+            Some(&0) | None => {
+                let buf = std::mem::take(buf);
+                let len = buf.iter().filter(|b| **b == 0).count();
+                Ok(CStrArray { buf, len })
+            }
             _ => Err(FromBytesError::MissingNul),
         }
     }
@@ -88,8 +135,11 @@ mod tests {
         let binary = b"foo\0bar\0".as_slice();
         let array = CStrArray::from_bytes(&mut &*binary).unwrap();
 
+        assert_eq!(array.len(), 2);
         let mut iter = array.iter();
+        assert_eq!(iter.len(), 2);
         assert_eq!(iter.next().unwrap(), c"foo");
+        assert_eq!(iter.len(), 1);
         assert_eq!(iter.next().unwrap(), c"bar");
         assert_eq!(iter.next(), None);
 
@@ -103,6 +153,7 @@ mod tests {
         let binary = b"".as_slice();
         let array = CStrArray::from_bytes(&mut &*binary).unwrap();
 
+        assert!(array.is_empty());
         let mut iter = array.iter();
         assert_eq!(iter.next(), None);
 
@@ -116,6 +167,7 @@ mod tests {
         let binary = b"\0\0\0".as_slice();
         let array = CStrArray::from_bytes(&mut &*binary).unwrap();
 
+        assert_eq!(array.len(), 3);
         let mut iter = array.iter();
         assert_eq!(iter.next().unwrap(), c"");
         assert_eq!(iter.next().unwrap(), c"");
@@ -25,24 +25,45 @@ impl<'a> Iterator for CStrPairArrayIter<'a> {
         let v = self.0.next()?;
         Some((k, v))
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.0.len() / 2;
+        (len, Some(len))
+    }
 }
 
+impl ExactSizeIterator for CStrPairArrayIter<'_> {}
+
 impl<'a> CStrPairArray<'a> {
     /// Return an iterator over the pairs of C-style strings in this array
     #[inline]
     pub fn iter(&self) -> CStrPairArrayIter<'a> {
         CStrPairArrayIter(self.0.iter())
     }
+
+    /// Return the number of pairs in this array
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    /// Check whether this array is empty
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
 }
 
 impl<'a> FromBytes<'a> for CStrPairArray<'a> {
     #[inline]
     fn from_bytes(buf: &mut &'a [u8]) -> Result<Self, FromBytesError> {
-        let nuls = buf.iter().filter(|b| **b == 0).count();
-        if !nuls.is_multiple_of(2) {
+        let mut tmp = *buf;
+        let array = CStrArray::from_bytes(&mut tmp)?;
+        if !array.len().is_multiple_of(2) {
             return Err(FromBytesError::OddPairItemCount);
         }
-        let array = CStrArray::from_bytes(buf)?;
+        *buf = tmp;
         Ok(Self(array))
     }
 }
@@ -93,7 +114,9 @@ mod tests {
         let binary = b"foo\0bar\0".as_slice();
         let pair_array = CStrPairArray::from_bytes(&mut &*binary).unwrap();
 
+        assert_eq!(pair_array.len(), 1);
         let mut iter = pair_array.iter();
+        assert_eq!(iter.len(), 1);
         assert_eq!(iter.next(), Some((c"foo", c"bar")));
         assert_eq!(iter.next(), None);
 
@@ -13,6 +13,7 @@ struct EventPayloadAttrs {
     source: syn::Expr,
     from_bytes_bound: Option<syn::WhereClause>,
     to_bytes_bound: Option<syn::WhereClause>,
+    out_of_line: bool,
 }
 
 fn derive_to_bytes(
@@ -87,9 +88,14 @@ fn derive_from_bytes(
         ref_where_clause.predicates.extend(c.predicates.clone());
     }
 
+    let inline = match attrs.out_of_line {
+        true => quote!(#[inline(never)]),
+        false => quote!(#[inline]),
+    };
+
     quote!(
         impl <#impl_ref_generics> #crate_path::events::FromRawEvent<'raw_event> for #name #ty_generics #ref_where_clause {
-            #[inline]
+            #inline
             fn parse(raw: &#crate_path::events::RawEvent<'raw_event>) -> Result<Self, #crate_path::events::PayloadFromBytesError> {
             use #crate_path::events::PayloadFromBytesError;
                 use #crate_path::events::RawEvent;
@@ -0,0 +1,48 @@
+/// # Define an enum covering a subset of event types
+///
+/// This generates an enum like [`events::AnyEvent`](`crate::events::AnyEvent`), but only
+/// with the listed event types as variants. It's most useful as the event type of parse
+/// or extract plugins that only care about a handful of event types: the plugin gets typed
+/// access to the events it handles and only gets called for these event types.
+///
+/// Each variant is named after its event type:
+///
+/// ```
+/// use falco_event::events::{Event, PayloadFromBytesError, RawEvent};
+/// use falco_event_schema::event_set;
+///
+/// event_set! {
+///     /// The events we're interested in
+///     pub FileEvents = [PPME_SYSCALL_OPEN_X, PPME_SYSCALL_CLOSE_X]
+/// }
+///
+/// fn handle(raw: &RawEvent) -> Result<(), PayloadFromBytesError> {
+///     let event: Event<FileEvents> = raw.try_into()?;
+///     match event.params {
+///         FileEvents::PPME_SYSCALL_OPEN_X(open) => println!("open({:?})", open.name),
+///         FileEvents::PPME_SYSCALL_CLOSE_X(close) => println!("close = {:?}", close.res),
+///     }
+///     Ok(())
+/// }
+/// ```
+///
+/// The enum has a lifetime parameter (even if none of the event types needs it) and implements
+/// the same traits as [`events::AnyEvent`](`crate::events::AnyEvent`), so you can use it e.g.
+/// as `type Event<'a> = Event<FileEvents<'a>>` in a plugin.
+#[macro_export]
+macro_rules! event_set {
+    ($(#[$attr:meta])* $vis:vis $name:ident = [$($event:ident),+ $(,)?]) => {
+        $(#[$attr])*
+        #[allow(non_camel_case_types)]
+        #[derive($crate::__private::AnyEvent)]
+        #[falco_event_crate($crate::__private::falco_event)]
+        $vis enum $name<'a> {
+            $(
+                $event(
+                    <$crate::events::event_set::$event
+                        as $crate::events::event_set::EventSetMember<'a>>::Event
+                ),
+            )+
+        }
+    };
+}
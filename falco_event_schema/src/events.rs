@@ -1,5 +1,8 @@
 use falco_schema_derive::event_info;
 
+mod event_type;
+pub use event_type::{EventFlags, EventType};
+
 event_info! {
         [PPME_GENERIC_E] = {"syscall",
                             EC_OTHER | EC_SYSCALL,
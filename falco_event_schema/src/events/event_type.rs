@@ -0,0 +1,134 @@
+use crate::events::EVENT_TYPE_INFO;
+use crate::ffi;
+use falco_event::events::EventPayload;
+
+bitflags::bitflags! {
+    /// # Flags describing an event type
+    ///
+    /// These correspond to the `EF_*` flags in the event schema.
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    pub struct EventFlags: u32 {
+        /// The event creates a file descriptor
+        const CREATES_FD = ffi::ppm_event_flags_EF_CREATES_FD;
+        /// The event destroys a file descriptor
+        const DESTROYS_FD = ffi::ppm_event_flags_EF_DESTROYS_FD;
+        /// The event uses a file descriptor
+        const USES_FD = ffi::ppm_event_flags_EF_USES_FD;
+        /// The event reads data from a file descriptor
+        const READS_FROM_FD = ffi::ppm_event_flags_EF_READS_FROM_FD;
+        /// The event writes data to a file descriptor
+        const WRITES_TO_FD = ffi::ppm_event_flags_EF_WRITES_TO_FD;
+        /// The event modifies the state (e.g. the thread or file descriptor tables)
+        const MODIFIES_STATE = ffi::ppm_event_flags_EF_MODIFIES_STATE;
+        /// The event type is not used
+        const UNUSED = ffi::ppm_event_flags_EF_UNUSED;
+        /// The event waits (e.g. `poll` or `select`)
+        const WAITS = ffi::ppm_event_flags_EF_WAITS;
+        /// The event does not reset the parser state
+        const SKIPPARSERESET = ffi::ppm_event_flags_EF_SKIPPARSERESET;
+        /// The event type is an old version, no longer generated by current drivers
+        const OLD_VERSION = ffi::ppm_event_flags_EF_OLD_VERSION;
+        /// The event uses 32-bit parameter lengths
+        const LARGE_PAYLOAD = ffi::ppm_event_flags_EF_LARGE_PAYLOAD;
+        /// The event type is managed by the scap converter
+        const CONVERTER_MANAGED = ffi::ppm_event_flags_EF_CONVERTER_MANAGED;
+    }
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct EventTypeInfo {
+    pub(crate) name: &'static str,
+    pub(crate) flags: u32,
+    pub(crate) nparams: u32,
+}
+
+impl EventTypeInfo {
+    pub(crate) const UNKNOWN: Self = Self {
+        name: "NA",
+        flags: ffi::ppm_event_flags_EF_UNUSED,
+        nparams: 0,
+    };
+}
+
+/// # An event type known to the schema
+///
+/// This provides access to the schema metadata of an event type, based on its numeric ID,
+/// without loading the event payload. It's mostly useful for code that handles raw events
+/// (e.g. encoders or validators) and needs to know how to frame a particular event.
+///
+/// ```
+/// use falco_event_schema::events::{EventFlags, EventType, PPME_SYSCALL_OPEN_X};
+///
+/// let event_type = EventType::of::<PPME_SYSCALL_OPEN_X>();
+/// assert_eq!(event_type.name(), "open");
+/// assert_eq!(event_type.nparams(), 6);
+/// assert!(!event_type.is_large_payload());
+/// assert!(event_type.flags().contains(EventFlags::CREATES_FD));
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct EventType(u16);
+
+impl EventType {
+    /// Get the event type with a particular ID, if it's known to the schema
+    pub const fn new(id: u16) -> Option<Self> {
+        if (id as usize) < EVENT_TYPE_INFO.len() {
+            Some(Self(id))
+        } else {
+            None
+        }
+    }
+
+    /// Get the event type of a particular payload type
+    pub const fn of<T: EventPayload>() -> Self {
+        Self(T::ID)
+    }
+
+    /// Get the numeric ID of the event type
+    pub const fn id(self) -> u16 {
+        self.0
+    }
+
+    /// Get the name of the event type (e.g. `open`)
+    ///
+    /// Note that the name is not unique: the entry and exit events share it and so do
+    /// different versions of the same event type.
+    pub const fn name(self) -> &'static str {
+        self.info().name
+    }
+
+    /// Get the flags of the event type
+    pub const fn flags(self) -> EventFlags {
+        EventFlags::from_bits_retain(self.info().flags)
+    }
+
+    /// Check whether the event type uses 32-bit parameter lengths
+    ///
+    /// Events of this type have a 4-byte length for each parameter, while all other
+    /// events use 2-byte lengths.
+    pub const fn is_large_payload(self) -> bool {
+        self.flags().contains(EventFlags::LARGE_PAYLOAD)
+    }
+
+    /// Get the number of parameters in events of this type
+    pub const fn nparams(self) -> u32 {
+        self.info().nparams
+    }
+
+    const fn info(self) -> EventTypeInfo {
+        EVENT_TYPE_INFO[self.0 as usize]
+    }
+}
+
+impl From<EventType> for u16 {
+    fn from(event_type: EventType) -> Self {
+        event_type.0
+    }
+}
+
+impl TryFrom<u16> for EventType {
+    type Error = falco_event::events::PayloadFromBytesError;
+
+    fn try_from(id: u16) -> Result<Self, Self::Error> {
+        Self::new(id).ok_or(falco_event::events::PayloadFromBytesError::UnsupportedEventType(id))
+    }
+}
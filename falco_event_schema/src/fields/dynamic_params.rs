@@ -1,4 +1,6 @@
+use falco_event::types::SyscallResult;
 use falco_schema_derive::dynamic_params;
+use std::time::Duration;
 
 dynamic_params! {
 const struct ppm_param_info sockopt_dynamic_param[PPM_SOCKOPT_IDX_MAX] = {
@@ -19,3 +21,69 @@ const struct ppm_param_info bpf_dynamic_param[PPM_BPF_IDX_MAX] = {
         [PPM_BPF_IDX_RES] = {{0}, PT_ERRNO, PF_DEC, 0, 0},
 };
 }
+
+/// # Typed access to socket option values
+///
+/// The value of a socket option (as passed to `setsockopt` or returned from `getsockopt`)
+/// is stored in one of several representations, depending on the option. These methods
+/// let you get at the value without matching every variant manually.
+impl<'a> PT_DYN_sockopt_dynamic_param<'a> {
+    /// Get the raw option value, for options the kernel driver does not decode
+    pub fn as_bytes(&self) -> Option<&'a [u8]> {
+        match self {
+            Self::PPM_SOCKOPT_IDX_UNKNOWN(val) => Some(val),
+            _ => None,
+        }
+    }
+
+    /// Get the error code, if reading the option value failed
+    pub fn as_errno(&self) -> Option<SyscallResult> {
+        match self {
+            Self::PPM_SOCKOPT_IDX_ERRNO(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// Get the option value as an integer
+    ///
+    /// This accepts both the 32-bit and the 64-bit integer representations.
+    pub fn as_int(&self) -> Option<u64> {
+        match self {
+            Self::PPM_SOCKOPT_IDX_UINT32(val) => Some(u64::from(*val)),
+            Self::PPM_SOCKOPT_IDX_UINT64(val) => Some(*val),
+            _ => None,
+        }
+    }
+
+    /// Get the option value as a time interval (`struct timeval`)
+    ///
+    /// This is used for e.g. `SO_RCVTIMEO` and `SO_SNDTIMEO`.
+    pub fn as_timeval(&self) -> Option<Duration> {
+        match self {
+            Self::PPM_SOCKOPT_IDX_TIMEVAL(val) => Some(*val),
+            _ => None,
+        }
+    }
+}
+
+/// Convert an integer socket option value into a `u64`
+///
+/// On failure, the original value is returned as the error.
+impl<'a> TryFrom<PT_DYN_sockopt_dynamic_param<'a>> for u64 {
+    type Error = PT_DYN_sockopt_dynamic_param<'a>;
+
+    fn try_from(value: PT_DYN_sockopt_dynamic_param<'a>) -> Result<Self, Self::Error> {
+        value.as_int().ok_or(value)
+    }
+}
+
+/// Convert a `struct timeval` socket option value into a [`Duration`]
+///
+/// On failure, the original value is returned as the error.
+impl<'a> TryFrom<PT_DYN_sockopt_dynamic_param<'a>> for Duration {
+    type Error = PT_DYN_sockopt_dynamic_param<'a>;
+
+    fn try_from(value: PT_DYN_sockopt_dynamic_param<'a>) -> Result<Self, Self::Error> {
+        value.as_timeval().ok_or(value)
+    }
+}
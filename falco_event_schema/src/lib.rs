@@ -25,9 +25,18 @@ pub mod events;
 #[doc(hidden)]
 pub mod ffi;
 
+mod event_set;
+
 #[cfg(test)]
 mod tests;
 
+// for macro use only
+#[doc(hidden)]
+pub mod __private {
+    pub use falco_event;
+    pub use falco_event_derive::AnyEvent;
+}
+
 /// The schema version supported by this crate
 ///
 /// If you're not using the same version of falco_event_schema and falco_plugin, you need
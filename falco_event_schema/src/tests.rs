@@ -57,3 +57,121 @@ fn test_any_event_to_bytes() {
     assert_eq!(evt2.params.dev, Some(0));
     assert_eq!(evt2.params.ino, Some(0));
 }
+
+#[test]
+fn test_event_debug() {
+    let evt = PPME_SYSCALL_OPEN_X {
+        fd: Some(PT_FD(5)),
+        name: Some(PT_FSPATH::new("/etc/passwd")),
+        flags: Some(PT_FLAGS32_file_flags::O_RDWR),
+        mode: Some(0o644),
+        dev: None,
+        ino: Some(0),
+    };
+
+    assert_eq!(
+        format!("{evt:?}"),
+        "< open fd=5 name=/etc/passwd flags=3 mode=644 dev=NULL ino=0"
+    );
+}
+
+#[test]
+fn test_sockopt_getters() {
+    use crate::fields::types::PT_DYN_sockopt_dynamic_param;
+    use falco_event::types::SyscallResult;
+    use std::time::Duration;
+
+    let val = PT_DYN_sockopt_dynamic_param::PPM_SOCKOPT_IDX_UINT32(5);
+    assert_eq!(val.as_int(), Some(5));
+    assert_eq!(val.as_timeval(), None);
+    assert_eq!(u64::try_from(val).ok(), Some(5));
+
+    let val = PT_DYN_sockopt_dynamic_param::PPM_SOCKOPT_IDX_UINT64(1 << 40);
+    assert_eq!(val.as_int(), Some(1 << 40));
+
+    let val = PT_DYN_sockopt_dynamic_param::PPM_SOCKOPT_IDX_TIMEVAL(Duration::from_millis(1500));
+    assert_eq!(val.as_timeval(), Some(Duration::from_millis(1500)));
+    assert_eq!(val.as_int(), None);
+    assert!(u64::try_from(val).is_err());
+    assert_eq!(
+        Duration::try_from(val).ok(),
+        Some(Duration::from_millis(1500))
+    );
+
+    let val = PT_DYN_sockopt_dynamic_param::PPM_SOCKOPT_IDX_ERRNO(SyscallResult(-22));
+    assert_eq!(val.as_errno(), Some(SyscallResult(-22)));
+
+    let val = PT_DYN_sockopt_dynamic_param::PPM_SOCKOPT_IDX_UNKNOWN(b"abc");
+    assert_eq!(val.as_bytes(), Some(b"abc".as_slice()));
+    assert_eq!(val.as_errno(), None);
+}
+
+#[test]
+fn test_event_type_info() {
+    use crate::events::{EventFlags, EventType, PPME_PLUGINEVENT_E};
+    use falco_event::events::EventPayload;
+
+    let event_type = EventType::new(PPME_PLUGINEVENT_E::ID).unwrap();
+    assert_eq!(event_type, EventType::of::<PPME_PLUGINEVENT_E>());
+    assert_eq!(event_type.name(), "pluginevent");
+    assert_eq!(event_type.nparams(), 2);
+    assert!(event_type.is_large_payload());
+
+    let event_type = EventType::of::<PPME_SYSCALL_OPEN_X>();
+    assert!(!event_type.is_large_payload());
+    assert!(
+        event_type
+            .flags()
+            .contains(EventFlags::CREATES_FD | EventFlags::MODIFIES_STATE)
+    );
+
+    assert_eq!(
+        EventType::new(crate::ffi::ppm_event_code_PPM_EVENT_MAX as u16),
+        None
+    );
+}
+
+#[test]
+fn test_event_set() {
+    use crate::events::{PPME_SYSCALL_CLOSE_E, PPME_SYSCALL_CLOSE_X};
+    use falco_event::events::{AnyEventPayload, EventPayload, PayloadFromBytesError};
+
+    crate::event_set! {
+        FdEvents = [PPME_SYSCALL_OPEN_X, PPME_SYSCALL_CLOSE_E]
+    }
+
+    assert_eq!(
+        FdEvents::EVENT_TYPES,
+        [PPME_SYSCALL_OPEN_X::ID, PPME_SYSCALL_CLOSE_E::ID]
+    );
+
+    let evt = Event {
+        metadata: EventMetadata { ts: 1, tid: 1 },
+        params: PPME_SYSCALL_CLOSE_E { fd: Some(PT_FD(5)) },
+    };
+    let mut buf = Vec::new();
+    evt.write(&mut buf).unwrap();
+
+    let raw = RawEvent::from(buf.as_slice()).unwrap();
+    let evt: Event<FdEvents> = (&raw).try_into().unwrap();
+    let FdEvents::PPME_SYSCALL_CLOSE_E(close) = evt.params else {
+        panic!("unexpected event {:?}", evt.params);
+    };
+    assert_eq!(close.fd, Some(PT_FD(5)));
+
+    let evt = Event {
+        metadata: EventMetadata { ts: 1, tid: 1 },
+        params: PPME_SYSCALL_CLOSE_X {
+            res: None,
+            fd: Some(PT_FD(5)),
+        },
+    };
+    let mut buf = Vec::new();
+    evt.write(&mut buf).unwrap();
+
+    let raw = RawEvent::from(buf.as_slice()).unwrap();
+    assert!(matches!(
+        Event::<FdEvents>::try_from(&raw),
+        Err(PayloadFromBytesError::UnsupportedEventType(id)) if id == PPME_SYSCALL_CLOSE_X::ID
+    ));
+}
@@ -2,7 +2,7 @@ use crate::ffi::{PPM_AF_INET, PPM_AF_INET6, PPM_AF_LOCAL, PPM_AF_UNSPEC};
 use falco_event::fields::{FromBytes, FromBytesError, ToBytes};
 use std::fmt::{Debug, Formatter};
 use std::io::Write;
-use std::net::{SocketAddrV4, SocketAddrV6};
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6};
 use typed_path::UnixPath;
 
 /// A socket address
@@ -11,6 +11,12 @@ pub enum SockAddr<'a> {
     /// Unix sockets
     Unix(&'a UnixPath),
 
+    /// Abstract unix sockets
+    ///
+    /// The name is stored without the leading NUL byte that marks the address as abstract.
+    /// It may contain further NUL bytes, as abstract socket names are not NUL-terminated strings.
+    AbstractUnix(&'a [u8]),
+
     /// IPv4 sockets
     V4(SocketAddrV4),
 
@@ -21,10 +27,32 @@ pub enum SockAddr<'a> {
     Other(u8, &'a [u8]),
 }
 
+impl From<SocketAddrV4> for SockAddr<'_> {
+    fn from(addr: SocketAddrV4) -> Self {
+        Self::V4(addr)
+    }
+}
+
+impl From<SocketAddrV6> for SockAddr<'_> {
+    fn from(addr: SocketAddrV6) -> Self {
+        Self::V6(addr)
+    }
+}
+
+impl From<SocketAddr> for SockAddr<'_> {
+    fn from(addr: SocketAddr) -> Self {
+        match addr {
+            SocketAddr::V4(addr) => Self::V4(addr),
+            SocketAddr::V6(addr) => Self::V6(addr),
+        }
+    }
+}
+
 impl ToBytes for SockAddr<'_> {
     fn binary_size(&self) -> usize {
         match self {
             Self::Unix(p) => 1 + p.binary_size(),
+            Self::AbstractUnix(name) => 1 + 1 + name.len() + 1,
             Self::V4(addr) => 1 + addr.binary_size(),
             Self::V6(addr) => 1 + addr.binary_size(),
             Self::Other(_, buf) => 1 + buf.len(),
@@ -37,6 +65,11 @@ impl ToBytes for SockAddr<'_> {
                 writer.write_all(&[PPM_AF_LOCAL as u8])?;
                 p.write(writer)
             }
+            Self::AbstractUnix(name) => {
+                writer.write_all(&[PPM_AF_LOCAL as u8, 0])?;
+                writer.write_all(name)?;
+                writer.write_all(&[0])
+            }
             Self::V4(addr) => {
                 writer.write_all(&[PPM_AF_INET as u8])?;
                 addr.write(writer)
@@ -62,6 +95,14 @@ impl<'a> FromBytes<'a> for SockAddr<'a> {
         let variant = buf.split_off_first().ok_or(FromBytesError::InvalidLength)?;
 
         match *variant as u32 {
+            PPM_AF_LOCAL if buf.len() > 1 && buf[0] == 0 => {
+                // abstract socket: the name runs until the end of the buffer (minus the terminating
+                // NUL, if any) and may contain NUL bytes itself
+                let name = std::mem::take(buf);
+                let name = &name[1..];
+                let name = name.strip_suffix(&[0]).unwrap_or(name);
+                Ok(Self::AbstractUnix(name))
+            }
             PPM_AF_LOCAL => {
                 let path: &UnixPath = FromBytes::from_bytes(buf)?;
                 Ok(Self::Unix(path))
@@ -83,9 +124,78 @@ impl Debug for SockAddr<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             SockAddr::Unix(u) => write!(f, "unix://{}", u.display()),
+            SockAddr::AbstractUnix(name) => write!(f, "unix://@{}", name.escape_ascii()),
             SockAddr::V4(v4) => write!(f, "{v4}"),
             SockAddr::V6(v6) => write!(f, "{v6}"),
             SockAddr::Other(af, raw) => write!(f, "<af={af}>{raw:02x?}"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn test_sockaddr_from_std() {
+        let addr = SocketAddr::from_str("172.31.33.48:47263").unwrap();
+        let sockaddr = SockAddr::from(addr);
+        assert_eq!(
+            sockaddr,
+            SockAddr::V4(SocketAddrV4::from_str("172.31.33.48:47263").unwrap())
+        );
+
+        let addr = SocketAddr::from_str("[2001:4860:4860::8844]:53").unwrap();
+        let sockaddr = SockAddr::from(addr);
+        assert_eq!(
+            sockaddr,
+            SockAddr::V6(SocketAddrV6::from_str("[2001:4860:4860::8844]:53").unwrap())
+        );
+    }
+
+    fn roundtrip(addr: SockAddr) -> Vec<u8> {
+        let mut buf = Vec::new();
+        addr.write(&mut buf).unwrap();
+        assert_eq!(buf.len(), addr.binary_size());
+
+        let mut slice = buf.as_slice();
+        let decoded = SockAddr::from_bytes(&mut slice).unwrap();
+        assert_eq!(decoded, addr);
+        assert!(slice.is_empty());
+
+        buf
+    }
+
+    #[test]
+    fn test_sockaddr_unix_roundtrip() {
+        let addr = SockAddr::Unix(UnixPath::new("/run/socket"));
+        assert_eq!(roundtrip(addr), b"\x01/run/socket\0");
+        assert_eq!(format!("{addr:?}"), "unix:///run/socket");
+    }
+
+    #[test]
+    fn test_sockaddr_abstract_unix_roundtrip() {
+        let addr = SockAddr::AbstractUnix(b"dbus-session");
+        assert_eq!(roundtrip(addr), b"\x01\0dbus-session\0");
+        assert_eq!(format!("{addr:?}"), "unix://@dbus-session");
+
+        let addr = SockAddr::AbstractUnix(b"with\0nul");
+        assert_eq!(roundtrip(addr), b"\x01\0with\0nul\0");
+        assert_eq!(format!("{addr:?}"), "unix://@with\\x00nul");
+    }
+
+    #[test]
+    fn test_sockaddr_abstract_unix_without_terminator() {
+        let mut buf = b"\x01\0name".as_slice();
+        let addr = SockAddr::from_bytes(&mut buf).unwrap();
+        assert_eq!(addr, SockAddr::AbstractUnix(b"name"));
+    }
+
+    #[test]
+    fn test_sockaddr_empty_unix_path() {
+        let mut buf = b"\x01\0".as_slice();
+        let addr = SockAddr::from_bytes(&mut buf).unwrap();
+        assert_eq!(addr, SockAddr::Unix(UnixPath::new("")));
+    }
+}
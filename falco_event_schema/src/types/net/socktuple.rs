@@ -2,7 +2,7 @@ use crate::ffi::{PPM_AF_INET, PPM_AF_INET6, PPM_AF_LOCAL};
 use falco_event::fields::{FromBytes, FromBytesError, ToBytes};
 use std::fmt::{Debug, Formatter};
 use std::io::Write;
-use std::net::{SocketAddrV4, SocketAddrV6};
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6};
 use typed_path::UnixPath;
 
 /// Socket tuple: describing both endpoints of a connection
@@ -38,6 +38,43 @@ pub enum SockTuple<'a> {
     Other(u8, &'a [u8]),
 }
 
+impl From<(SocketAddrV4, SocketAddrV4)> for SockTuple<'_> {
+    fn from((source, dest): (SocketAddrV4, SocketAddrV4)) -> Self {
+        Self::V4 { source, dest }
+    }
+}
+
+impl From<(SocketAddrV6, SocketAddrV6)> for SockTuple<'_> {
+    fn from((source, dest): (SocketAddrV6, SocketAddrV6)) -> Self {
+        Self::V6 { source, dest }
+    }
+}
+
+/// Build a socket tuple from a `(source, dest)` pair
+///
+/// If the addresses belong to different families, the IPv4 one is converted
+/// to an IPv4-mapped IPv6 address and an IPv6 tuple is returned.
+impl From<(SocketAddr, SocketAddr)> for SockTuple<'_> {
+    fn from((source, dest): (SocketAddr, SocketAddr)) -> Self {
+        fn to_v6(addr: SocketAddr) -> SocketAddrV6 {
+            match addr {
+                SocketAddr::V4(addr) => {
+                    SocketAddrV6::new(addr.ip().to_ipv6_mapped(), addr.port(), 0, 0)
+                }
+                SocketAddr::V6(addr) => addr,
+            }
+        }
+
+        match (source, dest) {
+            (SocketAddr::V4(source), SocketAddr::V4(dest)) => Self::V4 { source, dest },
+            (source, dest) => Self::V6 {
+                source: to_v6(source),
+                dest: to_v6(dest),
+            },
+        }
+    }
+}
+
 impl Debug for SockTuple<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -192,6 +229,28 @@ mod tests {
         assert_eq!(socktuple, socktuple2)
     }
 
+    #[test]
+    fn test_socktuple_from_std() {
+        let source = SocketAddr::from_str("172.31.33.48:47263").unwrap();
+        let dest = SocketAddr::from_str("172.31.0.2:53").unwrap();
+        assert_eq!(
+            SockTuple::from((source, dest)),
+            SockTuple::V4 {
+                source: SocketAddrV4::from_str("172.31.33.48:47263").unwrap(),
+                dest: SocketAddrV4::from_str("172.31.0.2:53").unwrap(),
+            }
+        );
+
+        let dest = SocketAddr::from_str("[2001:4860:4860::8800]:53").unwrap();
+        assert_eq!(
+            SockTuple::from((source, dest)),
+            SockTuple::V6 {
+                source: SocketAddrV6::from_str("[::ffff:172.31.33.48]:47263").unwrap(),
+                dest: SocketAddrV6::from_str("[2001:4860:4860::8800]:53").unwrap(),
+            }
+        );
+    }
+
     #[test]
     fn test_socktuple_unix() {
         let binary = b"\x01\x00\x00\x00\x00\x00\x00\x00\x00\x00 \xcfN\xbc\x98\xff\xff/var/run/nscd/socket\x00".as_slice();
@@ -0,0 +1,403 @@
+use serde::de::value::{Error, MapDeserializer, SeqDeserializer, StrDeserializer};
+use serde::de::{
+    DeserializeSeed, EnumAccess, Error as _, IntoDeserializer, Unexpected, VariantAccess, Visitor,
+};
+use serde::{Deserializer, forward_to_deserialize_any};
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+
+/// A dynamically typed event parameter value
+///
+/// This is used to build events from field name/value maps with [`crate::de::Event::from_fields`].
+/// The values are coerced to the parameter types from the schema where that's unambiguous,
+/// e.g. integers can be passed as strings (and vice versa), while byte buffers can be passed
+/// as strings.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DynValue {
+    /// No value (the parameter will be empty)
+    None,
+    /// A boolean
+    Bool(bool),
+    /// A signed integer
+    Int(i64),
+    /// An unsigned integer
+    UInt(u64),
+    /// A floating point number
+    Float(f64),
+    /// A string
+    Str(String),
+    /// A byte buffer
+    Bytes(Vec<u8>),
+    /// A list of values (e.g. for arrays of strings or tuples)
+    List(Vec<DynValue>),
+    /// An enum variant with a value, used for dynamic parameters (`PT_DYN*`)
+    Variant(String, Box<DynValue>),
+}
+
+impl Display for DynValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DynValue::None => f.write_str("none"),
+            DynValue::Bool(v) => write!(f, "bool {v}"),
+            DynValue::Int(v) => write!(f, "integer {v}"),
+            DynValue::UInt(v) => write!(f, "integer {v}"),
+            DynValue::Float(v) => write!(f, "float {v}"),
+            DynValue::Str(v) => write!(f, "string {v:?}"),
+            DynValue::Bytes(_) => f.write_str("byte buffer"),
+            DynValue::List(_) => f.write_str("list"),
+            DynValue::Variant(name, _) => write!(f, "variant {name}"),
+        }
+    }
+}
+
+macro_rules! impl_from {
+    ($($ty:ty => $variant:ident),*) => {
+        $(impl From<$ty> for DynValue {
+            fn from(v: $ty) -> Self {
+                DynValue::$variant(v.into())
+            }
+        })*
+    };
+}
+
+impl_from!(
+    bool => Bool,
+    i8 => Int, i16 => Int, i32 => Int, i64 => Int,
+    u8 => UInt, u16 => UInt, u32 => UInt, u64 => UInt,
+    f32 => Float, f64 => Float,
+    &str => Str, String => Str,
+    &[u8] => Bytes, Vec<u8> => Bytes
+);
+
+impl<T: Into<DynValue>> From<Option<T>> for DynValue {
+    fn from(v: Option<T>) -> Self {
+        v.map(Into::into).unwrap_or(DynValue::None)
+    }
+}
+
+/// Build the payload of an event called `event_type` from its parameters
+pub(crate) fn deserialize_event<'de, T: serde::Deserialize<'de>>(
+    event_type: &'de str,
+    fields: HashMap<&'de str, DynValue>,
+) -> Result<T, Error> {
+    T::deserialize(EventDeserializer { event_type, fields })
+}
+
+struct EventDeserializer<'a> {
+    event_type: &'a str,
+    fields: HashMap<&'a str, DynValue>,
+}
+
+impl<'de> Deserializer<'de> for EventDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_enum(self)
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+impl<'de> EnumAccess<'de> for EventDeserializer<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let name: StrDeserializer<Error> = self.event_type.into_deserializer();
+        Ok((seed.deserialize(name)?, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for EventDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Err(Error::invalid_type(Unexpected::NewtypeVariant, &"event"))
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        let event_type = self.event_type;
+        let fields = self.fields.into_iter().map(|(name, value)| {
+            let value = FieldValue {
+                event_type,
+                field: name,
+                value,
+            };
+            (name, value)
+        });
+        seed.deserialize(MapDeserializer::new(fields))
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::invalid_type(Unexpected::TupleVariant, &"event"))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(Error::invalid_type(Unexpected::StructVariant, &"event"))
+    }
+}
+
+/// A value of a single event parameter, with enough context to report errors
+struct FieldValue<'a> {
+    event_type: &'a str,
+    field: &'a str,
+    value: DynValue,
+}
+
+impl FieldValue<'_> {
+    fn with_value(&self, value: DynValue) -> Self {
+        Self {
+            event_type: self.event_type,
+            field: self.field,
+            value,
+        }
+    }
+
+    fn error(&self, expected: &str) -> Error {
+        Error::custom(format_args!(
+            "{}.{}: cannot convert {} to {}",
+            self.event_type, self.field, self.value, expected
+        ))
+    }
+
+    fn as_i128(&self) -> Option<i128> {
+        match &self.value {
+            DynValue::Bool(v) => Some(*v as i128),
+            DynValue::Int(v) => Some(*v as i128),
+            DynValue::UInt(v) => Some(*v as i128),
+            DynValue::Str(v) => v.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn as_f64(&self) -> Option<f64> {
+        match &self.value {
+            DynValue::Int(v) => Some(*v as f64),
+            DynValue::UInt(v) => Some(*v as f64),
+            DynValue::Float(v) => Some(*v),
+            DynValue::Str(v) => v.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    fn into_string(self) -> Result<String, Error> {
+        match self.value {
+            DynValue::Str(v) => Ok(v),
+            DynValue::Bool(v) => Ok(v.to_string()),
+            DynValue::Int(v) => Ok(v.to_string()),
+            DynValue::UInt(v) => Ok(v.to_string()),
+            DynValue::Float(v) => Ok(v.to_string()),
+            DynValue::Bytes(ref v) => match String::from_utf8(v.clone()) {
+                Ok(s) => Ok(s),
+                Err(_) => Err(self.error("string")),
+            },
+            _ => Err(self.error("string")),
+        }
+    }
+}
+
+macro_rules! deserialize_int {
+    ($($method:ident => $visit:ident: $ty:ty),*) => {
+        $(fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+            match self.as_i128().and_then(|v| <$ty>::try_from(v).ok()) {
+                Some(v) => visitor.$visit(v),
+                None => Err(self.error(stringify!($ty))),
+            }
+        })*
+    };
+}
+
+impl<'de> Deserializer<'de> for FieldValue<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            DynValue::None => visitor.visit_none(),
+            DynValue::Bool(v) => visitor.visit_bool(v),
+            DynValue::Int(v) => visitor.visit_i64(v),
+            DynValue::UInt(v) => visitor.visit_u64(v),
+            DynValue::Float(v) => visitor.visit_f64(v),
+            DynValue::Str(v) => visitor.visit_string(v),
+            DynValue::Bytes(v) => visitor.visit_byte_buf(v),
+            DynValue::List(_) => self.deserialize_seq(visitor),
+            DynValue::Variant(..) => self.deserialize_enum("", &[], visitor),
+        }
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match &self.value {
+            DynValue::Bool(v) => visitor.visit_bool(*v),
+            DynValue::Str(v) if v == "true" => visitor.visit_bool(true),
+            DynValue::Str(v) if v == "false" => visitor.visit_bool(false),
+            _ => match self.as_i128() {
+                Some(0) => visitor.visit_bool(false),
+                Some(1) => visitor.visit_bool(true),
+                _ => Err(self.error("bool")),
+            },
+        }
+    }
+
+    deserialize_int!(
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64
+    );
+
+    fn deserialize_f32<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_f64(visitor)
+    }
+
+    fn deserialize_f64<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.as_f64() {
+            Some(v) => visitor.visit_f64(v),
+            None => Err(self.error("float")),
+        }
+    }
+
+    fn deserialize_str<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.into_string()?)
+    }
+
+    fn deserialize_string<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_string(self.into_string()?)
+    }
+
+    fn deserialize_bytes<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_byte_buf(visitor)
+    }
+
+    fn deserialize_byte_buf<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            DynValue::Bytes(v) => visitor.visit_byte_buf(v),
+            DynValue::List(_) => self.deserialize_seq(visitor),
+            _ => visitor.visit_string(self.into_string()?),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.value {
+            DynValue::None => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let items = match self.value {
+            DynValue::List(ref items) => items.clone(),
+            _ => return Err(self.error("list")),
+        };
+        let mut seq = SeqDeserializer::new(items.into_iter().map(|v| self.with_value(v)));
+        let value = visitor.visit_seq(&mut seq)?;
+        seq.end()?;
+        Ok(value)
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(
+        self,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.value {
+            DynValue::Variant(..) | DynValue::Str(_) => visitor.visit_enum(self),
+            _ => Err(self.error("enum variant")),
+        }
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        // the only values ignored by the event payload structs are unknown fields
+        Err(Error::custom(format_args!(
+            "{}: unknown field {}",
+            self.event_type, self.field
+        )))
+    }
+
+    forward_to_deserialize_any! {
+        i128 u128 char unit unit_struct map struct identifier
+    }
+}
+
+impl<'de> IntoDeserializer<'de, Error> for FieldValue<'de> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+impl<'de> EnumAccess<'de> for FieldValue<'de> {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(self, seed: V) -> Result<(V::Value, Self), Error> {
+        let (name, value) = match self.value {
+            DynValue::Variant(ref name, ref value) => (name.clone(), (**value).clone()),
+            DynValue::Str(ref name) => (name.clone(), DynValue::None),
+            _ => return Err(self.error("enum variant")),
+        };
+        let name: serde::de::value::StringDeserializer<Error> = name.into_deserializer();
+        Ok((seed.deserialize(name)?, self.with_value(value)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for FieldValue<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, Error> {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, Error> {
+        self.deserialize_seq(visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Error> {
+        Err(self.error("struct"))
+    }
+}
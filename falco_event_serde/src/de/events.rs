@@ -1,7 +1,9 @@
+use crate::de::dyn_value::{DynValue, deserialize_event};
 use crate::de::repr::Repr;
 use falco_event::events::EventMetadata;
 use falco_event::fields::ToBytes;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Debug, Deserialize)]
 pub struct RawEvent {
@@ -74,6 +76,22 @@ pub struct Event {
 }
 
 impl Event {
+    /// Builds an event from a map of parameter names to values.
+    ///
+    /// `event_type` is the name of the event type, as used in the serialized form
+    /// (e.g. `GENERIC_E`). The parameters are validated against the event schema: unknown
+    /// parameters are rejected, missing ones are left empty, and the values are converted
+    /// to the parameter types where possible (see [`DynValue`] for details).
+    pub fn from_fields(
+        ts: u64,
+        tid: i64,
+        event_type: &str,
+        fields: HashMap<&str, DynValue>,
+    ) -> Result<Self, serde::de::value::Error> {
+        let event = deserialize_event(event_type, fields)?;
+        Ok(Self { ts, tid, event })
+    }
+
     /// Appends the serialized event to the provided byte vector.
     pub fn append_to_vec(self, buf: &mut Vec<u8>) {
         let metadata = EventMetadata {
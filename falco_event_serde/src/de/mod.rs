@@ -34,8 +34,10 @@
 //! assert_eq!(event.params.id, Some(PT_SYSCALLID(1)));
 //! assert_eq!(event.params.native_id, Some(1001));
 //! ```
+mod dyn_value;
 mod events;
 mod payload;
 mod repr;
 
+pub use dyn_value::DynValue;
 pub use events::Event;
@@ -9,6 +9,13 @@ impl Serialize for SerializedField<&types::PT_SOCKADDR<'_>> {
     {
         match self.0 {
             types::PT_SOCKADDR::Unix(path) => SerializedField(path).serialize(serializer),
+            types::PT_SOCKADDR::AbstractUnix(name) => {
+                // keep the leading NUL, so that the address deserializes as abstract again
+                let mut addr = Vec::with_capacity(name.len() + 1);
+                addr.push(0u8);
+                addr.extend_from_slice(name);
+                StrOrBytes(&addr).serialize(serializer)
+            }
             types::PT_SOCKADDR::V4(v4) => (v4.ip(), v4.port()).serialize(serializer),
             types::PT_SOCKADDR::V6(v6) => (v6.ip(), v6.port()).serialize(serializer),
             types::PT_SOCKADDR::Other(af, addr) => (af, StrOrBytes(addr)).serialize(serializer),
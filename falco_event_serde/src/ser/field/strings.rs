@@ -66,13 +66,8 @@ impl Serialize for SerializedField<&types::PT_CHARBUFARRAY<'_>> {
     where
         S: Serializer,
     {
-        // collect into a physical vec for serializers that don't support unknown-length sequences happy
-        let vec = self
-            .0
-            .iter()
-            .map(|s| StrOrBytes(s.to_bytes()))
-            .collect::<Vec<_>>();
-        vec.serialize(serializer)
+        // the iterator knows its exact length, so serializers that need it upfront are happy
+        serializer.collect_seq(self.0.iter().map(|s| StrOrBytes(s.to_bytes())))
     }
 }
 
@@ -81,12 +76,11 @@ impl Serialize for SerializedField<&types::PT_CHARBUF_PAIR_ARRAY<'_>> {
     where
         S: Serializer,
     {
-        // collect into a physical vec for serializers that don't support unknown-length sequences happy
-        let vec = self
-            .0
-            .iter()
-            .map(|(k, v)| (StrOrBytes(k.to_bytes()), StrOrBytes(v.to_bytes())))
-            .collect::<Vec<_>>();
-        vec.serialize(serializer)
+        // the iterator knows its exact length, so serializers that need it upfront are happy
+        serializer.collect_seq(
+            self.0
+                .iter()
+                .map(|(k, v)| (StrOrBytes(k.to_bytes()), StrOrBytes(v.to_bytes()))),
+        )
     }
 }
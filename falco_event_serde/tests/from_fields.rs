@@ -0,0 +1,81 @@
+use falco_event_schema::events::{PPME_GENERIC_E, PPME_SYSCALL_READ_X, PPME_TRACER_E};
+use falco_event_schema::fields::types::{PT_ERRNO, PT_SYSCALLID};
+use falco_event_serde::de::{DynValue, Event};
+use std::collections::HashMap;
+
+#[test]
+fn test_from_fields() {
+    let fields = HashMap::from([
+        ("id", DynValue::from("1")),
+        ("native_id", DynValue::from(1001u64)),
+    ]);
+
+    let event = Event::from_fields(1700000000, 12345, "GENERIC_E", fields).unwrap();
+    let bytes = event.to_vec();
+    let event = falco_event::events::RawEvent::from(&bytes).unwrap();
+    let event = event.load::<PPME_GENERIC_E>().unwrap();
+    assert_eq!(event.metadata.ts, 1700000000);
+    assert_eq!(event.metadata.tid, 12345);
+    assert_eq!(event.params.id, Some(PT_SYSCALLID(1)));
+    assert_eq!(event.params.native_id, Some(1001));
+}
+
+#[test]
+fn test_from_fields_strings() {
+    let fields = HashMap::from([
+        ("id", DynValue::Int(123)),
+        (
+            "tags",
+            DynValue::List(vec!["tag1".into(), b"tag2".as_slice().into()]),
+        ),
+        (
+            "args",
+            DynValue::List(vec![DynValue::List(vec!["arg1".into(), 1u8.into()])]),
+        ),
+    ]);
+
+    let event = Event::from_fields(0, 0, "TRACER_E", fields).unwrap();
+    let bytes = event.to_vec();
+    let event = falco_event::events::RawEvent::from(&bytes).unwrap();
+    let event = event.load::<PPME_TRACER_E>().unwrap();
+
+    assert_eq!(event.params.id, Some(123));
+    assert_eq!(
+        event.params.tags.map(|t| t.iter().collect()),
+        Some(vec![c"tag1", c"tag2"])
+    );
+    assert_eq!(
+        event.params.args.map(|a| a.iter().collect()),
+        Some(vec![(c"arg1", c"1")])
+    );
+}
+
+#[test]
+fn test_from_fields_missing() {
+    let fields = HashMap::from([("res", DynValue::Int(-2)), ("data", DynValue::None)]);
+
+    let event = Event::from_fields(0, 0, "SYSCALL_READ_X", fields).unwrap();
+    let bytes = event.to_vec();
+    let event = falco_event::events::RawEvent::from(&bytes).unwrap();
+    let event = event.load::<PPME_SYSCALL_READ_X>().unwrap();
+
+    assert_eq!(event.params.res, Some(PT_ERRNO(-2)));
+    assert_eq!(event.params.data, None);
+}
+
+#[test]
+fn test_from_fields_errors() {
+    let err = Event::from_fields(0, 0, "NO_SUCH_EVENT", HashMap::new()).unwrap_err();
+    assert!(err.to_string().contains("unknown variant"), "{err}");
+
+    let fields = HashMap::from([("bogus", DynValue::Int(1))]);
+    let err = Event::from_fields(0, 0, "GENERIC_E", fields).unwrap_err();
+    assert_eq!(err.to_string(), "GENERIC_E: unknown field bogus");
+
+    let fields = HashMap::from([("native_id", DynValue::Int(-1))]);
+    let err = Event::from_fields(0, 0, "GENERIC_E", fields).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "GENERIC_E.native_id: cannot convert integer -1 to u16"
+    );
+}
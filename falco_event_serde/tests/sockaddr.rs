@@ -228,3 +228,36 @@ fn test_roundtrip_sockaddr_other() {
     let json_output = serde_json::to_value(ser).unwrap();
     assert_eq!(json_value, json_output);
 }
+
+#[test]
+fn test_roundtrip_sockaddr_abstract_unix() {
+    let json = r#"{
+    "ts": 1700000000,
+    "tid": 12345,
+    "SOCKET_CONNECT_E": {
+        "fd": 1,
+        "addr": "\u0000dbus\u0000session"
+    }
+    }"#;
+
+    let json_value: serde_json::Value = serde_json::from_str(json).unwrap();
+    let event: falco_event_serde::de::Event = serde_json::from_str(json).unwrap();
+    let bytes = event.to_vec();
+    let event = falco_event::events::RawEvent::from(&bytes).unwrap();
+    let event = event.load::<PPME_SOCKET_CONNECT_E>().unwrap();
+
+    assert_eq!(event.params.fd, Some(PT_FD(1)));
+    match event.params.addr {
+        Some(PT_SOCKADDR::AbstractUnix(name)) => {
+            assert_eq!(name, b"dbus\0session");
+        }
+        _ => panic!(
+            "Expected PT_SOCKADDR::AbstractUnix, got {:?}",
+            event.params.addr
+        ),
+    }
+
+    let ser = falco_event_serde::ser::Event::from(&event);
+    let json_output = serde_json::to_value(ser).unwrap();
+    assert_eq!(json_value, json_output);
+}
@@ -1,7 +1,8 @@
 use crate::error::as_result::AsResult;
+use crate::event::AsyncEvent;
 use crate::strings::from_ptr::try_str_from_ptr;
 use anyhow::Context;
-use falco_event::events::EventToBytes;
+use falco_event::events::{EventPayload, EventToBytes, RawEvent};
 use falco_plugin_api::{ss_plugin_event, ss_plugin_owner_t, ss_plugin_rc, PLUGIN_MAX_ERRLEN};
 use std::ffi::c_char;
 
@@ -30,11 +31,65 @@ impl AsyncHandler {
     /// This method returns an error if and only if the asynchronous handler
     /// returns an error.
     pub fn emit(&self, event: impl EventToBytes) -> Result<(), anyhow::Error> {
-        let mut err = [0 as c_char; PLUGIN_MAX_ERRLEN as usize];
         let mut buf = Vec::new();
-        let err_ptr = &err as *const [c_char] as *const c_char;
 
         event.write(&mut buf)?;
+        self.emit_bytes(&buf)
+    }
+
+    /// # Emit a series of events asynchronously
+    ///
+    /// Submit several events to the main event loop, in order, reusing a single buffer
+    /// to serialize them. This is cheaper than calling [`AsyncHandler::emit`] in a loop
+    /// when emitting many events at once.
+    ///
+    /// This method stops at the first event that fails to serialize or gets rejected
+    /// by the asynchronous handler and returns the error.
+    pub fn emit_many<E: EventToBytes>(
+        &self,
+        events: impl IntoIterator<Item = E>,
+    ) -> Result<(), anyhow::Error> {
+        let mut buf = Vec::new();
+
+        for event in events {
+            buf.clear();
+            event.write(&mut buf)?;
+            self.emit_bytes(&buf)?;
+        }
+
+        Ok(())
+    }
+
+    /// # Emit an already serialized event
+    ///
+    /// Submit an event that's already encoded in the binary format, e.g. one kept
+    /// from an earlier [`EventToBytes::write`] call or produced by an external component.
+    /// The buffer must contain a complete async event (`PPME_ASYNCEVENT_E`), including
+    /// the header.
+    ///
+    /// This method returns an error if the buffer does not look like an async event
+    /// or if the asynchronous handler returns an error.
+    pub fn emit_raw(&self, buf: &[u8]) -> Result<(), anyhow::Error> {
+        let raw = RawEvent::from(buf).context("parsing raw async event")?;
+        if raw.event_type != <AsyncEvent<&[u8]> as EventPayload>::ID {
+            anyhow::bail!("not an async event: {:?}", raw);
+        }
+        if raw.len as usize > buf.len() {
+            anyhow::bail!(
+                "truncated async event: {} bytes, expected {}",
+                buf.len(),
+                raw.len
+            );
+        }
+
+        self.emit_bytes(buf)
+    }
+
+    /// # Emit an already serialized event, without validating it
+    pub(crate) fn emit_bytes(&self, buf: &[u8]) -> Result<(), anyhow::Error> {
+        let mut err = [0 as c_char; PLUGIN_MAX_ERRLEN as usize];
+        let err_ptr = &err as *const [c_char] as *const c_char;
+
         match unsafe {
             (self.raw_handler)(self.owner, buf.as_ptr() as *const _, err.as_mut_ptr()).as_result()
         } {
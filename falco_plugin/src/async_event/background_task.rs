@@ -1,6 +1,8 @@
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Condvar, Mutex};
 use std::thread::JoinHandle;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 /// A trivial enum to indicate the requested state of the async background task
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
@@ -10,6 +12,67 @@ pub enum RequestedState {
     Stopped,
 }
 
+/// # Health statistics of a [`BackgroundTask`]
+///
+/// These are only collected for threads started with [`BackgroundTask::spawn`]. A growing
+/// number of overruns (or a long last run time) means the background work cannot keep up
+/// with the requested interval.
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct BackgroundTaskStats {
+    /// The number of spawned threads that are still running
+    pub threads: u32,
+    /// The total number of times the closure was called
+    pub iterations: u64,
+    /// The number of times the closure took longer than the interval to run
+    pub overruns: u64,
+    /// How long the most recent call to the closure took
+    pub last_run: Duration,
+}
+
+impl BackgroundTaskStats {
+    /// Convert the statistics into metrics
+    ///
+    /// You can return these from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`)
+    /// to make them visible to operators.
+    pub fn metrics(&self) -> [Metric; 4] {
+        [
+            Metric::new(
+                MetricLabel::new(c"background_task_threads", MetricType::NonMonotonic),
+                MetricValue::U32(self.threads),
+            ),
+            Metric::new(
+                MetricLabel::new(c"background_task_iterations", MetricType::Monotonic),
+                MetricValue::U64(self.iterations),
+            ),
+            Metric::new(
+                MetricLabel::new(c"background_task_overruns", MetricType::Monotonic),
+                MetricValue::U64(self.overruns),
+            ),
+            Metric::new(
+                MetricLabel::new(c"background_task_last_run_us", MetricType::NonMonotonic),
+                MetricValue::U64(self.last_run.as_micros() as u64),
+            ),
+        ]
+    }
+}
+
+#[derive(Default, Debug)]
+struct BackgroundTaskCounters {
+    threads: AtomicU32,
+    iterations: AtomicU64,
+    overruns: AtomicU64,
+    last_run_us: AtomicU64,
+}
+
+/// Decrements the running thread count when the thread exits (even by panicking)
+struct ThreadGuard<'a>(&'a AtomicU32);
+
+impl Drop for ThreadGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
 /// # A helper to periodically run a background task until shutdown is requested
 ///
 /// Can be used to spawn a separate thread or as a building block for some other
@@ -20,6 +83,7 @@ pub enum RequestedState {
 pub struct BackgroundTask {
     lock: Mutex<RequestedState>,
     cond: Condvar,
+    counters: BackgroundTaskCounters,
 }
 
 impl BackgroundTask {
@@ -68,6 +132,27 @@ impl BackgroundTask {
         Ok(wait_res.timed_out())
     }
 
+    /// Get the health statistics of threads started with [`BackgroundTask::spawn`]
+    pub fn stats(&self) -> BackgroundTaskStats {
+        BackgroundTaskStats {
+            threads: self.counters.threads.load(Ordering::Relaxed),
+            iterations: self.counters.iterations.load(Ordering::Relaxed),
+            overruns: self.counters.overruns.load(Ordering::Relaxed),
+            last_run: Duration::from_micros(self.counters.last_run_us.load(Ordering::Relaxed)),
+        }
+    }
+
+    fn record_run(&self, elapsed: Duration, interval: Duration) {
+        let counters = &self.counters;
+        counters.iterations.fetch_add(1, Ordering::Relaxed);
+        if elapsed > interval {
+            counters.overruns.fetch_add(1, Ordering::Relaxed);
+        }
+        counters
+            .last_run_us
+            .store(elapsed.as_micros() as u64, Ordering::Relaxed);
+    }
+
     /// Spawn a background thread that calls `func` every `interval` until shutdown
     ///
     /// Ideally, the called closure should not block for any noticeable time, as shutdown
@@ -91,10 +176,15 @@ impl BackgroundTask {
     {
         self.request_start()?;
         let clone = Arc::clone(self);
+        clone.counters.threads.fetch_add(1, Ordering::Relaxed);
 
         Ok(std::thread::spawn(move || {
+            let _guard = ThreadGuard(&clone.counters.threads);
             while clone.should_keep_running(interval)? {
-                func()?
+                let start = Instant::now();
+                let res = func();
+                clone.record_run(start.elapsed(), interval);
+                res?
             }
 
             Ok(())
@@ -165,4 +255,27 @@ mod tests {
         assert!(millis >= 450);
         assert!(millis < 500);
     }
+    #[test]
+    fn test_spawn_stats() {
+        let req = Arc::new(BackgroundTask::default());
+        assert_eq!(req.stats(), Default::default());
+
+        let handle = req
+            .spawn(Duration::from_millis(10), move || {
+                std::thread::sleep(Duration::from_millis(20));
+                Ok(())
+            })
+            .unwrap();
+        assert_eq!(req.stats().threads, 1);
+
+        std::thread::sleep(Duration::from_millis(100));
+        req.request_stop_and_notify().unwrap();
+        handle.join().unwrap().unwrap();
+
+        let stats = req.stats();
+        assert_eq!(stats.threads, 0);
+        assert!(stats.iterations > 0);
+        assert_eq!(stats.overruns, stats.iterations);
+        assert!(stats.last_run >= Duration::from_millis(20));
+    }
 }
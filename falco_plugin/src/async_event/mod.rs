@@ -92,16 +92,29 @@
 
 use crate::async_event::wrappers::AsyncPluginExported;
 use crate::base::Plugin;
+use crate::event::PayloadFormat;
 use falco_event::events::Event;
+use serde::Serialize;
 
 mod async_handler;
 mod background_task;
+mod names;
+mod queue;
+mod task_group;
+mod throttle;
+#[cfg(feature = "tokio")]
+pub mod tokio;
 #[doc(hidden)]
 pub mod wrappers;
 
 pub use crate::event::AsyncEvent;
 pub use async_handler::AsyncHandler;
-pub use background_task::BackgroundTask;
+pub use background_task::{BackgroundTask, BackgroundTaskStats};
+pub use falco_plugin_derive::AsyncEventNames;
+pub use names::AsyncEventNames;
+pub use queue::{AsyncHandlerQueue, AsyncHandlerQueueStats, AsyncQueueSender, OverflowPolicy};
+pub use task_group::{TaskGroup, TaskInfo, TaskStatus};
+pub use throttle::{ThrottledHandler, ThrottledHandlerStats};
 
 /// Support for asynchronous event plugins
 pub trait AsyncEventPlugin: Plugin + AsyncPluginExported {
@@ -156,6 +169,18 @@ pub trait AsyncEventPlugin: Plugin + AsyncPluginExported {
     }
 
     /// # A helper method to create an asynchronous event
+    ///
+    /// The event has no timestamp or thread id, so the framework uses the time it receives
+    /// the event. To place the event elsewhere on the capture timeline (e.g. when replaying
+    /// historical data), set them explicitly:
+    ///
+    /// ```ignore
+    /// handler.emit(
+    ///     Self::async_event(c"sample_async", b"hello")
+    ///         .with_timestamp(record.timestamp)
+    ///         .with_tid(record.tid),
+    /// )?;
+    /// ```
     fn async_event<'a>(
         name: &'a std::ffi::CStr,
         data: &'a [u8],
@@ -173,4 +198,28 @@ pub trait AsyncEventPlugin: Plugin + AsyncPluginExported {
             params: event,
         }
     }
+
+    /// # A helper method to create an asynchronous event with a typed payload
+    ///
+    /// The payload is serialized using `format`. Consumers (e.g. parse or extract plugins)
+    /// can decode it using [`EventInput::parse_async_event`](`crate::event::EventInput::parse_async_event`)
+    /// with the same type and format.
+    fn async_event_typed<'a, T: Serialize + ?Sized>(
+        name: &'a std::ffi::CStr,
+        data: &T,
+        format: PayloadFormat,
+    ) -> Result<Event<AsyncEvent<'a, Vec<u8>>>, anyhow::Error> {
+        let event = AsyncEvent {
+            plugin_id: 0, // gets populated by the framework, shall be None
+            name,
+            data: format.encode(data)?,
+        };
+
+        let metadata = falco_event::events::EventMetadata::default();
+
+        Ok(Event {
+            metadata,
+            params: event,
+        })
+    }
 }
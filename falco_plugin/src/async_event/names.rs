@@ -0,0 +1,66 @@
+use crate::event::AsyncEvent;
+use falco_event::events::{Event, EventMetadata};
+use std::ffi::CStr;
+
+/// # A set of async event names
+///
+/// This trait is meant to be derived on an enum with unit variants, one for each async event
+/// your plugin emits, so that the list of names passed to the framework cannot get out of sync
+/// with the names actually used:
+///
+/// ```
+/// use falco_plugin::async_event::AsyncEventNames;
+///
+/// #[derive(Clone, Copy, AsyncEventNames)]
+/// enum MyAsyncEvents {
+///     ContainerAdded,
+///     ContainerRemoved,
+///     #[name(c"container-updated")]
+///     ContainerUpdated,
+/// }
+///
+/// assert_eq!(
+///     MyAsyncEvents::NAMES,
+///     &["container_added", "container_removed", "container-updated"]
+/// );
+/// assert_eq!(MyAsyncEvents::ContainerAdded.name(), c"container_added");
+/// ```
+///
+/// The event names default to the variant names converted to snake case, unless overridden
+/// with a `#[name(c"...")]` attribute. Use the generated list as
+/// [`AsyncEventPlugin::ASYNC_EVENTS`](`crate::async_event::AsyncEventPlugin::ASYNC_EVENTS`)
+/// and create the events with [`AsyncEventNames::event`]:
+///
+/// ```ignore
+/// impl AsyncEventPlugin for MyPlugin {
+///     const ASYNC_EVENTS: &'static [&'static str] = MyAsyncEvents::NAMES;
+///     // ...
+///
+///     fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+///         handler.emit(MyAsyncEvents::ContainerAdded.event(b"container id"))?;
+///         // ...
+///     }
+/// }
+/// ```
+pub trait AsyncEventNames: Copy {
+    /// The names of all the events, in declaration order
+    const NAMES: &'static [&'static str];
+
+    /// The name of this particular event
+    fn name(self) -> &'static CStr;
+
+    /// # Create an asynchronous event with this name
+    ///
+    /// This is equivalent to [`AsyncEventPlugin::async_event`](`crate::async_event::AsyncEventPlugin::async_event`)
+    /// with the name taken from `self`.
+    fn event(self, data: &[u8]) -> Event<AsyncEvent<'static, &[u8]>> {
+        Event {
+            metadata: EventMetadata::default(),
+            params: AsyncEvent {
+                plugin_id: 0, // gets populated by the framework, shall be None
+                name: self.name(),
+                data,
+            },
+        }
+    }
+}
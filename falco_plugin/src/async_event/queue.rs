@@ -0,0 +1,432 @@
+use crate::async_event::AsyncHandler;
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use falco_event::events::EventToBytes;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::mpsc::{Receiver, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+
+/// # What to do with new events when an [`AsyncHandlerQueue`] is full
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum OverflowPolicy {
+    /// Wait until the drain thread makes room in the queue
+    #[default]
+    Block,
+    /// Drop the new event (and count it in [`AsyncHandlerQueueStats::dropped`])
+    Drop,
+}
+
+/// # Statistics of an [`AsyncHandlerQueue`]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct AsyncHandlerQueueStats {
+    /// The number of events currently waiting in the queue
+    pub queued: u64,
+    /// The total number of events submitted to the framework
+    pub emitted: u64,
+    /// The total number of events dropped because the queue was full
+    /// (or enqueued while the queue was stopping)
+    pub dropped: u64,
+    /// The total number of events rejected by the framework
+    pub errors: u64,
+}
+
+impl AsyncHandlerQueueStats {
+    /// Convert the statistics into metrics
+    ///
+    /// You can return these from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`)
+    /// to make them visible to operators.
+    pub fn metrics(&self) -> [Metric; 4] {
+        [
+            Metric::new(
+                MetricLabel::new(c"async_queue_queued", MetricType::NonMonotonic),
+                MetricValue::U64(self.queued),
+            ),
+            Metric::new(
+                MetricLabel::new(c"async_queue_emitted", MetricType::Monotonic),
+                MetricValue::U64(self.emitted),
+            ),
+            Metric::new(
+                MetricLabel::new(c"async_queue_dropped", MetricType::Monotonic),
+                MetricValue::U64(self.dropped),
+            ),
+            Metric::new(
+                MetricLabel::new(c"async_queue_errors", MetricType::Monotonic),
+                MetricValue::U64(self.errors),
+            ),
+        ]
+    }
+}
+
+/// A message for the drain thread
+#[derive(Debug)]
+enum QueueMessage {
+    /// A serialized event to submit
+    Event(Vec<u8>),
+    /// Submit everything queued before this message and exit
+    Stop,
+}
+
+#[derive(Default, Debug)]
+struct QueueCounters {
+    stopped: AtomicBool,
+    queued: AtomicU64,
+    emitted: AtomicU64,
+    dropped: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl QueueCounters {
+    fn stats(&self) -> AsyncHandlerQueueStats {
+        AsyncHandlerQueueStats {
+            queued: self.queued.load(Ordering::Relaxed),
+            emitted: self.emitted.load(Ordering::Relaxed),
+            dropped: self.dropped.load(Ordering::Relaxed),
+            errors: self.errors.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// # A handle to enqueue events in an [`AsyncHandlerQueue`]
+///
+/// Get one from [`AsyncHandlerQueue::sender`] for every producer thread.
+#[derive(Debug, Clone)]
+pub struct AsyncQueueSender {
+    sender: SyncSender<QueueMessage>,
+    policy: OverflowPolicy,
+    counters: Arc<QueueCounters>,
+}
+
+impl AsyncQueueSender {
+    /// # Enqueue an event
+    ///
+    /// The event is serialized right away and submitted to the framework later, from the drain
+    /// thread. When the queue is full, this method either waits or drops the event, depending
+    /// on the [`OverflowPolicy`] of the queue.
+    ///
+    /// This method returns an error if the event cannot be serialized or the queue
+    /// has been stopped. Errors returned by the framework are not reported here,
+    /// but by [`AsyncHandlerQueue::stop`].
+    pub fn emit(&self, event: impl EventToBytes) -> Result<(), anyhow::Error> {
+        if self.counters.stopped.load(Ordering::Acquire) {
+            anyhow::bail!("async event queue already stopped");
+        }
+
+        let mut buf = Vec::new();
+        event.write(&mut buf)?;
+        let buf = QueueMessage::Event(buf);
+
+        // count the event before sending, so the drain thread never sees a negative depth
+        self.counters.queued.fetch_add(1, Ordering::Relaxed);
+        let res = match self.policy {
+            OverflowPolicy::Block => self.sender.send(buf).map_err(|_| ()),
+            OverflowPolicy::Drop => match self.sender.try_send(buf) {
+                Err(TrySendError::Full(_)) => {
+                    self.counters.dropped.fetch_add(1, Ordering::Relaxed);
+                    self.counters.queued.fetch_sub(1, Ordering::Relaxed);
+                    return Ok(());
+                }
+                res => res.map_err(|_| ()),
+            },
+        };
+
+        res.map_err(|_| {
+            self.counters.queued.fetch_sub(1, Ordering::Relaxed);
+            anyhow::anyhow!("async event queue already stopped")
+        })
+    }
+}
+
+/// # A bounded queue in front of an [`AsyncHandler`]
+///
+/// [`AsyncHandler::emit`] calls into the framework synchronously, so a burst of events
+/// blocks the producing thread for as long as the framework needs to accept them.
+/// This type moves the actual submission to a dedicated drain thread: producers only serialize
+/// the event and put it in a bounded queue, and the drain thread submits all the queued events
+/// in a batch whenever it wakes up.
+///
+/// When the queue is full, the [`OverflowPolicy`] decides whether producers wait
+/// for room in the queue or drop their events. The number of dropped events (and other
+/// statistics) is available from [`AsyncHandlerQueue::stats`].
+///
+/// ```ignore
+/// fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+///     let queue = AsyncHandlerQueue::new(handler, 1024, OverflowPolicy::Drop)?;
+///     let sender = queue.sender();
+///     self.queue = Some(queue);
+///
+///     self.thread = Some(self.task.spawn(Duration::from_millis(100), move || {
+///         sender.emit(Self::async_event(c"sample_async", b"hello"))
+///     })?);
+///     Ok(())
+/// }
+///
+/// fn stop_async(&mut self) -> Result<(), Error> {
+///     // stop the producers first, so that no events are lost
+///     self.task.request_stop_and_notify()?;
+///     if let Some(handle) = self.thread.take() {
+///         handle.join().map_err(|e| anyhow::anyhow!("{e:?}"))??;
+///     }
+///
+///     // then flush the queue
+///     match self.queue.take() {
+///         Some(mut queue) => queue.stop(),
+///         None => Ok(()),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct AsyncHandlerQueue {
+    sender: Option<AsyncQueueSender>,
+    counters: Arc<QueueCounters>,
+    thread: Option<JoinHandle<Result<(), anyhow::Error>>>,
+}
+
+impl AsyncHandlerQueue {
+    /// Create a queue holding up to `capacity` events and start its drain thread
+    pub fn new(
+        handler: AsyncHandler,
+        capacity: usize,
+        policy: OverflowPolicy,
+    ) -> Result<Self, anyhow::Error> {
+        let (sender, receiver) = std::sync::mpsc::sync_channel(capacity);
+        let counters = Arc::new(QueueCounters::default());
+
+        let thread = {
+            let counters = Arc::clone(&counters);
+            std::thread::Builder::new()
+                .name("async-queue".to_string())
+                .spawn(move || Self::drain(handler, receiver, &counters))?
+        };
+
+        Ok(Self {
+            sender: Some(AsyncQueueSender {
+                sender,
+                policy,
+                counters: Arc::clone(&counters),
+            }),
+            counters,
+            thread: Some(thread),
+        })
+    }
+
+    fn drain(
+        handler: AsyncHandler,
+        receiver: Receiver<QueueMessage>,
+        counters: &QueueCounters,
+    ) -> Result<(), anyhow::Error> {
+        let mut first_error = None;
+        let mut batch = Vec::new();
+        let mut stopping = false;
+
+        // wait for the first event, then grab everything else that's already queued
+        while !stopping {
+            let Ok(message) = receiver.recv() else {
+                break;
+            };
+            for message in std::iter::once(message).chain(receiver.try_iter()) {
+                match message {
+                    QueueMessage::Event(event) => batch.push(event),
+                    QueueMessage::Stop => {
+                        stopping = true;
+                        break;
+                    }
+                }
+            }
+
+            counters
+                .queued
+                .fetch_sub(batch.len() as u64, Ordering::Relaxed);
+            for event in batch.drain(..) {
+                match handler.emit_bytes(&event) {
+                    Ok(()) => counters.emitted.fetch_add(1, Ordering::Relaxed),
+                    Err(e) => {
+                        first_error.get_or_insert(e);
+                        counters.errors.fetch_add(1, Ordering::Relaxed)
+                    }
+                };
+            }
+        }
+
+        // events that raced with `stop` are not submitted
+        for message in receiver.try_iter() {
+            if let QueueMessage::Event(_) = message {
+                counters.queued.fetch_sub(1, Ordering::Relaxed);
+                counters.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Get a new handle to enqueue events
+    ///
+    /// # Panics
+    ///
+    /// This method panics if called after [`AsyncHandlerQueue::stop`].
+    pub fn sender(&self) -> AsyncQueueSender {
+        self.sender
+            .clone()
+            .expect("AsyncHandlerQueue::sender called after stop")
+    }
+
+    /// Enqueue an event
+    ///
+    /// See [`AsyncQueueSender::emit`] for details.
+    pub fn emit(&self, event: impl EventToBytes) -> Result<(), anyhow::Error> {
+        match &self.sender {
+            Some(sender) => sender.emit(event),
+            None => anyhow::bail!("async event queue already stopped"),
+        }
+    }
+
+    /// Get the queue statistics
+    pub fn stats(&self) -> AsyncHandlerQueueStats {
+        self.counters.stats()
+    }
+
+    /// Tell the drain thread to submit the events queued so far and exit
+    ///
+    /// Returns the drain thread handle, if it's still running.
+    fn shutdown(&mut self) -> Option<JoinHandle<Result<(), anyhow::Error>>> {
+        let thread = self.thread.take()?;
+        self.counters.stopped.store(true, Ordering::Release);
+
+        if let Some(sender) = self.sender.take() {
+            // this only fails if the drain thread is already gone (e.g. it panicked)
+            let _ = sender.sender.send(QueueMessage::Stop);
+        }
+
+        Some(thread)
+    }
+
+    /// Flush the queue and stop the drain thread
+    ///
+    /// The events enqueued before this call are submitted to the framework. Afterwards,
+    /// [`AsyncQueueSender::emit`] returns an error, even if other threads still hold
+    /// their [`AsyncQueueSender`] handles, so stop your producer threads first
+    /// if you do not want to lose any events.
+    ///
+    /// Returns the first error returned by the framework while emitting the queued events.
+    pub fn stop(&mut self) -> Result<(), anyhow::Error> {
+        let Some(thread) = self.shutdown() else {
+            return Ok(());
+        };
+
+        match thread.join() {
+            Ok(res) => res,
+            Err(e) => std::panic::resume_unwind(e),
+        }
+    }
+}
+
+impl Drop for AsyncHandlerQueue {
+    fn drop(&mut self) {
+        // the handler must not be used after the plugin returns from `stop_async`,
+        // so we have to wait for the drain thread even if there's nobody to report errors to
+        if let Some(thread) = self.shutdown() {
+            let _ = thread.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::async_event::queue::{AsyncHandlerQueue, OverflowPolicy};
+    use crate::async_event::{AsyncEvent, AsyncHandler};
+    use falco_event::events::{Event, EventMetadata};
+    use falco_plugin_api::{
+        ss_plugin_event, ss_plugin_owner_t, ss_plugin_rc, ss_plugin_rc_SS_PLUGIN_SUCCESS,
+    };
+    use std::ffi::c_char;
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    static FRAMEWORK_LOCK: Mutex<()> = Mutex::new(());
+
+    unsafe extern "C-unwind" fn slow_handler(
+        _o: *mut ss_plugin_owner_t,
+        _evt: *const ss_plugin_event,
+        _err: *mut c_char,
+    ) -> ss_plugin_rc {
+        drop(FRAMEWORK_LOCK.lock());
+        ss_plugin_rc_SS_PLUGIN_SUCCESS
+    }
+
+    fn event() -> Event<AsyncEvent<'static, &'static [u8]>> {
+        Event {
+            metadata: EventMetadata::default(),
+            params: AsyncEvent {
+                plugin_id: 0,
+                name: c"test",
+                data: b"",
+            },
+        }
+    }
+
+    #[test]
+    fn test_drop_when_full() {
+        let handler = AsyncHandler {
+            owner: std::ptr::null_mut(),
+            raw_handler: slow_handler,
+        };
+        let mut queue = AsyncHandlerQueue::new(handler, 2, OverflowPolicy::Drop).unwrap();
+
+        let guard = FRAMEWORK_LOCK.lock().unwrap();
+
+        // wait for the drain thread to get stuck in the framework
+        queue.emit(event()).unwrap();
+        while queue.stats().queued > 0 {
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        for _ in 0..5 {
+            queue.emit(event()).unwrap();
+        }
+        assert_eq!(queue.stats().queued, 2);
+        assert_eq!(queue.stats().dropped, 3);
+
+        drop(guard);
+        queue.stop().unwrap();
+
+        let stats = queue.stats();
+        assert_eq!(stats.emitted, 3);
+        assert_eq!(stats.dropped, 3);
+        assert_eq!(stats.queued, 0);
+        assert_eq!(stats.errors, 0);
+    }
+
+    #[test]
+    fn test_stop_with_live_sender() {
+        let handler = AsyncHandler {
+            owner: std::ptr::null_mut(),
+            raw_handler: slow_handler,
+        };
+        let mut queue = AsyncHandlerQueue::new(handler, 2, OverflowPolicy::Block).unwrap();
+        let sender = queue.sender();
+
+        sender.emit(event()).unwrap();
+        queue.stop().unwrap();
+        assert!(sender.emit(event()).is_err());
+
+        let stats = queue.stats();
+        assert_eq!(stats.emitted, 1);
+        assert_eq!(stats.queued, 0);
+    }
+
+    #[test]
+    fn test_drop_with_live_sender() {
+        let handler = AsyncHandler {
+            owner: std::ptr::null_mut(),
+            raw_handler: slow_handler,
+        };
+        let queue = AsyncHandlerQueue::new(handler, 2, OverflowPolicy::Block).unwrap();
+        let sender = queue.sender();
+
+        sender.emit(event()).unwrap();
+        drop(queue);
+        assert!(sender.emit(event()).is_err());
+    }
+}
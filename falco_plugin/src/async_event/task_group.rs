@@ -0,0 +1,321 @@
+use crate::async_event::background_task::{BackgroundTask, BackgroundTaskStats};
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use anyhow::Context;
+use std::any::Any;
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// # The state of a task in a [`TaskGroup`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TaskStatus {
+    /// The task thread is still running
+    Running,
+    /// The task thread has exited (by itself, returning an error or panicking), but the task
+    /// has not been stopped yet, so its result is not known
+    Exited,
+}
+
+/// # Information about a task in a [`TaskGroup`]
+#[derive(Debug, Clone)]
+pub struct TaskInfo<'a> {
+    /// The task name
+    pub name: &'a str,
+    /// Whether the task is still running
+    pub status: TaskStatus,
+    /// Health statistics of the task
+    pub stats: BackgroundTaskStats,
+}
+
+#[derive(Debug)]
+struct GroupTask {
+    name: String,
+    task: Arc<BackgroundTask>,
+    handle: JoinHandle<Result<(), anyhow::Error>>,
+}
+
+impl GroupTask {
+    fn stop(self) -> Result<(), anyhow::Error> {
+        self.task.request_stop_and_notify()?;
+        match self.handle.join() {
+            Ok(res) => res.with_context(|| format!("task {} failed", self.name)),
+            Err(e) => std::panic::resume_unwind(Box::new(format!(
+                "task {} panicked: {}",
+                self.name,
+                panic_message(&*e)
+            ))),
+        }
+    }
+}
+
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    if let Some(msg) = payload.downcast_ref::<&'static str>() {
+        msg
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.as_str()
+    } else {
+        "Box<dyn Any>"
+    }
+}
+
+/// # A set of named background tasks
+///
+/// Each task is a thread started with [`BackgroundTask::spawn`], identified by its name.
+/// Tasks can be stopped individually ([`TaskGroup::stop`]) or all at once ([`TaskGroup::stop_all`]),
+/// which makes it a good fit for [`AsyncEventPlugin`](`crate::async_event::AsyncEventPlugin`)
+/// implementations doing more than one thing in the background:
+///
+/// ```ignore
+/// fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+///     self.tasks.stop_all()?;
+///
+///     let h = handler.clone();
+///     self.tasks.spawn("heartbeat", Duration::from_secs(1), move || {
+///         h.emit(Self::async_event(c"heartbeat", b""))
+///     })?;
+///     self.tasks.spawn("poller", Duration::from_millis(100), move || {
+///         handler.emit(Self::async_event(c"poll", b"result"))
+///     })?;
+///     Ok(())
+/// }
+///
+/// fn stop_async(&mut self) -> Result<(), Error> {
+///     self.tasks.stop_all()
+/// }
+/// ```
+///
+/// Errors and panics from the tasks are reported when they are stopped, with the task name
+/// attached.
+#[derive(Debug, Default)]
+pub struct TaskGroup {
+    tasks: Vec<GroupTask>,
+}
+
+impl TaskGroup {
+    /// Create an empty task group
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start a new named task
+    ///
+    /// The task calls `func` every `interval` until stopped, just like [`BackgroundTask::spawn`].
+    /// Returns an error if a task with the same name is already in the group.
+    pub fn spawn<F>(
+        &mut self,
+        name: impl Into<String>,
+        interval: Duration,
+        func: F,
+    ) -> Result<(), anyhow::Error>
+    where
+        F: FnMut() -> Result<(), anyhow::Error> + 'static + Send,
+    {
+        let name = name.into();
+        if self.tasks.iter().any(|t| t.name == name) {
+            anyhow::bail!("task {name} already exists");
+        }
+
+        let task = Arc::new(BackgroundTask::default());
+        let handle = task
+            .spawn(interval, func)
+            .with_context(|| format!("starting task {name}"))?;
+
+        self.tasks.push(GroupTask { name, task, handle });
+        Ok(())
+    }
+
+    /// Stop a single task and wait for it to finish
+    ///
+    /// Returns the error returned by the task, if any. If the task panicked, the panic
+    /// is propagated to the caller. Stopping a task that does not exist is not an error.
+    pub fn stop(&mut self, name: &str) -> Result<(), anyhow::Error> {
+        match self.tasks.iter().position(|t| t.name == name) {
+            Some(index) => self.tasks.remove(index).stop(),
+            None => Ok(()),
+        }
+    }
+
+    /// Stop all tasks and wait for them to finish
+    ///
+    /// All tasks are stopped, even if some of them fail. Returns the first error returned
+    /// by any of the tasks. If a task panicked, the panic is propagated to the caller
+    /// after all the other tasks have stopped.
+    pub fn stop_all(&mut self) -> Result<(), anyhow::Error> {
+        // request all tasks to stop first, so that they shut down in parallel
+        for task in &self.tasks {
+            task.task.request_stop_and_notify()?;
+        }
+
+        let mut first_error = None;
+        let mut first_panic = None;
+        let tasks = std::mem::take(&mut self.tasks);
+        for task in tasks {
+            let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| task.stop()));
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) => {
+                    first_panic.get_or_insert(e);
+                }
+            }
+        }
+
+        if let Some(panic) = first_panic {
+            std::panic::resume_unwind(panic);
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+
+    /// Get the names of all tasks in the group, in the order they were started
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.tasks.iter().map(|t| t.name.as_str())
+    }
+
+    /// Get the status of all tasks in the group, in the order they were started
+    pub fn status(&self) -> impl Iterator<Item = TaskInfo<'_>> {
+        self.tasks.iter().map(|t| TaskInfo {
+            name: &t.name,
+            status: if t.handle.is_finished() {
+                TaskStatus::Exited
+            } else {
+                TaskStatus::Running
+            },
+            stats: t.task.stats(),
+        })
+    }
+
+    /// Summarize the task statuses as metrics
+    ///
+    /// You can return these from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`)
+    /// to make them visible to operators. Use [`TaskGroup::status`] for per-task details.
+    pub fn metrics(&self) -> [Metric; 2] {
+        let (mut running, mut exited) = (0u32, 0u32);
+        for info in self.status() {
+            match info.status {
+                TaskStatus::Running => running += 1,
+                TaskStatus::Exited => exited += 1,
+            }
+        }
+
+        [
+            Metric::new(
+                MetricLabel::new(c"task_group_running", MetricType::NonMonotonic),
+                MetricValue::U32(running),
+            ),
+            Metric::new(
+                MetricLabel::new(c"task_group_exited", MetricType::NonMonotonic),
+                MetricValue::U32(exited),
+            ),
+        ]
+    }
+}
+
+impl Drop for TaskGroup {
+    fn drop(&mut self) {
+        // make sure no task outlives the group (and e.g. uses the async handler after
+        // `stop_async` returns), but don't panic in a destructor
+        for task in &self.tasks {
+            let _ = task.task.request_stop_and_notify();
+        }
+        let tasks = std::mem::take(&mut self.tasks);
+        for task in tasks {
+            let _ = task.handle.join();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::async_event::task_group::{TaskGroup, TaskStatus};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_stop_individually() {
+        let mut group = TaskGroup::new();
+        let counter_a = Arc::new(AtomicUsize::default());
+        let counter_b = Arc::new(AtomicUsize::default());
+
+        let a = Arc::clone(&counter_a);
+        group
+            .spawn("a", Duration::from_millis(10), move || {
+                a.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .unwrap();
+        let b = Arc::clone(&counter_b);
+        group
+            .spawn("b", Duration::from_millis(10), move || {
+                b.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            })
+            .unwrap();
+        assert!(group
+            .spawn("a", Duration::from_millis(10), || Ok(()))
+            .is_err());
+
+        std::thread::sleep(Duration::from_millis(55));
+        group.stop("a").unwrap();
+        assert_eq!(group.names().collect::<Vec<_>>(), ["b"]);
+
+        let stopped_a = counter_a.load(Ordering::Relaxed);
+        std::thread::sleep(Duration::from_millis(55));
+        assert_eq!(counter_a.load(Ordering::Relaxed), stopped_a);
+        assert!(counter_b.load(Ordering::Relaxed) > stopped_a);
+
+        group.stop_all().unwrap();
+        assert_eq!(group.names().count(), 0);
+    }
+
+    #[test]
+    fn test_errors_and_status() {
+        let mut group = TaskGroup::new();
+        group
+            .spawn("ok", Duration::from_millis(10), || Ok(()))
+            .unwrap();
+        group
+            .spawn("failing", Duration::from_millis(10), || {
+                anyhow::bail!("oops")
+            })
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(50));
+        let status = group
+            .status()
+            .map(|info| (info.name.to_string(), info.status))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            status,
+            [
+                ("ok".to_string(), TaskStatus::Running),
+                ("failing".to_string(), TaskStatus::Exited),
+            ]
+        );
+
+        let err = group.stop_all().unwrap_err();
+        assert_eq!(format!("{err:#}"), "task failing failed: oops");
+    }
+
+    #[test]
+    fn test_panic_has_task_name() {
+        let mut group = TaskGroup::new();
+        group
+            .spawn("panicky", Duration::from_millis(10), || panic!("boom"))
+            .unwrap();
+
+        std::thread::sleep(Duration::from_millis(50));
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| group.stop_all()))
+            .unwrap_err();
+        assert_eq!(
+            panic.downcast_ref::<String>().unwrap(),
+            "task panicky panicked: boom"
+        );
+    }
+}
@@ -0,0 +1,412 @@
+use crate::async_event::AsyncHandler;
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use falco_event::events::{EventToBytes, RawEvent};
+use std::collections::{HashMap, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// # Statistics of a [`ThrottledHandler`]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq)]
+pub struct ThrottledHandlerStats {
+    /// The total number of events submitted to the framework
+    pub emitted: u64,
+    /// The total number of events suppressed by the rate limit
+    pub rate_limited: u64,
+    /// The total number of events suppressed as duplicates
+    pub deduplicated: u64,
+}
+
+impl ThrottledHandlerStats {
+    /// Convert the statistics into metrics
+    ///
+    /// You can return these from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`)
+    /// to make them visible to operators.
+    pub fn metrics(&self) -> [Metric; 3] {
+        [
+            Metric::new(
+                MetricLabel::new(c"async_throttle_emitted", MetricType::Monotonic),
+                MetricValue::U64(self.emitted),
+            ),
+            Metric::new(
+                MetricLabel::new(c"async_throttle_rate_limited", MetricType::Monotonic),
+                MetricValue::U64(self.rate_limited),
+            ),
+            Metric::new(
+                MetricLabel::new(c"async_throttle_deduplicated", MetricType::Monotonic),
+                MetricValue::U64(self.deduplicated),
+            ),
+        ]
+    }
+}
+
+#[derive(Default, Debug)]
+struct ThrottleCounters {
+    emitted: AtomicU64,
+    rate_limited: AtomicU64,
+    deduplicated: AtomicU64,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Verdict {
+    Emit,
+    RateLimited,
+    Duplicate,
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    rate: f64,
+    burst: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(rate: u32, burst: u32, now: Instant) -> Self {
+        let burst = burst.max(1) as f64;
+        Self {
+            rate: rate as f64,
+            burst,
+            tokens: burst,
+            last_refill: now,
+        }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.last_refill);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.rate).min(self.burst);
+        self.last_refill = now;
+    }
+
+    fn has_token(&mut self, now: Instant) -> bool {
+        self.refill(now);
+        self.tokens >= 1.0
+    }
+}
+
+#[derive(Debug)]
+struct DedupWindow {
+    window: Duration,
+    // payload -> time it was last emitted
+    seen: HashMap<Vec<u8>, Instant>,
+    // emission order, for expiring old entries
+    order: VecDeque<(Instant, Vec<u8>)>,
+}
+
+impl DedupWindow {
+    fn new(window: Duration) -> Self {
+        Self {
+            window,
+            seen: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    fn expire(&mut self, now: Instant) {
+        while let Some((when, _)) = self.order.front() {
+            if now.saturating_duration_since(*when) < self.window {
+                break;
+            }
+            let Some((when, payload)) = self.order.pop_front() else {
+                break;
+            };
+            if self.seen.get(&payload) == Some(&when) {
+                self.seen.remove(&payload);
+            }
+        }
+    }
+
+    fn is_duplicate(&mut self, payload: &[u8], now: Instant) -> bool {
+        self.expire(now);
+        self.seen.contains_key(payload)
+    }
+
+    fn record(&mut self, payload: &[u8], now: Instant) {
+        self.seen.insert(payload.to_vec(), now);
+        self.order.push_back((now, payload.to_vec()));
+    }
+
+    fn forget(&mut self, payload: &[u8], when: Instant) {
+        // the stale entry in `order` gets skipped by `expire`
+        if self.seen.get(payload) == Some(&when) {
+            self.seen.remove(payload);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct ThrottleState {
+    bucket: Option<TokenBucket>,
+    dedup: Option<DedupWindow>,
+}
+
+impl ThrottleState {
+    fn check(&mut self, payload: &[u8], now: Instant) -> Verdict {
+        // check for duplicates first, so that they don't use up the rate limit
+        if let Some(dedup) = &mut self.dedup {
+            if dedup.is_duplicate(payload, now) {
+                return Verdict::Duplicate;
+            }
+        }
+
+        if let Some(bucket) = &mut self.bucket {
+            if !bucket.has_token(now) {
+                return Verdict::RateLimited;
+            }
+            bucket.tokens -= 1.0;
+        }
+
+        if let Some(dedup) = &mut self.dedup {
+            dedup.record(payload, now);
+        }
+
+        Verdict::Emit
+    }
+
+    /// Undo a successful `check` if the event could not be emitted after all
+    fn rollback(&mut self, payload: &[u8], when: Instant) {
+        if let Some(bucket) = &mut self.bucket {
+            bucket.tokens = (bucket.tokens + 1.0).min(bucket.burst);
+        }
+
+        if let Some(dedup) = &mut self.dedup {
+            dedup.forget(payload, when);
+        }
+    }
+}
+
+/// # An [`AsyncHandler`] that limits how many events get emitted
+///
+/// Plugins enriching events from an external source can easily produce far more async
+/// events than the main event loop is willing to process (e.g. when the source sends
+/// the same update over and over). This type wraps an [`AsyncHandler`] and silently
+/// suppresses events that would exceed the configured limits:
+///
+/// - with [`ThrottledHandler::with_rate_limit`], events are emitted at an average rate
+///   of at most `rate` per second, allowing bursts of up to `burst` events
+///   (a token bucket)
+/// - with [`ThrottledHandler::with_dedup`], an event with the same name and payload
+///   as one emitted less than `window` ago is dropped
+///
+/// The number of suppressed events is available from [`ThrottledHandler::stats`].
+///
+/// ```ignore
+/// fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+///     let handler = ThrottledHandler::new(handler)
+///         .with_rate_limit(100, 1000)
+///         .with_dedup(Duration::from_secs(10));
+///
+///     self.thread = Some(self.task.spawn(Duration::from_millis(100), move || {
+///         handler.emit(Self::async_event(c"sample_async", b"hello"))
+///     })?);
+///     Ok(())
+/// }
+/// ```
+///
+/// Without any limits configured, all events are passed to the wrapped handler.
+#[derive(Debug)]
+pub struct ThrottledHandler {
+    handler: AsyncHandler,
+    state: Mutex<ThrottleState>,
+    counters: ThrottleCounters,
+}
+
+impl ThrottledHandler {
+    /// Wrap an [`AsyncHandler`], with no limits configured yet
+    pub fn new(handler: AsyncHandler) -> Self {
+        Self {
+            handler,
+            state: Mutex::new(ThrottleState::default()),
+            counters: ThrottleCounters::default(),
+        }
+    }
+
+    /// Limit the rate of emitted events
+    ///
+    /// On average, at most `rate` events per second get emitted, with bursts of up to
+    /// `burst` events (at least one) allowed after a quiet period.
+    pub fn with_rate_limit(self, rate: u32, burst: u32) -> Self {
+        let mut state = self.state.into_inner().unwrap_or_else(|e| e.into_inner());
+        state.bucket = Some(TokenBucket::new(rate, burst, Instant::now()));
+        Self {
+            state: Mutex::new(state),
+            ..self
+        }
+    }
+
+    /// Drop events identical to ones emitted recently
+    ///
+    /// An event is considered a duplicate if an event with the same name and payload
+    /// was emitted less than `window` ago. Duplicates do not count towards the rate limit.
+    pub fn with_dedup(self, window: Duration) -> Self {
+        let mut state = self.state.into_inner().unwrap_or_else(|e| e.into_inner());
+        state.dedup = Some(DedupWindow::new(window));
+        Self {
+            state: Mutex::new(state),
+            ..self
+        }
+    }
+
+    /// Get the wrapped handler, e.g. to emit an event bypassing the limits
+    pub fn handler(&self) -> &AsyncHandler {
+        &self.handler
+    }
+
+    /// # Emit an event, unless it exceeds the configured limits
+    ///
+    /// Suppressed events are not an error, they only get counted in [`ThrottledHandler::stats`].
+    ///
+    /// This method returns an error if the event cannot be serialized or the asynchronous
+    /// handler returns an error.
+    pub fn emit(&self, event: impl EventToBytes) -> Result<(), anyhow::Error> {
+        let mut buf = Vec::new();
+        event.write(&mut buf)?;
+
+        // the payload includes the event name and data, but not the metadata
+        // (like the timestamp), which is irrelevant for deduplication
+        let raw = RawEvent::from(&buf)?;
+        let now = Instant::now();
+        let verdict = {
+            let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+            state.check(raw.payload, now)
+        };
+
+        match verdict {
+            Verdict::Emit => {
+                if let Err(e) = self.handler.emit_bytes(&buf) {
+                    // a failed event neither uses a token nor suppresses its duplicates
+                    let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+                    state.rollback(raw.payload, now);
+                    return Err(e);
+                }
+                self.counters.emitted.fetch_add(1, Ordering::Relaxed);
+            }
+            Verdict::RateLimited => {
+                self.counters.rate_limited.fetch_add(1, Ordering::Relaxed);
+            }
+            Verdict::Duplicate => {
+                self.counters.deduplicated.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Get the throttling statistics
+    pub fn stats(&self) -> ThrottledHandlerStats {
+        ThrottledHandlerStats {
+            emitted: self.counters.emitted.load(Ordering::Relaxed),
+            rate_limited: self.counters.rate_limited.load(Ordering::Relaxed),
+            deduplicated: self.counters.deduplicated.load(Ordering::Relaxed),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::async_event::throttle::{DedupWindow, ThrottleState, TokenBucket, Verdict};
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_rate_limit() {
+        let start = Instant::now();
+        let mut state = ThrottleState {
+            bucket: Some(TokenBucket::new(10, 3, start)),
+            dedup: None,
+        };
+
+        let verdicts = (0..5)
+            .map(|_| state.check(b"event", start))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            verdicts,
+            [
+                Verdict::Emit,
+                Verdict::Emit,
+                Verdict::Emit,
+                Verdict::RateLimited,
+                Verdict::RateLimited,
+            ]
+        );
+
+        // 10 events per second == one event per 100ms
+        let later = start + Duration::from_millis(150);
+        assert_eq!(state.check(b"event", later), Verdict::Emit);
+        assert_eq!(state.check(b"event", later), Verdict::RateLimited);
+
+        // the bucket never holds more than `burst` tokens
+        let much_later = start + Duration::from_secs(60);
+        let emitted = (0..10)
+            .filter(|_| state.check(b"event", much_later) == Verdict::Emit)
+            .count();
+        assert_eq!(emitted, 3);
+    }
+
+    #[test]
+    fn test_dedup() {
+        let start = Instant::now();
+        let mut state = ThrottleState {
+            bucket: None,
+            dedup: Some(DedupWindow::new(Duration::from_secs(1))),
+        };
+
+        assert_eq!(state.check(b"a", start), Verdict::Emit);
+        assert_eq!(state.check(b"b", start), Verdict::Emit);
+        assert_eq!(state.check(b"a", start), Verdict::Duplicate);
+
+        let later = start + Duration::from_millis(500);
+        assert_eq!(state.check(b"a", later), Verdict::Duplicate);
+
+        // the window starts when the event was last emitted, not last seen
+        let after_window = start + Duration::from_secs(1);
+        assert_eq!(state.check(b"a", after_window), Verdict::Emit);
+        assert_eq!(state.check(b"b", after_window), Verdict::Emit);
+        assert_eq!(state.check(b"a", after_window), Verdict::Duplicate);
+
+        let dedup = state.dedup.as_ref().unwrap();
+        assert_eq!(dedup.seen.len(), 2);
+        assert_eq!(dedup.order.len(), 2);
+    }
+
+    #[test]
+    fn test_duplicates_do_not_use_tokens() {
+        let start = Instant::now();
+        let mut state = ThrottleState {
+            bucket: Some(TokenBucket::new(1, 2, start)),
+            dedup: Some(DedupWindow::new(Duration::from_secs(1))),
+        };
+
+        assert_eq!(state.check(b"a", start), Verdict::Emit);
+        assert_eq!(state.check(b"a", start), Verdict::Duplicate);
+        assert_eq!(state.check(b"a", start), Verdict::Duplicate);
+        assert_eq!(state.check(b"b", start), Verdict::Emit);
+        assert_eq!(state.check(b"c", start), Verdict::RateLimited);
+
+        // a rate limited event is not remembered as emitted
+        let later = start + Duration::from_millis(1000);
+        assert_eq!(state.check(b"c", later), Verdict::Emit);
+    }
+
+    #[test]
+    fn test_rollback() {
+        let start = Instant::now();
+        let mut state = ThrottleState {
+            bucket: Some(TokenBucket::new(1, 1, start)),
+            dedup: Some(DedupWindow::new(Duration::from_secs(1))),
+        };
+
+        assert_eq!(state.check(b"a", start), Verdict::Emit);
+        state.rollback(b"a", start);
+
+        // the failed event is neither a duplicate nor rate limited
+        assert_eq!(state.check(b"a", start), Verdict::Emit);
+        assert_eq!(state.check(b"a", start), Verdict::Duplicate);
+        assert_eq!(state.check(b"b", start), Verdict::RateLimited);
+
+        // the stale entry does not confuse the expiry
+        let after_window = start + Duration::from_secs(1);
+        assert_eq!(state.check(b"a", after_window), Verdict::Emit);
+        assert_eq!(state.dedup.as_ref().unwrap().seen.len(), 1);
+    }
+}
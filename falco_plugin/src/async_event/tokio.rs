@@ -0,0 +1,218 @@
+//! # Running asynchronous event tasks on a tokio runtime
+//!
+//! See [`AsyncTasks`] for details.
+
+use crate::async_event::AsyncHandler;
+use std::fmt::{Debug, Formatter};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+use tokio::sync::oneshot;
+use tokio::task::JoinHandle;
+
+type TaskFuture = Pin<Box<dyn Future<Output = Result<(), anyhow::Error>> + Send>>;
+type TaskFactory = Box<dyn Fn(AsyncHandler) -> TaskFuture + Send>;
+
+/// # A set of async tasks emitting asynchronous events, running on a tokio runtime
+///
+/// Implementing [`AsyncEventPlugin`](`crate::async_event::AsyncEventPlugin`) with threads
+/// and [`BackgroundTask`](`crate::async_event::BackgroundTask`) is clunky when the actual
+/// work is async (e.g. polling an HTTP API or watching a gRPC stream). Instead, register
+/// your async tasks with this type and forward the `start_async`/`stop_async` calls to it:
+///
+/// ```
+/// use std::ffi::CStr;
+/// use std::time::Duration;
+/// use falco_plugin::anyhow::Error;
+/// use falco_plugin::base::Plugin;
+/// use falco_plugin::{async_event_plugin, plugin};
+/// use falco_plugin::async_event::{AsyncEventPlugin, AsyncHandler};
+/// use falco_plugin::async_event::tokio::AsyncTasks;
+/// use falco_plugin::tables::TablesInput;
+///
+/// struct MyAsyncPlugin {
+///     tasks: AsyncTasks,
+/// }
+///
+/// impl Plugin for MyAsyncPlugin {
+///     // ...
+/// #    const NAME: &'static CStr = c"sample-plugin-rs";
+/// #    const PLUGIN_VERSION: &'static CStr = c"0.0.1";
+/// #    const DESCRIPTION: &'static CStr = c"A sample Falco plugin that does nothing";
+/// #    const CONTACT: &'static CStr = c"you@example.com";
+/// #    type ConfigType = ();
+///
+///     fn new(input: Option<&TablesInput>, config: Self::ConfigType) -> Result<Self, Error> {
+///         let mut tasks = AsyncTasks::new()?;
+///         tasks.register(|handler: AsyncHandler| async move {
+///             loop {
+///                 falco_plugin::tokio::time::sleep(Duration::from_millis(100)).await;
+///                 handler.emit(Self::async_event(c"sample_async", b"hello"))?;
+///             }
+///         });
+///
+///         Ok(MyAsyncPlugin { tasks })
+///     }
+/// }
+///
+/// impl AsyncEventPlugin for MyAsyncPlugin {
+///     const ASYNC_EVENTS: &'static [&'static str] = &["sample_async"];
+///     const EVENT_SOURCES: &'static [&'static str] = &[];
+///
+///     fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+///         self.tasks.start(handler)
+///     }
+///
+///     fn stop_async(&mut self) -> Result<(), Error> {
+///         self.tasks.stop()
+///     }
+/// }
+///
+/// plugin!(MyAsyncPlugin);
+/// async_event_plugin!(MyAsyncPlugin);
+/// ```
+///
+/// Each call to [`AsyncTasks::start`] spawns a fresh instance of every registered task,
+/// passing it a clone of the [`AsyncHandler`]. [`AsyncTasks::stop`] cancels them, dropping
+/// the futures at their current `.await` point, so make sure no important work gets lost
+/// when that happens.
+pub struct AsyncTasks {
+    runtime: Arc<Runtime>,
+    driver: Option<RuntimeDriver>,
+    factories: Vec<TaskFactory>,
+    running: Vec<JoinHandle<Result<(), anyhow::Error>>>,
+}
+
+/// A background thread running a current-thread runtime until told to stop
+struct RuntimeDriver {
+    stop: oneshot::Sender<()>,
+    thread: std::thread::JoinHandle<()>,
+}
+
+impl Debug for AsyncTasks {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AsyncTasks")
+            .field("runtime", &self.runtime)
+            .field("registered", &self.factories.len())
+            .field("running", &self.running.len())
+            .finish()
+    }
+}
+
+impl AsyncTasks {
+    /// Create an empty task set, with a new runtime running on a single background thread
+    pub fn new() -> std::io::Result<Self> {
+        let runtime = Arc::new(
+            tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()?,
+        );
+
+        let (stop, stopped) = oneshot::channel();
+        let thread = std::thread::Builder::new()
+            .name(String::from("async-tasks"))
+            .spawn({
+                let runtime = Arc::clone(&runtime);
+                move || {
+                    let _ = runtime.block_on(stopped);
+                }
+            })?;
+
+        Ok(Self {
+            runtime,
+            driver: Some(RuntimeDriver { stop, thread }),
+            factories: Vec::new(),
+            running: Vec::new(),
+        })
+    }
+
+    /// Create an empty task set, using a runtime you built yourself
+    ///
+    /// The runtime must be able to run the tasks on its own, i.e. it must be
+    /// a multi-threaded runtime (which needs the `rt-multi-thread` feature of tokio).
+    pub fn with_runtime(runtime: Runtime) -> Self {
+        Self {
+            runtime: Arc::new(runtime),
+            driver: None,
+            factories: Vec::new(),
+            running: Vec::new(),
+        }
+    }
+
+    /// Get the runtime running the tasks
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Register a task
+    ///
+    /// `task` is called on each [`AsyncTasks::start`] to create the future to run. If the future
+    /// completes with an error, the error is returned from the next [`AsyncTasks::stop`] call.
+    pub fn register<F, Fut>(&mut self, task: F)
+    where
+        F: Fn(AsyncHandler) -> Fut + Send + 'static,
+        Fut: Future<Output = Result<(), anyhow::Error>> + Send + 'static,
+    {
+        self.factories
+            .push(Box::new(move |handler| Box::pin(task(handler))));
+    }
+
+    /// Start all registered tasks
+    ///
+    /// Call this from [`AsyncEventPlugin::start_async`](`crate::async_event::AsyncEventPlugin::start_async`).
+    /// Tasks still running from a previous call are stopped first.
+    pub fn start(&mut self, handler: AsyncHandler) -> Result<(), anyhow::Error> {
+        self.stop()?;
+
+        for factory in &self.factories {
+            let task = factory(handler.clone());
+            self.running.push(self.runtime.spawn(task));
+        }
+
+        Ok(())
+    }
+
+    /// Stop all running tasks and wait for them to finish
+    ///
+    /// Call this from [`AsyncEventPlugin::stop_async`](`crate::async_event::AsyncEventPlugin::stop_async`).
+    /// Returns the first error returned by any of the tasks. If a task panicked, the panic
+    /// is propagated to the caller.
+    pub fn stop(&mut self) -> Result<(), anyhow::Error> {
+        for task in &self.running {
+            task.abort();
+        }
+
+        let mut first_error = None;
+        let running = std::mem::take(&mut self.running);
+        for task in running {
+            let res = self.runtime.block_on(task);
+            match res {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    first_error.get_or_insert(e);
+                }
+                Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+                Err(_) => {} // cancelled
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for AsyncTasks {
+    fn drop(&mut self) {
+        for task in &self.running {
+            task.abort();
+        }
+
+        if let Some(driver) = self.driver.take() {
+            let _ = driver.stop.send(());
+            let _ = driver.thread.join();
+        }
+    }
+}
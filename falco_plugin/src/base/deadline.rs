@@ -0,0 +1,227 @@
+//! # Deadlines for plugin callbacks
+//!
+//! Callbacks running on the Falco event loop ([`next_batch`](`crate::source::SourcePluginInstance::next_batch`),
+//! [`extract_fields`](`crate::extract::ExtractPlugin::EXTRACT_FIELDS`) and
+//! [`parse_event`](`crate::parse::ParsePlugin::parse_event`)) block all event processing while
+//! they run, so a misbehaving dependency (e.g. a slow network service) can stall the whole capture.
+//!
+//! Each of these capabilities has an opt-in deadline constant:
+//! - [`SourcePlugin::NEXT_BATCH_DEADLINE`](`crate::source::SourcePlugin::NEXT_BATCH_DEADLINE`)
+//! - [`ExtractPlugin::EXTRACT_DEADLINE`](`crate::extract::ExtractPlugin::EXTRACT_DEADLINE`)
+//! - [`ParsePlugin::PARSE_DEADLINE`](`crate::parse::ParsePlugin::PARSE_DEADLINE`)
+//!
+//! The SDK cannot interrupt a running callback, but every overrun is logged and counted
+//! in a monotonic `deadline_overruns.<callback>` metric (e.g. `deadline_overruns.next_batch`),
+//! added to the ones returned from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`).
+//! The metric for a callback is reported once the callback has been called.
+//!
+//! What happens to the result of an overrunning callback depends on the [`DeadlinePolicy`]
+//! set next to the deadline (e.g. [`SourcePlugin::NEXT_BATCH_DEADLINE_POLICY`](`crate::source::SourcePlugin::NEXT_BATCH_DEADLINE_POLICY`)).
+//! By default, the result is used as usual. With [`DeadlinePolicy::Fail`], the callback fails
+//! with an error describing the overrun:
+//! - `next_batch` returns `SS_PLUGIN_TIMEOUT`, so the framework retries later (the events
+//!   already added to the batch are still delivered)
+//! - `extract_fields` and `parse_event` return `SS_PLUGIN_FAILURE`
+//!
+//! To actually stay within the deadline, long-running callbacks can check [`time_remaining`]
+//! or [`deadline_exceeded`] and bail out early.
+
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use crate::FailureReason;
+use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::ffi::CString;
+use std::time::{Duration, Instant};
+
+thread_local! {
+    static CURRENT_DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// # Get the time left until the deadline of the current callback
+///
+/// Returns `None` when called outside a callback with a deadline and [`Duration::ZERO`]
+/// once the deadline has passed.
+pub fn time_remaining() -> Option<Duration> {
+    CURRENT_DEADLINE
+        .get()
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// # Check whether the deadline of the current callback has passed
+///
+/// Always returns `false` when called outside a callback with a deadline.
+pub fn deadline_exceeded() -> bool {
+    time_remaining() == Some(Duration::ZERO)
+}
+
+/// # What to do when a callback overruns its deadline
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum DeadlinePolicy {
+    /// Log and count the overrun, but use the result of the callback as usual
+    #[default]
+    Report,
+
+    /// Log and count the overrun, and fail the callback
+    Fail,
+}
+
+/// The number of deadline overruns of every callback with a deadline
+#[derive(Debug, Default)]
+pub(crate) struct DeadlineMetrics(BTreeMap<&'static str, u64>);
+
+impl DeadlineMetrics {
+    /// Collect the metrics
+    ///
+    /// Each metric is passed to `func` along with its full name, as the names include
+    /// the callback name
+    pub(crate) fn collect(&self, mut func: impl FnMut(CString, Metric)) {
+        for (callback, count) in &self.0 {
+            // the name is ASCII only and has no NUL bytes
+            let name = CString::new(format!("deadline_overruns.{callback}")).unwrap();
+            let metric = Metric::new(
+                MetricLabel::new(c"deadline_overruns", MetricType::Monotonic),
+                MetricValue::U64(*count),
+            );
+            func(name, metric)
+        }
+    }
+}
+
+struct RunningWatchdog {
+    callback: &'static str,
+    limit: Duration,
+    policy: DeadlinePolicy,
+    start: Instant,
+    prev: Option<Instant>,
+}
+
+/// Tracks the execution time of a callback against its deadline, if any
+#[must_use]
+pub(crate) struct Watchdog(Option<RunningWatchdog>);
+
+impl Watchdog {
+    /// Start tracking `callback`, which should complete within `limit`
+    ///
+    /// Without a limit, this does not even look at the clock.
+    pub(crate) fn start(
+        callback: &'static str,
+        limit: Option<Duration>,
+        policy: DeadlinePolicy,
+    ) -> Self {
+        Self(limit.map(|limit| {
+            let start = Instant::now();
+            RunningWatchdog {
+                callback,
+                limit,
+                policy,
+                start,
+                prev: CURRENT_DEADLINE.replace(Some(start + limit)),
+            }
+        }))
+    }
+
+    /// Stop tracking the callback, logging and counting an overrun of its deadline
+    ///
+    /// With [`DeadlinePolicy::Fail`], an overrun is returned as an error with `reason`
+    /// attached.
+    pub(crate) fn finish(
+        self,
+        metrics: &mut DeadlineMetrics,
+        reason: FailureReason,
+    ) -> Result<(), anyhow::Error> {
+        let Some(running) = &self.0 else {
+            return Ok(());
+        };
+
+        let overruns = metrics.0.entry(running.callback).or_default();
+        let elapsed = running.start.elapsed();
+        if elapsed <= running.limit {
+            return Ok(());
+        }
+
+        let msg = format!(
+            "{} took {:?}, exceeding its deadline of {:?}",
+            running.callback, elapsed, running.limit
+        );
+        log::warn!("{msg}");
+        *overruns += 1;
+        match running.policy {
+            DeadlinePolicy::Report => Ok(()),
+            DeadlinePolicy::Fail => Err(anyhow::anyhow!(msg).context(reason)),
+        }
+    }
+}
+
+impl Drop for Watchdog {
+    fn drop(&mut self) {
+        if let Some(running) = &self.0 {
+            CURRENT_DEADLINE.set(running.prev);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_no_deadline() {
+        let mut metrics = DeadlineMetrics::default();
+        let watchdog = Watchdog::start("test", None, DeadlinePolicy::Fail);
+        assert_eq!(time_remaining(), None);
+        assert!(!deadline_exceeded());
+        assert!(watchdog
+            .finish(&mut metrics, FailureReason::Failure)
+            .is_ok());
+        assert!(metrics.0.is_empty());
+    }
+
+    #[test]
+    fn test_deadline_met() {
+        let mut metrics = DeadlineMetrics::default();
+        let watchdog = Watchdog::start("test", Some(Duration::from_secs(60)), DeadlinePolicy::Fail);
+        assert!(time_remaining().unwrap() > Duration::ZERO);
+        assert!(!deadline_exceeded());
+        assert!(watchdog
+            .finish(&mut metrics, FailureReason::Failure)
+            .is_ok());
+        assert_eq!(time_remaining(), None);
+        assert_eq!(metrics.0.get("test"), Some(&0));
+    }
+
+    #[test]
+    fn test_deadline_exceeded() {
+        let mut metrics = DeadlineMetrics::default();
+        let watchdog = Watchdog::start(
+            "test",
+            Some(Duration::from_millis(1)),
+            DeadlinePolicy::Report,
+        );
+        std::thread::sleep(Duration::from_millis(10));
+        assert!(deadline_exceeded());
+
+        assert!(watchdog
+            .finish(&mut metrics, FailureReason::Failure)
+            .is_ok());
+        assert!(!deadline_exceeded());
+        assert_eq!(metrics.0.get("test"), Some(&1));
+    }
+
+    #[test]
+    fn test_deadline_exceeded_fail() {
+        let mut metrics = DeadlineMetrics::default();
+        let watchdog =
+            Watchdog::start("test", Some(Duration::from_millis(1)), DeadlinePolicy::Fail);
+        std::thread::sleep(Duration::from_millis(10));
+
+        let err = watchdog
+            .finish(&mut metrics, FailureReason::Timeout)
+            .unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FailureReason>(),
+            Some(FailureReason::Timeout)
+        ));
+        assert!(err.root_cause().to_string().starts_with("test took "));
+        assert_eq!(metrics.0.get("test"), Some(&1));
+    }
+}
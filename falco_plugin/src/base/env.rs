@@ -0,0 +1,197 @@
+use crate::base::schema::{ConfigSchema, ConfigSchemaType, SchemaError, SchemaResult};
+
+/// A wrapper expanding environment variable references in the configuration
+///
+/// After the configuration is parsed by the inner type `C` (e.g. [`Json<T>`](`crate::base::Json`)),
+/// but before it's deserialized into your config type, every `${NAME}` in a string value
+/// is replaced with the value of the environment variable `NAME` and every `$$` with
+/// a single `$`. References can provide a default value, used when the variable is unset
+/// or empty: `${NAME:-default}`. A reference to an unset variable without a default fails
+/// the whole configuration.
+///
+/// Since the references are expanded in the parsed document, the values cannot break
+/// its syntax and need no escaping. Only string values are expanded (not keys or numbers),
+/// so a field taking its value from the environment must be a string in the document
+/// and deserialize from one.
+///
+/// The schema of the inner type is reported to Falco unchanged (including the defaults
+/// set with `#[serde(default)]`), so Falco validates the configuration *before* the variables
+/// are expanded.
+#[derive(Debug)]
+pub struct Env<C: ConfigSchema>(pub C);
+
+impl<C: ConfigSchema> ConfigSchema for Env<C> {
+    fn get_schema() -> ConfigSchemaType {
+        C::get_schema()
+    }
+
+    fn from_str(s: &str) -> SchemaResult<Self> {
+        let expand = |s: &str| expand_env(s, |name| std::env::var(name).ok());
+        Ok(Env(C::from_str_with_env(s, &expand)?))
+    }
+}
+
+/// Expand environment variable references in all the strings in a JSON document
+pub(crate) fn expand_json(
+    value: &mut serde_json::Value,
+    expand: &dyn Fn(&str) -> SchemaResult<String>,
+) -> SchemaResult<()> {
+    match value {
+        serde_json::Value::String(s) => *s = expand(s)?,
+        serde_json::Value::Array(values) => {
+            for value in values {
+                expand_json(value, expand)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                expand_json(value, expand)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand environment variable references in all the strings in a YAML document
+#[cfg(feature = "yaml")]
+pub(crate) fn expand_yaml(
+    value: &mut serde_yaml_ng::Value,
+    expand: &dyn Fn(&str) -> SchemaResult<String>,
+) -> SchemaResult<()> {
+    match value {
+        serde_yaml_ng::Value::String(s) => *s = expand(s)?,
+        serde_yaml_ng::Value::Sequence(values) => {
+            for value in values {
+                expand_yaml(value, expand)?;
+            }
+        }
+        serde_yaml_ng::Value::Mapping(map) => {
+            for value in map.values_mut() {
+                expand_yaml(value, expand)?;
+            }
+        }
+        serde_yaml_ng::Value::Tagged(tagged) => expand_yaml(&mut tagged.value, expand)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Expand environment variable references in all the strings in a TOML document
+#[cfg(feature = "toml")]
+pub(crate) fn expand_toml(
+    value: &mut toml::Value,
+    expand: &dyn Fn(&str) -> SchemaResult<String>,
+) -> SchemaResult<()> {
+    match value {
+        toml::Value::String(s) => *s = expand(s)?,
+        toml::Value::Array(values) => {
+            for value in values {
+                expand_toml(value, expand)?;
+            }
+        }
+        toml::Value::Table(table) => {
+            for (_, value) in table.iter_mut() {
+                expand_toml(value, expand)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn expand_env(s: &str, lookup: impl Fn(&str) -> Option<String>) -> SchemaResult<String> {
+    let mut out = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 1..];
+
+        if let Some(after) = after.strip_prefix('$') {
+            out.push('$');
+            rest = after;
+        } else if let Some(reference) = after.strip_prefix('{') {
+            let Some(end) = reference.find('}') else {
+                return Err(SchemaError::UnterminatedEnvVar(s.len() - rest.len() + pos));
+            };
+
+            let (name, default) = match reference[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&reference[..end], None),
+            };
+            match (lookup(name).filter(|value| !value.is_empty()), default) {
+                (Some(value), _) => out.push_str(&value),
+                (None, Some(default)) => out.push_str(default),
+                (None, None) => return Err(SchemaError::EnvVarNotSet(name.to_string())),
+            }
+            rest = &reference[end + 1..];
+        } else {
+            out.push('$');
+            rest = after;
+        }
+    }
+
+    out.push_str(rest);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOST" => Some(String::from("example.com")),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_expand_env() {
+        let expand = |s| expand_env(s, lookup).unwrap();
+
+        assert_eq!(expand("no references"), "no references");
+        assert_eq!(expand("host=${HOST}"), "host=example.com");
+        assert_eq!(expand("${HOST}:${PORT:-8080}"), "example.com:8080");
+        assert_eq!(expand("${EMPTY:-default}"), "default");
+        assert_eq!(expand("${HOST:-unused}"), "example.com");
+        assert_eq!(expand("$$HOST costs $5 ${EMPTY:-}"), "$HOST costs $5 ");
+    }
+
+    #[test]
+    fn test_expand_env_errors() {
+        assert!(matches!(
+            expand_env("${PORT}", lookup),
+            Err(SchemaError::EnvVarNotSet(name)) if name == "PORT"
+        ));
+        assert!(matches!(
+            expand_env("${EMPTY}", lookup),
+            Err(SchemaError::EnvVarNotSet(_))
+        ));
+        assert!(matches!(
+            expand_env("ok ${HOST} ${HOST", lookup),
+            Err(SchemaError::UnterminatedEnvVar(11))
+        ));
+    }
+
+    #[test]
+    fn test_expand_json() {
+        let expand = |s: &str| {
+            expand_env(s, |name| match name {
+                "QUOTED" => Some(String::from(r#"say "hi""#)),
+                _ => None,
+            })
+        };
+
+        let mut value: serde_json::Value =
+            serde_json::from_str(r#"{"${QUOTED}": ["${QUOTED}", 5], "nested": {"s": "${X:-x}"}}"#)
+                .unwrap();
+        expand_json(&mut value, &expand).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"${QUOTED}": [r#"say "hi""#, 5], "nested": {"s": "x"}})
+        );
+    }
+}
@@ -0,0 +1,149 @@
+use std::fmt::{Debug, Formatter};
+
+/// # A plugin lifecycle event
+///
+/// See [`PluginHooks`] for details.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LifecycleEvent {
+    /// A capture has been started
+    ///
+    /// Only delivered to plugins implementing [`CaptureListenPlugin`](`crate::listen::CaptureListenPlugin`),
+    /// right after [`CaptureListenPlugin::capture_open`](`crate::listen::CaptureListenPlugin::capture_open`) succeeds.
+    CaptureOpen,
+
+    /// A capture is being stopped
+    ///
+    /// Only delivered to plugins implementing [`CaptureListenPlugin`](`crate::listen::CaptureListenPlugin`),
+    /// right before [`CaptureListenPlugin::capture_close`](`crate::listen::CaptureListenPlugin::capture_close`)
+    /// gets called.
+    CaptureClose,
+
+    /// The plugin configuration has been updated
+    ///
+    /// Delivered right after [`Plugin::set_config`](`crate::base::Plugin::set_config`) succeeds.
+    ConfigChange,
+
+    /// The plugin is about to be destroyed
+    ///
+    /// Errors returned by hooks for this event are logged, but otherwise ignored.
+    Shutdown,
+}
+
+type Hook<P> = Box<dyn FnMut(&mut P) -> Result<(), anyhow::Error>>;
+
+/// # Hooks called on plugin lifecycle events
+///
+/// A plugin implementing several capabilities (e.g. source, parsing, extraction and capture
+/// listening) often needs to react to an event seen by one capability in the code of another one,
+/// e.g. to reset an extraction cache when the capture is restarted. Instead of wiring that up
+/// manually in every capability method, register hooks in [`Plugin::register_hooks`](`crate::base::Plugin::register_hooks`)
+/// and the SDK will call them when the corresponding [`LifecycleEvent`] happens:
+///
+/// ```ignore
+/// fn register_hooks(hooks: &mut PluginHooks<Self>) {
+///     hooks
+///         .on_capture_open(|plugin| {
+///             plugin.cache.clear();
+///             Ok(())
+///         })
+///         .on_config_change(|plugin| plugin.reconnect());
+/// }
+/// ```
+///
+/// Hooks for the same event are called in the order they were registered. If a hook fails,
+/// the remaining ones are still called and the first error is reported to the framework
+/// as the error of the method that triggered the event.
+///
+/// **Note**: the framework only notifies plugins about captures starting and stopping through
+/// the capture listening capability, so [`LifecycleEvent::CaptureOpen`] and
+/// [`LifecycleEvent::CaptureClose`] hooks are only called if the plugin implements
+/// [`CaptureListenPlugin`](`crate::listen::CaptureListenPlugin`). In particular, opening
+/// and closing a source plugin instance does not fire them.
+pub struct PluginHooks<P> {
+    hooks: Vec<(LifecycleEvent, Hook<P>)>,
+}
+
+impl<P> Default for PluginHooks<P> {
+    fn default() -> Self {
+        Self { hooks: Vec::new() }
+    }
+}
+
+impl<P> Debug for PluginHooks<P> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_list()
+            .entries(self.hooks.iter().map(|(event, _)| event))
+            .finish()
+    }
+}
+
+impl<P> PluginHooks<P> {
+    /// Register a hook for an arbitrary event
+    pub fn on<F>(&mut self, event: LifecycleEvent, hook: F) -> &mut Self
+    where
+        F: FnMut(&mut P) -> Result<(), anyhow::Error> + 'static,
+    {
+        self.hooks.push((event, Box::new(hook)));
+        self
+    }
+
+    /// Register a hook for [`LifecycleEvent::CaptureOpen`]
+    ///
+    /// The hook is only called if the plugin has the capture listening capability.
+    pub fn on_capture_open<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnMut(&mut P) -> Result<(), anyhow::Error> + 'static,
+    {
+        self.on(LifecycleEvent::CaptureOpen, hook)
+    }
+
+    /// Register a hook for [`LifecycleEvent::CaptureClose`]
+    ///
+    /// The hook is only called if the plugin has the capture listening capability.
+    pub fn on_capture_close<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnMut(&mut P) -> Result<(), anyhow::Error> + 'static,
+    {
+        self.on(LifecycleEvent::CaptureClose, hook)
+    }
+
+    /// Register a hook for [`LifecycleEvent::ConfigChange`]
+    pub fn on_config_change<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnMut(&mut P) -> Result<(), anyhow::Error> + 'static,
+    {
+        self.on(LifecycleEvent::ConfigChange, hook)
+    }
+
+    /// Register a hook for [`LifecycleEvent::Shutdown`]
+    pub fn on_shutdown<F>(&mut self, hook: F) -> &mut Self
+    where
+        F: FnMut(&mut P) -> Result<(), anyhow::Error> + 'static,
+    {
+        self.on(LifecycleEvent::Shutdown, hook)
+    }
+
+    /// Call all hooks registered for `event`, returning the first error
+    pub(crate) fn fire(
+        &mut self,
+        event: LifecycleEvent,
+        plugin: &mut P,
+    ) -> Result<(), anyhow::Error> {
+        let mut first_error = None;
+        for (hook_event, hook) in &mut self.hooks {
+            if *hook_event != event {
+                continue;
+            }
+
+            if let Err(e) = hook(plugin) {
+                first_error.get_or_insert(e);
+            }
+        }
+
+        match first_error {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
@@ -9,7 +9,9 @@ use falco_plugin_api::{
     ss_plugin_metric_value_type_SS_PLUGIN_METRIC_VALUE_TYPE_U32,
     ss_plugin_metric_value_type_SS_PLUGIN_METRIC_VALUE_TYPE_U64,
 };
-use std::ffi::CStr;
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::ffi::{c_void, CStr, CString};
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[allow(missing_docs)]
@@ -78,16 +80,22 @@ impl MetricValue {
 ///
 /// It contains the metric name and the type (monotonic/non-monotonic) but does not
 /// contain a specific value
+///
+/// The name is usually a `&'static CStr`, but it can also be a [`CString`] built at runtime
+/// (e.g. to include the name of an object the metric describes).
 #[derive(Debug, Clone)]
 pub struct MetricLabel {
-    name: &'static CStr,
+    name: Cow<'static, CStr>,
     metric_type: MetricType,
 }
 
 impl MetricLabel {
     /// Create a new metric label
-    pub fn new(name: &'static CStr, metric_type: MetricType) -> Self {
-        Self { name, metric_type }
+    pub fn new(name: impl Into<Cow<'static, CStr>>, metric_type: MetricType) -> Self {
+        Self {
+            name: name.into(),
+            metric_type,
+        }
     }
 
     /// Create a [`Metric`], assigning a specific value to a label
@@ -114,15 +122,88 @@ impl Metric {
         Self { label, value }
     }
 
+    /// Build the raw metric
+    ///
+    /// The returned object borrows the metric name, so if the name is owned (see
+    /// [`Metric::into_owned_name`]), it must outlive the raw metric.
     pub(crate) fn as_raw(&self) -> ss_plugin_metric {
+        self.as_raw_with_name(&self.label.name)
+    }
+
+    /// Take the name out of the metric, if it's owned
+    pub(crate) fn into_owned_name(self) -> Option<CString> {
+        match self.label.name {
+            Cow::Borrowed(_) => None,
+            Cow::Owned(name) => Some(name),
+        }
+    }
+
+    /// Build the raw metric, overriding its name
+    ///
+    /// The returned object borrows `name`, so it must outlive the raw metric.
+    pub(crate) fn as_raw_with_name(&self, name: &CStr) -> ss_plugin_metric {
         let (value_type, value) = self.value.as_raw();
         let metric_type = self.label.metric_type.as_raw();
 
         ss_plugin_metric {
-            name: self.label.name.as_ptr(),
+            name: name.as_ptr(),
             type_: metric_type,
             value_type,
             value,
         }
     }
 }
+
+/// A type-erased function collecting the metrics of an instance
+pub(crate) type InstanceMetricsFn = unsafe fn(*mut c_void, &mut dyn FnMut(Metric));
+
+/// The open instances of a plugin, reporting their own metrics
+///
+/// Each instance gets a unique index when registered, which is appended to the names
+/// of its metrics, so that metrics from different instances can be told apart.
+#[derive(Debug, Default)]
+pub(crate) struct InstanceMetrics {
+    next_index: usize,
+    instances: BTreeMap<usize, (*mut c_void, InstanceMetricsFn)>,
+}
+
+impl InstanceMetrics {
+    /// Register an instance, returning its index
+    ///
+    /// # Safety
+    ///
+    /// `instance` must remain valid (and must be accepted by `get_metrics`) until
+    /// the instance is unregistered
+    pub(crate) unsafe fn register(
+        &mut self,
+        instance: *mut c_void,
+        get_metrics: InstanceMetricsFn,
+    ) -> usize {
+        let index = self.next_index;
+        self.next_index += 1;
+        self.instances.insert(index, (instance, get_metrics));
+        index
+    }
+
+    /// Unregister an instance
+    pub(crate) fn unregister(&mut self, index: usize) {
+        self.instances.remove(&index);
+    }
+
+    /// Collect the metrics of all open instances
+    ///
+    /// Each metric is passed to `func` along with its full name, including the instance label
+    pub(crate) fn collect(&self, mut func: impl FnMut(CString, Metric)) {
+        for (index, (instance, get_metrics)) in &self.instances {
+            let mut emit = |metric: Metric| {
+                let mut name = metric.label.name.to_bytes().to_vec();
+                name.extend_from_slice(format!(".instance_{index}").as_bytes());
+                // the original name is a valid C string and the suffix has no NUL bytes
+                let name = CString::new(name).unwrap();
+                func(name, metric)
+            };
+            // SAFETY: guaranteed by the contract of `register`
+            unsafe { get_metrics(*instance, &mut emit) }
+        }
+    }
+}
@@ -10,14 +10,25 @@ use crate::tables::TablesInput;
 use schema::ConfigSchema;
 use std::ffi::CStr;
 
+pub mod deadline;
+mod env;
+mod hooks;
 mod logger;
 mod metrics;
-mod schema;
+mod requirements;
+pub(crate) mod schema;
 #[doc(hidden)]
 pub mod wrappers;
 
+pub use env::Env;
+pub use hooks::{LifecycleEvent, PluginHooks};
 pub use metrics::{Metric, MetricLabel, MetricType, MetricValue};
-pub use schema::Json;
+pub use requirements::ApiFeature;
+#[cfg(feature = "toml")]
+pub use schema::Toml;
+#[cfg(feature = "yaml")]
+pub use schema::Yaml;
+pub use schema::{AnyConfig, Json};
 
 /// The latest schema supported by the current SDK version
 pub use falco_plugin_api::SCHEMA_VERSION as CURRENT_SCHEMA_VERSION;
@@ -74,6 +85,25 @@ pub trait Plugin: BasePluginExported + Sized {
     /// Usually should just be left as the default, but you may need to override it
     /// if you're using a different version of the falco_event_schema crate.
     const SCHEMA_VERSION: &'static CStr = CURRENT_SCHEMA_VERSION;
+    /// the plugin API features required by this plugin
+    ///
+    /// By default, the plugin advertises the plugin API version supported by this crate,
+    /// so it won't load into older Falco versions at all. If you advertise an older version
+    /// for increased compatibility (using the `unsafe { ... }` form of [`plugin!`](`crate::plugin`)
+    /// or [`static_plugin!`](`crate::static_plugin`)), list the features your plugin cannot
+    /// work without here:
+    ///
+    /// - the build fails if the advertised version is older than
+    ///   [`ApiFeature::min_api_version`] of any listed feature
+    /// - for features that can be detected when the plugin is loaded (currently only
+    ///   [`ApiFeature::Tables`]), `init` fails with an error naming the missing feature
+    ///   if the plugin framework does not provide it, instead of the plugin failing
+    ///   (or crashing) later. The other features are only checked at compile time
+    ///
+    /// ```ignore
+    /// const REQUIRED_API_FEATURES: &'static [ApiFeature] = &[ApiFeature::Tables];
+    /// ```
+    const REQUIRED_API_FEATURES: &'static [ApiFeature] = &[];
 
     /// The plugin can be configured in three different ways. In all cases, an instance of the type
     /// you specify will be passed to the [`Plugin::new`] method.
@@ -172,6 +202,25 @@ pub trait Plugin: BasePluginExported + Sized {
     /// }
     ///# plugin!(#[no_capabilities] MyPlugin);
     /// ```
+    ///
+    /// ### Configuration as YAML or TOML
+    ///
+    /// Falco configs are commonly written in YAML, so plugins may receive their configuration
+    /// as a YAML (or TOML) document in a string. With the `yaml` (or `toml`) feature enabled,
+    /// set the `ConfigType` to `Yaml<T>` (or `Toml<T>`) to have the SDK parse it, just like
+    /// with `Json<T>` (`T` only needs to implement [`serde::de::DeserializeOwned`]).
+    /// If you want to accept any of the formats, use [`AnyConfig<T>`](`crate::base::AnyConfig`),
+    /// which detects the format from the config string.
+    ///
+    /// Parse errors include the line and column of the problem. The plugin API only supports
+    /// JSON schemas, so these wrappers do not report a schema to Falco.
+    ///
+    /// ### Environment variables
+    ///
+    /// To keep e.g. secrets out of the Falco config file, wrap any of the above in
+    /// [`Env`](`crate::base::Env`), like `Env<Json<MyConfig>>`. This expands `${NAME}`
+    /// (and `${NAME:-default}`) references to environment variables in the string values
+    /// of the configuration.
     type ConfigType: ConfigSchema;
 
     /// This method takes a [`TablesInput`](`crate::tables::TablesInput`) instance, which lets you
@@ -187,6 +236,18 @@ pub trait Plugin: BasePluginExported + Sized {
         Ok(())
     }
 
+    /// Register hooks for plugin lifecycle events
+    ///
+    /// This method gets called once, right after [`Plugin::new`]. See [`PluginHooks`]
+    /// for details.
+    ///
+    /// The default implementation does not register any hooks
+    fn register_hooks(_hooks: &mut PluginHooks<Self>)
+    where
+        Self: Sized,
+    {
+    }
+
     /// Return the plugin metrics
     ///
     /// Metrics are described by:
@@ -0,0 +1,102 @@
+use crate::base::Plugin;
+use falco_plugin_api::ss_plugin_init_input;
+use std::fmt::{Display, Formatter};
+
+/// # A plugin API feature required by the plugin
+///
+/// Features are listed in [`Plugin::REQUIRED_API_FEATURES`]. Each feature is only available
+/// since a particular version of the plugin API (see [`ApiFeature::min_api_version`]), so
+/// the plugin must not advertise an older API version (using the `unsafe { ... }` form
+/// of [`plugin!`](`crate::plugin`) or [`static_plugin!`](`crate::static_plugin`)),
+/// which is checked at compile time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ApiFeature {
+    /// Access to the table API (the [`TablesInput`](`crate::tables::TablesInput`)
+    /// passed to [`Plugin::new`])
+    ///
+    /// With this feature, the plugin fails to initialize if the tables input is missing
+    /// or incomplete, instead of getting `None` in [`Plugin::new`].
+    Tables,
+    /// The capture listening capability (`capture_open`/`capture_close` and the thread pool)
+    ///
+    /// This feature is only checked at compile time (against the advertised API version),
+    /// as the plugin framework does not expose it at init time.
+    CaptureListen,
+    /// Value offsets in field extraction requests
+    /// (see [`ExtractRequest::offset`](`crate::extract::ExtractRequest::offset`))
+    ///
+    /// This feature is only checked at compile time (against the advertised API version),
+    /// as the plugin framework does not expose it at init time.
+    ValueOffsets,
+}
+
+impl ApiFeature {
+    /// The oldest plugin API version (major, minor) providing this feature
+    pub const fn min_api_version(self) -> (usize, usize) {
+        match self {
+            ApiFeature::Tables => (3, 0),
+            ApiFeature::CaptureListen => (3, 9),
+            ApiFeature::ValueOffsets => (3, 10),
+        }
+    }
+
+    fn check(self, init_input: &ss_plugin_init_input) -> bool {
+        match self {
+            ApiFeature::Tables => {
+                let Some(tables) = (unsafe { init_input.tables.as_ref() }) else {
+                    return false;
+                };
+                !tables.reader_ext.is_null()
+                    && !tables.writer_ext.is_null()
+                    && !tables.fields_ext.is_null()
+            }
+            // compile-time-only: nothing in the init input tells us whether the framework
+            // supports these, so we rely on the advertised API version
+            ApiFeature::CaptureListen | ApiFeature::ValueOffsets => true,
+        }
+    }
+}
+
+impl Display for ApiFeature {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (major, minor) = self.min_api_version();
+        let name = match self {
+            ApiFeature::Tables => "the table API",
+            ApiFeature::CaptureListen => "the capture listening capability",
+            ApiFeature::ValueOffsets => "value offsets in field extraction",
+        };
+        write!(f, "{name} (plugin API {major}.{minor}.0)")
+    }
+}
+
+/// Fail the build if the advertised API version is older than required by the plugin features
+#[doc(hidden)]
+pub const fn check_required_api_version<P: Plugin>(major: usize, minor: usize) {
+    let features = P::REQUIRED_API_FEATURES;
+    let mut i = 0;
+    while i < features.len() {
+        let (req_major, req_minor) = features[i].min_api_version();
+        if major < req_major || (major == req_major && minor < req_minor) {
+            panic!(
+                "The advertised plugin API version is older than required by REQUIRED_API_FEATURES"
+            );
+        }
+        i += 1;
+    }
+}
+
+/// Make sure the plugin framework provides all the features required by the plugin
+pub(crate) fn check_required_features<P: Plugin>(
+    init_input: &ss_plugin_init_input,
+) -> Result<(), anyhow::Error> {
+    for feature in P::REQUIRED_API_FEATURES {
+        if !feature.check(init_input) {
+            anyhow::bail!(
+                "Plugin requires {feature}, which is not provided by the plugin framework"
+            );
+        }
+    }
+
+    Ok(())
+}
@@ -1,3 +1,8 @@
+use crate::base::env::expand_json;
+#[cfg(feature = "toml")]
+use crate::base::env::expand_toml;
+#[cfg(feature = "yaml")]
+use crate::base::env::expand_yaml;
 use schemars::{schema_for, JsonSchema};
 use serde::de::DeserializeOwned;
 use std::any::TypeId;
@@ -9,11 +14,26 @@ use thiserror::Error;
 #[derive(Error, Debug)]
 pub enum SchemaError {
     #[error("JSON deserialization error: {0}")]
-    JsonError(#[from] serde_json::Error),
+    Json(#[from] serde_json::Error),
+
+    #[error("environment variable {0} is not set")]
+    EnvVarNotSet(String),
+
+    #[error("unterminated environment variable reference at offset {0}")]
+    UnterminatedEnvVar(usize),
+
+    #[cfg(feature = "yaml")]
+    #[error("YAML deserialization error: {0}")]
+    Yaml(#[from] serde_yaml_ng::Error),
+
+    #[cfg(feature = "toml")]
+    #[error("TOML deserialization error: {0}")]
+    Toml(#[from] toml::de::Error),
 }
 
 pub type SchemaResult<T> = Result<T, SchemaError>;
 
+#[derive(Debug)]
 pub enum ConfigSchemaType {
     None,
     Json(&'static CStr),
@@ -26,10 +46,49 @@ pub enum ConfigSchemaType {
 #[derive(Debug)]
 pub struct Json<T: JsonSchema + DeserializeOwned>(pub T);
 
+/// A wrapper to mark a configuration as YAML-encoded
+///
+/// The plugin API only supports JSON schemas, so no schema is reported to Falco
+/// and the configuration is only validated when deserializing it.
+#[cfg(feature = "yaml")]
+#[derive(Debug)]
+pub struct Yaml<T: DeserializeOwned>(pub T);
+
+/// A wrapper to mark a configuration as TOML-encoded
+///
+/// The plugin API only supports JSON schemas, so no schema is reported to Falco
+/// and the configuration is only validated when deserializing it.
+#[cfg(feature = "toml")]
+#[derive(Debug)]
+pub struct Toml<T: DeserializeOwned>(pub T);
+
+/// A wrapper for configuration in any supported format
+///
+/// The format is detected from the configuration string:
+/// - JSON, if it starts with `{`
+/// - TOML, if it's syntactically valid TOML (needs the `toml` feature)
+/// - YAML otherwise (needs the `yaml` feature)
+///
+/// As with `Yaml<T>` and `Toml<T>`, no schema is reported to Falco.
+#[derive(Debug)]
+pub struct AnyConfig<T: DeserializeOwned>(pub T);
+
 pub trait ConfigSchema: Sized {
     fn get_schema() -> ConfigSchemaType;
 
     fn from_str(s: &str) -> SchemaResult<Self>;
+
+    /// Parse the configuration, passing all its string values through `expand`
+    ///
+    /// This is used by [`Env`](`crate::base::Env`) to expand environment variable references.
+    /// The default implementation passes the whole configuration string through `expand`
+    /// before parsing it, which is only correct for formats without any quoting or escaping.
+    fn from_str_with_env(
+        s: &str,
+        expand: &dyn Fn(&str) -> SchemaResult<String>,
+    ) -> SchemaResult<Self> {
+        Self::from_str(&expand(s)?)
+    }
 }
 
 impl<T: JsonSchema + DeserializeOwned + 'static> ConfigSchema for Json<T> {
@@ -66,6 +125,114 @@ impl<T: JsonSchema + DeserializeOwned + 'static> ConfigSchema for Json<T> {
         let target: T = serde_json::from_str(s)?;
         Ok(Json(target))
     }
+
+    fn from_str_with_env(
+        s: &str,
+        expand: &dyn Fn(&str) -> SchemaResult<String>,
+    ) -> SchemaResult<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(s)?;
+        expand_json(&mut value, expand)?;
+        Ok(Json(serde_json::from_value(value)?))
+    }
+}
+
+#[cfg(feature = "yaml")]
+impl<T: DeserializeOwned> ConfigSchema for Yaml<T> {
+    fn get_schema() -> ConfigSchemaType {
+        ConfigSchemaType::None
+    }
+
+    fn from_str(s: &str) -> SchemaResult<Self> {
+        let target: T = serde_yaml_ng::from_str(s)?;
+        Ok(Yaml(target))
+    }
+
+    fn from_str_with_env(
+        s: &str,
+        expand: &dyn Fn(&str) -> SchemaResult<String>,
+    ) -> SchemaResult<Self> {
+        let mut value: serde_yaml_ng::Value = serde_yaml_ng::from_str(s)?;
+        expand_yaml(&mut value, expand)?;
+        Ok(Yaml(serde_yaml_ng::from_value(value)?))
+    }
+}
+
+#[cfg(feature = "toml")]
+impl<T: DeserializeOwned> ConfigSchema for Toml<T> {
+    fn get_schema() -> ConfigSchemaType {
+        ConfigSchemaType::None
+    }
+
+    fn from_str(s: &str) -> SchemaResult<Self> {
+        let target: T = toml::from_str(s)?;
+        Ok(Toml(target))
+    }
+
+    fn from_str_with_env(
+        s: &str,
+        expand: &dyn Fn(&str) -> SchemaResult<String>,
+    ) -> SchemaResult<Self> {
+        let mut value = toml::Value::Table(toml::from_str(s)?);
+        expand_toml(&mut value, expand)?;
+        Ok(Toml(value.try_into()?))
+    }
+}
+
+impl<T: DeserializeOwned> ConfigSchema for AnyConfig<T> {
+    fn get_schema() -> ConfigSchemaType {
+        ConfigSchemaType::None
+    }
+
+    fn from_str(s: &str) -> SchemaResult<Self> {
+        if s.trim_start().starts_with('{') {
+            return Ok(AnyConfig(serde_json::from_str(s)?));
+        }
+
+        #[cfg(feature = "toml")]
+        if toml::from_str::<toml::Table>(s).is_ok() {
+            return Ok(AnyConfig(toml::from_str(s)?));
+        }
+
+        #[cfg(feature = "yaml")]
+        return Ok(AnyConfig(serde_yaml_ng::from_str(s)?));
+
+        // without YAML support, report the error for JSON, the only format always supported
+        #[cfg(not(feature = "yaml"))]
+        Ok(AnyConfig(serde_json::from_str(s)?))
+    }
+
+    fn from_str_with_env(
+        s: &str,
+        expand: &dyn Fn(&str) -> SchemaResult<String>,
+    ) -> SchemaResult<Self> {
+        if s.trim_start().starts_with('{') {
+            let mut value: serde_json::Value = serde_json::from_str(s)?;
+            expand_json(&mut value, expand)?;
+            return Ok(AnyConfig(serde_json::from_value(value)?));
+        }
+
+        #[cfg(feature = "toml")]
+        if let Ok(table) = toml::from_str::<toml::Table>(s) {
+            let mut value = toml::Value::Table(table);
+            expand_toml(&mut value, expand)?;
+            return Ok(AnyConfig(value.try_into()?));
+        }
+
+        #[cfg(feature = "yaml")]
+        {
+            let mut value: serde_yaml_ng::Value = serde_yaml_ng::from_str(s)?;
+            expand_yaml(&mut value, expand)?;
+            Ok(AnyConfig(serde_yaml_ng::from_value(value)?))
+        }
+
+        // without YAML support, report the error for JSON, the only format always supported
+        #[cfg(not(feature = "yaml"))]
+        {
+            let mut value: serde_json::Value = serde_json::from_str(s)?;
+            expand_json(&mut value, expand)?;
+            Ok(AnyConfig(serde_json::from_value(value)?))
+        }
+    }
 }
 
 impl ConfigSchema for String {
@@ -86,4 +253,8 @@ impl ConfigSchema for () {
     fn from_str(_: &str) -> SchemaResult<Self> {
         Ok(())
     }
+
+    fn from_str_with_env(_: &str, _: &dyn Fn(&str) -> SchemaResult<String>) -> SchemaResult<Self> {
+        Ok(())
+    }
 }
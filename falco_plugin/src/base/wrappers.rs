@@ -1,8 +1,16 @@
+use crate::base::deadline::DeadlineMetrics;
+use crate::base::hooks::{LifecycleEvent, PluginHooks};
 use crate::base::logger::{FalcoPluginLoggerImpl, FALCO_LOGGER};
+use crate::base::metrics::{InstanceMetrics, Metric};
+use crate::base::requirements::check_required_features;
 use crate::base::schema::{ConfigSchema, ConfigSchemaType};
 use crate::base::Plugin;
 use crate::error::ffi_result::FfiResult;
 use crate::error::last_error::LastError;
+use crate::extract::cache::ExtractCache;
+use crate::listen::RoutineHandle;
+use crate::parse::batch::ParseBatch;
+use crate::parse::metrics::ParseMetrics;
 use crate::strings::from_ptr::try_str_from_ptr;
 use crate::strings::WriteIntoCString;
 use crate::tables::TablesInput;
@@ -30,6 +38,8 @@ use std::sync::Mutex;
 )]
 pub unsafe trait BasePluginExported {}
 
+pub use crate::base::requirements::check_required_api_version;
+
 pub extern "C-unwind" fn plugin_get_required_api_version<
     const MAJOR: usize,
     const MINOR: usize,
@@ -76,6 +86,8 @@ pub unsafe extern "C-unwind" fn plugin_init<P: Plugin>(
         let init_input = unsafe { init_input.as_ref() }
             .ok_or_else(|| anyhow::anyhow!("Got empty init_input"))?;
 
+        check_required_features::<P>(init_input)?;
+
         let init_config =
             try_str_from_ptr(&init_input.config).context("Failed to get config string")?;
 
@@ -101,8 +113,11 @@ pub unsafe extern "C-unwind" fn plugin_init<P: Plugin>(
 
         let last_error = unsafe { LastError::from(init_input)? };
 
-        P::new(tables_input.as_ref(), config)
-            .map(|plugin| Box::into_raw(Box::new(PluginWrapper::new(plugin, last_error))))
+        let plugin = P::new(tables_input.as_ref(), config)?;
+        let mut wrapper = PluginWrapper::new(plugin, last_error);
+        P::register_hooks(&mut wrapper.hooks);
+
+        Ok(Box::into_raw(Box::new(wrapper)))
     })();
 
     match res {
@@ -154,9 +169,14 @@ pub unsafe extern "C-unwind" fn plugin_get_init_schema<P: Plugin>(
 pub unsafe extern "C-unwind" fn plugin_destroy<P: Plugin>(
     plugin: *mut falco_plugin_api::ss_plugin_t,
 ) {
-    unsafe {
-        let plugin = plugin as *mut PluginWrapper<P>;
-        let _ = Box::from_raw(plugin);
+    let mut plugin = unsafe { Box::from_raw(plugin as *mut PluginWrapper<P>) };
+    if let Some(actual_plugin) = &mut plugin.plugin {
+        if let Err(e) = plugin
+            .hooks
+            .fire(LifecycleEvent::Shutdown, &mut actual_plugin.plugin)
+        {
+            log::warn!("Shutdown hook failed: {e:#}");
+        }
     }
 }
 
@@ -196,7 +216,10 @@ pub unsafe extern "C-unwind" fn plugin_set_config<P: Plugin>(
             try_str_from_ptr(&config_input.config).context("Failed to get config string")?;
         let config = P::ConfigType::from_str(updated_config).context("Failed to parse config")?;
 
-        actual_plugin.plugin.set_config(config)
+        actual_plugin.plugin.set_config(config)?;
+        plugin
+            .hooks
+            .fire(LifecycleEvent::ConfigChange, &mut actual_plugin.plugin)
     })();
 
     res.rc(&mut plugin.error_buf)
@@ -228,9 +251,25 @@ pub unsafe extern "C-unwind" fn plugin_get_metrics<P: Plugin>(
     };
 
     plugin.metric_storage.clear();
+    plugin.metric_names.clear();
     for metric in actual_plugin.plugin.get_metrics() {
         plugin.metric_storage.push(metric.as_raw());
+        // keep owned names alive; as below, moving the CString doesn't move the string data
+        if let Some(name) = metric.into_owned_name() {
+            plugin.metric_names.push(name);
+        }
+    }
+    let mut push_named = |name: CString, metric: Metric| {
+        // the raw metric points into the CString's heap buffer, which doesn't move
+        // when the CString itself is moved into `metric_names`
+        plugin.metric_storage.push(metric.as_raw_with_name(&name));
+        plugin.metric_names.push(name);
+    };
+    plugin.instance_metrics.collect(&mut push_named);
+    if let Some(parse_metrics) = &plugin.parse_metrics {
+        parse_metrics.collect(&mut push_named);
     }
+    plugin.deadline_metrics.collect(&mut push_named);
 
     *num_metrics = plugin.metric_storage.len() as u32;
     plugin.metric_storage.as_ptr().cast_mut()
@@ -339,7 +378,9 @@ macro_rules! wrap_ffi {
 ///
 /// **Note**: this does not affect the actual version supported in any way. If you use this form,
 /// it's **entirely your responsibility** to ensure the advertised version is compatible with the actual
-/// version supported by this crate.
+/// version supported by this crate. List the features your plugin relies on in
+/// [`Plugin::REQUIRED_API_FEATURES`](`crate::base::Plugin::REQUIRED_API_FEATURES`) to have them
+/// checked against the advertised version (at build time) and the plugin framework (at init time).
 #[macro_export]
 macro_rules! plugin {
     (unsafe { $maj:expr; $min:expr; $patch:expr } => #[no_capabilities] $ty:ty) => {
@@ -455,7 +496,9 @@ macro_rules! plugin {
 ///
 /// **Note**: this does not affect the actual version supported in any way. If you use this form,
 /// it's **entirely your responsibility** to ensure the advertised version is compatible with the actual
-/// version supported by this crate.
+/// version supported by this crate. List the features your plugin relies on in
+/// [`Plugin::REQUIRED_API_FEATURES`](`crate::base::Plugin::REQUIRED_API_FEATURES`) to have them
+/// checked against the advertised version (at build time) and the plugin framework (at init time).
 #[macro_export]
 macro_rules! static_plugin {
     ($(#[$attr:tt])? $vis:vis $name:ident = $ty:ty) => {
@@ -524,6 +567,8 @@ macro_rules! ensure_plugin_capabilities {
 #[macro_export]
 macro_rules! base_plugin_ffi_wrappers {
     ($maj:expr; $min:expr; $patch:expr => #[$attr:meta] $ty:ty) => {
+        const _: () = $crate::base::wrappers::check_required_api_version::<$ty>($maj, $min);
+
         #[$attr]
         pub extern "C-unwind" fn plugin_get_required_api_version() -> *const std::ffi::c_char {
             $crate::base::wrappers::plugin_get_required_api_version::<
@@ -612,8 +657,16 @@ pub struct PluginWrapper<P: Plugin> {
     pub(crate) plugin: Option<ActualPlugin<P>>,
     pub(crate) error_buf: CString,
     pub(crate) field_storage: bumpalo::Bump,
+    pub(crate) extract_cache: ExtractCache,
     pub(crate) string_storage: CString,
     pub(crate) metric_storage: Vec<ss_plugin_metric>,
+    pub(crate) metric_names: Vec<CString>,
+    pub(crate) instance_metrics: InstanceMetrics,
+    pub(crate) parse_metrics: Option<ParseMetrics>,
+    pub(crate) parse_batch: ParseBatch,
+    pub(crate) deadline_metrics: DeadlineMetrics,
+    pub(crate) listen_routines: Vec<RoutineHandle>,
+    pub(crate) hooks: PluginHooks<P>,
 }
 
 impl<P: Plugin> PluginWrapper<P> {
@@ -622,8 +675,16 @@ impl<P: Plugin> PluginWrapper<P> {
             plugin: Some(ActualPlugin { plugin, last_error }),
             error_buf: Default::default(),
             field_storage: bumpalo::Bump::new(),
+            extract_cache: Default::default(),
             string_storage: Default::default(),
             metric_storage: Default::default(),
+            metric_names: Default::default(),
+            instance_metrics: Default::default(),
+            parse_metrics: None,
+            parse_batch: Default::default(),
+            deadline_metrics: Default::default(),
+            listen_routines: Default::default(),
+            hooks: Default::default(),
         }
     }
 
@@ -632,8 +693,16 @@ impl<P: Plugin> PluginWrapper<P> {
             plugin: None,
             error_buf: Default::default(),
             field_storage: bumpalo::Bump::new(),
+            extract_cache: Default::default(),
             string_storage: Default::default(),
             metric_storage: vec![],
+            metric_names: vec![],
+            instance_metrics: Default::default(),
+            parse_metrics: None,
+            parse_batch: Default::default(),
+            deadline_metrics: Default::default(),
+            listen_routines: Default::default(),
+            hooks: Default::default(),
         };
 
         plugin
@@ -1,3 +1,4 @@
+use crate::error::SourceError;
 use crate::FailureReason;
 use falco_plugin_api::ss_plugin_rc;
 use std::ffi::CString;
@@ -15,14 +16,27 @@ pub trait FfiResult {
 
 impl FfiResult for anyhow::Error {
     fn status_code(&self) -> ss_plugin_rc {
-        match self.downcast_ref::<FailureReason>() {
-            Some(reason) => ss_plugin_rc::from(*reason),
+        if let Some(reason) = self.downcast_ref::<FailureReason>() {
+            return ss_plugin_rc::from(*reason);
+        }
+
+        match self.downcast_ref::<SourceError>() {
+            Some(err) => ss_plugin_rc::from(err.reason()),
             None => falco_plugin_api::ss_plugin_rc_SS_PLUGIN_FAILURE,
         }
     }
 
     fn set_last_error(&self, lasterr: &mut CString) {
-        let msg = self.to_string();
+        let mut msg = self.to_string();
+        if let Some(reason) = self.downcast_ref::<FailureReason>() {
+            // `.context(FailureReason::...)` only attaches the status code, so report
+            // the underlying error instead of just the name of the reason
+            if msg == reason.to_string() {
+                if let Some(cause) = self.chain().nth(1) {
+                    msg = cause.to_string();
+                }
+            }
+        }
 
         #[cfg(debug_assertions)]
         match self.status_code() {
@@ -55,3 +69,46 @@ impl<T> FfiResult for Result<T, anyhow::Error> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rc_and_message(err: anyhow::Error) -> (ss_plugin_rc, String) {
+        let mut lasterr = CString::default();
+        let rc = err.rc(&mut lasterr);
+        (rc, lasterr.into_string().unwrap())
+    }
+
+    #[test]
+    fn test_plain_error() {
+        let (rc, msg) = rc_and_message(anyhow::anyhow!("broken"));
+        assert_eq!(rc, falco_plugin_api::ss_plugin_rc_SS_PLUGIN_FAILURE);
+        assert_eq!(msg, "broken");
+    }
+
+    #[test]
+    fn test_failure_reason_context() {
+        let err = anyhow::anyhow!("no more events").context(FailureReason::Eof);
+        let (rc, msg) = rc_and_message(err);
+        assert_eq!(rc, falco_plugin_api::ss_plugin_rc_SS_PLUGIN_EOF);
+        assert_eq!(msg, "no more events");
+
+        let (rc, msg) = rc_and_message(FailureReason::Timeout.into());
+        assert_eq!(rc, falco_plugin_api::ss_plugin_rc_SS_PLUGIN_TIMEOUT);
+        assert_eq!(msg, "timeout");
+    }
+
+    #[test]
+    fn test_source_error() {
+        let (rc, msg) = rc_and_message(SourceError::eof("no more events").into());
+        assert_eq!(rc, falco_plugin_api::ss_plugin_rc_SS_PLUGIN_EOF);
+        assert_eq!(msg, "no more events");
+
+        let err = anyhow::Error::from(SourceError::not_supported(format!("no {}", "seeking")))
+            .context("cannot open");
+        let (rc, msg) = rc_and_message(err);
+        assert_eq!(rc, falco_plugin_api::ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED);
+        assert_eq!(msg, "cannot open");
+    }
+}
@@ -1,7 +1,9 @@
 pub mod as_result;
 pub mod ffi_result;
 pub mod last_error;
+mod source_error;
 
+pub use source_error::SourceError;
 use thiserror::Error;
 
 use falco_plugin_api::ss_plugin_rc;
@@ -24,6 +26,11 @@ pub enum FailureReason {
     /// method.
     ///
     /// The framework will retry the call at a later time.
+    ///
+    /// Field extractors can also use it to report a value that is temporarily unavailable,
+    /// as opposed to one that does not apply to the event at all. The framework does not retry
+    /// failed extractions though (see
+    /// [`ExtractPlugin::EXTRACT_FIELDS`](`crate::extract::ExtractPlugin::EXTRACT_FIELDS`)).
     #[error("timeout")]
     Timeout,
 
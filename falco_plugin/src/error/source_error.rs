@@ -0,0 +1,78 @@
+use crate::FailureReason;
+use std::borrow::Cow;
+use thiserror::Error;
+
+/// # A typed error for source plugins
+///
+/// Instead of attaching a [`FailureReason`] to an error with `.context()`, source plugins
+/// can return one of these variants from [`SourcePlugin::open`](`crate::source::SourcePlugin::open`)
+/// or [`SourcePluginInstance::next_batch`](`crate::source::SourcePluginInstance::next_batch`).
+/// Each variant carries both the status reported to the framework and the message that becomes
+/// the plugin's last error:
+///
+/// ```ignore
+/// fn next_batch(
+///     &mut self,
+///     plugin: &mut Self::Plugin,
+///     batch: &mut EventBatch,
+/// ) -> Result<(), anyhow::Error> {
+///     Err(SourceError::eof(format!("reached the end of {}", self.path)))?
+/// }
+/// ```
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SourceError {
+    /// # General failure, see [`FailureReason::Failure`]
+    #[error("{0}")]
+    Failure(Cow<'static, str>),
+
+    /// # No data available yet, see [`FailureReason::Timeout`]
+    #[error("{0}")]
+    Timeout(Cow<'static, str>),
+
+    /// # No more data, see [`FailureReason::Eof`]
+    #[error("{0}")]
+    Eof(Cow<'static, str>),
+
+    /// # Operation not supported, see [`FailureReason::NotSupported`]
+    #[error("{0}")]
+    NotSupported(Cow<'static, str>),
+}
+
+impl SourceError {
+    /// Create a [`SourceError::Failure`] with a particular message
+    pub fn failure(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self::Failure(msg.into())
+    }
+
+    /// Create a [`SourceError::Timeout`] with a particular message
+    pub fn timeout(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self::Timeout(msg.into())
+    }
+
+    /// Create a [`SourceError::Eof`] with a particular message
+    pub fn eof(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self::Eof(msg.into())
+    }
+
+    /// Create a [`SourceError::NotSupported`] with a particular message
+    pub fn not_supported(msg: impl Into<Cow<'static, str>>) -> Self {
+        Self::NotSupported(msg.into())
+    }
+
+    /// Get the failure reason reported to the framework
+    pub fn reason(&self) -> FailureReason {
+        match self {
+            SourceError::Failure(_) => FailureReason::Failure,
+            SourceError::Timeout(_) => FailureReason::Timeout,
+            SourceError::Eof(_) => FailureReason::Eof,
+            SourceError::NotSupported(_) => FailureReason::NotSupported,
+        }
+    }
+}
+
+impl From<&SourceError> for FailureReason {
+    fn from(err: &SourceError) -> Self {
+        err.reason()
+    }
+}
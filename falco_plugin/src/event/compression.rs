@@ -0,0 +1,397 @@
+//! # Compressed event payloads
+//!
+//! Plugins shipping bulky data (like large JSON documents) through the event stream can
+//! compress the payloads of their plugin and async events. This module provides:
+//! - [`CompressedPayload`], a payload wrapper (similar to [`JsonPayload`](crate::event::JsonPayload))
+//!   that compresses the wrapped payload when writing an event and decompresses it when reading one
+//! - [`compress`] and [`decompress`], doing the same for raw byte buffers, e.g. for extract
+//!   plugins working on `PluginEvent<&[u8]>` directly
+//!
+//! Each compression algorithm is only available if the corresponding cargo feature
+//! (`zstd` or `lz4`) is enabled.
+//!
+//! ## Payload format
+//!
+//! Compressed payloads are stored in the standard frame format of the respective algorithm,
+//! which starts with a well-known magic number. When decoding, the algorithm is detected
+//! based on that number, while any other data is considered uncompressed and passed through
+//! as is. This means that:
+//! - consumers do not need to know in advance whether (and how) a payload was compressed,
+//!   so producers can change their mind (e.g. only compress large payloads) without any
+//!   coordination
+//! - payloads that are not compressed must not start with one of the magic numbers; this
+//!   is never the case for e.g. JSON or text data
+//!
+//! Since a small compressed payload can expand to a huge amount of data, decompression fails
+//! once the output exceeds a size limit: [`MAX_DECOMPRESSED_SIZE`] by default, or one passed
+//! to [`decompress_with_limit`].
+//!
+//! ```
+//!# #[cfg(feature = "zstd")]
+//!# {
+//! use falco_plugin::event::compression::{compress, decompress, Compression};
+//!
+//! let data = br#"{"some": "json"}"#;
+//! let compressed = compress(Compression::Zstd { level: 3 }, data).unwrap();
+//!
+//! assert_eq!(&*decompress(&compressed).unwrap(), data);
+//! // uncompressed data passes through unchanged
+//! assert_eq!(&*decompress(data).unwrap(), data);
+//!# }
+//! ```
+
+use crate::event::EventSource;
+use falco_event::fields::{FromBytes, FromBytesError, ToBytes};
+use std::borrow::Cow;
+use std::cell::RefCell;
+use std::fmt::Debug;
+use std::io::Write;
+
+/// Magic number starting a zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Magic number starting an LZ4 frame
+const LZ4_MAGIC: [u8; 4] = [0x04, 0x22, 0x4d, 0x18];
+
+/// # The default limit for the size of decompressed payloads
+///
+/// This is used by [`decompress`] and [`CompressedPayload`].
+pub const MAX_DECOMPRESSED_SIZE: usize = 64 << 20;
+
+/// # Compression algorithm for event payloads
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    /// Store the payload uncompressed
+    None,
+
+    /// Compress the payload with zstd, at the specified level
+    ///
+    /// Level 0 means the zstd default (currently 3).
+    #[cfg(feature = "zstd")]
+    Zstd {
+        /// Compression level
+        level: i32,
+    },
+
+    /// Compress the payload with LZ4
+    #[cfg(feature = "lz4")]
+    Lz4,
+}
+
+impl Compression {
+    /// Detect the compression algorithm used for a payload
+    ///
+    /// Returns an error if the payload is compressed with an algorithm that has not been
+    /// enabled in this build.
+    ///
+    /// Detection is level-agnostic: the zstd compression level is not stored in the payload,
+    /// so zstd-compressed data is always reported as `Compression::Zstd { level: 0 }`,
+    /// whatever level it was compressed with. To check the algorithm only, use
+    /// `matches!(compression, Compression::Zstd { .. })` rather than comparing with `==`.
+    pub fn detect(data: &[u8]) -> std::io::Result<Self> {
+        match data.get(..4) {
+            Some(magic) if magic == ZSTD_MAGIC => {
+                #[cfg(feature = "zstd")]
+                return Ok(Compression::Zstd { level: 0 });
+                #[cfg(not(feature = "zstd"))]
+                return Err(unsupported("zstd"));
+            }
+            Some(magic) if magic == LZ4_MAGIC => {
+                #[cfg(feature = "lz4")]
+                return Ok(Compression::Lz4);
+                #[cfg(not(feature = "lz4"))]
+                return Err(unsupported("lz4"));
+            }
+            _ => Ok(Compression::None),
+        }
+    }
+}
+
+#[cfg(not(all(feature = "zstd", feature = "lz4")))]
+fn unsupported(algorithm: &str) -> std::io::Error {
+    std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        format!("payload compressed with {algorithm}, enable the `{algorithm}` feature to read it"),
+    )
+}
+
+/// # Compress a payload
+///
+/// With [`Compression::None`], this just copies the data.
+pub fn compress(compression: Compression, data: &[u8]) -> std::io::Result<Vec<u8>> {
+    match compression {
+        Compression::None => Ok(data.to_vec()),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd { level } => zstd::stream::encode_all(data, level),
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => {
+            let mut encoder = lz4_flex::frame::FrameEncoder::new(Vec::new());
+            encoder.write_all(data)?;
+            encoder.finish().map_err(std::io::Error::other)
+        }
+    }
+}
+
+/// # Decompress a payload
+///
+/// The compression algorithm is detected automatically (see [`Compression::detect`]).
+/// Uncompressed payloads are returned as is, without copying.
+///
+/// Fails if the decompressed data is larger than [`MAX_DECOMPRESSED_SIZE`].
+pub fn decompress(data: &[u8]) -> std::io::Result<Cow<'_, [u8]>> {
+    decompress_with_limit(data, MAX_DECOMPRESSED_SIZE)
+}
+
+/// # Decompress a payload, with a custom size limit
+///
+/// This works like [`decompress`], but fails if the decompressed data is larger
+/// than `max_size` bytes.
+#[cfg_attr(not(any(feature = "zstd", feature = "lz4")), allow(unused_variables))]
+pub fn decompress_with_limit(data: &[u8], max_size: usize) -> std::io::Result<Cow<'_, [u8]>> {
+    match Compression::detect(data)? {
+        Compression::None => Ok(Cow::Borrowed(data)),
+        #[cfg(feature = "zstd")]
+        Compression::Zstd { .. } => {
+            let decoder = zstd::stream::read::Decoder::with_buffer(data)?;
+            read_limited(decoder, max_size).map(Cow::Owned)
+        }
+        #[cfg(feature = "lz4")]
+        Compression::Lz4 => {
+            let decoder = lz4_flex::frame::FrameDecoder::new(data);
+            read_limited(decoder, max_size).map(Cow::Owned)
+        }
+    }
+}
+
+#[cfg(any(feature = "zstd", feature = "lz4"))]
+fn read_limited(reader: impl std::io::Read, max_size: usize) -> std::io::Result<Vec<u8>> {
+    use std::io::Read;
+
+    let mut buf = Vec::new();
+    reader
+        .take((max_size as u64).saturating_add(1))
+        .read_to_end(&mut buf)?;
+    if buf.len() > max_size {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            format!("decompressed payload exceeds {max_size} bytes"),
+        ));
+    }
+    Ok(buf)
+}
+
+/// A wrapper that enables compressed event payloads in [`crate::event::AsyncEvent`] and [`crate::event::PluginEvent`]
+///
+/// The wrapped payload type `T` is serialized as usual, and the result is compressed
+/// with the algorithm passed to [`CompressedPayload::new`]. Since the algorithm is detected
+/// when reading the event, the consumer does not need to know it in advance, and uncompressed
+/// payloads of type `T` can be read as well:
+/// ```
+/// use falco_event::events::{AnyEventPayload, RawEvent};
+/// use falco_plugin::event::compression::CompressedPayload;
+/// use falco_plugin::event::{EventSource, JsonPayload, PluginEvent};
+/// use falco_plugin::event::events::Event;
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct MyEvent {
+///     param1: u32,
+///     param2: u32,
+/// }
+///
+/// impl EventSource for MyEvent {
+///     const SOURCE: Option<&'static str> = Some("my_plugin");
+/// }
+///
+///# trait FakePluginTrait {
+///#     type Event<'a>: AnyEventPayload + TryFrom<&'a RawEvent<'a>> where Self: 'a;
+///# }
+///# struct FakePlugin;
+///# impl FakePluginTrait for FakePlugin {
+/// // in a plugin trait implementation:
+/// type Event<'a> = Event<PluginEvent<CompressedPayload<JsonPayload<MyEvent>>>>;
+///# }
+/// ```
+///
+/// **Note**: the payload type cannot borrow from the event, as it's parsed from a temporary
+/// buffer holding the decompressed data.
+///
+/// The payload is serialized and compressed once, when its size is first needed. If that fails,
+/// [`ToBytes::binary_size`] (which cannot return an error) reports a size of 0 and the error
+/// is returned from [`ToBytes::write`] instead, so adding the event to a batch fails as usual.
+pub struct CompressedPayload<T> {
+    inner: T,
+    compression: Compression,
+    compressed: RefCell<Option<Result<Vec<u8>, std::io::Error>>>,
+}
+
+impl<T> Debug for CompressedPayload<T>
+where
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        T::fmt(&self.inner, f)
+    }
+}
+
+impl<T> CompressedPayload<T> {
+    /// Create a [`CompressedPayload`] object, compressing the data with `compression`
+    pub fn new(inner: T, compression: Compression) -> Self {
+        Self {
+            inner,
+            compression,
+            compressed: RefCell::new(None),
+        }
+    }
+
+    /// Get the compression algorithm
+    ///
+    /// For payloads read from an event, this is the algorithm detected in the payload
+    /// (with `Compression::Zstd` always reporting level 0, as it's not stored in the data).
+    pub fn compression(&self) -> Compression {
+        self.compression
+    }
+
+    /// Get a reference to the data inside
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Get a mutable reference to the data inside
+    pub fn get_mut(&mut self) -> &mut T {
+        self.compressed.replace(None);
+        &mut self.inner
+    }
+
+    /// Return the wrapped data, dropping the wrapper
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<T> CompressedPayload<T>
+where
+    T: ToBytes,
+{
+    fn update_compressed(&self) {
+        if self.compressed.borrow().is_none() {
+            let mut buf = Vec::with_capacity(self.inner.binary_size());
+            let compressed = self
+                .inner
+                .write(&mut buf)
+                .and_then(|_| compress(self.compression, &buf));
+            self.compressed.replace(Some(compressed));
+        }
+    }
+}
+
+impl<'a, T> FromBytes<'a> for CompressedPayload<T>
+where
+    T: for<'b> FromBytes<'b>,
+{
+    fn from_bytes(buf: &mut &'a [u8]) -> Result<Self, FromBytesError> {
+        let compression = Compression::detect(buf)?;
+        let data = decompress(buf)?;
+        let inner = T::from_bytes(&mut data.as_ref())?;
+        *buf = &[];
+        Ok(Self::new(inner, compression))
+    }
+}
+
+impl<T> ToBytes for CompressedPayload<T>
+where
+    T: ToBytes,
+{
+    fn binary_size(&self) -> usize {
+        self.update_compressed();
+        match self.compressed.borrow().as_ref().unwrap() {
+            Ok(v) => v.len(),
+            Err(_) => 0,
+        }
+    }
+
+    fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        self.update_compressed();
+        match self.compressed.take().unwrap() {
+            Ok(v) => {
+                let ret = v.as_slice().write(writer).map(|_| ());
+                self.compressed.replace(Some(Ok(v)));
+                ret
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn default_repr() -> impl ToBytes {
+        &[] as &[u8]
+    }
+}
+
+impl<T> EventSource for CompressedPayload<T>
+where
+    T: EventSource,
+{
+    const SOURCE: Option<&'static str> = T::SOURCE;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DATA: &[u8] = br#"{"key": "a value repeated, a value repeated, a value repeated"}"#;
+
+    fn roundtrip(compression: Compression) {
+        let compressed = compress(compression, DATA).unwrap();
+        assert_eq!(Compression::detect(&compressed).unwrap(), compression);
+        assert_eq!(&*decompress(&compressed).unwrap(), DATA);
+    }
+
+    #[test]
+    fn test_uncompressed() {
+        roundtrip(Compression::None);
+        assert!(matches!(decompress(DATA).unwrap(), Cow::Borrowed(_)));
+        assert!(matches!(decompress(&[]).unwrap(), Cow::Borrowed(_)));
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd() {
+        roundtrip(Compression::Zstd { level: 0 });
+
+        // the level is not stored in the payload
+        let compressed = compress(Compression::Zstd { level: 19 }, DATA).unwrap();
+        assert_eq!(
+            Compression::detect(&compressed).unwrap(),
+            Compression::Zstd { level: 0 }
+        );
+        assert_eq!(&*decompress(&compressed).unwrap(), DATA);
+    }
+
+    #[cfg(feature = "lz4")]
+    #[test]
+    fn test_lz4() {
+        roundtrip(Compression::Lz4);
+    }
+
+    #[cfg(any(feature = "zstd", feature = "lz4"))]
+    #[test]
+    fn test_size_limit() {
+        let compressions = [
+            #[cfg(feature = "zstd")]
+            Compression::Zstd { level: 0 },
+            #[cfg(feature = "lz4")]
+            Compression::Lz4,
+        ];
+
+        for compression in compressions {
+            let compressed = compress(compression, DATA).unwrap();
+            assert_eq!(
+                &*decompress_with_limit(&compressed, DATA.len()).unwrap(),
+                DATA
+            );
+
+            let err = decompress_with_limit(&compressed, DATA.len() - 1).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::InvalidData);
+        }
+    }
+}
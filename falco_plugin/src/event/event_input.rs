@@ -1,9 +1,27 @@
+use crate::event::compression::decompress;
+use crate::event::{AsyncEvent, PayloadFormat, PluginEvent, PluginEventPayload};
 use anyhow::Context;
-use falco_event::events::RawEvent;
+use falco_event::events::{EventPayload, RawEvent};
 use falco_plugin_api::ss_plugin_event_input;
-use std::ffi::CStr;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::ffi::{CStr, CString};
+use std::fmt::{Display, Formatter};
 use std::marker::PhantomData;
 
+/// Context attached to the errors returned from [`EventInput::event`]
+///
+/// This lets the parse wrapper tell conversion failures apart from other errors
+/// (see [`ParsePlugin::UNPARSABLE_EVENTS`](`crate::parse::ParsePlugin::UNPARSABLE_EVENTS`)).
+#[derive(Debug)]
+pub(crate) struct EventConversionError(String);
+
+impl Display for EventConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
 /// # An event from which additional data may be extracted
 #[derive(Debug)]
 pub struct EventInput<'a, T>(
@@ -23,11 +41,34 @@ where
     pub fn event(&self) -> anyhow::Result<T> {
         let raw = unsafe { RawEvent::from_ptr(self.0.evt as *const _) }?;
         let event = Ok(<&RawEvent<'_> as TryInto<T>>::try_into(&raw)
-            .with_context(|| format!("parsing event {raw:?}"))?);
+            .with_context(|| EventConversionError(format!("parsing event {raw:?}")))?);
         #[allow(clippy::let_and_return)]
         event
     }
 
+    /// # Render the payload of a plugin event as JSON
+    ///
+    /// For events parsed as `Event<PluginEvent<MyPayload>>`, where `MyPayload` implements
+    /// [`Serialize`], return the payload serialized to JSON. This is useful for binary payloads,
+    /// which the default [`SourcePlugin::event_to_string`](`crate::source::SourcePlugin::event_to_string`)
+    /// cannot render in a readable form (see [`EventInput::to_cstring_lossy`]):
+    ///
+    /// ```ignore
+    /// fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+    ///     event.to_json_cstring()
+    /// }
+    /// ```
+    pub fn to_json_cstring(&self) -> anyhow::Result<CString>
+    where
+        T: PluginEventPayload,
+        T::Payload: Serialize,
+    {
+        let event = self.event()?;
+        Ok(CString::new(serde_json::to_string(event.payload())?)?)
+    }
+}
+
+impl<T> EventInput<'_, T> {
     /// # Get the event source
     ///
     /// Return the event source (if any)
@@ -43,8 +84,99 @@ where
 
     /// # Get the event number
     ///
-    /// Return the event number as determined by the plugin framework
+    /// Return the event number as determined by the plugin framework. Event numbers increase
+    /// monotonically within a capture, so they can be used e.g. to correlate state
+    /// stored while parsing an event with later field extraction from the same event.
     pub fn event_number(&self) -> usize {
         self.0.evtnum as usize
     }
+
+    /// # Get the ID of the plugin that generated the event
+    ///
+    /// For plugin events (`PPME_PLUGINEVENT_E`), return the ID of the source plugin
+    /// that produced the event. For all other event types, return `None`.
+    ///
+    /// This only parses the event header and the plugin ID, so it's cheaper than
+    /// loading the whole event just to check where it came from.
+    pub fn plugin_id(&self) -> anyhow::Result<Option<u32>> {
+        let raw = unsafe { RawEvent::from_ptr(self.0.evt as *const _) }?;
+        if raw.event_type != <PluginEvent<&[u8]> as EventPayload>::ID {
+            return Ok(None);
+        }
+
+        let event = raw.load::<PluginEvent<&[u8]>>()?;
+        Ok(Some(event.params.plugin_id))
+    }
+
+    /// # Get the payload of a plugin event
+    ///
+    /// For plugin events (`PPME_PLUGINEVENT_E`), return the raw event data, without parsing
+    /// it into any particular type. For all other event types, return `None`.
+    pub fn plugin_event_data(&self) -> anyhow::Result<Option<&[u8]>> {
+        let raw = unsafe { RawEvent::from_ptr(self.0.evt as *const _) }?;
+        if raw.event_type != <PluginEvent<&[u8]> as EventPayload>::ID {
+            return Ok(None);
+        }
+
+        let event = raw.load::<PluginEvent<&[u8]>>()?;
+        Ok(Some(event.params.event_data))
+    }
+
+    /// # Get the name and payload of an async event
+    ///
+    /// For async events (`PPME_ASYNCEVENT_E`), return the event name and the raw event data,
+    /// without parsing it into any particular type. For all other event types, return `None`.
+    pub fn async_event_data(&self) -> anyhow::Result<Option<(&CStr, &[u8])>> {
+        let raw = unsafe { RawEvent::from_ptr(self.0.evt as *const _) }?;
+        if raw.event_type != <AsyncEvent<&[u8]> as EventPayload>::ID {
+            return Ok(None);
+        }
+
+        let event = raw.load::<AsyncEvent<&[u8]>>()?;
+        Ok(Some((event.params.name, event.params.data)))
+    }
+
+    /// # Decode the payload of a typed async event
+    ///
+    /// If this is an async event called `name`, decode its payload as `T`, using `format`.
+    /// This is the counterpart of [`AsyncEventPlugin::async_event_typed`](`crate::async_event::AsyncEventPlugin::async_event_typed`).
+    /// For all other events, return `None`.
+    pub fn parse_async_event<P: DeserializeOwned>(
+        &self,
+        name: &CStr,
+        format: PayloadFormat,
+    ) -> anyhow::Result<Option<P>> {
+        match self.async_event_data()? {
+            Some((event_name, data)) if event_name == name => format
+                .decode(data)
+                .with_context(|| format!("decoding async event {}", name.to_string_lossy()))
+                .map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// # Render the event as a string
+    ///
+    /// This is the default implementation of [`SourcePlugin::event_to_string`](`crate::source::SourcePlugin::event_to_string`):
+    /// - plugin events render their payload as (lossy) UTF-8, which covers both plain text
+    ///   and [`JsonPayload`](`crate::event::JsonPayload`) payloads; compressed payloads
+    ///   (see [`crate::event::compression`]) are decompressed first
+    /// - other events render as their debug representation
+    ///
+    /// Binary payloads do not render well this way. If the payload type implements
+    /// [`Serialize`], use [`EventInput::to_json_cstring`] instead.
+    pub fn to_cstring_lossy(&self) -> anyhow::Result<CString> {
+        let text = match self.plugin_event_data()? {
+            Some(data) => {
+                let data = decompress(data)?;
+                String::from_utf8_lossy(&data).into_owned()
+            }
+            None => {
+                let raw = unsafe { RawEvent::from_ptr(self.0.evt as *const _) }?;
+                format!("{raw:?}")
+            }
+        };
+
+        Ok(CString::new(text.replace('\0', "\\0"))?)
+    }
 }
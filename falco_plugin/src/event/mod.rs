@@ -4,17 +4,21 @@
 //! the event types defined by this crate (the minimal subset of the full Falco schema)
 
 mod async_event;
+pub mod compression;
 mod event_input;
 mod json;
 mod plugin_event;
+mod typed;
 
 pub use async_event::AsyncEvent;
+pub(crate) use event_input::EventConversionError;
 pub use event_input::EventInput;
 use falco_event::fields::{FromBytes, ToBytes};
 pub use falco_event::{events, fields};
 pub use json::JsonPayload;
-pub use plugin_event::PluginEvent;
+pub use plugin_event::{PluginEvent, PluginEventPayload};
 use std::fmt::Debug;
+pub use typed::PayloadFormat;
 
 /// Provide an event source name for an event type
 ///
@@ -31,6 +35,10 @@ impl EventSource for &[u8] {
     const SOURCE: Option<&'static str> = None;
 }
 
+impl EventSource for Vec<u8> {
+    const SOURCE: Option<&'static str> = None;
+}
+
 /// A generic enum for custom plugin/async event payloads
 ///
 /// Type parameters:
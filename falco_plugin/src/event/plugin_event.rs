@@ -1,4 +1,5 @@
 use crate::event::EventSource;
+use falco_event::events::Event;
 use falco_event::fields::{FromBytes, ToBytes};
 use std::fmt::{Debug, Formatter};
 
@@ -93,6 +94,27 @@ where
     pub event_data: T,
 }
 
+/// # Access to the payload of a parsed plugin event
+///
+/// This is implemented for `Event<PluginEvent<T>>`, so that generic code (like
+/// [`ExtractRequest::event_payload`](`crate::extract::ExtractRequest::event_payload`))
+/// can get to the deserialized `T` inside.
+pub trait PluginEventPayload {
+    /// The payload type
+    type Payload;
+
+    /// Get the payload
+    fn payload(&self) -> &Self::Payload;
+}
+
+impl<T: EventSource> PluginEventPayload for Event<PluginEvent<T>> {
+    type Payload = T;
+
+    fn payload(&self) -> &T {
+        &self.params.event_data
+    }
+}
+
 impl<T> Debug for PluginEvent<T>
 where
     T: EventSource + Debug,
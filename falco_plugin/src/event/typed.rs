@@ -0,0 +1,42 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// # Serialization format for typed event payloads
+///
+/// Used by [`AsyncEventPlugin::async_event_typed`](`crate::async_event::AsyncEventPlugin::async_event_typed`)
+/// on the producer side and [`EventInput::parse_async_event`](`crate::event::EventInput::parse_async_event`)
+/// on the consumer side. Unlike [compressed payloads](`crate::event::compression`), the format
+/// is not detected automatically, so both sides must agree on it (along with the payload type).
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PayloadFormat {
+    /// JSON, as produced by `serde_json`
+    ///
+    /// Easy to inspect (e.g. in `event_to_string` output), but large and slow to process.
+    #[default]
+    Json,
+
+    /// A compact binary format, as produced by `bincode`
+    #[cfg(feature = "bincode")]
+    Bincode,
+}
+
+impl PayloadFormat {
+    /// Serialize a value into a byte buffer
+    pub fn encode<T: Serialize + ?Sized>(self, value: &T) -> anyhow::Result<Vec<u8>> {
+        match self {
+            PayloadFormat::Json => Ok(serde_json::to_vec(value)?),
+            #[cfg(feature = "bincode")]
+            PayloadFormat::Bincode => Ok(bincode::serialize(value)?),
+        }
+    }
+
+    /// Deserialize a value from a byte buffer
+    pub fn decode<T: DeserializeOwned>(self, data: &[u8]) -> anyhow::Result<T> {
+        match self {
+            PayloadFormat::Json => Ok(serde_json::from_slice(data)?),
+            #[cfg(feature = "bincode")]
+            PayloadFormat::Bincode => Ok(bincode::deserialize(data)?),
+        }
+    }
+}
@@ -0,0 +1,274 @@
+use crate::event::EventInput;
+use crate::extract::{ExtractPlugin, INVALID_RANGE};
+use crate::tables::LazyTableReader;
+use falco_plugin_api::{
+    ss_plugin_event, ss_plugin_event_input, ss_plugin_extract_field,
+    ss_plugin_extract_value_offsets, ss_plugin_field_extract_input,
+};
+use std::ffi::{CStr, CString};
+
+const INVALID_OFFSET: (u32, u32) = (
+    INVALID_RANGE.start as u32,
+    INVALID_RANGE.end.wrapping_sub(INVALID_RANGE.start) as u32,
+);
+
+#[derive(Debug, PartialEq, Eq)]
+enum CachedArg {
+    None,
+    Int(u64),
+    String(CString),
+}
+
+impl CachedArg {
+    /// # Safety
+    ///
+    /// `field.arg_key` must be null or a valid C string
+    unsafe fn matches(&self, field: &ss_plugin_extract_field) -> bool {
+        match self {
+            CachedArg::None => field.arg_present == 0,
+            CachedArg::Int(index) => {
+                field.arg_present != 0 && field.arg_key.is_null() && field.arg_index == *index
+            }
+            CachedArg::String(key) => {
+                field.arg_present != 0
+                    && !field.arg_key.is_null()
+                    && unsafe { CStr::from_ptr(field.arg_key) } == key.as_c_str()
+            }
+        }
+    }
+
+    /// # Safety
+    ///
+    /// `field.arg_key` must be null or a valid C string
+    unsafe fn from_field(field: &ss_plugin_extract_field) -> Self {
+        if field.arg_present == 0 {
+            CachedArg::None
+        } else if field.arg_key.is_null() {
+            CachedArg::Int(field.arg_index)
+        } else {
+            CachedArg::String(unsafe { CStr::from_ptr(field.arg_key) }.to_owned())
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CachedValue {
+    field_id: u32,
+    arg: CachedArg,
+    res: *mut u64,
+    res_len: u64,
+    /// `None` if the range was not requested when the value was extracted
+    offset: Option<(u32, u32)>,
+}
+
+/// The identity of a cached event
+///
+/// Event numbers restart from 1 with every capture, so they alone cannot tell apart
+/// events from different captures. The event pointer and timestamp are checked as well.
+#[derive(Debug, PartialEq, Eq)]
+struct CachedEvent {
+    number: u64,
+    ptr: *const ss_plugin_event,
+    ts: u64,
+}
+
+impl CachedEvent {
+    /// # Safety
+    ///
+    /// `input.evt` must be null or point to a valid event
+    unsafe fn from_input(input: &ss_plugin_event_input) -> Self {
+        Self {
+            number: input.evtnum,
+            ptr: input.evt,
+            ts: unsafe { input.evt.as_ref() }.map_or(0, |evt| evt.ts),
+        }
+    }
+}
+
+/// Values extracted from the most recent event
+///
+/// The values themselves live in the plugin's field storage arena, which only gets reset
+/// when a new event comes in (see [`ExtractCache::start_event`]), so the pointers stay valid
+/// for as long as they're in the cache.
+#[derive(Debug, Default)]
+pub(crate) struct ExtractCache {
+    event: Option<CachedEvent>,
+    values: Vec<CachedValue>,
+}
+
+impl ExtractCache {
+    /// Prepare the cache for extracting fields from the event in `event_input`
+    ///
+    /// Returns true if this is a different event than the one cached, i.e. all the cached
+    /// values have been dropped and the field storage needs to be reset
+    ///
+    /// # Safety
+    ///
+    /// `event_input.evt` must be null or point to a valid event
+    pub(crate) unsafe fn start_event(&mut self, event_input: &ss_plugin_event_input) -> bool {
+        let event = unsafe { CachedEvent::from_input(event_input) };
+        if self.event.as_ref() == Some(&event) {
+            return false;
+        }
+
+        self.event = Some(event);
+        self.values.clear();
+        true
+    }
+
+    /// # Safety
+    ///
+    /// `field.arg_key` must be null or a valid C string
+    unsafe fn get(
+        &self,
+        field: &ss_plugin_extract_field,
+        want_offset: bool,
+    ) -> Option<&CachedValue> {
+        self.values.iter().find(|v| {
+            v.field_id == field.field_id
+                && unsafe { v.arg.matches(field) }
+                && (!want_offset || v.offset.is_some())
+        })
+    }
+
+    /// Extract fields, reusing any values already extracted from the same event
+    ///
+    /// Only the fields missing from the cache are passed to [`ExtractPlugin::extract_fields`],
+    /// with the results merged back into the fields (and offsets) of `raw_input` afterward.
+    ///
+    /// # Safety
+    ///
+    /// `raw_input.fields` must point to `raw_input.num_fields` valid fields, all with
+    /// `arg_key` pointers either null or valid C strings. `raw_input.value_offsets` must be
+    /// null or valid
+    pub(crate) unsafe fn extract_fields<'a, P: ExtractPlugin>(
+        &mut self,
+        plugin: &'a mut P,
+        event_input: &EventInput<'a, P::Event<'a>>,
+        raw_input: &ss_plugin_field_extract_input,
+        table_reader: &LazyTableReader,
+        storage: &'a bumpalo::Bump,
+    ) -> Result<(), anyhow::Error> {
+        let fields = unsafe {
+            std::slice::from_raw_parts_mut(raw_input.fields, raw_input.num_fields as usize)
+        };
+        let offsets = unsafe { raw_input.value_offsets.as_mut() };
+        let want_offsets = offsets.is_some();
+        let mut misses = bumpalo::collections::Vec::new_in(storage);
+        let mut miss_fields = bumpalo::collections::Vec::new_in(storage);
+
+        for (i, field) in fields.iter_mut().enumerate() {
+            match unsafe { self.get(field, want_offsets) } {
+                Some(value) => {
+                    field.res.u64_ = value.res;
+                    field.res_len = value.res_len;
+                }
+                None => {
+                    misses.push(i);
+                    miss_fields.push(*field);
+                }
+            }
+        }
+
+        if !miss_fields.is_empty() {
+            let mut miss_offsets = ss_plugin_extract_value_offsets {
+                start: std::ptr::null_mut(),
+                length: std::ptr::null_mut(),
+            };
+
+            plugin.extract_fields(
+                event_input,
+                table_reader,
+                &mut miss_fields,
+                want_offsets.then_some(&mut miss_offsets),
+                storage,
+            )?;
+
+            for (j, (i, extracted)) in misses.iter().zip(miss_fields.iter()).enumerate() {
+                let field = &mut fields[*i];
+                field.res = extracted.res;
+                field.res_len = extracted.res_len;
+
+                let offset = match (want_offsets, miss_offsets.start.is_null()) {
+                    (false, _) => None,
+                    (true, true) => Some(INVALID_OFFSET),
+                    (true, false) => unsafe {
+                        Some((*miss_offsets.start.add(j), *miss_offsets.length.add(j)))
+                    },
+                };
+
+                self.values.push(CachedValue {
+                    field_id: field.field_id,
+                    arg: unsafe { CachedArg::from_field(field) },
+                    res: unsafe { field.res.u64_ },
+                    res_len: field.res_len,
+                    offset,
+                });
+            }
+        }
+
+        if let Some(offsets) = offsets {
+            let mut starts = bumpalo::collections::Vec::with_capacity_in(fields.len(), storage);
+            let mut lengths = bumpalo::collections::Vec::with_capacity_in(fields.len(), storage);
+            let mut any_offsets = false;
+
+            for field in fields.iter() {
+                let (start, length) = unsafe { self.get(field, true) }
+                    .and_then(|value| value.offset)
+                    .unwrap_or(INVALID_OFFSET);
+                if (start, length) != INVALID_OFFSET {
+                    any_offsets = true;
+                }
+                starts.push(start);
+                lengths.push(length);
+            }
+
+            if any_offsets {
+                offsets.start = starts.into_bump_slice_mut().as_mut_ptr();
+                offsets.length = lengths.into_bump_slice_mut().as_mut_ptr();
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event_input(evt: &ss_plugin_event, evtnum: u64) -> ss_plugin_event_input {
+        ss_plugin_event_input {
+            evt,
+            evtnum,
+            evtsrc: std::ptr::null(),
+        }
+    }
+
+    #[test]
+    fn test_start_event_across_captures() {
+        let first = ss_plugin_event {
+            ts: 1000,
+            tid: 1,
+            len: 26,
+            type_: 322,
+            nparams: 0,
+        };
+        let mut second = ss_plugin_event { ts: 2000, ..first };
+
+        let mut cache = ExtractCache::default();
+        unsafe {
+            assert!(cache.start_event(&event_input(&first, 1)));
+            assert!(!cache.start_event(&event_input(&first, 1)));
+            assert!(cache.start_event(&event_input(&first, 2)));
+
+            // event numbers restart in a new capture
+            assert!(cache.start_event(&event_input(&second, 1)));
+            assert!(!cache.start_event(&event_input(&second, 1)));
+
+            // the same buffer reused for another event
+            second.ts = 3000;
+            assert!(cache.start_event(&event_input(&second, 1)));
+        }
+    }
+}
@@ -4,6 +4,7 @@ use crate::extract::{ExtractPlugin, ExtractRequest};
 use anyhow::Error;
 use falco_plugin_api::ss_plugin_extract_field;
 use std::ffi::CStr;
+use std::marker::PhantomData;
 
 /// The actual argument passed to the extractor function
 ///
@@ -81,6 +82,58 @@ pub struct OptIntArg;
 #[derive(Debug)]
 pub struct OptStringArg;
 
+/// Marker for extractors returning a string borrowed from the request
+///
+/// This lets extractors return e.g. a `&CStr` allocated in [`ExtractRequest::storage`]
+/// instead of a heap-allocated `CString`.
+#[derive(Debug)]
+pub struct Borrowed<A>(PhantomData<A>);
+
+fn no_arg(arg: ExtractFieldRequestArg) -> Result<(), Error> {
+    anyhow::ensure!(matches!(arg, ExtractFieldRequestArg::None));
+    Ok(())
+}
+
+fn int_arg(arg: ExtractFieldRequestArg) -> Result<u64, Error> {
+    let ExtractFieldRequestArg::Int(arg) = arg else {
+        anyhow::bail!("Expected index argument, got {:?}", arg);
+    };
+    Ok(arg)
+}
+
+fn opt_int_arg(arg: ExtractFieldRequestArg) -> Result<Option<u64>, Error> {
+    match arg {
+        ExtractFieldRequestArg::Int(arg) => Ok(Some(arg)),
+        ExtractFieldRequestArg::None => Ok(None),
+        _ => anyhow::bail!("Expected index argument, got {:?}", arg),
+    }
+}
+
+fn string_arg(arg: ExtractFieldRequestArg<'_>) -> Result<&CStr, Error> {
+    let ExtractFieldRequestArg::String(arg) = arg else {
+        anyhow::bail!("Expected key argument, got {:?}", arg);
+    };
+    Ok(arg)
+}
+
+fn opt_string_arg(arg: ExtractFieldRequestArg<'_>) -> Result<Option<&CStr>, Error> {
+    match arg {
+        ExtractFieldRequestArg::String(arg) => Ok(Some(arg)),
+        ExtractFieldRequestArg::None => Ok(None),
+        _ => anyhow::bail!("Expected key argument, got {:?}", arg),
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "invalid signature for a field extractor",
+    label = "not a valid field extractor",
+    note = "extractors must look like `fn(&mut self, req: ExtractRequest<Self>) -> Result<R, anyhow::Error>`, \
+        optionally taking an extra argument after `req`",
+    note = "the argument (if any) must be `u64`, `&CStr`, `Option<u64>` or `Option<&CStr>`",
+    note = "`R` must be `u64`, `bool`, `CString`, `Duration`, `SystemTime`, `IpAddr` or `IpNet`, \
+        optionally wrapped in `Vec<_>` or `SmallVec<[_; N]>` and/or `Option<_>`, \
+        or a `&CStr` borrowed from the request"
+)]
 pub trait ExtractorFn<P, R, A>
 where
     P: ExtractPlugin,
@@ -88,23 +141,13 @@ where
 {
     const ARG_TYPE: ExtractArgType;
 
-    fn call(
-        obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error>;
-
     fn extract<'a>(
         obj: *const (),
         plugin: &'a mut P,
         field: &mut ss_plugin_extract_field,
         request: ExtractRequest<'a, '_, '_, '_, P>,
         storage: &bumpalo::Bump,
-    ) -> Result<(), Error> {
-        let result = Self::call(obj, plugin, request, unsafe { field.key_unchecked() })?;
-        Ok(result.extract_to(field, storage)?)
-    }
+    ) -> Result<(), Error>;
 }
 
 impl<P, R, F> ExtractorFn<P, R, NoArg> for F
@@ -115,111 +158,108 @@ where
 {
     const ARG_TYPE: ExtractArgType = ExtractArgType::None;
 
-    fn call(
+    fn extract<'a>(
         obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error> {
-        anyhow::ensure!(matches!(arg, ExtractFieldRequestArg::None));
+        plugin: &'a mut P,
+        field: &mut ss_plugin_extract_field,
+        request: ExtractRequest<'a, '_, '_, '_, P>,
+        storage: &bumpalo::Bump,
+    ) -> Result<(), Error> {
+        no_arg(unsafe { field.key_unchecked() })?;
 
         let func = obj as *const F;
-        unsafe { (*func)(plugin, req) }
+        let result = unsafe { (*func)(plugin, request) }?;
+        Ok(result.extract_to(field, storage)?)
     }
 }
 
-impl<P, R, F> ExtractorFn<P, R, IntArg> for F
+impl<P, F> ExtractorFn<P, &'static CStr, Borrowed<NoArg>> for F
 where
     P: ExtractPlugin,
-    R: Extract,
-    F: Fn(&mut P, ExtractRequest<P>, u64) -> Result<R, Error> + 'static,
+    F: for<'c> Fn(&mut P, ExtractRequest<'c, '_, '_, '_, P>) -> Result<&'c CStr, Error> + 'static,
 {
-    const ARG_TYPE: ExtractArgType = ExtractArgType::RequiredIndex;
+    const ARG_TYPE: ExtractArgType = ExtractArgType::None;
 
-    fn call(
+    fn extract<'a>(
         obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error> {
-        let ExtractFieldRequestArg::Int(arg) = arg else {
-            anyhow::bail!("Expected index argument, got {:?}", arg);
-        };
+        plugin: &'a mut P,
+        field: &mut ss_plugin_extract_field,
+        request: ExtractRequest<'a, '_, '_, '_, P>,
+        storage: &bumpalo::Bump,
+    ) -> Result<(), Error> {
+        no_arg(unsafe { field.key_unchecked() })?;
 
         let func = obj as *const F;
-        unsafe { (*func)(plugin, req, arg) }
+        let result = unsafe { (*func)(plugin, request) }?;
+        Ok(result.extract_to(field, storage)?)
     }
 }
 
-impl<P, R, F> ExtractorFn<P, R, OptIntArg> for F
-where
-    P: ExtractPlugin,
-    R: Extract,
-    F: Fn(&mut P, ExtractRequest<P>, Option<u64>) -> Result<R, Error> + 'static,
-{
-    const ARG_TYPE: ExtractArgType = ExtractArgType::OptionalIndex;
+macro_rules! extractor_fn_with_arg {
+    ($marker:ty, $arg_ty:ty, $arg_type:expr, $convert:ident) => {
+        impl<P, R, F> ExtractorFn<P, R, $marker> for F
+        where
+            P: ExtractPlugin,
+            R: Extract,
+            F: Fn(&mut P, ExtractRequest<P>, $arg_ty) -> Result<R, Error> + 'static,
+        {
+            const ARG_TYPE: ExtractArgType = $arg_type;
 
-    fn call(
-        obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error> {
-        let arg = match arg {
-            ExtractFieldRequestArg::Int(arg) => Some(arg),
-            ExtractFieldRequestArg::None => None,
-            _ => anyhow::bail!("Expected index argument, got {:?}", arg),
-        };
+            fn extract<'a>(
+                obj: *const (),
+                plugin: &'a mut P,
+                field: &mut ss_plugin_extract_field,
+                request: ExtractRequest<'a, '_, '_, '_, P>,
+                storage: &bumpalo::Bump,
+            ) -> Result<(), Error> {
+                let arg = $convert(unsafe { field.key_unchecked() })?;
 
-        let func = obj as *const F;
-        unsafe { (*func)(plugin, req, arg) }
-    }
-}
+                let func = obj as *const F;
+                let result = unsafe { (*func)(plugin, request, arg) }?;
+                Ok(result.extract_to(field, storage)?)
+            }
+        }
 
-impl<P, R, F> ExtractorFn<P, R, StringArg> for F
-where
-    P: ExtractPlugin,
-    R: Extract,
-    F: Fn(&mut P, ExtractRequest<P>, &CStr) -> Result<R, Error> + 'static,
-{
-    const ARG_TYPE: ExtractArgType = ExtractArgType::RequiredKey;
+        impl<P, F> ExtractorFn<P, &'static CStr, Borrowed<$marker>> for F
+        where
+            P: ExtractPlugin,
+            F: for<'c> Fn(
+                    &mut P,
+                    ExtractRequest<'c, '_, '_, '_, P>,
+                    $arg_ty,
+                ) -> Result<&'c CStr, Error>
+                + 'static,
+        {
+            const ARG_TYPE: ExtractArgType = $arg_type;
 
-    fn call(
-        obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error> {
-        let ExtractFieldRequestArg::String(arg) = arg else {
-            anyhow::bail!("Expected key argument, got {:?}", arg);
-        };
+            fn extract<'a>(
+                obj: *const (),
+                plugin: &'a mut P,
+                field: &mut ss_plugin_extract_field,
+                request: ExtractRequest<'a, '_, '_, '_, P>,
+                storage: &bumpalo::Bump,
+            ) -> Result<(), Error> {
+                let arg = $convert(unsafe { field.key_unchecked() })?;
 
-        let func = obj as *const F;
-        unsafe { (*func)(plugin, req, arg) }
-    }
+                let func = obj as *const F;
+                let result = unsafe { (*func)(plugin, request, arg) }?;
+                Ok(result.extract_to(field, storage)?)
+            }
+        }
+    };
 }
 
-impl<P, R, F> ExtractorFn<P, R, OptStringArg> for F
-where
-    P: ExtractPlugin,
-    R: Extract,
-    F: Fn(&mut P, ExtractRequest<P>, Option<&CStr>) -> Result<R, Error> + 'static,
-{
-    const ARG_TYPE: ExtractArgType = ExtractArgType::OptionalKey;
-
-    fn call(
-        obj: *const (),
-        plugin: &mut P,
-        req: ExtractRequest<P>,
-        arg: ExtractFieldRequestArg,
-    ) -> Result<R, Error> {
-        let arg = match arg {
-            ExtractFieldRequestArg::String(arg) => Some(arg),
-            ExtractFieldRequestArg::None => None,
-            _ => anyhow::bail!("Expected key argument, got {:?}", arg),
-        };
-
-        let func = obj as *const F;
-        unsafe { (*func)(plugin, req, arg) }
-    }
-}
+extractor_fn_with_arg!(IntArg, u64, ExtractArgType::RequiredIndex, int_arg);
+extractor_fn_with_arg!(
+    OptIntArg,
+    Option<u64>,
+    ExtractArgType::OptionalIndex,
+    opt_int_arg
+);
+extractor_fn_with_arg!(StringArg, &CStr, ExtractArgType::RequiredKey, string_arg);
+extractor_fn_with_arg!(
+    OptStringArg,
+    Option<&CStr>,
+    ExtractArgType::OptionalKey,
+    opt_string_arg
+);
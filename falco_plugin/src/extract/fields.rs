@@ -7,7 +7,8 @@ use falco_plugin_api::{
     ss_plugin_field_type_FTYPE_STRING, ss_plugin_field_type_FTYPE_UINT64,
 };
 use num_derive::FromPrimitive;
-use std::ffi::{c_void, CString};
+use smallvec::SmallVec;
+use std::ffi::{c_void, CStr, CString};
 use std::net::IpAddr;
 use std::ptr::null_mut;
 use std::time::Duration;
@@ -34,6 +35,12 @@ pub enum ExtractFieldTypeId {
     IpNet = ss_plugin_field_type_FTYPE_IPNET,
 }
 
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be returned from a field extractor",
+    label = "unsupported field type",
+    note = "supported types are `u64`, `bool`, `CString` (or `&CStr`), `Duration`, `SystemTime`, `IpAddr` and `IpNet`",
+    note = "each of these can also be wrapped in `Vec<_>` or `SmallVec<[_; N]>` (for list fields) and/or `Option<_>`"
+)]
 pub trait Extract {
     const IS_LIST: bool;
     const TYPE_ID: ExtractFieldTypeId;
@@ -244,6 +251,45 @@ macro_rules! extract {
                 Ok(())
             }
         }
+        impl<const N: usize> Extract for Option<SmallVec<[$ty; N]>> {
+            const IS_LIST: bool = true;
+            const TYPE_ID: ExtractFieldTypeId = $type_id;
+
+            fn extract_to(
+                &self,
+                req: &mut ss_plugin_extract_field,
+                storage: &bumpalo::Bump,
+            ) -> Result<(), std::io::Error> {
+                match &self {
+                    Some(val) => {
+                        let (buf, len) = $strategy_mod::extract_many(val.as_slice(), storage)?;
+                        req.res.u64_ = buf as *mut _;
+                        req.res_len = len;
+                    }
+                    None => {
+                        req.res.u64_ = null_mut();
+                        req.res_len = 0;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl<const N: usize> Extract for SmallVec<[$ty; N]> {
+            const IS_LIST: bool = true;
+            const TYPE_ID: ExtractFieldTypeId = $type_id;
+
+            fn extract_to(
+                &self,
+                req: &mut ss_plugin_extract_field,
+                storage: &bumpalo::Bump,
+            ) -> Result<(), std::io::Error> {
+                let (buf, len) = $strategy_mod::extract_many(self.as_slice(), storage)?;
+                req.res.u64_ = buf as *mut _;
+                req.res_len = len;
+                Ok(())
+            }
+        }
     };
 }
 
@@ -252,5 +298,6 @@ extract!(Duration: direct => ExtractFieldTypeId::RelTime);
 extract!(SystemTime: direct => ExtractFieldTypeId::AbsTime);
 extract!(bool: direct => ExtractFieldTypeId::Bool);
 extract!(CString: by_ref => ExtractFieldTypeId::String);
+extract!(&CStr: by_ref => ExtractFieldTypeId::String);
 extract!(IpAddr: by_bytebuf => ExtractFieldTypeId::IpAddr);
 extract!(IpNet: by_bytebuf => ExtractFieldTypeId::IpNet);
@@ -85,19 +85,27 @@
 //!
 //! See the [`ExtractPlugin`] trait documentation for details.
 
+use crate::base::deadline::DeadlinePolicy;
 use crate::base::Plugin;
+use crate::event::PluginEventPayload;
 use crate::extract::wrappers::ExtractPluginExported;
 use crate::tables::LazyTableReader;
 use falco_event::events::{AnyEventPayload, RawEvent};
-use falco_plugin_api::{ss_plugin_extract_field, ss_plugin_extract_value_offsets};
+use falco_plugin_api::{
+    ss_plugin_extract_field, ss_plugin_extract_value_offsets, ss_plugin_field_extract_input,
+};
 use std::any::TypeId;
+use std::cell::OnceCell;
 use std::collections::BTreeMap;
 use std::ffi::{CStr, CString};
 use std::ops::Range;
 use std::sync::Mutex;
+use std::time::Duration;
 
+pub(crate) mod cache;
 mod extractor_fn;
 mod fields;
+pub(crate) mod raw_input;
 mod schema;
 #[doc(hidden)]
 pub mod wrappers;
@@ -177,6 +185,12 @@ pub struct ExtractRequest<'c, 'e, 'r, 't, P: ExtractPlugin> {
     /// The event being processed
     pub event: &'e EventInput<'r, P::Event<'r>>,
 
+    /// The event, parsed on first use by [`ExtractRequest::parsed_event`]
+    parsed_event: &'c OnceCell<P::Event<'r>>,
+
+    /// The raw extraction input, see [`ExtractRequest::raw_extract_input`]
+    raw_input: Option<&'t ss_plugin_field_extract_input>,
+
     /// An interface to access tables exposed from Falco core and other plugins
     ///
     /// See [`crate::tables`] for details.
@@ -194,6 +208,111 @@ pub struct ExtractRequest<'c, 'e, 'r, 't, P: ExtractPlugin> {
     ///
     /// **Note**: range support is optional, and this field can be ignored.
     pub offset: &'c mut ExtractByteRange,
+
+    /// An arena for temporary allocations
+    ///
+    /// Anything allocated here stays valid until the extraction callback returns to the
+    /// framework (i.e. across all fields extracted from the same event in a single call)
+    /// and is freed in bulk afterward, so it's a cheap place for scratch buffers that
+    /// would otherwise need a heap allocation per field.
+    ///
+    /// String fields can also be returned straight from the arena, as a `&CStr` borrowed
+    /// for the lifetime of the request:
+    ///
+    /// ```ignore
+    /// fn extract_upper<'c>(&mut self, req: ExtractRequest<'c, '_, '_, '_, Self>)
+    ///     -> Result<&'c CStr, Error> {
+    ///     let mut buf = bumpalo::collections::Vec::new_in(req.storage);
+    ///     // ... fill `buf`, including the trailing NUL
+    ///     Ok(CStr::from_bytes_with_nul(buf.into_bump_slice())?)
+    /// }
+    /// ```
+    pub storage: &'c bumpalo::Bump,
+}
+
+impl<'r, P: ExtractPlugin> ExtractRequest<'_, '_, 'r, '_, P> {
+    /// # Get the parsed event
+    ///
+    /// This is equivalent to [`EventInput::event`], except that the event is only parsed
+    /// the first time any extractor asks for it. All the other fields extracted from the same
+    /// event (in a single batch) get a reference to the already parsed value.
+    ///
+    /// This is especially useful for plugins extracting many fields from their own plugin
+    /// events, where [`ExtractPlugin::Event`] is e.g. `Event<PluginEvent<MyPayload>>`,
+    /// so that the payload only gets deserialized once.
+    pub fn parsed_event(&self) -> anyhow::Result<&P::Event<'r>>
+    where
+        for<'b> P::Event<'r>: TryFrom<&'b RawEvent<'r>>,
+        for<'b> <P::Event<'r> as TryFrom<&'b RawEvent<'r>>>::Error:
+            std::error::Error + Send + Sync + 'static,
+    {
+        if let Some(event) = self.parsed_event.get() {
+            return Ok(event);
+        }
+
+        let event = self.event.event()?;
+        Ok(self.parsed_event.get_or_init(|| event))
+    }
+
+    /// # Get the payload of the parsed plugin event
+    ///
+    /// For plugins extracting fields from plugin events, with [`ExtractPlugin::Event`] set
+    /// to `Event<PluginEvent<MyPayload>>`, this returns the event data already deserialized
+    /// into `MyPayload`. Like with [`ExtractRequest::parsed_event`], the payload is only
+    /// deserialized once per event, no matter how many fields are extracted from it.
+    pub fn event_payload(&self) -> anyhow::Result<&<P::Event<'r> as PluginEventPayload>::Payload>
+    where
+        P::Event<'r>: PluginEventPayload,
+        for<'b> P::Event<'r>: TryFrom<&'b RawEvent<'r>>,
+        for<'b> <P::Event<'r> as TryFrom<&'b RawEvent<'r>>>::Error:
+            std::error::Error + Send + Sync + 'static,
+    {
+        Ok(self.parsed_event()?.payload())
+    }
+
+    /// # Get the last error reported by the plugin framework
+    ///
+    /// If a call into the Falco plugin API (e.g. a table access) failed, this returns
+    /// the error message the framework stored for it.
+    pub fn owner_last_error(&self) -> Option<String> {
+        self.table_reader.last_error.get()
+    }
+
+    /// # Get the source of the event being processed
+    ///
+    /// See [`EventInput::source`] for details.
+    pub fn event_source(&self) -> Option<&CStr> {
+        self.event.source()
+    }
+
+    /// # Get the ID of the plugin that generated the event being processed
+    ///
+    /// See [`EventInput::plugin_id`] for details.
+    pub fn event_plugin_id(&self) -> anyhow::Result<Option<u32>> {
+        self.event.plugin_id()
+    }
+
+    /// # Get the raw extraction input
+    ///
+    /// This is an escape hatch for advanced use cases, exposing the whole input structure
+    /// passed to the plugin by the framework: the owner pointer, the full list of fields
+    /// requested in this batch (`num_fields`/`fields`) and the vtables. You can use it e.g.
+    /// to optimize across multiple requested fields, or to call newer plugin API functions
+    /// the SDK does not wrap yet.
+    ///
+    /// **Note**: the structure describes the request exactly as received from the framework.
+    /// With [`ExtractPlugin::CACHE_EXTRACTED_VALUES`] enabled, some of the requested fields
+    /// may have been served from the cache, so they won't be passed to any extractor.
+    ///
+    /// Getting the structure is safe, but dereferencing any of the pointers inside is not.
+    /// In particular, the `fields` array is being filled in while the extraction is in progress,
+    /// so do not modify it, and do not keep any pointers past the end of the extractor call.
+    ///
+    /// Returns `None` if [`ExtractPlugin::extract_fields`] was not called by the SDK
+    /// on behalf of the framework.
+    pub fn raw_extract_input(&self) -> Option<&ss_plugin_field_extract_input> {
+        self.raw_input
+    }
 }
 
 /// Support for field extraction plugins
@@ -214,6 +333,10 @@ where
     /// ```
     /// type Event<'a> = falco_event::events::RawEvent<'a>;
     /// ```
+    ///
+    /// For plugins working on their own plugin events, this also serves as the payload type:
+    /// with `type Event<'a> = Event<PluginEvent<MyPayload>>`, the extractors can get the
+    /// deserialized payload from [`ExtractRequest::event_payload`].
     type Event<'a>: AnyEventPayload + TryFrom<&'a RawEvent<'a>>;
 
     /// The extraction context
@@ -225,8 +348,10 @@ where
     /// If you do not need a context to share between extracting fields of the same event, use `()`
     /// as the type.
     ///
-    /// Since the context is created using the [`Default`] trait, you may prefer to use an Option
-    /// wrapping the actual context type:
+    /// The context is created once per extraction batch by [`ExtractPlugin::make_context`],
+    /// which you can override to do the shared preprocessing up front. The default implementation
+    /// uses the [`Default`] trait, so if only some of your fields need the context, you may prefer
+    /// to build it lazily instead, using an Option wrapping the actual context type:
     ///
     /// ```ignore
     /// impl ExtractPlugin for MyPlugin {
@@ -272,7 +397,11 @@ where
     /// - [`std::net::IpAddr`]
     /// - [`falco_event::types::IpNet`]
     ///
-    /// and `A` is the argument to the field extraction:
+    /// List fields usually hold just a few values, so instead of a [`Vec`], you can also return
+    /// a [`SmallVec<[T; N]>`](smallvec::SmallVec) (with any inline capacity `N`), which avoids
+    /// a heap allocation as long as the list fits inline.
+    ///
+    /// `A` is the argument to the field extraction:
     ///
     /// | Argument declaration | `field` lookup | `field[5]` lookup | `field[foo]` lookup |
     /// |----------------------|----------------|-------------------|---------------------|
@@ -342,8 +471,103 @@ where
     ///# plugin!(SampleExtractPlugin);
     ///# extract_plugin!(SampleExtractPlugin);
     /// ```
+    ///
+    /// ## Missing and unavailable values
+    ///
+    /// If a field does not apply to a particular event (e.g. the event does not carry the data
+    /// the field describes), return `Ok(None)` from an extractor returning an `Option<_>`.
+    /// The framework treats the field as absent from the event.
+    ///
+    /// If the value should exist, but cannot be obtained right now (e.g. it depends on a table
+    /// entry that has not been populated yet, or on a temporarily unreachable backend), do not
+    /// return `Ok(None)`, as rules would then silently treat the field as absent. Instead, return
+    /// an error with [`FailureReason::Timeout`](crate::FailureReason::Timeout) attached:
+    /// ```no_run
+    ///# use falco_plugin::anyhow;
+    ///# use falco_plugin::FailureReason;
+    ///# fn extract_metadata() -> Result<Option<u64>, anyhow::Error> {
+    /// Err(anyhow::anyhow!("metadata not available yet").context(FailureReason::Timeout))
+    ///# }
+    /// ```
+    ///
+    /// The extraction then fails with `SS_PLUGIN_TIMEOUT` (instead of `SS_PLUGIN_FAILURE`,
+    /// used for all other errors). Note that the framework does not currently handle the two
+    /// return codes differently for field extraction, so this does not cause a retry by itself:
+    /// both make the extraction fail. Nothing from a failed
+    /// extraction gets cached (see [`ExtractPlugin::CACHE_EXTRACTED_VALUES`]), so the next request
+    /// for the field calls the extractor again.
+    ///
+    /// Field names must be unique within a plugin (this is checked at compile time). They also
+    /// must not collide with the fields of any other plugin loaded into the same framework,
+    /// so they should start with a prefix specific to your plugin (see also
+    /// [`ExtractPlugin::FIELD_PREFIX`]).
     const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>];
 
+    /// # A prefix for all field names
+    ///
+    /// This string is prepended to the names of all fields from [`ExtractPlugin::EXTRACT_FIELDS`]
+    /// when reporting them to the framework, so that you can namespace all of your fields
+    /// in one place (or change the namespace easily, e.g. when the same extractors are used
+    /// in several plugins, each with their own [`ExtractPlugin::FIELD_PREFIX`]):
+    ///
+    /// ```ignore
+    /// const FIELD_PREFIX: &'static str = "my_plugin.";
+    /// const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+    ///     field("sample", &Self::extract_sample), // available as `my_plugin.sample`
+    /// ];
+    /// ```
+    ///
+    /// The default is an empty string (no prefix).
+    const FIELD_PREFIX: &'static str = "";
+
+    /// Cache extracted values for the duration of an event
+    ///
+    /// When Falco evaluates many rules against the same event, the same field (with the same
+    /// argument) may be requested several times. If this constant is set to `true`, the SDK
+    /// remembers the values extracted from the current event (keyed by the event number,
+    /// pointer and timestamp, as well as the field and argument) and serves repeated requests from the cache, without calling
+    /// the extractor function again.
+    ///
+    /// This is worth enabling if some of your fields are expensive to extract. Since the cached
+    /// values are only dropped when a different event arrives, your extractors
+    /// must return the same value every time they're called for the same event.
+    ///
+    /// The default is `false` (no caching).
+    const CACHE_EXTRACTED_VALUES: bool = false;
+
+    /// # Deadline for field extraction
+    ///
+    /// If set, extracting the fields requested for an event taking longer than this is logged
+    /// and counted in a metric and handled according to [`ExtractPlugin::EXTRACT_DEADLINE_POLICY`].
+    /// See [`crate::base::deadline`] for details.
+    ///
+    /// The default is `None` (no deadline).
+    const EXTRACT_DEADLINE: Option<Duration> = None;
+
+    /// # What to do when [`ExtractPlugin::EXTRACT_DEADLINE`] is exceeded
+    ///
+    /// With [`DeadlinePolicy::Fail`], an overrunning extraction fails.
+    ///
+    /// The default is [`DeadlinePolicy::Report`] (the extracted values are used as usual).
+    const EXTRACT_DEADLINE_POLICY: DeadlinePolicy = DeadlinePolicy::Report;
+
+    /// Create the extraction context for an event
+    ///
+    /// This method is called once per extraction batch (i.e. once for every set of fields
+    /// requested from the same event together), before any extractor function runs.
+    /// The returned value is available to all extractors as [`ExtractRequest::context`].
+    ///
+    /// The default implementation returns [`Default::default()`]. Override it if all (or most)
+    /// of your fields need the same preprocessing of the event, e.g. parsing the payload
+    /// or looking up a table entry. If it returns an error, the whole extraction fails.
+    fn make_context<'a>(
+        &mut self,
+        _event_input: &EventInput<'a, Self::Event<'a>>,
+        _table_reader: &LazyTableReader,
+    ) -> Result<Self::ExtractContext, anyhow::Error> {
+        Ok(Default::default())
+    }
+
     /// Generate the field schema for the Falco plugin framework
     ///
     /// The default implementation inspects all fields from [`Self::EXTRACT_FIELDS`] and generates
@@ -390,7 +614,11 @@ where
         offsets: Option<&mut ss_plugin_extract_value_offsets>,
         storage: &'a bumpalo::Bump,
     ) -> Result<(), anyhow::Error> {
-        let mut context = Self::ExtractContext::default();
+        let mut context = self.make_context(event_input, table_reader)?;
+        let parsed_event = OnceCell::new();
+        // SAFETY: the input is only borrowed for the duration of this call, which is within
+        // the scope entered by the extraction wrapper (if any)
+        let raw_input = unsafe { raw_input::current() };
 
         let (mut offset_vec, mut length_vec) = if offsets.is_some() {
             (
@@ -423,8 +651,11 @@ where
             let request = ExtractRequest::<Self> {
                 context: &mut context,
                 event: event_input,
+                parsed_event: &parsed_event,
+                raw_input,
                 table_reader,
                 offset: &mut offset,
+                storage,
             };
 
             info.func.call(self, req, request, storage)?;
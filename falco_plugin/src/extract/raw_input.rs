@@ -0,0 +1,37 @@
+use falco_plugin_api::ss_plugin_field_extract_input;
+use std::cell::Cell;
+
+thread_local! {
+    static RAW_EXTRACT_INPUT: Cell<*const ss_plugin_field_extract_input> =
+        const { Cell::new(std::ptr::null()) };
+}
+
+/// Make the raw input of a field extraction call available to [`crate::extract::ExtractRequest`]
+///
+/// The input stays available on the current thread until the returned guard is dropped.
+pub(crate) struct RawExtractInputScope {
+    prev: *const ss_plugin_field_extract_input,
+}
+
+impl RawExtractInputScope {
+    pub(crate) fn enter(input: &ss_plugin_field_extract_input) -> Self {
+        Self {
+            prev: RAW_EXTRACT_INPUT.replace(input),
+        }
+    }
+}
+
+impl Drop for RawExtractInputScope {
+    fn drop(&mut self) {
+        RAW_EXTRACT_INPUT.set(self.prev);
+    }
+}
+
+/// Get the raw input of the field extraction call in progress on the current thread
+///
+/// # Safety
+///
+/// The returned reference must not outlive the [`RawExtractInputScope`] it came from
+pub(crate) unsafe fn current<'a>() -> Option<&'a ss_plugin_field_extract_input> {
+    unsafe { RAW_EXTRACT_INPUT.get().as_ref() }
+}
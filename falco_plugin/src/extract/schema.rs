@@ -76,6 +76,51 @@ fn is_false(b: &bool) -> bool {
     !*b
 }
 
+fn serialize_field_name<P: ExtractPlugin, S: Serializer>(
+    name: &&'static str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    if P::FIELD_PREFIX.is_empty() {
+        serializer.serialize_str(name)
+    } else {
+        serializer.serialize_str(&format!("{}{}", P::FIELD_PREFIX, name))
+    }
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() {
+        return false;
+    }
+
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Check whether any two fields share the same name
+pub(crate) const fn has_duplicate_field_names<P: ExtractPlugin>(
+    fields: &[ExtractFieldInfo<P>],
+) -> bool {
+    let mut i = 0;
+    while i < fields.len() {
+        let mut j = i + 1;
+        while j < fields.len() {
+            if str_eq(fields[i].name, fields[j].name) {
+                return true;
+            }
+            j += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
 /// # A description of an extracted field
 ///
 /// You should create instances of this struct by calling [`field`].
@@ -84,6 +129,9 @@ fn is_false(b: &bool) -> bool {
 #[derive(Serialize)]
 pub struct ExtractFieldInfo<P: ExtractPlugin> {
     /// the name of the extracted field, generally of the form `<plugin>.<field>`
+    ///
+    /// The name reported to the framework is prefixed with [`ExtractPlugin::FIELD_PREFIX`].
+    #[serde(serialize_with = "serialize_field_name::<P, _>")]
     pub name: &'static str,
     #[serde(rename = "type")]
     #[serde(serialize_with = "serialize_field_type")]
@@ -1,8 +1,14 @@
+use crate::base::deadline::Watchdog;
 use crate::base::wrappers::PluginWrapper;
 use crate::error::ffi_result::FfiResult;
 use crate::event::EventInput;
+use crate::extract::raw_input::RawExtractInputScope;
+use crate::extract::schema::has_duplicate_field_names;
 use crate::extract::ExtractPlugin;
+use crate::tables::EntryCacheScope;
 use crate::tables::LazyTableReader;
+use crate::tables::ReadOnlyPhase;
+use crate::FailureReason;
 use falco_event::events::AnyEventPayload;
 use falco_plugin_api::plugin_api__bindgen_ty_2 as extract_plugin_api;
 use falco_plugin_api::ss_plugin_rc;
@@ -43,11 +49,17 @@ impl<T> ExtractPluginFallbackApi for T {}
 pub struct ExtractPluginApi<T>(std::marker::PhantomData<T>);
 
 impl<T: ExtractPlugin> ExtractPluginApi<T> {
-    pub const EXTRACT_API: extract_plugin_api = extract_plugin_api {
-        get_extract_event_types: Some(plugin_get_extract_event_types::<T>),
-        get_extract_event_sources: Some(plugin_get_extract_event_sources::<T>),
-        get_fields: Some(plugin_get_fields::<T>),
-        extract_fields: Some(plugin_extract_fields::<T>),
+    pub const EXTRACT_API: extract_plugin_api = {
+        if has_duplicate_field_names(T::EXTRACT_FIELDS) {
+            panic!("Field names in EXTRACT_FIELDS must be unique")
+        }
+
+        extract_plugin_api {
+            get_extract_event_types: Some(plugin_get_extract_event_types::<T>),
+            get_extract_event_sources: Some(plugin_get_extract_event_sources::<T>),
+            get_fields: Some(plugin_get_fields::<T>),
+            extract_fields: Some(plugin_extract_fields::<T>),
+        }
     };
 
     pub const IMPLEMENTS_EXTRACT: bool = true;
@@ -112,28 +124,52 @@ pub unsafe extern "C-unwind" fn plugin_extract_fields<T: ExtractPlugin>(
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
 
-        let fields =
-            std::slice::from_raw_parts_mut(extract_input.fields, extract_input.num_fields as usize);
-
         let Some(reader_ext) = extract_input.table_reader_ext.as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
 
-        let offsets = extract_input.value_offsets.as_mut();
-
         let table_reader = LazyTableReader::new(reader_ext, actual_plugin.last_error.clone());
-
-        plugin.field_storage.reset();
-        actual_plugin
-            .plugin
-            .extract_fields(
+        let _raw_input = RawExtractInputScope::enter(extract_input);
+        let read_only = ReadOnlyPhase::enter("field extraction");
+        let _entry_cache = EntryCacheScope::enter();
+        let watchdog = Watchdog::start(
+            "extract_fields",
+            T::EXTRACT_DEADLINE,
+            T::EXTRACT_DEADLINE_POLICY,
+        );
+
+        let res = if !T::CACHE_EXTRACTED_VALUES {
+            let fields = std::slice::from_raw_parts_mut(
+                extract_input.fields,
+                extract_input.num_fields as usize,
+            );
+            let offsets = extract_input.value_offsets.as_mut();
+
+            plugin.field_storage.reset();
+            actual_plugin.plugin.extract_fields(
                 &event_input,
                 &table_reader,
                 fields,
                 offsets,
                 &plugin.field_storage,
             )
-            .rc(&mut plugin.error_buf)
+        } else {
+            if plugin.extract_cache.start_event(&event_input.0) {
+                plugin.field_storage.reset();
+            }
+            plugin.extract_cache.extract_fields(
+                &mut actual_plugin.plugin,
+                &event_input,
+                extract_input,
+                &table_reader,
+                &plugin.field_storage,
+            )
+        };
+
+        drop(read_only);
+        let deadline_result = watchdog.finish(&mut plugin.deadline_metrics, FailureReason::Failure);
+        let res = res.and(deadline_result);
+        res.rc(&mut plugin.error_buf)
     }
 }
 
@@ -146,6 +182,10 @@ macro_rules! extract_plugin {
     ($ty:ty) => {
         unsafe impl $crate::extract::wrappers::ExtractPluginExported for $ty {}
 
+        // evaluate the API at compile time to run the checks on the field definitions
+        const _: falco_plugin::api::plugin_api__bindgen_ty_2 =
+            $crate::extract::wrappers::ExtractPluginApi::<$ty>::EXTRACT_API;
+
         $crate::wrap_ffi! {
             #[unsafe(no_mangle)]
             use $crate::extract::wrappers: <$ty>;
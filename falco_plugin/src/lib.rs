@@ -3,12 +3,20 @@
 #![warn(missing_debug_implementations)]
 #![deny(rustdoc::broken_intra_doc_links)]
 
+// let derive macros refer to `::falco_plugin` from within this crate
+extern crate self as falco_plugin;
+
 // reexport dependencies
 pub use anyhow;
+pub use bumpalo;
 pub use falco_plugin_api as api;
 pub use phf;
 pub use schemars;
 pub use serde;
+pub use serde_json;
+pub use smallvec;
+#[cfg(feature = "tokio")]
+pub use tokio;
 
 pub use error::FailureReason;
 
@@ -20,5 +28,6 @@ pub mod extract;
 pub mod listen;
 pub mod parse;
 pub mod source;
+pub mod state;
 pub mod strings;
 pub mod tables;
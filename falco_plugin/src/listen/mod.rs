@@ -9,7 +9,7 @@
 //! Capture listening plugins receive a reference to a thread pool, which can be used to submit
 //! "routines" (tasks running in a separate thread, effectively).
 //!
-//! *Note* there is no built-in mechanism to stop a running routine, so you should avoid doing this
+//! *Note* there is no built-in mechanism to interrupt a running routine, so you should avoid doing this
 //! in the routine:
 //! ```ignore
 //! loop {
@@ -25,9 +25,40 @@
 //! std::ops::ControlFlow::Continue(())
 //! ```
 //!
+//! For periodic or delayed work, use [`ThreadPool::subscribe_every`] or [`ThreadPool::subscribe_after`]
+//! rather than sleeping in the routine yourself.
+//!
 //! If you insist on using an infinite loop inside a routine, consider using e.g.
 //! [`BackgroundTask`](crate::async_event::BackgroundTask) to manage the lifetime of the routine.
 //!
+//! To stop routines deterministically (e.g. in [`CaptureListenPlugin::capture_close`]), submit them
+//! with [`ThreadPool::subscribe_cancellable`] and call [`RoutineHandle::stop_and_join`] on
+//! the returned handle. This waits for the current iteration to complete, so the routine is
+//! guaranteed not to run afterwards.
+//!
+//! If the routines only need to run while the capture is open, you can let the SDK manage them
+//! instead, using [`CaptureListenInput::spawn`]. Such routines are stopped automatically when
+//! the capture is closed, so you don't need to store their handles or implement
+//! [`CaptureListenPlugin::capture_close`] at all:
+//! ```ignore
+//! fn capture_open(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+//!     listen_input.spawn(|| {
+//!         do_something();
+//!         std::ops::ControlFlow::Continue(())
+//!     })?;
+//!     Ok(())
+//! }
+//! ```
+//!
+//! ## Persisting tables
+//!
+//! Since capture listening plugins get notified when a capture stops, they're a natural place
+//! to save the state of the plugin. [`TableSnapshot`] can save the contents of an exported table
+//! to a file in [`CaptureListenPlugin::capture_close`] and load them back
+//! in [`CaptureListenPlugin::capture_open`].
+//!
+//! ## Implementing the plugin
+//!
 //! For your plugin to support event parsing, you will need to implement the [`CaptureListenPlugin`]
 //! trait and invoke the [`capture_listen_plugin`](crate::capture_listen_plugin) macro, for example:
 //!
@@ -93,12 +124,16 @@ use crate::listen::wrappers::CaptureListenPluginExported;
 use crate::tables::LazyTableReader;
 use crate::tables::LazyTableWriter;
 use falco_plugin_api::ss_plugin_capture_listen_input;
+use std::cell::RefCell;
+use std::ops::ControlFlow;
 
 mod routine;
+mod snapshot;
 #[doc(hidden)]
 pub mod wrappers;
 
-pub use routine::{Routine, ThreadPool};
+pub use routine::{CancellationToken, Routine, RoutineHandle, ThreadPool};
+pub use snapshot::TableSnapshot;
 
 /// Support for capture listening plugins
 pub trait CaptureListenPlugin: Plugin + CaptureListenPluginExported {
@@ -109,8 +144,13 @@ pub trait CaptureListenPlugin: Plugin + CaptureListenPluginExported {
 
     /// # Capture close notification
     ///
-    /// This method gets called whenever the capture is stopped
-    fn capture_close(&mut self, listen_input: &CaptureListenInput) -> Result<(), anyhow::Error>;
+    /// This method gets called whenever the capture is stopped. Routines started with
+    /// [`CaptureListenInput::spawn`] are stopped automatically after it returns.
+    ///
+    /// The default implementation does nothing.
+    fn capture_close(&mut self, _listen_input: &CaptureListenInput) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
 }
 
 /// # The input to a capture listening plugin
@@ -118,6 +158,12 @@ pub trait CaptureListenPlugin: Plugin + CaptureListenPluginExported {
 /// It has two fields containing the vtables needed to access tables imported through
 /// the [tables API](`crate::tables`), as well as a [`ThreadPool`] to run tasks
 /// in the background.
+///
+/// *Note*: the plugin API (as of version 3.12) does not pass any information about the capture
+/// itself (like machine info or whether it's a live capture or a capture file) to capture
+/// listening plugins, so it's not available here either. If your plugin needs to behave
+/// differently when replaying a capture file, let the user configure it via the plugin
+/// config or open parameters.
 #[derive(Debug)]
 pub struct CaptureListenInput<'t> {
     /// Accessors to the thread pool for submitting routines to
@@ -126,6 +172,8 @@ pub struct CaptureListenInput<'t> {
     pub reader: LazyTableReader<'t>,
     /// Accessors to modify table entries
     pub writer: LazyTableWriter<'t>,
+
+    managed_routines: RefCell<Vec<RoutineHandle>>,
 }
 
 impl CaptureListenInput<'_> {
@@ -161,6 +209,29 @@ impl CaptureListenInput<'_> {
             thread_pool,
             reader,
             writer,
+            managed_routines: Default::default(),
         })
     }
+
+    /// # Run a task in a background thread until the capture is closed
+    ///
+    /// This works like [`ThreadPool::subscribe_cancellable`], except you don't need to keep
+    /// the [`RoutineHandle`]: the SDK keeps it for you and stops the routine (waiting for it
+    /// to finish) right after [`CaptureListenPlugin::capture_close`] returns. Errors
+    /// from stopping the routine are reported as errors from `capture_close`.
+    ///
+    /// Returns a [`CancellationToken`] you can use to stop the routine earlier.
+    pub fn spawn<F>(&self, mut func: F) -> Result<CancellationToken, anyhow::Error>
+    where
+        F: FnMut() -> ControlFlow<()> + Send + 'static,
+    {
+        let handle = self.thread_pool.subscribe_cancellable(move |_| func())?;
+        let token = handle.token();
+        self.managed_routines.borrow_mut().push(handle);
+        Ok(token)
+    }
+
+    pub(crate) fn take_managed_routines(&self) -> Vec<RoutineHandle> {
+        self.managed_routines.take()
+    }
 }
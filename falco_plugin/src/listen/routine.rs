@@ -4,7 +4,11 @@ use falco_plugin_api::{
     ss_plugin_bool, ss_plugin_owner_t, ss_plugin_rc, ss_plugin_routine_fn_t,
     ss_plugin_routine_state_t, ss_plugin_routine_t, ss_plugin_routine_vtable, ss_plugin_t,
 };
+use std::collections::BTreeMap;
 use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -36,6 +40,281 @@ impl Drop for Routine {
     }
 }
 
+/// The longest time a scheduled routine sleeps in a single iteration
+///
+/// The thread pool may run other routines on the same thread, so we can't just sleep until
+/// the routine is due.
+const SCHEDULE_SLICE: Duration = Duration::from_millis(10);
+
+/// Sleep until `deadline`, or for a single time slice, whichever comes first
+///
+/// Returns true if the deadline has been reached.
+fn wait_until(deadline: Instant) -> bool {
+    let now = Instant::now();
+    if now >= deadline {
+        return true;
+    }
+
+    std::thread::sleep((deadline - now).min(SCHEDULE_SLICE));
+    Instant::now() >= deadline
+}
+
+#[derive(Debug, Default)]
+struct RoutineState {
+    cancelled: bool,
+    running: bool,
+    finished: bool,
+    error: Option<anyhow::Error>,
+}
+
+#[derive(Debug, Default)]
+struct RoutineControl {
+    state: Mutex<RoutineState>,
+    idle: Condvar,
+}
+
+impl RoutineControl {
+    fn lock(&self) -> std::sync::MutexGuard<'_, RoutineState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Mark the routine as running, unless it has been cancelled
+    fn enter(&self) -> bool {
+        let mut state = self.lock();
+        if state.cancelled {
+            state.finished = true;
+            return false;
+        }
+        state.running = true;
+        true
+    }
+
+    /// Mark the routine as idle and decide whether it should be scheduled again
+    fn exit(&self, result: ControlFlow<Result<(), anyhow::Error>>) -> ControlFlow<()> {
+        let mut state = self.lock();
+        state.running = false;
+        match result {
+            ControlFlow::Continue(()) => {}
+            ControlFlow::Break(Ok(())) => state.finished = true,
+            ControlFlow::Break(Err(e)) => {
+                log::error!("Background routine failed: {e:#}");
+                state.finished = true;
+                state.error = Some(e);
+            }
+        }
+        if state.cancelled {
+            state.finished = true;
+        }
+        self.idle.notify_all();
+
+        if state.finished {
+            ControlFlow::Break(())
+        } else {
+            ControlFlow::Continue(())
+        }
+    }
+
+    fn wait_idle(&self) {
+        let mut state = self.lock();
+        while state.running {
+            state = self.idle.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+    }
+}
+
+type RoutineFn =
+    Box<dyn FnMut(&CancellationToken) -> ControlFlow<Result<(), anyhow::Error>> + Send>;
+
+/// The closure of a cancellable routine
+///
+/// The trampoline only gets a reference to this, so that [`RoutineHandle::stop_and_join`]
+/// can drop the closure while a late call from the thread pool may still hold on to the trampoline.
+struct RoutineSlot(Mutex<Option<RoutineFn>>);
+
+impl RoutineSlot {
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<RoutineFn>> {
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl std::fmt::Debug for RoutineSlot {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RoutineSlot").finish_non_exhaustive()
+    }
+}
+
+/// The part of a cancellable routine called by the thread pool
+#[derive(Debug)]
+struct Trampoline {
+    token: CancellationToken,
+    func: Arc<RoutineSlot>,
+}
+
+impl Trampoline {
+    fn run(&self) -> ControlFlow<()> {
+        if !self.token.0.enter() {
+            return ControlFlow::Break(());
+        }
+        let result = match self.func.lock().as_mut() {
+            Some(func) => func(&self.token),
+            None => ControlFlow::Break(Ok(())),
+        };
+        self.token.0.exit(result)
+    }
+}
+
+/// Trampolines of all cancellable routines, by id
+///
+/// The thread pool may still call a routine after it has been unsubscribed (if the call
+/// was already on its way), so instead of a pointer to the trampoline, it gets an id that
+/// is never reused. A late call finds the id gone and returns immediately, so trampolines
+/// can be freed as soon as their [`RoutineHandle`] is done with them.
+static TRAMPOLINES: Mutex<BTreeMap<usize, Arc<Trampoline>>> = Mutex::new(BTreeMap::new());
+static NEXT_TRAMPOLINE_ID: AtomicUsize = AtomicUsize::new(1);
+
+fn trampolines() -> std::sync::MutexGuard<'static, BTreeMap<usize, Arc<Trampoline>>> {
+    TRAMPOLINES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+unsafe extern "C-unwind" fn call_trampoline(
+    _plugin: *mut ss_plugin_t,
+    data: *mut ss_plugin_routine_state_t,
+) -> ss_plugin_bool {
+    let Some(trampoline) = trampolines().get(&data.addr()).cloned() else {
+        return 0;
+    };
+    match trampoline.run() {
+        ControlFlow::Continue(()) => 1,
+        ControlFlow::Break(()) => 0,
+    }
+}
+
+unsafe fn release_trampoline(data: *mut ss_plugin_routine_state_t) {
+    // drop the trampoline outside the lock
+    let trampoline = trampolines().remove(&data.addr());
+    drop(trampoline);
+}
+
+/// # A token to request cancellation of a routine
+///
+/// The token is checked automatically before and after every iteration of the routine
+/// started with [`ThreadPool::subscribe_cancellable`]. Routines doing a lot of work
+/// in a single iteration can also check it themselves, using [`CancellationToken::is_cancelled`].
+#[derive(Debug, Clone)]
+pub struct CancellationToken(Arc<RoutineControl>);
+
+impl CancellationToken {
+    /// Request the routine to stop
+    ///
+    /// The routine won't be called again, but an iteration that's currently running
+    /// is not interrupted.
+    pub fn cancel(&self) {
+        self.0.lock().cancelled = true;
+    }
+
+    /// Check whether cancellation has been requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.lock().cancelled
+    }
+}
+
+/// # A handle for a cancellable routine running in the background
+///
+/// This is what you get from [`ThreadPool::subscribe_cancellable`] (and the other `subscribe_*`
+/// methods returning it). Unlike [`Routine`],
+/// it knows whether the routine is currently running, so it can stop it deterministically
+/// using [`RoutineHandle::stop_and_join`] (typically from
+/// [`CaptureListenPlugin::capture_close`](`crate::listen::CaptureListenPlugin::capture_close`)).
+///
+/// If you drop the handle without calling [`RoutineHandle::stop_and_join`], the routine gets
+/// cancelled and its closure is dropped once the current iteration (if any) completes,
+/// without waiting for it.
+#[derive(Debug)]
+#[must_use]
+pub struct RoutineHandle {
+    routine: Option<Routine>,
+    func: Arc<RoutineSlot>,
+    token: CancellationToken,
+}
+
+impl RoutineHandle {
+    /// Get the cancellation token for this routine
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Request the routine to stop, without waiting for it
+    ///
+    /// See [`CancellationToken::cancel`] for details.
+    pub fn cancel(&self) {
+        self.token.cancel()
+    }
+
+    /// Check whether the routine has finished
+    ///
+    /// A routine is finished when it returned [`ControlFlow::Break`] (possibly with an error)
+    /// or noticed it has been cancelled. It won't be called again.
+    pub fn is_finished(&self) -> bool {
+        let state = self.token.0.lock();
+        state.finished || (state.cancelled && !state.running)
+    }
+
+    /// Take the error the routine failed with, if any
+    ///
+    /// Routines started with [`ThreadPool::subscribe_fallible`] stop when they return
+    /// an error. The error is logged and kept here until taken (or returned from
+    /// [`RoutineHandle::stop_and_join`]).
+    pub fn take_error(&self) -> Option<anyhow::Error> {
+        self.token.0.lock().error.take()
+    }
+
+    /// # Stop the routine and wait for it to finish
+    ///
+    /// Cancel the routine, remove it from the thread pool and wait until the current
+    /// iteration (if any) completes. After this method returns, the routine is guaranteed
+    /// not to be running and its closure is dropped.
+    ///
+    /// Returns the error the routine failed with, unless it was already taken with
+    /// [`RoutineHandle::take_error`].
+    ///
+    /// *Note*: calling this method from within the routine itself will deadlock.
+    pub fn stop_and_join(mut self, thread_pool: &ThreadPool) -> Result<(), anyhow::Error> {
+        self.token.cancel();
+        let Some(routine) = self.routine.take() else {
+            return Ok(());
+        };
+
+        if let Err(e) = thread_pool.unsubscribe(&routine) {
+            // the routine is cancelled, so it won't run again, but we can't tell
+            // whether the thread pool is done with it
+            std::mem::forget(routine);
+            return Err(e);
+        }
+
+        // once the current iteration (if any) completes, the closure is never called again
+        self.token.0.wait_idle();
+        drop(self.func.lock().take());
+
+        // any call still on its way finds the trampoline gone (see `TRAMPOLINES`)
+        drop(routine);
+
+        match self.take_error() {
+            Some(e) => Err(e),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for RoutineHandle {
+    fn drop(&mut self) {
+        if let Some(routine) = self.routine.take() {
+            self.token.cancel();
+            // an iteration in progress holds its own reference to the trampoline
+            drop(routine);
+        }
+    }
+}
+
 /// # Thread pool for managing background tasks
 ///
 /// The thread pool operates on "routines", which are effectively closures called repeatedly
@@ -132,6 +411,123 @@ impl ThreadPool {
         }
     }
 
+    /// Run a task in a background thread, with a handle to stop it
+    ///
+    /// The task receives a [`CancellationToken`] it can check during long iterations.
+    /// It stops being scheduled once it returns [`ControlFlow::Break`] or gets cancelled
+    /// via the token or the returned [`RoutineHandle`].
+    pub fn subscribe_cancellable<F>(&self, mut func: F) -> Result<RoutineHandle, anyhow::Error>
+    where
+        F: FnMut(&CancellationToken) -> ControlFlow<()> + Send + 'static,
+    {
+        self.subscribe_fallible(move |token| match func(token) {
+            ControlFlow::Continue(()) => ControlFlow::Continue(()),
+            ControlFlow::Break(()) => ControlFlow::Break(Ok(())),
+        })
+    }
+
+    /// Run a fallible task in a background thread, with a handle to stop it
+    ///
+    /// This works just like [`ThreadPool::subscribe_cancellable`], except the task can also
+    /// stop with an error, by returning `ControlFlow::Break(Err(e))`. The error gets logged
+    /// and can be retrieved later using [`RoutineHandle::take_error`]
+    /// or [`RoutineHandle::stop_and_join`].
+    pub fn subscribe_fallible<F>(&self, func: F) -> Result<RoutineHandle, anyhow::Error>
+    where
+        F: FnMut(&CancellationToken) -> ControlFlow<Result<(), anyhow::Error>> + Send + 'static,
+    {
+        let token = CancellationToken(Arc::default());
+        let func: RoutineFn = Box::new(func);
+        let slot = Arc::new(RoutineSlot(Mutex::new(Some(func))));
+
+        let id = NEXT_TRAMPOLINE_ID.fetch_add(1, Ordering::Relaxed);
+        trampolines().insert(
+            id,
+            Arc::new(Trampoline {
+                token: token.clone(),
+                func: Arc::clone(&slot),
+            }),
+        );
+        let state = std::ptr::without_provenance_mut(id);
+
+        let ptr = unsafe { (self.subscribe)(self.owner, Some(call_trampoline), state) };
+        if ptr.is_null() {
+            unsafe { release_trampoline(state) };
+            return Err(anyhow::anyhow!("Failed to subscribe function"))
+                .with_last_error(&self.last_error);
+        }
+
+        let routine = Routine {
+            routine: ptr,
+            state,
+            dtor: release_trampoline,
+        };
+
+        Ok(RoutineHandle {
+            routine: Some(routine),
+            func: slot,
+            token,
+        })
+    }
+
+    /// Run a task once in a background thread, after a delay
+    ///
+    /// The delay is measured from the time of this call. The task can be cancelled
+    /// before it runs via the returned [`RoutineHandle`].
+    pub fn subscribe_after<F>(
+        &self,
+        delay: Duration,
+        func: F,
+    ) -> Result<RoutineHandle, anyhow::Error>
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        let deadline = Instant::now() + delay;
+        let mut func = Some(func);
+        self.subscribe_cancellable(move |_| {
+            if !wait_until(deadline) {
+                return ControlFlow::Continue(());
+            }
+
+            if let Some(func) = func.take() {
+                func();
+            }
+            ControlFlow::Break(())
+        })
+    }
+
+    /// Run a task in a background thread every `interval`
+    ///
+    /// The first run happens one `interval` after this call. The task keeps getting scheduled
+    /// until it returns [`ControlFlow::Break`] or gets cancelled via the returned [`RoutineHandle`].
+    ///
+    /// If a run takes longer than `interval`, the missed runs are skipped rather than executed
+    /// back-to-back. Since the thread pool may be shared with other routines, the timing is only
+    /// approximate (within ~10 milliseconds, if no other routine blocks the thread pool).
+    pub fn subscribe_every<F>(
+        &self,
+        interval: Duration,
+        mut func: F,
+    ) -> Result<RoutineHandle, anyhow::Error>
+    where
+        F: FnMut() -> ControlFlow<()> + Send + 'static,
+    {
+        let mut deadline = Instant::now() + interval;
+        self.subscribe_cancellable(move |_| {
+            if !wait_until(deadline) {
+                return ControlFlow::Continue(());
+            }
+
+            let result = func();
+            deadline += interval;
+            let now = Instant::now();
+            if deadline < now {
+                deadline = now + interval;
+            }
+            result
+        })
+    }
+
     /// Cancel a task running in a background thread
     ///
     /// *Note*: this does not kill a running task, only prevent it from being scheduled again
@@ -143,3 +539,42 @@ impl ThreadPool {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_late_call_after_release() {
+        let token = CancellationToken(Arc::default());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let func: RoutineFn = {
+            let calls = Arc::clone(&calls);
+            Box::new(move |_| {
+                calls.fetch_add(1, Ordering::Relaxed);
+                ControlFlow::Continue(())
+            })
+        };
+        let slot = Arc::new(RoutineSlot(Mutex::new(Some(func))));
+
+        let id = NEXT_TRAMPOLINE_ID.fetch_add(1, Ordering::Relaxed);
+        trampolines().insert(
+            id,
+            Arc::new(Trampoline {
+                token,
+                func: Arc::clone(&slot),
+            }),
+        );
+        let state = std::ptr::without_provenance_mut(id);
+
+        unsafe {
+            assert_eq!(call_trampoline(std::ptr::null_mut(), state), 1);
+            release_trampoline(state);
+            assert_eq!(call_trampoline(std::ptr::null_mut(), state), 0);
+        }
+
+        assert_eq!(calls.load(Ordering::Relaxed), 1);
+        // the trampoline has been freed, so we hold the only reference to the closure
+        assert_eq!(Arc::strong_count(&slot), 1);
+    }
+}
@@ -0,0 +1,133 @@
+use crate::event::PayloadFormat;
+use crate::tables::export::traits::{Entry, TableMetadata};
+use crate::tables::export::{Table, TableMap, TableValue};
+use crate::tables::Key;
+use anyhow::Context;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::hash::Hash;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// # Persist table contents across captures
+///
+/// This helper saves the contents of an [exported table](`crate::tables::export`) to a file
+/// and loads them back, so that the table survives plugin restarts. The typical use is to call
+/// [`TableSnapshot::save`] from [`CaptureListenPlugin::capture_close`](`crate::listen::CaptureListenPlugin::capture_close`)
+/// and [`TableSnapshot::restore`] from [`CaptureListenPlugin::capture_open`](`crate::listen::CaptureListenPlugin::capture_open`):
+///
+/// ```ignore
+/// impl CaptureListenPlugin for MyPlugin {
+///     fn capture_open(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+///         self.snapshot.restore(&mut self.table)?;
+///         Ok(())
+///     }
+///
+///     fn capture_close(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+///         self.snapshot.save(&self.table)
+///     }
+/// }
+/// ```
+///
+/// The key and entry types must implement `serde::Serialize` and `serde::Deserialize`,
+/// with the same limitations as [`Table::dump_state`]. The file contains the same data as the events
+/// emitted by [`Table::dump_state`] (a single list of `[key, entry]` pairs).
+///
+/// Imported tables cannot be saved directly, since their fields are only accessible one at a time.
+/// Collect the data you need into a serializable type and use [`TableSnapshot::save_value`]
+/// and [`TableSnapshot::load_value`] instead.
+#[derive(Debug, Clone)]
+pub struct TableSnapshot {
+    path: PathBuf,
+    format: PayloadFormat,
+}
+
+impl TableSnapshot {
+    /// Create a snapshot stored at `path`, encoded using `format`
+    pub fn new(path: impl Into<PathBuf>, format: PayloadFormat) -> Self {
+        Self {
+            path: path.into(),
+            format,
+        }
+    }
+
+    /// Get the path to the snapshot file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// # Save the table contents to the snapshot file
+    ///
+    /// The file is replaced atomically, so a crash while saving leaves the previous
+    /// snapshot intact.
+    pub fn save<K, E, M>(&self, table: &Table<K, E, M>) -> Result<(), anyhow::Error>
+    where
+        K: Key + Ord + Serialize,
+        K: Borrow<<K as Key>::Borrowed>,
+        <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+        E: Entry + Serialize,
+        E::Metadata: TableMetadata,
+        M: TableMap<K, TableValue<E>>,
+    {
+        let data = match table.serialize_entries(self.format, usize::MAX)?.pop() {
+            Some(data) => data,
+            None => self.format.encode::<[()]>(&[])?,
+        };
+        self.write(&data)
+    }
+
+    /// # Load the table contents from the snapshot file
+    ///
+    /// The saved entries are inserted into the table, replacing any existing entries with
+    /// the same keys. A missing snapshot file is not an error (nothing gets restored).
+    ///
+    /// Returns the number of restored entries.
+    pub fn restore<K, E, M>(&self, table: &mut Table<K, E, M>) -> Result<usize, anyhow::Error>
+    where
+        K: Key + Ord + DeserializeOwned,
+        K: Borrow<<K as Key>::Borrowed>,
+        <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+        E: Entry + DeserializeOwned,
+        E::Metadata: TableMetadata,
+        M: TableMap<K, TableValue<E>>,
+    {
+        match self.load_value::<Vec<(K, E)>>()? {
+            Some(entries) => table.restore_entries(entries),
+            None => Ok(0),
+        }
+    }
+
+    /// Save an arbitrary value to the snapshot file
+    pub fn save_value<T: Serialize + ?Sized>(&self, value: &T) -> Result<(), anyhow::Error> {
+        let data = self.format.encode(value)?;
+        self.write(&data)
+    }
+
+    /// Load a value saved with [`TableSnapshot::save_value`]
+    ///
+    /// Returns `None` if the snapshot file does not exist.
+    pub fn load_value<T: DeserializeOwned>(&self) -> Result<Option<T>, anyhow::Error> {
+        let data = match std::fs::read(&self.path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => {
+                return Err(e).with_context(|| format!("reading snapshot {}", self.path.display()))
+            }
+        };
+
+        self.format
+            .decode(&data)
+            .map(Some)
+            .with_context(|| format!("decoding snapshot {}", self.path.display()))
+    }
+
+    fn write(&self, data: &[u8]) -> Result<(), anyhow::Error> {
+        let mut tmp_path = self.path.clone().into_os_string();
+        tmp_path.push(".tmp");
+
+        std::fs::write(&tmp_path, data)
+            .and_then(|()| std::fs::rename(&tmp_path, &self.path))
+            .with_context(|| format!("writing snapshot {}", self.path.display()))
+    }
+}
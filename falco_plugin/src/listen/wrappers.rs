@@ -1,4 +1,5 @@
 use crate::base::wrappers::PluginWrapper;
+use crate::base::LifecycleEvent;
 use crate::error::ffi_result::FfiResult;
 use crate::listen::CaptureListenInput;
 use crate::listen::CaptureListenPlugin;
@@ -66,7 +67,18 @@ pub unsafe extern "C-unwind" fn plugin_capture_open<T: CaptureListenPlugin>(
         listen_input
     };
 
-    if let Err(e) = actual_plugin.plugin.capture_open(&listen_input) {
+    let res = actual_plugin
+        .plugin
+        .capture_open(&listen_input)
+        .and_then(|()| {
+            plugin
+                .hooks
+                .fire(LifecycleEvent::CaptureOpen, &mut actual_plugin.plugin)
+        });
+    plugin
+        .listen_routines
+        .extend(listen_input.take_managed_routines());
+    if let Err(e) = res {
         e.set_last_error(&mut plugin.error_buf);
         return e.status_code();
     }
@@ -98,7 +110,27 @@ pub unsafe extern "C-unwind" fn plugin_capture_close<T: CaptureListenPlugin>(
         listen_input
     };
 
-    if let Err(e) = actual_plugin.plugin.capture_close(&listen_input) {
+    let hooks_res = plugin
+        .hooks
+        .fire(LifecycleEvent::CaptureClose, &mut actual_plugin.plugin);
+    let close_res = actual_plugin.plugin.capture_close(&listen_input);
+    let mut res = hooks_res.and(close_res);
+
+    // stop all the managed routines, including any started in `capture_close` itself
+    plugin
+        .listen_routines
+        .extend(listen_input.take_managed_routines());
+    let routines = std::mem::take(&mut plugin.listen_routines);
+    for routine in routines {
+        let stopped = routine.stop_and_join(&listen_input.thread_pool);
+        if let Err(e) = stopped {
+            if res.is_ok() {
+                res = Err(e);
+            }
+        }
+    }
+
+    if let Err(e) = res {
         e.set_last_error(&mut plugin.error_buf);
         return e.status_code();
     }
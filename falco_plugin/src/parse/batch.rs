@@ -0,0 +1,76 @@
+use falco_event::events::RawEvent;
+use falco_plugin_api::{ss_plugin_event, ss_plugin_event_input};
+use std::ffi::{CStr, CString};
+
+/// Events buffered by the parse wrapper when [`ParsePlugin::PARSE_BATCH_SIZE`] is greater than 1
+///
+/// The event pointers passed by the framework are only valid for the duration of a single call,
+/// so the events (and their source names) are copied into owned storage.
+///
+/// [`ParsePlugin::PARSE_BATCH_SIZE`]: crate::parse::ParsePlugin::PARSE_BATCH_SIZE
+#[derive(Debug, Default)]
+pub(crate) struct ParseBatch {
+    data: Vec<u8>,
+    // (offset into `data`, event number, index into `sources`)
+    events: Vec<(usize, u64, Option<usize>)>,
+    sources: Vec<CString>,
+}
+
+impl ParseBatch {
+    /// Copy an event into the batch
+    ///
+    /// # Safety
+    ///
+    /// `event` must contain valid pointers to an event and (if not null) to its source name
+    pub(crate) unsafe fn push(&mut self, event: &ss_plugin_event_input) -> std::io::Result<()> {
+        let raw = unsafe { RawEvent::from_ptr(event.evt as *const u8) }?;
+        let bytes = unsafe { std::slice::from_raw_parts(event.evt as *const u8, raw.len as usize) };
+
+        let source = if event.evtsrc.is_null() {
+            None
+        } else {
+            let source = unsafe { CStr::from_ptr(event.evtsrc) };
+            // there are only a few event sources, so a linear search is fine
+            Some(
+                match self.sources.iter().position(|s| s.as_c_str() == source) {
+                    Some(index) => index,
+                    None => {
+                        self.sources.push(source.to_owned());
+                        self.sources.len() - 1
+                    }
+                },
+            )
+        };
+
+        self.events.push((self.data.len(), event.evtnum, source));
+        self.data.extend_from_slice(bytes);
+        Ok(())
+    }
+
+    /// Return the number of buffered events
+    pub(crate) fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Return the buffered events, pointing into the batch storage
+    pub(crate) fn inputs(&self) -> impl Iterator<Item = ss_plugin_event_input> + '_ {
+        self.events.iter().map(|(offset, evtnum, source)| {
+            let evtsrc = match source {
+                Some(index) => self.sources[*index].as_ptr(),
+                None => std::ptr::null(),
+            };
+
+            ss_plugin_event_input {
+                evt: self.data[*offset..].as_ptr() as *const ss_plugin_event,
+                evtnum: *evtnum,
+                evtsrc,
+            }
+        })
+    }
+
+    /// Drop all the buffered events
+    pub(crate) fn clear(&mut self) {
+        self.data.clear();
+        self.events.clear();
+    }
+}
@@ -0,0 +1,61 @@
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use std::collections::BTreeMap;
+use std::ffi::CString;
+
+/// Counters maintained by the parse wrapper when [`ParsePlugin::PARSE_METRICS`] is enabled
+///
+/// [`ParsePlugin::PARSE_METRICS`]: crate::parse::ParsePlugin::PARSE_METRICS
+#[derive(Debug, Default)]
+pub(crate) struct ParseMetrics {
+    events: u64,
+    errors: u64,
+    unparsable: u64,
+    by_type: BTreeMap<u16, u64>,
+}
+
+impl ParseMetrics {
+    /// Count an event passed to the plugin, before any processing
+    pub(crate) fn record_event(&mut self, event_type: u16) {
+        self.events += 1;
+        *self.by_type.entry(event_type).or_default() += 1;
+    }
+
+    /// Count an event skipped due to a conversion failure
+    pub(crate) fn record_unparsable(&mut self) {
+        self.unparsable += 1;
+    }
+
+    /// Count an event for which `parse_event` returned an error
+    pub(crate) fn record_error(&mut self) {
+        self.errors += 1;
+    }
+
+    /// Collect the metrics
+    ///
+    /// Each metric is passed to `func` along with its full name, as the per-event type
+    /// totals have names generated at runtime
+    pub(crate) fn collect(&self, mut func: impl FnMut(CString, Metric)) {
+        let counters = [
+            (c"parsed_events", self.events),
+            (c"parse_errors", self.errors),
+            (c"unparsable_events", self.unparsable),
+        ];
+        for (name, value) in counters {
+            let metric = Metric::new(
+                MetricLabel::new(name, MetricType::Monotonic),
+                MetricValue::U64(value),
+            );
+            func(name.to_owned(), metric)
+        }
+
+        for (event_type, count) in &self.by_type {
+            // the name is ASCII only and has no NUL bytes
+            let name = CString::new(format!("parsed_events.type_{event_type}")).unwrap();
+            let metric = Metric::new(
+                MetricLabel::new(c"parsed_events", MetricType::Monotonic),
+                MetricValue::U64(*count),
+            );
+            func(name, metric)
+        }
+    }
+}
@@ -51,6 +51,7 @@
 //! parse_plugin!(MyParsePlugin);
 //! ```
 
+use crate::base::deadline::DeadlinePolicy;
 use crate::base::Plugin;
 use crate::error::last_error::LastError;
 use crate::parse::wrappers::ParsePluginExported;
@@ -58,7 +59,10 @@ use crate::tables::LazyTableReader;
 use crate::tables::LazyTableWriter;
 use falco_event::events::{AnyEventPayload, RawEvent};
 use falco_plugin_api::ss_plugin_event_parse_input;
+use std::time::Duration;
 
+pub(crate) mod batch;
+pub(crate) mod metrics;
 #[doc(hidden)]
 pub mod wrappers;
 
@@ -79,6 +83,24 @@ pub trait ParsePlugin: Plugin + ParsePluginExported {
     /// ```
     /// type Event<'a> = falco_event::events::RawEvent<'a>;
     /// ```
+    ///
+    /// This type also determines which events the plugin receives at all: the event types
+    /// and sources it can represent are reported to the framework (via `get_parse_event_types`
+    /// and `get_parse_event_sources`), so that other events are never passed to
+    /// [`ParsePlugin::parse_event`]. For example, with an event enum limited to a few event types
+    /// (generated by `falco_event_schema::event_set!`):
+    /// ```ignore
+    /// event_set! {
+    ///     ProcessEvent = [PPME_SYSCALL_EXECVE_19_X, PPME_SYSCALL_CLONE_20_X]
+    /// }
+    ///
+    /// type Event<'a> = Event<ProcessEvent<'a>>;
+    /// ```
+    /// only `execve` and `clone` exit events get parsed, while `RawEvent` subscribes the plugin
+    /// to all events from all sources.
+    ///
+    /// What happens to events that match the event types but fail the conversion (e.g. because
+    /// they are malformed) is determined by [`ParsePlugin::UNPARSABLE_EVENTS`].
     type Event<'a>: AnyEventPayload + TryFrom<&'a RawEvent<'a>>
     where
         Self: 'a;
@@ -94,6 +116,117 @@ pub trait ParsePlugin: Plugin + ParsePluginExported {
         event: &EventInput<Self::Event<'_>>,
         parse_input: &ParseInput,
     ) -> anyhow::Result<()>;
+
+    /// # Parse a batch of events
+    ///
+    /// Receives [`ParsePlugin::PARSE_BATCH_SIZE`] events at once (or a single event, if batching
+    /// is not enabled). Override this method if you can process multiple events more efficiently
+    /// than one by one.
+    ///
+    /// The default implementation calls [`ParsePlugin::parse_event`] for each event,
+    /// stopping at the first error.
+    fn parse_events(
+        &mut self,
+        events: &[EventInput<Self::Event<'_>>],
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        for event in events {
+            self.parse_event(event, parse_input)?;
+        }
+        Ok(())
+    }
+
+    /// # Number of events to pass to [`ParsePlugin::parse_events`] at once
+    ///
+    /// The plugin API delivers events one at a time, so with a batch size greater than 1,
+    /// the SDK copies the events into an internal buffer and calls [`ParsePlugin::parse_events`]
+    /// once the buffer is full. This amortizes the per-call overhead for plugins doing
+    /// trivial per-event processing (e.g. maintaining counters), but it has important
+    /// consequences:
+    /// - events are parsed late, so any state updated while parsing an event is not visible
+    ///   to other plugins (or to field extraction) for that event
+    /// - the [`ParseInput`] passed to [`ParsePlugin::parse_events`] is the one received with
+    ///   the last event in the batch
+    /// - events remaining in the buffer when the capture stops are never parsed
+    /// - a conversion error cannot be attributed to a single event, so
+    ///   [`ParsePlugin::UNPARSABLE_EVENTS`] does not apply and the error fails the call
+    ///   for the last event in the batch
+    ///
+    /// Only enable batching if your plugin does not share any state with the rest
+    /// of the event processing pipeline.
+    ///
+    /// The default is 1 (no batching).
+    const PARSE_BATCH_SIZE: usize = 1;
+
+    /// # Deadline for [`ParsePlugin::parse_event`]
+    ///
+    /// If set, parsing an event taking longer than this is logged and counted in a metric
+    /// and handled according to [`ParsePlugin::PARSE_DEADLINE_POLICY`].
+    /// See [`crate::base::deadline`] for details.
+    ///
+    /// The default is `None` (no deadline).
+    const PARSE_DEADLINE: Option<Duration> = None;
+
+    /// # What to do when [`ParsePlugin::PARSE_DEADLINE`] is exceeded
+    ///
+    /// With [`DeadlinePolicy::Fail`], an overrunning `parse_event` fails.
+    ///
+    /// The default is [`DeadlinePolicy::Report`] (the call succeeds as usual).
+    const PARSE_DEADLINE_POLICY: DeadlinePolicy = DeadlinePolicy::Report;
+
+    /// # Policy for events that cannot be converted to [`ParsePlugin::Event`]
+    ///
+    /// The default is [`UnparsableEventPolicy::Fail`], which leaves handling conversion errors
+    /// to [`ParsePlugin::parse_event`]. With the other policies, a conversion error returned
+    /// from [`EventInput::event`] and propagated out of `parse_event` does not fail the parse
+    /// call. Instead, the event is skipped, after passing it to [`ParsePlugin::on_unparsable`].
+    ///
+    /// The SDK does not convert the events itself, so an event is only considered unparsable
+    /// if your plugin tried to convert it.
+    const UNPARSABLE_EVENTS: UnparsableEventPolicy = UnparsableEventPolicy::Fail;
+
+    /// # Handle an event that cannot be converted to [`ParsePlugin::Event`]
+    ///
+    /// This is called for every skipped event when [`ParsePlugin::UNPARSABLE_EVENTS`]
+    /// is not [`UnparsableEventPolicy::Fail`], e.g. to count such events in a metric.
+    ///
+    /// The default implementation does nothing.
+    fn on_unparsable(&mut self, raw: &RawEvent, err: &anyhow::Error) {
+        let _ = (raw, err);
+    }
+
+    /// # Report parsing metrics automatically
+    ///
+    /// If set to `true`, the SDK counts the events passed to the plugin and adds the following
+    /// monotonic metrics to the ones returned from [`Plugin::get_metrics`]:
+    /// - `parsed_events`: the total number of events received
+    /// - `parse_errors`: the number of events for which [`ParsePlugin::parse_event`] failed
+    /// - `unparsable_events`: the number of events skipped according to
+    ///   [`ParsePlugin::UNPARSABLE_EVENTS`]
+    /// - `parsed_events.type_<N>`: the number of events received with event type `N`
+    ///
+    /// The metrics are reported once the plugin receives its first event.
+    ///
+    /// The default is `false`.
+    const PARSE_METRICS: bool = false;
+}
+
+/// # What to do with events that fail the conversion to the parsed event type
+///
+/// See [`ParsePlugin::UNPARSABLE_EVENTS`] for details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnparsableEventPolicy {
+    /// Pass all events to [`ParsePlugin::parse_event`]
+    ///
+    /// The conversion error is returned from [`EventInput::event`], and propagating it fails
+    /// the parse call, which gets logged by the framework.
+    Fail,
+
+    /// Skip events that fail the conversion without logging anything
+    Skip,
+
+    /// Skip events that fail the conversion, logging a warning for each of them
+    LogAndSkip,
 }
 
 /// # The input to a parse plugin
@@ -104,6 +237,11 @@ pub trait ParsePlugin: Plugin + ParsePluginExported {
 /// You will pass these vtables to all methods that read or write data from tables,
 /// but you won't interact with them otherwise. They're effectively tokens proving
 /// you're in the right context to read/write tables.
+///
+/// The per-event metadata passed by the framework (the event number and the name of the event
+/// source) is available from the [`EventInput`] instead, via [`EventInput::event_number`]
+/// and [`EventInput::source`]. The plugin API does not pass a numeric source index,
+/// so use the source name to tell event sources apart.
 #[derive(Debug)]
 pub struct ParseInput<'t> {
     /// Accessors to read table entries
@@ -1,12 +1,16 @@
+use crate::base::deadline::Watchdog;
 use crate::base::wrappers::PluginWrapper;
 use crate::error::ffi_result::FfiResult;
+use crate::event::EventConversionError;
 use crate::parse::EventInput;
-use crate::parse::{ParseInput, ParsePlugin};
-use falco_event::events::AnyEventPayload;
+use crate::parse::{ParseInput, ParsePlugin, UnparsableEventPolicy};
+use crate::tables::EntryCacheScope;
+use crate::FailureReason;
+use falco_event::events::{AnyEventPayload, RawEvent};
 use falco_plugin_api::plugin_api__bindgen_ty_3 as parse_plugin_api;
 use falco_plugin_api::{
     ss_plugin_event_input, ss_plugin_event_parse_input, ss_plugin_rc,
-    ss_plugin_rc_SS_PLUGIN_FAILURE, ss_plugin_t,
+    ss_plugin_rc_SS_PLUGIN_FAILURE, ss_plugin_rc_SS_PLUGIN_SUCCESS, ss_plugin_t,
 };
 use std::any::TypeId;
 use std::collections::BTreeMap;
@@ -106,17 +110,75 @@ pub unsafe extern "C-unwind" fn plugin_parse_event<T: ParsePlugin>(
         let Some(event) = event.as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
-        let event = EventInput(*event, PhantomData);
+        let parse_metrics = if T::PARSE_METRICS {
+            let Some(header) = event.evt.as_ref() else {
+                return ss_plugin_rc_SS_PLUGIN_FAILURE;
+            };
+            let parse_metrics = plugin.parse_metrics.get_or_insert_with(Default::default);
+            parse_metrics.record_event(header.type_);
+            Some(parse_metrics)
+        } else {
+            None
+        };
+        if T::PARSE_BATCH_SIZE > 1 {
+            if let Err(e) = plugin.parse_batch.push(event) {
+                return Err::<(), _>(e.into()).rc(&mut plugin.error_buf);
+            }
+            if plugin.parse_batch.len() < T::PARSE_BATCH_SIZE {
+                return ss_plugin_rc_SS_PLUGIN_SUCCESS;
+            }
+        }
 
         let Ok(parse_input) = ParseInput::try_from(parse_input, actual_plugin.last_error.clone())
         else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
 
-        actual_plugin
-            .plugin
-            .parse_event(&event, &parse_input)
-            .rc(&mut plugin.error_buf)
+        let _entry_cache = EntryCacheScope::enter();
+        let watchdog = Watchdog::start("parse_event", T::PARSE_DEADLINE, T::PARSE_DEADLINE_POLICY);
+        let event = EventInput(*event, PhantomData);
+        let res = if T::PARSE_BATCH_SIZE > 1 {
+            let events = plugin
+                .parse_batch
+                .inputs()
+                .map(|input| EventInput(input, PhantomData))
+                .collect::<Vec<_>>();
+            let res = actual_plugin.plugin.parse_events(&events, &parse_input);
+            plugin.parse_batch.clear();
+            res
+        } else {
+            actual_plugin
+                .plugin
+                .parse_events(std::slice::from_ref(&event), &parse_input)
+        };
+        let deadline_result = watchdog.finish(&mut plugin.deadline_metrics, FailureReason::Failure);
+        let res = match res {
+            Err(err)
+                if T::UNPARSABLE_EVENTS != UnparsableEventPolicy::Fail
+                    && T::PARSE_BATCH_SIZE <= 1
+                    && err.downcast_ref::<EventConversionError>().is_some() =>
+            {
+                let raw = match RawEvent::from_ptr(event.0.evt as *const _) {
+                    Ok(raw) => raw,
+                    Err(e) => return Err::<(), _>(e.into()).rc(&mut plugin.error_buf),
+                };
+                if T::UNPARSABLE_EVENTS == UnparsableEventPolicy::LogAndSkip {
+                    log::warn!("Skipping unparsable event {raw:?}: {err:#}");
+                }
+                if let Some(parse_metrics) = parse_metrics {
+                    parse_metrics.record_unparsable();
+                }
+                actual_plugin.plugin.on_unparsable(&raw, &err);
+                return ss_plugin_rc_SS_PLUGIN_SUCCESS;
+            }
+            res => res.and(deadline_result),
+        };
+        if res.is_err() {
+            if let Some(parse_metrics) = parse_metrics {
+                parse_metrics.record_error();
+            }
+        }
+        res.rc(&mut plugin.error_buf)
     }
 }
 
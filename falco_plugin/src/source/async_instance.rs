@@ -0,0 +1,204 @@
+use crate::base::Metric;
+use crate::source::{
+    EventBatch, PauseHandle, ProgressInfo, SourceError, SourcePlugin, SourcePluginInstance,
+};
+use std::future::Future;
+use std::time::Duration;
+use tokio::runtime::Runtime;
+
+/// # An open instance of a source plugin, with an async `next_batch`
+///
+/// This is the async counterpart of [`SourcePluginInstance`], useful for plugins reading
+/// from naturally async sources (like network services). It cannot be used directly
+/// as a plugin instance; instead, wrap it in [`AsyncSourceInstance`], which owns an async
+/// runtime and drives the futures returned from [`AsyncSourcePluginInstance::next_batch`]:
+///
+/// ```
+/// use std::ffi::CStr;
+/// use std::time::Duration;
+/// use anyhow::Error;
+/// use falco_event::events::{Event, RawEvent};
+/// use falco_plugin::base::Plugin;
+/// use falco_plugin::{plugin, source_plugin};
+/// use falco_plugin::source::{
+///     AsyncSourceInstance, AsyncSourcePluginInstance, EventBatch, PluginEvent,
+///     SourcePlugin, SourcePluginInstance,
+/// };
+/// use falco_plugin::tables::TablesInput;
+///
+/// struct MyAsyncPlugin;
+///
+/// impl Plugin for MyAsyncPlugin {
+///     // ...
+/// #    const NAME: &'static CStr = c"sample-async-plugin-rs";
+/// #    const PLUGIN_VERSION: &'static CStr = c"0.0.1";
+/// #    const DESCRIPTION: &'static CStr = c"A sample Falco plugin with an async source";
+/// #    const CONTACT: &'static CStr = c"you@example.com";
+/// #    type ConfigType = ();
+/// #
+/// #    fn new(input: Option<&TablesInput>, config: Self::ConfigType)
+/// #        -> Result<Self, anyhow::Error> {
+/// #        Ok(MyAsyncPlugin)
+/// #    }
+/// }
+///
+/// struct MyAsyncPluginInstance;
+///
+/// impl SourcePlugin for MyAsyncPlugin {
+///     type Instance = AsyncSourceInstance<MyAsyncPluginInstance>;
+///     const EVENT_SOURCE: &'static CStr = c"my-async-plugin";
+///     const PLUGIN_ID: u32 = 0; // we do not have one assigned for this example :)
+///
+///     type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+///
+///     fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, Error> {
+///         Ok(AsyncSourceInstance::new(MyAsyncPluginInstance)?)
+///     }
+/// }
+///
+/// impl AsyncSourcePluginInstance for MyAsyncPluginInstance {
+///     type Plugin = MyAsyncPlugin;
+///
+///     async fn next_batch(&mut self, plugin: &mut Self::Plugin, batch: &mut EventBatch<'_>)
+///     -> Result<(), Error> {
+///         // wait for some data to arrive
+///         falco_plugin::tokio::time::sleep(Duration::from_millis(10)).await;
+///         batch.add(AsyncSourceInstance::<Self>::plugin_event(b"hello, world"))?;
+///
+///         Ok(())
+///     }
+/// }
+///
+/// plugin!(MyAsyncPlugin);
+/// source_plugin!(MyAsyncPlugin);
+/// ```
+pub trait AsyncSourcePluginInstance: Sized {
+    /// # The [`SourcePlugin`] this instance belongs to.
+    ///
+    /// Its [`SourcePlugin::Instance`] type must be `AsyncSourceInstance<Self>`.
+    type Plugin: SourcePlugin<Instance = AsyncSourceInstance<Self>>;
+
+    /// # Maximum time to wait for a batch of events
+    ///
+    /// If the future returned from [`AsyncSourcePluginInstance::next_batch`] does not complete
+    /// within this time, it gets dropped and the framework is told to retry later,
+    /// as if the instance returned [`FailureReason::Timeout`](`crate::FailureReason::Timeout`).
+    /// See the timing considerations in [`SourcePluginInstance::next_batch`] for picking a value.
+    const TIMEOUT: Duration = Duration::from_millis(100);
+
+    /// # Fill the next batch of events
+    ///
+    /// This works like [`SourcePluginInstance::next_batch`], with two differences:
+    /// - you do not need to return [`FailureReason::Timeout`](`crate::FailureReason::Timeout`) when no data is ready,
+    ///   just wait for it and the adapter will handle the timeout for you
+    /// - the returned future may be dropped before completion (when it does not complete within
+    ///   [`AsyncSourcePluginInstance::TIMEOUT`]), so it must be cancel safe, i.e. must not lose
+    ///   any data when cancelled at an `.await` point. Events already added to the batch
+    ///   at that time are still returned to the framework.
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        batch: &mut EventBatch<'_>,
+    ) -> impl Future<Output = Result<(), anyhow::Error>>;
+
+    /// # Get progress information
+    ///
+    /// See [`SourcePluginInstance::get_progress`].
+    fn get_progress(&mut self) -> ProgressInfo<'_> {
+        ProgressInfo {
+            value: 0.0,
+            detail: None,
+        }
+    }
+
+    /// # Return the instance metrics
+    ///
+    /// See [`SourcePluginInstance::get_metrics`].
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        []
+    }
+
+    /// # Get the pause handle
+    ///
+    /// See [`SourcePluginInstance::pause_handle`].
+    fn pause_handle(&self) -> Option<&PauseHandle> {
+        None
+    }
+}
+
+/// # An adapter running an [`AsyncSourcePluginInstance`] on a tokio runtime
+///
+/// This type implements [`SourcePluginInstance`], so you can use it as
+/// [`SourcePlugin::Instance`] for plugins with async instances.
+///
+/// The adapter owns a single-threaded runtime, which only runs while the framework waits
+/// for the next batch of events. Any tasks spawned on it (e.g. from [`SourcePlugin::open`],
+/// via [`AsyncSourceInstance::runtime`]) make progress only during that time too.
+#[derive(Debug)]
+pub struct AsyncSourceInstance<I> {
+    instance: I,
+    runtime: Runtime,
+}
+
+impl<I: AsyncSourcePluginInstance> AsyncSourceInstance<I> {
+    /// Create a new adapter for `instance`, with a new single-threaded runtime
+    pub fn new(instance: I) -> std::io::Result<Self> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        Ok(Self::with_runtime(instance, runtime))
+    }
+
+    /// Create a new adapter for `instance`, using a runtime you built yourself
+    pub fn with_runtime(instance: I, runtime: Runtime) -> Self {
+        Self { instance, runtime }
+    }
+
+    /// Get the runtime driving the instance
+    pub fn runtime(&self) -> &Runtime {
+        &self.runtime
+    }
+
+    /// Get a reference to the wrapped instance
+    pub fn get_ref(&self) -> &I {
+        &self.instance
+    }
+
+    /// Get a mutable reference to the wrapped instance
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.instance
+    }
+}
+
+impl<I: AsyncSourcePluginInstance> SourcePluginInstance for AsyncSourceInstance<I> {
+    type Plugin = I::Plugin;
+
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), anyhow::Error> {
+        let next_batch = self.instance.next_batch(plugin, batch);
+        let result = self
+            .runtime
+            .block_on(async { tokio::time::timeout(I::TIMEOUT, next_batch).await });
+        match result {
+            Ok(res) => res,
+            Err(_) => {
+                Err(SourceError::timeout(format!("no events within {:?}", I::TIMEOUT)).into())
+            }
+        }
+    }
+
+    fn get_progress(&mut self) -> ProgressInfo<'_> {
+        self.instance.get_progress()
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        self.instance.get_metrics()
+    }
+
+    fn pause_handle(&self) -> Option<&PauseHandle> {
+        self.instance.pause_handle()
+    }
+}
@@ -1,10 +1,15 @@
-use falco_event::events::EventToBytes;
+use falco_event::events::{EventToBytes, RawEvent};
 
 /// # An object that describes a batch of events
 ///
 /// This is only available by reference, not by ownership, since the data needs to outlive
 /// the plugin API call and is stored elsewhere (in a wrapper struct that's not exposed to
 /// plugin developers)
+///
+/// The underlying storage is reused across batches: the arena holding the events is only reset
+/// (not freed) before each batch, and the list of events starts with room for as many events
+/// as the largest batch so far, so a plugin generating batches of similar sizes quickly
+/// settles into not allocating any memory at all.
 #[derive(Debug)]
 pub struct EventBatch<'a> {
     alloc: &'a bumpalo::Bump,
@@ -12,8 +17,21 @@ pub struct EventBatch<'a> {
 }
 
 impl EventBatch<'_> {
-    pub(super) fn new(alloc: &bumpalo::Bump) -> EventBatch<'_> {
-        let pointers = bumpalo::collections::Vec::new_in(alloc);
+    /// Prepare the arena for a new batch
+    ///
+    /// This frees all the events from the previous batch, but keeps the memory around.
+    /// [`bumpalo::Bump::reset`] only retains the most recent chunk of the arena, so if the
+    /// previous batch did not fit in a single chunk, the arena gets replaced with one that does.
+    pub(super) fn reset_storage(alloc: &mut bumpalo::Bump) {
+        let used = alloc.allocated_bytes();
+        alloc.reset();
+        if alloc.allocated_bytes() < used {
+            *alloc = bumpalo::Bump::with_capacity(used);
+        }
+    }
+
+    pub(super) fn with_capacity(alloc: &bumpalo::Bump, capacity: usize) -> EventBatch<'_> {
+        let pointers = bumpalo::collections::Vec::with_capacity_in(capacity, alloc);
         EventBatch { alloc, pointers }
     }
 
@@ -38,6 +56,33 @@ impl EventBatch<'_> {
         Ok(())
     }
 
+    /// # Add a pre-serialized event to a batch
+    ///
+    /// This copies the bytes of an already encoded event (e.g. one received from another
+    /// Falco instance) directly into the batch, without going through [`EventBatch::add`]
+    /// and a typed event.
+    ///
+    /// Only the event header is validated: the buffer must be at least as long as the header
+    /// and its length must match the length stored in the header. The parameters are passed
+    /// as-is, so it's up to you to ensure they match the event type.
+    pub fn add_raw(&mut self, event: &[u8]) -> std::io::Result<()> {
+        let raw = RawEvent::from(event)?;
+        if raw.len as usize != event.len() {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "event length mismatch: header says {} bytes, got {}",
+                    raw.len,
+                    event.len()
+                ),
+            ));
+        }
+
+        let event_buf = self.alloc.alloc_slice_copy(event);
+        self.pointers.push(event_buf.as_ptr());
+        Ok(())
+    }
+
     /// # Reserve space for a specific number of events
     ///
     /// If your plugin knows it's going to generate a specific number of events
@@ -47,11 +92,95 @@ impl EventBatch<'_> {
     /// The passed value is only a hint, the actual batch can be smaller or larger
     /// than the reserved size, but that mostly defeats the purpose of reserving
     /// space
+    ///
+    /// **Note**: since the batch already starts with room for as many events as the largest
+    /// previous batch, this is mostly useful for the first batch, or when the batch size
+    /// suddenly grows.
     pub fn reserve(&mut self, num_events: usize) {
         self.pointers.reserve(num_events);
     }
 
+    /// # Get the number of events in the batch
+    pub fn len(&self) -> usize {
+        self.pointers.len()
+    }
+
+    /// # Check whether the batch is empty
+    pub fn is_empty(&self) -> bool {
+        self.pointers.is_empty()
+    }
+
+    /// # Get the number of events the batch can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.pointers.capacity()
+    }
+
     pub(super) fn get_events(&self) -> &[*const u8] {
         self.pointers.as_slice()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::PluginEvent;
+    use falco_event::events::{Event, EventMetadata};
+
+    fn fill_batch(alloc: &bumpalo::Bump, capacity: usize, num_events: usize) -> usize {
+        let mut batch = EventBatch::with_capacity(alloc, capacity);
+        for _ in 0..num_events {
+            batch
+                .add(Event {
+                    metadata: EventMetadata::default(),
+                    params: PluginEvent {
+                        plugin_id: 1,
+                        event_data: b"hello".as_slice(),
+                    },
+                })
+                .unwrap();
+        }
+        batch.len()
+    }
+
+    #[test]
+    fn test_add_raw() {
+        let event = Event {
+            metadata: EventMetadata { ts: 1, tid: 2 },
+            params: PluginEvent {
+                plugin_id: 1,
+                event_data: b"hello".as_slice(),
+            },
+        };
+        let mut buf = Vec::new();
+        event.write(&mut buf).unwrap();
+
+        let alloc = bumpalo::Bump::new();
+        let mut batch = EventBatch::with_capacity(&alloc, 0);
+        batch.add_raw(&buf).unwrap();
+        assert!(batch.add_raw(&buf[..buf.len() - 1]).is_err());
+        assert!(batch.add_raw(&buf[..10]).is_err());
+        assert_eq!(batch.len(), 1);
+
+        let added = unsafe { RawEvent::from_ptr(batch.get_events()[0]) }.unwrap();
+        let added = added.load::<PluginEvent<&[u8]>>().unwrap();
+        assert_eq!(added.metadata.tid, 2);
+        assert_eq!(added.params.event_data, b"hello");
+    }
+
+    #[test]
+    fn test_batch_storage_reuse() {
+        let mut alloc = bumpalo::Bump::new();
+        let capacity = fill_batch(&alloc, 0, 1000);
+        assert_eq!(capacity, 1000);
+
+        EventBatch::reset_storage(&mut alloc);
+        fill_batch(&alloc, capacity, 1000);
+        let allocated = alloc.allocated_bytes();
+
+        for _ in 0..10 {
+            EventBatch::reset_storage(&mut alloc);
+            assert_eq!(fill_batch(&alloc, capacity, 1000), 1000);
+            assert_eq!(alloc.allocated_bytes(), allocated);
+        }
+    }
+}
@@ -57,17 +57,8 @@
 //!         Ok((MySourcePluginInstance))
 //!     }
 //!
-//!     fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
-//!         // a string representation for our event; just copy out the whole event data
-//!         // (it's an ASCII string); please note we need the copy because we need to add
-//!         // a NUL terminator to convert the byte buffer to a C string
-//!
-//!         // get the plugin event
-//!         let plugin_event = event.event()?;
-//!
-//!         // convert the data to a CString and return it
-//!         Ok(CString::new(plugin_event.params.event_data)?)
-//!     }
+//!     // the default `event_to_string` implementation renders the event data as a string,
+//!     // which is exactly what we need here (it's an ASCII string)
 //! }
 //!
 //! impl SourcePluginInstance for MySourcePluginInstance {
@@ -84,22 +75,107 @@
 //! plugin!(MySourcePlugin);
 //! source_plugin!(MySourcePlugin);
 //! ```
+//!
+//! ## Syscall sources
+//!
+//! A plugin with [`SourcePlugin::PLUGIN_ID`] set to zero and an empty
+//! [`SourcePlugin::EVENT_SOURCE`] does not define a new event source. Instead, it injects
+//! its events into the `syscall` event stream, which means it can emit events of any type,
+//! not just plugin events. [`EventBatch::add`] accepts any event type from
+//! `falco_event_schema`, so the only change is
+//! building the events yourself instead of using [`SourcePluginInstance::plugin_event`]:
+//!
+//! ```
+//! use std::ffi::{CStr, CString};
+//! use anyhow::Error;
+//! use falco_event::events::{Event, EventMetadata, RawEvent};
+//! use falco_event_schema::events::PPME_GENERIC_E;
+//! use falco_event_schema::fields::types::PT_SYSCALLID;
+//! use falco_plugin::base::Plugin;
+//! use falco_plugin::{plugin, source_plugin};
+//! use falco_plugin::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
+//! use falco_plugin::tables::TablesInput;
+//!
+//! struct MySyscallPlugin;
+//!
+//! impl Plugin for MySyscallPlugin {
+//!     // ...
+//! #    const NAME: &'static CStr = c"sample-syscall-plugin-rs";
+//! #    const PLUGIN_VERSION: &'static CStr = c"0.0.1";
+//! #    const DESCRIPTION: &'static CStr = c"A sample Falco plugin generating syscall events";
+//! #    const CONTACT: &'static CStr = c"you@example.com";
+//! #    type ConfigType = ();
+//! #
+//! #    fn new(input: Option<&TablesInput>, config: Self::ConfigType)
+//! #        -> Result<Self, anyhow::Error> {
+//! #        Ok(MySyscallPlugin)
+//! #    }
+//! }
+//!
+//! struct MySyscallPluginInstance;
+//!
+//! impl SourcePlugin for MySyscallPlugin {
+//!     type Instance = MySyscallPluginInstance;
+//!     const EVENT_SOURCE: &'static CStr = c"";
+//!     const PLUGIN_ID: u32 = 0;
+//!
+//!     type Event<'a> = RawEvent<'a>;
+//!
+//!     fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, Error> {
+//!         Ok(MySyscallPluginInstance)
+//!     }
+//!
+//!     fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+//!         Ok(CString::new(format!("{:?}", event.event()?))?)
+//!     }
+//! }
+//!
+//! impl SourcePluginInstance for MySyscallPluginInstance {
+//!     type Plugin = MySyscallPlugin;
+//!
+//!     fn next_batch(&mut self, plugin: &mut Self::Plugin, batch: &mut EventBatch)
+//!     -> Result<(), Error> {
+//!         batch.add(Event {
+//!             metadata: EventMetadata::default(),
+//!             params: PPME_GENERIC_E {
+//!                 id: Some(PT_SYSCALLID(1)),
+//!                 native_id: Some(1),
+//!             },
+//!         })?;
+//!
+//!         Ok(())
+//!     }
+//! }
+//!
+//! plugin!(MySyscallPlugin);
+//! source_plugin!(MySyscallPlugin);
+//! ```
 
-use crate::base::Plugin;
+use crate::base::deadline::DeadlinePolicy;
+use crate::base::{Metric, Plugin};
 use crate::source::wrappers::SourcePluginExported;
 use falco_event::events::{AnyEventPayload, EventMetadata};
 use falco_event::events::{Event, RawEvent};
 use std::ffi::{CStr, CString};
+use std::time::Duration;
 
+#[cfg(feature = "tokio")]
+mod async_instance;
 mod event_batch;
 mod open_params;
+mod pause;
+pub mod readers;
 #[doc(hidden)]
 pub mod wrappers;
 
+pub use crate::error::SourceError;
 pub use crate::event::EventInput;
 pub use crate::event::PluginEvent;
+#[cfg(feature = "tokio")]
+pub use async_instance::{AsyncSourceInstance, AsyncSourcePluginInstance};
 pub use event_batch::EventBatch;
-pub use open_params::{serialize_open_params, OpenParam};
+pub use open_params::{parse_open_params, serialize_open_params, OpenParam};
+pub use pause::PauseHandle;
 
 /// Support for event sourcing plugins
 pub trait SourcePlugin: Plugin + SourcePluginExported {
@@ -122,7 +198,9 @@ pub trait SourcePlugin: Plugin + SourcePluginExported {
     /// (as a non-zero value), it will only be allowed to emit plugin events (e.g. [`crate::event::PluginEvent`])
     /// with the `plugin_id` field matching `PLUGIN_ID` in the definition of this trait.
     ///
-    /// This constant must be a non-empty string if `PLUGIN_ID` is set.
+    /// This constant must be a non-empty string if `PLUGIN_ID` is set. Leave both empty/zero
+    /// for a plugin injecting arbitrary events into the syscall event stream
+    /// (see [syscall sources](crate::source#syscall-sources)).
     const EVENT_SOURCE: &'static CStr;
 
     /// # Plugin ID
@@ -175,8 +253,28 @@ pub trait SourcePlugin: Plugin + SourcePluginExported {
     ///
     /// This method receives the `open` parameter from Falco configuration and returns
     /// a new instance of the source plugin.
+    ///
+    /// To parse the parameters like the init config (e.g. as [`Json<T>`](`crate::base::Json`)),
+    /// use [`parse_open_params`].
     fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, anyhow::Error>;
 
+    /// # Deadline for [`SourcePluginInstance::next_batch`]
+    ///
+    /// If set, a call to `next_batch` taking longer than this is logged and counted in a metric
+    /// and handled according to [`SourcePlugin::NEXT_BATCH_DEADLINE_POLICY`].
+    /// See [`crate::base::deadline`] for details.
+    ///
+    /// The default is `None` (no deadline).
+    const NEXT_BATCH_DEADLINE: Option<Duration> = None;
+
+    /// # What to do when [`SourcePlugin::NEXT_BATCH_DEADLINE`] is exceeded
+    ///
+    /// With [`DeadlinePolicy::Fail`], an overrunning `next_batch` returns `SS_PLUGIN_TIMEOUT`
+    /// (the events already added to the batch are still delivered).
+    ///
+    /// The default is [`DeadlinePolicy::Report`] (the batch is delivered as usual).
+    const NEXT_BATCH_DEADLINE_POLICY: DeadlinePolicy = DeadlinePolicy::Report;
+
     /// # Close a capture instance
     ///
     /// The default implementation does nothing, leaving all cleanup to the instance type's
@@ -187,10 +285,19 @@ pub trait SourcePlugin: Plugin + SourcePluginExported {
     ///
     /// This string will be available as `%evt.plugininfo` in Falco rules. You may consider
     /// using the helpers from [`crate::strings`] to build the resulting CString.
+    ///
+    /// The default implementation renders the payload of plugin events as (lossy) UTF-8,
+    /// which works for text and JSON payloads, so you only need to override it when you
+    /// want custom formatting (see [`EventInput::to_cstring_lossy`] for details).
+    ///
+    /// The default cannot tell whether the payload type implements `serde::Serialize`,
+    /// so for binary payloads that do, override it to call [`EventInput::to_json_cstring`].
     fn event_to_string(
         &mut self,
         event: &EventInput<Self::Event<'_>>,
-    ) -> Result<CString, anyhow::Error>;
+    ) -> Result<CString, anyhow::Error> {
+        event.to_cstring_lossy()
+    }
 }
 
 /// Information about capture progress
@@ -205,6 +312,10 @@ pub struct ProgressInfo<'a> {
 struct SourcePluginInstanceWrapper<I: SourcePluginInstance> {
     instance: I,
     batch: bumpalo::Bump,
+    /// The number of events in the largest batch so far
+    batch_capacity: usize,
+    /// The index of the instance, used to label its metrics
+    metrics_index: usize,
 }
 
 /// # An open instance of a source plugin
@@ -258,6 +369,45 @@ pub trait SourcePluginInstance {
     /// }
     /// ```
     ///
+    /// ## Returning a partial batch
+    ///
+    /// When the data dries up in the middle of a batch, you can still return the error
+    /// (e.g. [`FailureReason::Timeout`](`crate::FailureReason::Timeout`)) after adding some
+    /// events to the batch. The events get delivered first and the framework acts on the error
+    /// afterwards, so nothing is lost. The same applies to
+    /// [`FailureReason::Eof`](`crate::FailureReason::Eof`), which lets you return the last events
+    /// together with the end of data.
+    ///
+    /// ```ignore
+    /// fn next_batch(
+    ///     &mut self,
+    ///     plugin: &mut Self::Plugin,
+    ///     batch: &mut EventBatch,
+    /// ) -> Result<(), anyhow::Error> {
+    ///     while let Some(event) = self.buffered_events.pop_front() {
+    ///         batch.add(Self::plugin_event(&event))?;
+    ///     }
+    ///     Err(anyhow::anyhow!("no more events right now").context(FailureReason::Timeout))
+    /// }
+    /// ```
+    ///
+    /// Events added before any other error are discarded.
+    ///
+    /// ## Typed errors
+    ///
+    /// Instead of attaching a [`FailureReason`](`crate::FailureReason`) with `.context()`,
+    /// you can return a [`SourceError`], which carries both the reason and the error message:
+    ///
+    /// ```ignore
+    /// fn next_batch(
+    ///     &mut self,
+    ///     plugin: &mut Self::Plugin,
+    ///     batch: &mut EventBatch,
+    /// ) -> Result<(), anyhow::Error> {
+    ///     Err(SourceError::timeout("no events right now"))?
+    /// }
+    /// ```
+    ///
     /// ## Returning no events, permanently
     ///
     /// If there will be no more events coming from this instance, you should return\
@@ -302,6 +452,31 @@ pub trait SourcePluginInstance {
         }
     }
 
+    /// # Return the instance metrics
+    ///
+    /// This works just like [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`), except
+    /// the metrics describe a single open instance (e.g. events or bytes read, or how far
+    /// behind the data source it is). They are reported along with the plugin metrics,
+    /// for as long as the instance remains open.
+    ///
+    /// Each instance gets a numeric index when opened (starting at zero and never reused
+    /// within a plugin), and the metric names are labeled with it, so a metric called `foo`
+    /// from the first instance of a plugin called `bar` will be emitted as `bar.foo.instance_0`.
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        []
+    }
+
+    /// # Get the pause handle
+    ///
+    /// Return a [`PauseHandle`] to let the instance be paused and resumed (e.g. for maintenance
+    /// windows, or when the consumers cannot keep up). While paused, [`SourcePluginInstance::next_batch`]
+    /// is not called and the framework is told there are no events ready.
+    ///
+    /// The default implementation returns `None`, so the instance cannot be paused.
+    fn pause_handle(&self) -> Option<&PauseHandle> {
+        None
+    }
+
     /// # A helper for generating plugin events
     ///
     /// If your plugin defines a PLUGIN_ID and a source name, the only allowed events are
@@ -311,13 +486,26 @@ pub trait SourcePluginInstance {
     /// This method makes it easy to generate such events: just pass it the event data and get
     /// the complete event, with all the metadata set to reasonable defaults.
     fn plugin_event(data: &[u8]) -> Event<PluginEvent<&[u8]>> {
+        Self::plugin_event_with_metadata(EventMetadata::default(), data)
+    }
+
+    /// # A helper for generating plugin events with custom metadata
+    ///
+    /// This works just like [`SourcePluginInstance::plugin_event`], except the event timestamp
+    /// and thread id come from `metadata`, instead of being left for the framework to fill in
+    /// (with the current time). This is useful e.g. for plugins replaying historical data.
+    ///
+    /// You can still leave either of the fields at its default value (see
+    /// [`EventMetadata::default`]) to have the framework fill it in.
+    fn plugin_event_with_metadata(
+        metadata: EventMetadata,
+        data: &[u8],
+    ) -> Event<PluginEvent<&[u8]>> {
         let event = PluginEvent {
             plugin_id: Self::Plugin::PLUGIN_ID,
             event_data: data,
         };
 
-        let metadata = EventMetadata::default();
-
         Event {
             metadata,
             params: event,
@@ -1,3 +1,5 @@
+use crate::base::schema::ConfigSchema;
+use anyhow::Context;
 use serde::ser::SerializeStruct;
 use serde::{Serialize, Serializer};
 use std::ffi::{CStr, CString};
@@ -70,3 +72,21 @@ pub fn serialize_open_params<'a>(
     std::mem::swap(&mut buf, storage);
     Ok(storage.as_c_str())
 }
+
+/// # Parse the open parameters
+///
+/// [`SourcePlugin::open`](`crate::source::SourcePlugin::open`) receives the open parameters
+/// verbatim. Call this function in your `open` method to parse them into any type supported
+/// as [`Plugin::ConfigType`](`crate::base::Plugin::ConfigType`), e.g. [`Json<T>`](`crate::base::Json`).
+///
+/// Missing or empty open parameters are returned as `None`.
+pub fn parse_open_params<P: ConfigSchema>(
+    params: Option<&str>,
+) -> Result<Option<P>, anyhow::Error> {
+    match params {
+        None | Some("") => Ok(None),
+        Some(params) => Ok(Some(
+            P::from_str(params).context("Failed to parse open parameters")?,
+        )),
+    }
+}
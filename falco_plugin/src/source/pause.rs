@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// # A switch to pause and resume event production
+///
+/// Return a reference to a `PauseHandle` from [`SourcePluginInstance::pause_handle`](`crate::source::SourcePluginInstance::pause_handle`)
+/// to make an instance pausable. While the handle is paused, the SDK does not call
+/// [`SourcePluginInstance::next_batch`](`crate::source::SourcePluginInstance::next_batch`) at all
+/// and returns a timeout to the framework instead, so that the instance does not need to
+/// handle the paused state itself. The instance also reports a `paused` metric (1 when paused,
+/// 0 otherwise), labeled like all the other [instance metrics](`crate::source::SourcePluginInstance::get_metrics`).
+///
+/// The handle can be cloned and all the clones control the same state, so you can keep one
+/// in the instance and pass another one to whatever decides about pausing, e.g. the plugin
+/// (to pause the instance from [`Plugin::set_config`](`crate::base::Plugin::set_config`)),
+/// or a background thread watching for backpressure. The handle is thread-safe.
+///
+/// ```
+/// use falco_plugin::source::PauseHandle;
+///
+/// let handle = PauseHandle::default();
+/// let control = handle.clone();
+///
+/// control.pause();
+/// assert!(handle.is_paused());
+/// control.resume();
+/// assert!(!handle.is_paused());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct PauseHandle(Arc<AtomicBool>);
+
+impl PauseHandle {
+    /// Create a new handle, initially paused or not
+    pub fn new(paused: bool) -> Self {
+        Self(Arc::new(AtomicBool::new(paused)))
+    }
+
+    /// Pause event production
+    pub fn pause(&self) {
+        self.set_paused(true)
+    }
+
+    /// Resume event production
+    pub fn resume(&self) {
+        self.set_paused(false)
+    }
+
+    /// Pause or resume event production, depending on `paused`
+    pub fn set_paused(&self, paused: bool) {
+        self.0.store(paused, Ordering::Relaxed)
+    }
+
+    /// Check whether event production is paused
+    pub fn is_paused(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,303 @@
+//! # Adapters for record-based source plugins
+//!
+//! Many source plugins just read a sequence of records (e.g. lines from a file, a socket or
+//! the output of a command) and emit each one as a plugin event. The types in this module
+//! implement [`SourcePluginInstance`] for such plugins, taking care of batching, end of data
+//! and timeouts, so all that's left to do is to build the record source in [`SourcePlugin::open`]:
+//!
+//! ```
+//! use std::ffi::CStr;
+//! use std::fs::File;
+//! use std::io::BufReader;
+//! use anyhow::Error;
+//! use falco_event::events::Event;
+//! use falco_plugin::base::Plugin;
+//! use falco_plugin::{plugin, source_plugin};
+//! use falco_plugin::source::{PluginEvent, SourcePlugin};
+//! use falco_plugin::source::readers::{lines, LineSource};
+//! use falco_plugin::tables::TablesInput;
+//!
+//! struct LogFilePlugin;
+//!
+//! impl Plugin for LogFilePlugin {
+//!     // ...
+//! #    const NAME: &'static CStr = c"sample-log-file-plugin-rs";
+//! #    const PLUGIN_VERSION: &'static CStr = c"0.0.1";
+//! #    const DESCRIPTION: &'static CStr = c"A sample Falco plugin reading a log file";
+//! #    const CONTACT: &'static CStr = c"you@example.com";
+//! #    type ConfigType = ();
+//! #
+//! #    fn new(input: Option<&TablesInput>, config: Self::ConfigType)
+//! #        -> Result<Self, anyhow::Error> {
+//! #        Ok(LogFilePlugin)
+//! #    }
+//! }
+//!
+//! impl SourcePlugin for LogFilePlugin {
+//!     type Instance = LineSource<Self, BufReader<File>>;
+//!     const EVENT_SOURCE: &'static CStr = c"log-file";
+//!     const PLUGIN_ID: u32 = 0; // we do not have one assigned for this example :)
+//!
+//!     type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+//!
+//!     fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, Error> {
+//!         let path = params.unwrap_or("/var/log/messages");
+//!         let file = BufReader::new(File::open(path)?);
+//!         Ok(LineSource::new(lines(file)))
+//!     }
+//! }
+//!
+//! plugin!(LogFilePlugin);
+//! source_plugin!(LogFilePlugin);
+//! ```
+
+use crate::error::ffi_result::FfiResult;
+use crate::source::{EventBatch, SourceError, SourcePlugin, SourcePluginInstance};
+use crate::FailureReason;
+use std::fmt::{Debug, Formatter};
+use std::io::{BufRead, ErrorKind};
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
+
+/// # A source plugin instance emitting records from an iterator
+///
+/// Each `Ok` item produced by the iterator becomes the payload of a single plugin event.
+///
+/// Batches are closed when they reach [`IteratorSource::with_max_batch_size`] events
+/// or when filling them takes longer than [`IteratorSource::with_max_batch_time`],
+/// whichever comes first. When the iterator runs out of items, the capture ends with
+/// end of data, after delivering the last batch.
+///
+/// The iterator can also return errors:
+/// - errors with [`FailureReason::Timeout`] (or [`SourceError::Timeout`]) attached mean that
+///   no data is available yet; the batch collected so far is delivered and the framework
+///   calls the instance again later
+/// - errors with [`FailureReason::Eof`] (or [`SourceError::Eof`]) end the capture, just like
+///   running out of items
+/// - all other errors fail the capture, but only after delivering the events collected
+///   before the error
+pub struct IteratorSource<P, I> {
+    iter: I,
+    max_batch_size: usize,
+    max_batch_time: Option<Duration>,
+    pending_error: Option<anyhow::Error>,
+    plugin: PhantomData<fn() -> P>,
+}
+
+impl<P, I> IteratorSource<P, I> {
+    /// The default maximum number of events in a batch
+    pub const DEFAULT_MAX_BATCH_SIZE: usize = 128;
+
+    /// Create a new instance emitting the records from `iter`
+    pub fn new(iter: I) -> Self {
+        Self {
+            iter,
+            max_batch_size: Self::DEFAULT_MAX_BATCH_SIZE,
+            max_batch_time: None,
+            pending_error: None,
+            plugin: PhantomData,
+        }
+    }
+
+    /// Set the maximum number of events in a batch
+    ///
+    /// The default is [`IteratorSource::DEFAULT_MAX_BATCH_SIZE`].
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = max_batch_size.max(1);
+        self
+    }
+
+    /// Set the maximum time spent filling a single batch
+    ///
+    /// This is useful with slow record sources, to deliver the events collected so far
+    /// without waiting for a full batch. The limit is only checked between records,
+    /// so a single slow record can still make a batch take longer than this.
+    ///
+    /// The default is no limit.
+    pub fn with_max_batch_time(mut self, max_batch_time: Duration) -> Self {
+        self.max_batch_time = Some(max_batch_time);
+        self
+    }
+
+    /// Get a reference to the underlying iterator
+    pub fn get_ref(&self) -> &I {
+        &self.iter
+    }
+
+    /// Get a mutable reference to the underlying iterator
+    pub fn get_mut(&mut self) -> &mut I {
+        &mut self.iter
+    }
+}
+
+impl<P, I> Debug for IteratorSource<P, I> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IteratorSource")
+            .field("max_batch_size", &self.max_batch_size)
+            .field("max_batch_time", &self.max_batch_time)
+            .field("pending_error", &self.pending_error)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<P, I> SourcePluginInstance for IteratorSource<P, I>
+where
+    P: SourcePlugin<Instance = Self>,
+    I: Iterator<Item = anyhow::Result<Vec<u8>>>,
+{
+    type Plugin = P;
+
+    fn next_batch(&mut self, _plugin: &mut P, batch: &mut EventBatch) -> anyhow::Result<()> {
+        if let Some(err) = self.pending_error.take() {
+            return Err(err);
+        }
+
+        let start = self.max_batch_time.map(|limit| (Instant::now(), limit));
+        while batch.len() < self.max_batch_size {
+            match self.iter.next() {
+                Some(Ok(record)) => batch.add(Self::plugin_event(&record))?,
+                Some(Err(err)) => {
+                    return match err.status_code() {
+                        // the partial batch gets delivered along with these
+                        falco_plugin_api::ss_plugin_rc_SS_PLUGIN_TIMEOUT
+                        | falco_plugin_api::ss_plugin_rc_SS_PLUGIN_EOF => Err(err),
+                        _ if batch.is_empty() => Err(err),
+                        // other errors would discard the batch, so report them next time
+                        _ => {
+                            self.pending_error = Some(err);
+                            Ok(())
+                        }
+                    };
+                }
+                None => return Err(SourceError::eof("end of input").into()),
+            }
+
+            if let Some((start, limit)) = start {
+                if start.elapsed() >= limit {
+                    break;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// # A source plugin instance emitting lines from a reader
+///
+/// See [`lines`] and [`IteratorSource`] for details.
+pub type LineSource<P, R> = IteratorSource<P, Lines<R>>;
+
+/// # Split a reader into lines
+///
+/// This returns an iterator suitable for [`IteratorSource`], yielding the lines read
+/// from `reader`, with the line terminators (`\n` or `\r\n`) removed. Unlike
+/// [`BufRead::lines`], the lines do not need to be valid UTF-8.
+///
+/// Reads that fail with [`ErrorKind::WouldBlock`] or [`ErrorKind::TimedOut`] (e.g. from
+/// non-blocking sockets, or sockets with a read timeout) are reported as
+/// [`FailureReason::Timeout`], so the instance gets called again later. Any partial line read
+/// before the timeout is kept until the rest of it arrives.
+pub fn lines<R: BufRead>(reader: R) -> Lines<R> {
+    Lines {
+        reader,
+        buf: Vec::new(),
+    }
+}
+
+/// # An iterator over the lines of a reader
+///
+/// This is returned from [`lines`].
+#[derive(Debug)]
+pub struct Lines<R> {
+    reader: R,
+    buf: Vec<u8>,
+}
+
+impl<R> Lines<R> {
+    /// Get a reference to the underlying reader
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    /// Get a mutable reference to the underlying reader
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.reader
+    }
+}
+
+impl<R: BufRead> Iterator for Lines<R> {
+    type Item = anyhow::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.reader.read_until(b'\n', &mut self.buf) {
+                Ok(0) if self.buf.is_empty() => return None,
+                Ok(_) => {
+                    if self.buf.last() == Some(&b'\n') {
+                        self.buf.pop();
+                        if self.buf.last() == Some(&b'\r') {
+                            self.buf.pop();
+                        }
+                    }
+                    return Some(Ok(std::mem::take(&mut self.buf)));
+                }
+                Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+                Err(e) if matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                    return Some(Err(anyhow::Error::new(e).context(FailureReason::Timeout)));
+                }
+                Err(e) => return Some(Err(e.into())),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufReader, Read};
+
+    /// A reader returning the chunks in turn, with `None` meaning "would block"
+    struct ChunkReader(Vec<Option<&'static [u8]>>);
+
+    impl Read for ChunkReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+            match self.0.remove(0) {
+                Some(chunk) => {
+                    buf[..chunk.len()].copy_from_slice(chunk);
+                    Ok(chunk.len())
+                }
+                None => Err(ErrorKind::WouldBlock.into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut lines = lines(b"foo\nbar\r\n\nbaz".as_slice());
+        assert_eq!(lines.next().unwrap().unwrap(), b"foo");
+        assert_eq!(lines.next().unwrap().unwrap(), b"bar");
+        assert_eq!(lines.next().unwrap().unwrap(), b"");
+        assert_eq!(lines.next().unwrap().unwrap(), b"baz");
+        assert!(lines.next().is_none());
+    }
+
+    #[test]
+    fn test_lines_would_block() {
+        let reader = ChunkReader(vec![Some(b"foo\nba"), None, Some(b"r\n")]);
+        let mut lines = lines(BufReader::new(reader));
+        assert_eq!(lines.next().unwrap().unwrap(), b"foo");
+
+        let err = lines.next().unwrap().unwrap_err();
+        assert!(matches!(
+            err.downcast_ref::<FailureReason>(),
+            Some(FailureReason::Timeout)
+        ));
+
+        assert_eq!(lines.next().unwrap().unwrap(), b"bar");
+        assert!(lines.next().is_none());
+    }
+}
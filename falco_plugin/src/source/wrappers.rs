@@ -1,15 +1,21 @@
+use crate::base::deadline::Watchdog;
 use crate::base::wrappers::PluginWrapper;
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
 use crate::error::ffi_result::FfiResult;
 use crate::source::SourcePluginInstanceWrapper;
 use crate::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
 use crate::strings::cstring_writer::WriteIntoCString;
 use crate::strings::from_ptr::try_str_from_ptr;
+use crate::tables::EntryCacheScope;
+use crate::tables::ReadOnlyPhase;
+use crate::FailureReason;
 use falco_plugin_api::plugin_api__bindgen_ty_1 as source_plugin_api;
 use falco_plugin_api::{
     ss_instance_t, ss_plugin_event, ss_plugin_event_input, ss_plugin_rc,
-    ss_plugin_rc_SS_PLUGIN_FAILURE, ss_plugin_rc_SS_PLUGIN_SUCCESS, ss_plugin_t,
+    ss_plugin_rc_SS_PLUGIN_FAILURE, ss_plugin_rc_SS_PLUGIN_SUCCESS, ss_plugin_rc_SS_PLUGIN_TIMEOUT,
+    ss_plugin_t,
 };
-use std::ffi::c_char;
+use std::ffi::{c_char, c_void};
 use std::io::Write;
 use std::marker::PhantomData;
 
@@ -152,11 +158,17 @@ pub unsafe extern "C-unwind" fn plugin_open<T: SourcePlugin>(
         match actual_plugin.plugin.open(params) {
             Ok(instance) => {
                 *rc = ss_plugin_rc_SS_PLUGIN_SUCCESS;
-                Box::into_raw(Box::new(SourcePluginInstanceWrapper {
+                let instance = Box::into_raw(Box::new(SourcePluginInstanceWrapper {
                     instance,
                     batch: Default::default(),
-                }))
-                .cast()
+                    batch_capacity: 0,
+                    metrics_index: 0,
+                }));
+                // the instance stays registered until `plugin_close` frees it
+                (*instance).metrics_index = plugin
+                    .instance_metrics
+                    .register(instance.cast(), instance_metrics::<T::Instance>);
+                instance.cast()
             }
             Err(e) => {
                 e.set_last_error(&mut plugin.error_buf);
@@ -191,10 +203,34 @@ pub unsafe extern "C-unwind" fn plugin_close<T: SourcePlugin>(
     }
     unsafe {
         let mut inst = Box::from_raw(instance);
+        plugin.instance_metrics.unregister(inst.metrics_index);
         actual_plugin.plugin.close(&mut inst.instance);
     }
 }
 
+/// # Safety
+///
+/// `instance` must be a valid pointer to `SourcePluginInstanceWrapper<I>`
+unsafe fn instance_metrics<I: SourcePluginInstance>(
+    instance: *mut c_void,
+    emit: &mut dyn FnMut(Metric),
+) {
+    let instance = instance as *mut SourcePluginInstanceWrapper<I>;
+    if let Some(instance) = unsafe { instance.as_mut() } {
+        instance
+            .instance
+            .get_metrics()
+            .into_iter()
+            .for_each(&mut *emit);
+        if let Some(pause) = instance.instance.pause_handle() {
+            emit(Metric::new(
+                MetricLabel::new(c"paused", MetricType::NonMonotonic),
+                MetricValue::U32(pause.is_paused() as u32),
+            ));
+        }
+    }
+}
+
 /// # Safety
 ///
 /// All pointers must be valid
@@ -218,25 +254,56 @@ pub unsafe extern "C-unwind" fn plugin_next_batch<T: SourcePlugin>(
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
 
-        instance.batch.reset();
-        let mut batch = EventBatch::new(&instance.batch);
+        if instance
+            .instance
+            .pause_handle()
+            .is_some_and(|pause| pause.is_paused())
+        {
+            *nevts = 0;
+            *evts = std::ptr::null_mut();
+            return ss_plugin_rc_SS_PLUGIN_TIMEOUT;
+        }
+
+        EventBatch::reset_storage(&mut instance.batch);
+        let mut batch = EventBatch::with_capacity(&instance.batch, instance.batch_capacity);
+        let _read_only = ReadOnlyPhase::enter("event generation");
+        let _entry_cache = EntryCacheScope::enter();
+        let watchdog = Watchdog::start(
+            "next_batch",
+            T::NEXT_BATCH_DEADLINE,
+            T::NEXT_BATCH_DEADLINE_POLICY,
+        );
         let batch_result = instance
             .instance
             .next_batch(&mut actual_plugin.plugin, &mut batch);
-        match batch_result {
-            Ok(()) => {
+        let deadline_result = watchdog.finish(&mut plugin.deadline_metrics, FailureReason::Timeout);
+        let batch_result = batch_result.and(deadline_result);
+        let rc = match batch_result {
+            Ok(()) => ss_plugin_rc_SS_PLUGIN_SUCCESS,
+            Err(e) => {
+                e.set_last_error(&mut plugin.error_buf);
+                e.status_code()
+            }
+        };
+
+        match rc {
+            // a timeout or EOF may come with a partial batch, which gets delivered
+            // before the framework acts on the return code
+            falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS
+            | falco_plugin_api::ss_plugin_rc_SS_PLUGIN_TIMEOUT
+            | falco_plugin_api::ss_plugin_rc_SS_PLUGIN_EOF => {
                 let events = batch.get_events();
+                instance.batch_capacity = instance.batch_capacity.max(events.len());
                 *nevts = events.len() as u32;
                 *evts = events as *const _ as *mut _;
-                ss_plugin_rc_SS_PLUGIN_SUCCESS
             }
-            Err(e) => {
+            _ => {
                 *nevts = 0;
                 *evts = std::ptr::null_mut();
-                e.set_last_error(&mut plugin.error_buf);
-                e.status_code()
             }
         }
+
+        rc
     }
 }
 
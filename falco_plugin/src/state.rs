@@ -0,0 +1,129 @@
+//! # State shared between parsing and extraction
+//!
+//! Plugins implementing both [`ParsePlugin`] and [`ExtractPlugin`] often build some
+//! in-memory state while parsing events and read it back when extracting fields, without
+//! exporting it to other plugins as a [table](`crate::tables`). Since both capabilities are
+//! implemented on the same type, the state can simply live in a field of the plugin, but it's
+//! easy to accidentally modify it during extraction, which makes the extracted values depend
+//! on the order in which the framework requests fields.
+//!
+//! [`StateCell`] wraps such state and only grants access in the right context:
+//! - mutable access requires a [`ParseInput`], so it's only available while parsing events
+//! - shared access requires an [`ExtractRequest`] (or a [`ParseInput`])
+//!
+//! ```
+//! # use std::collections::HashMap;
+//! # use std::ffi::CStr;
+//! use falco_event::events::RawEvent;
+//! use falco_plugin::anyhow::Error;
+//! use falco_plugin::base::Plugin;
+//! use falco_plugin::extract::{field, EventInput, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+//! use falco_plugin::parse::{ParseInput, ParsePlugin};
+//! use falco_plugin::state::StateCell;
+//! use falco_plugin::tables::TablesInput;
+//! use falco_plugin::{extract_plugin, parse_plugin, plugin};
+//!
+//! struct MyPlugin {
+//!     events_per_thread: StateCell<HashMap<i64, u64>>,
+//! }
+//!
+//! impl Plugin for MyPlugin {
+//!     // ...
+//! #    const NAME: &'static CStr = c"sample-plugin-rs";
+//! #    const PLUGIN_VERSION: &'static CStr = c"0.0.1";
+//! #    const DESCRIPTION: &'static CStr = c"A sample Falco plugin that does nothing";
+//! #    const CONTACT: &'static CStr = c"you@example.com";
+//! #    type ConfigType = ();
+//! #
+//!     fn new(input: Option<&TablesInput>, config: Self::ConfigType) -> Result<Self, Error> {
+//!         Ok(Self {
+//!             events_per_thread: StateCell::default(),
+//!         })
+//!     }
+//! }
+//!
+//! impl ParsePlugin for MyPlugin {
+//!     type Event<'a> = RawEvent<'a>;
+//!
+//!     fn parse_event(&mut self, event: &EventInput<RawEvent>, parse_input: &ParseInput)
+//!         -> Result<(), Error> {
+//!         let tid = event.event()?.metadata.tid;
+//!         *self.events_per_thread.get_mut(parse_input).entry(tid).or_default() += 1;
+//!         Ok(())
+//!     }
+//! }
+//!
+//! impl MyPlugin {
+//!     fn extract_count(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+//!         let tid = req.event.event()?.metadata.tid;
+//!         Ok(self.events_per_thread.get(&req).get(&tid).copied().unwrap_or_default())
+//!     }
+//! }
+//!
+//! impl ExtractPlugin for MyPlugin {
+//!     type Event<'a> = RawEvent<'a>;
+//!     type ExtractContext = ();
+//!
+//!     const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+//!         field("my_plugin.thread_events", &Self::extract_count),
+//!     ];
+//! }
+//!
+//! plugin!(MyPlugin);
+//! parse_plugin!(MyPlugin);
+//! extract_plugin!(MyPlugin);
+//! ```
+//!
+//! [`ParsePlugin`]: crate::parse::ParsePlugin
+//! [`ExtractPlugin`]: crate::extract::ExtractPlugin
+
+use crate::extract::{ExtractPlugin, ExtractRequest};
+use crate::parse::ParseInput;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// # A proof of being in a context allowed to read a [`StateCell`]
+///
+/// This trait is sealed and implemented for [`ParseInput`] and [`ExtractRequest`].
+pub trait StateReadAccess: private::Sealed {}
+
+impl private::Sealed for ParseInput<'_> {}
+impl StateReadAccess for ParseInput<'_> {}
+
+impl<P: ExtractPlugin> private::Sealed for ExtractRequest<'_, '_, '_, '_, P> {}
+impl<P: ExtractPlugin> StateReadAccess for ExtractRequest<'_, '_, '_, '_, P> {}
+
+/// # Plugin state, writable while parsing and readable while extracting
+///
+/// See the [module documentation](`crate::state`) for details.
+#[derive(Debug, Default)]
+pub struct StateCell<T>(T);
+
+impl<T> StateCell<T> {
+    /// Create a new cell holding the initial state
+    pub fn new(value: T) -> Self {
+        Self(value)
+    }
+
+    /// Get a shared reference to the state
+    ///
+    /// Pass the [`ExtractRequest`] (or [`ParseInput`]) you received as the proof that you're
+    /// in the right context.
+    pub fn get(&self, _access: &impl StateReadAccess) -> &T {
+        &self.0
+    }
+
+    /// Get a mutable reference to the state
+    ///
+    /// Pass the [`ParseInput`] you received as the proof that you're parsing an event.
+    pub fn get_mut(&mut self, _input: &ParseInput) -> &mut T {
+        &mut self.0
+    }
+
+    /// Consume the cell, returning the state
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
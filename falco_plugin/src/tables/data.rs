@@ -60,6 +60,13 @@ pub trait TableData: seal::Sealed {
 }
 
 /// # A trait describing types usable as table keys
+///
+/// Tables can be keyed by any integer type (`u8` through `u64`, `i8` through `i64`),
+/// by strings ([`CString`], borrowed as [`CStr`]) and by booleans.
+///
+/// **Note**: the plugin API represents booleans as 32-bit values, so a native [`bool`] cannot
+/// be borrowed from the raw data. Use [`Bool`] (which converts to and from `bool`) as the key
+/// type instead.
 pub trait Key: TableData {
     /// The type borrowed from the FFI representation
     type Borrowed: ?Sized;
@@ -100,6 +107,17 @@ pub trait Value: TableData {
         assoc: &Self::AssocData,
     ) -> Self::Value<'a>;
 
+    /// Check that a [`ss_plugin_state_data`] value is valid for this type
+    ///
+    /// This gets called before [`Value::from_data_with_assoc`]. All values are valid
+    /// for most types, except e.g. [`TableEnum`](`crate::tables::TableEnum`) types.
+    ///
+    /// # Safety
+    /// `data` must contain valid data of the correct type
+    unsafe fn validate_data(_data: &ss_plugin_state_data) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
     /// Given a raw table, fetch the field's metadata
     ///
     /// The only interesting implementation is for `Box<Table>`, which gets all the fields
@@ -0,0 +1,205 @@
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::export::field_value::traits::{
+    seal, FieldValue, FromDynamicFieldValue, StaticField,
+};
+use crate::tables::FieldTypeId;
+use falco_plugin_api::ss_plugin_state_data;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::ffi::{CStr, CString};
+use std::fmt::{Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+use std::sync::OnceLock;
+
+/// # A structured value stored in table fields as a JSON string
+///
+/// The plugin API only supports integers, booleans and strings as field values. To expose
+/// e.g. a list or a small struct in an exported table, wrap it in `Encoded`:
+///
+/// ```
+/// use falco_plugin::tables::{export, Encoded};
+///
+/// #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+/// struct Peer {
+///     addr: String,
+///     port: u16,
+/// }
+///
+/// #[derive(export::Entry)]
+/// struct Connection {
+///     peers: export::Public<Encoded<Vec<Peer>>>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut table = export::Table::<u64, Connection>::new(c"connections")?;
+/// let mut entry = table.create_entry()?;
+/// entry.peers.push(Peer { addr: String::from("127.0.0.1"), port: 8080 });
+/// assert_eq!(
+///     entry.peers.encoded()?,
+///     cr#"[{"addr":"127.0.0.1","port":8080}]"#,
+/// );
+/// # table.insert(&1, entry);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Your plugin gets typed access to the value (`Encoded<T>` derefs to `T`), while other plugins
+/// see a string field holding the value serialized as JSON. This is a convention of the Rust SDK,
+/// not of the plugin API, so plugins importing the table read (and write) the field as a plain
+/// string. Rust plugins can use [`Encoded::decode`] to get the typed value back (and
+/// [`Encoded::encoded`] to build a string to write):
+///
+/// ```ignore
+/// // in the importing plugin, with `peers: import::Field<CStr, Connection>`
+/// let peers = Encoded::<Vec<Peer>>::decode(conn.get_peers(reader)?)?;
+/// ```
+///
+/// Writes from other plugins must be valid JSON for `T`, otherwise they fail.
+///
+/// The encoded string is computed when first requested and cached until the value is modified.
+#[derive(Default)]
+pub struct Encoded<T> {
+    value: T,
+    encoded: OnceLock<CString>,
+}
+
+impl<T> Encoded<T> {
+    /// Wrap a value
+    pub fn new(value: T) -> Self {
+        Self {
+            value,
+            encoded: OnceLock::new(),
+        }
+    }
+
+    /// Unwrap the value
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<T: Serialize> Encoded<T> {
+    /// Get the value serialized as JSON
+    ///
+    /// This is the string other plugins see when reading the field.
+    pub fn encoded(&self) -> Result<&CStr, anyhow::Error> {
+        if let Some(encoded) = self.encoded.get() {
+            return Ok(encoded);
+        }
+
+        // JSON escapes NUL characters in strings, so the output never contains NUL bytes
+        let encoded = CString::new(serde_json::to_vec(&self.value)?)?;
+        Ok(self.encoded.get_or_init(|| encoded))
+    }
+}
+
+impl<T: DeserializeOwned> Encoded<T> {
+    /// Decode a value from its JSON representation
+    pub fn decode(encoded: &CStr) -> Result<Self, anyhow::Error> {
+        let value = serde_json::from_slice(encoded.to_bytes())?;
+        Ok(Self {
+            value,
+            encoded: OnceLock::from(encoded.to_owned()),
+        })
+    }
+}
+
+impl<T> Deref for Encoded<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.value
+    }
+}
+
+impl<T> DerefMut for Encoded<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        // the value may change, so it will need to be encoded again
+        self.encoded.take();
+        &mut self.value
+    }
+}
+
+impl<T: Debug> Debug for Encoded<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Encoded").field(&self.value).finish()
+    }
+}
+
+impl<T: Serialize> Serialize for Encoded<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Encoded<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+// exported tables
+
+impl<T: Serialize + DeserializeOwned> seal::Sealed for Encoded<T> {}
+
+impl<T: Serialize + DeserializeOwned> FieldValue for Encoded<T> {
+    fn to_data(
+        &self,
+        out: &mut ss_plugin_state_data,
+        type_id: FieldTypeId,
+    ) -> Result<(), anyhow::Error> {
+        if type_id != FieldTypeId::String {
+            anyhow::bail!(
+                "Type mismatch, requested {:?}, got {}",
+                type_id,
+                std::any::type_name::<Self>()
+            )
+        }
+
+        out.str_ = self.encoded()?.as_ptr();
+        Ok(())
+    }
+}
+
+impl<T: Serialize + DeserializeOwned> StaticField for Encoded<T> {
+    const TYPE_ID: FieldTypeId = FieldTypeId::String;
+    const READONLY: bool = false;
+}
+
+impl<T: DeserializeOwned> FromDynamicFieldValue for Encoded<T> {
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, anyhow::Error> {
+        Self::decode(&CString::try_from(value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoded_cache() {
+        let mut tags = Encoded::new(vec![1u32, 2]);
+        assert_eq!(tags.encoded().unwrap(), c"[1,2]");
+
+        tags.push(3);
+        assert_eq!(tags.encoded().unwrap(), c"[1,2,3]");
+
+        let decoded = Encoded::<Vec<u32>>::decode(c"[4]").unwrap();
+        assert_eq!(*decoded, [4]);
+        assert!(Encoded::<Vec<u32>>::decode(c"{}").is_err());
+    }
+
+    #[test]
+    fn test_encoded_field() {
+        let tags = Encoded::new(vec![String::from("a\0b")]);
+        let mut data = ss_plugin_state_data { u64_: 0 };
+        tags.to_data(&mut data, FieldTypeId::String).unwrap();
+        let encoded = unsafe { CStr::from_ptr(data.str_) };
+        assert_eq!(encoded, cr#"["a\u0000b"]"#);
+        assert!(tags.to_data(&mut data, FieldTypeId::U64).is_err());
+
+        let value = DynamicFieldValue::String(encoded.to_owned());
+        let decoded = Encoded::<Vec<String>>::from_dynamic_field_value(value).unwrap();
+        assert_eq!(*decoded, *tags);
+    }
+}
@@ -0,0 +1,183 @@
+use crate::async_event::{AsyncEvent, AsyncHandler};
+use crate::event::{EventInput, PayloadFormat};
+use crate::tables::export::entry::table_metadata::traits::TableMetadata;
+use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::{Table, TableValue};
+use crate::tables::Key;
+use falco_event::events::{Event, EventMetadata};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::ffi::CStr;
+use std::hash::Hash;
+
+impl<K, E, M> Table<K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    /// # Dump the table contents as a series of async events
+    ///
+    /// Call this from [`AsyncEventPlugin::dump_state`](`crate::async_event::AsyncEventPlugin::dump_state`)
+    /// to save the table contents in a capture file. Each event is called `name` and carries
+    /// a list of up to `chunk_size` `[key, entry]` pairs, encoded using `format`
+    /// (pass a `chunk_size` of 1 to emit one event per entry).
+    ///
+    /// To make the entry type serializable, derive `serde::Serialize` along with
+    /// [`Entry`](`crate::tables::export::Entry`). Only the fields defined in the entry struct
+    /// are saved, not the ones added at runtime by other plugins. Tables with nested tables
+    /// are not supported.
+    ///
+    /// The entries are collected like in [`Table::iter`], so without the `thread-safe-tables`
+    /// feature, entries locked at the time of the call (e.g. ones you're holding on to)
+    /// are skipped.
+    ///
+    /// Use [`Table::restore_state`] to load the table contents back.
+    pub fn dump_state(
+        &self,
+        handler: &AsyncHandler,
+        name: &CStr,
+        format: PayloadFormat,
+        chunk_size: usize,
+    ) -> Result<(), anyhow::Error>
+    where
+        K: Serialize,
+        E: Serialize,
+    {
+        // serialize everything first, so that we don't call into the framework
+        // with the table locked
+        let payloads = self.serialize_entries(format, chunk_size)?;
+
+        for data in payloads {
+            handler.emit(Event {
+                metadata: EventMetadata::default(),
+                params: AsyncEvent {
+                    plugin_id: 0,
+                    name,
+                    data,
+                },
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// # Restore the table contents from an async event
+    ///
+    /// Call this from [`ParsePlugin::parse_event`](`crate::parse::ParsePlugin::parse_event`)
+    /// for every event. If it's an async event called `name`, the entries it contains
+    /// (as emitted by [`Table::dump_state`]) are inserted into the table, replacing
+    /// any existing entries with the same keys.
+    ///
+    /// Returns the number of restored entries (zero for unrelated events).
+    pub fn restore_state<T>(
+        &mut self,
+        event: &EventInput<T>,
+        name: &CStr,
+        format: PayloadFormat,
+    ) -> Result<usize, anyhow::Error>
+    where
+        K: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let Some(entries) = event.parse_async_event::<Vec<(K, E)>>(name, format)? else {
+            return Ok(0);
+        };
+
+        self.restore_entries(entries)
+    }
+
+    /// # Convert the table contents to JSON
+    ///
+    /// Returns a JSON object mapping the (stringified) keys to the serialized entries, which is
+    /// useful e.g. for debug output and test assertions. As with [`Table::dump_state`], the entry
+    /// type needs to derive `serde::Serialize` and only the fields defined in the entry struct
+    /// are included.
+    ///
+    /// The entries are collected like in [`Table::iter`], so without the `thread-safe-tables`
+    /// feature, entries locked at the time of the call are skipped.
+    ///
+    /// Use [`Table::from_json`] to build a table from the returned value.
+    pub fn to_json(&self) -> Result<serde_json::Value, anyhow::Error>
+    where
+        K: Serialize,
+        E: Serialize,
+    {
+        let mut json = serde_json::Map::new();
+        for (key, entry) in self.iter() {
+            let key = match serde_json::to_value(&key)? {
+                serde_json::Value::String(key) => key,
+                serde_json::Value::Number(key) => key.to_string(),
+                serde_json::Value::Bool(key) => key.to_string(),
+                // `CString` keys get serialized as byte arrays
+                bytes @ serde_json::Value::Array(_) => {
+                    String::from_utf8(serde_json::from_value(bytes)?)?
+                }
+                key => anyhow::bail!("Cannot use {key} as a JSON object key"),
+            };
+            json.insert(key, serde_json::to_value(&**entry)?);
+        }
+
+        Ok(serde_json::Value::Object(json))
+    }
+
+    /// # Create a table from JSON
+    ///
+    /// This is the counterpart to [`Table::to_json`]: it creates a new table called `name`,
+    /// filled with the entries from a JSON object mapping keys to serialized entries.
+    /// The entry type needs to derive `serde::Deserialize`.
+    pub fn from_json(name: &'static CStr, json: serde_json::Value) -> Result<Self, anyhow::Error>
+    where
+        K: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let entries: BTreeMap<K, E> = serde_json::from_value(json)?;
+        let mut table = Self::new(name)?;
+        table.restore_entries(entries.into_iter().collect())?;
+        Ok(table)
+    }
+
+    /// Serialize the table contents as lists of up to `chunk_size` `(key, entry)` pairs
+    pub(crate) fn serialize_entries(
+        &self,
+        format: PayloadFormat,
+        chunk_size: usize,
+    ) -> Result<Vec<Vec<u8>>, anyhow::Error>
+    where
+        K: Serialize,
+        E: Serialize,
+    {
+        // this locks the table one shard at a time and the entries one by one,
+        // skipping the ones that are currently locked (see `Table::iter`)
+        let values = self.iter().collect::<Vec<_>>();
+
+        values
+            .chunks(chunk_size.max(1))
+            .map(|chunk| {
+                let chunk = chunk
+                    .iter()
+                    .map(|(key, entry)| (key, &***entry))
+                    .collect::<Vec<(&K, &E)>>();
+                format.encode(&chunk)
+            })
+            .collect()
+    }
+
+    /// Insert deserialized `(key, entry)` pairs into the table
+    pub(crate) fn restore_entries(&mut self, entries: Vec<(K, E)>) -> Result<usize, anyhow::Error> {
+        let count = entries.len();
+        for (key, value) in entries {
+            let mut entry = self.create_entry()?;
+            **entry = value;
+            self.insert(key.borrow(), entry);
+        }
+
+        Ok(count)
+    }
+}
@@ -0,0 +1,135 @@
+use crate::tables::export::entry::extensible::ExtensibleEntry;
+use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::field_descriptor::FieldDescriptor;
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::FieldTypeId;
+use std::sync::Arc;
+
+/// # The type of a field listed in the plugin config
+///
+/// In the config, the types are spelled in lowercase (`"u64"`, `"string"` etc.).
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+#[allow(missing_docs)]
+pub enum DynamicFieldType {
+    U8,
+    I8,
+    U16,
+    I16,
+    U32,
+    I32,
+    U64,
+    I64,
+    Bool,
+    String,
+}
+
+impl From<DynamicFieldType> for FieldTypeId {
+    fn from(value: DynamicFieldType) -> Self {
+        match value {
+            DynamicFieldType::U8 => FieldTypeId::U8,
+            DynamicFieldType::I8 => FieldTypeId::I8,
+            DynamicFieldType::U16 => FieldTypeId::U16,
+            DynamicFieldType::I16 => FieldTypeId::I16,
+            DynamicFieldType::U32 => FieldTypeId::U32,
+            DynamicFieldType::I32 => FieldTypeId::I32,
+            DynamicFieldType::U64 => FieldTypeId::U64,
+            DynamicFieldType::I64 => FieldTypeId::I64,
+            DynamicFieldType::Bool => FieldTypeId::Bool,
+            DynamicFieldType::String => FieldTypeId::String,
+        }
+    }
+}
+
+/// # A field to add to a table, as listed in the plugin config
+///
+/// This type implements [`serde::Deserialize`] and [`schemars::JsonSchema`], so it can be
+/// embedded directly in the plugin's [`ConfigType`](`crate::base::Plugin::ConfigType`):
+///
+/// ```
+/// use falco_plugin::schemars::JsonSchema;
+/// use falco_plugin::serde::Deserialize;
+/// use falco_plugin::tables::export;
+///
+/// #[derive(JsonSchema, Deserialize)]
+/// #[schemars(crate = "falco_plugin::schemars")]
+/// #[serde(crate = "falco_plugin::serde")]
+/// struct MyConfig {
+///     // e.g. [{"name": "owner", "type": "string"}, {"name": "score", "type": "u64"}]
+///     extra_fields: Vec<export::DynamicFieldSpec>,
+/// }
+/// ```
+///
+/// Pass the list to [`Table::add_dynamic_fields`](`crate::tables::export::Table::add_dynamic_fields`)
+/// to register the fields.
+#[derive(
+    Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize, schemars::JsonSchema,
+)]
+pub struct DynamicFieldSpec {
+    /// The field name, as seen by other plugins
+    pub name: String,
+    /// The field type
+    #[serde(rename = "type")]
+    pub field_type: DynamicFieldType,
+    /// Whether other plugins are forbidden from writing to the field
+    #[serde(default)]
+    pub readonly: bool,
+}
+
+/// # A handle to a field added at runtime
+///
+/// Returned from [`Table::add_dynamic_fields`](`crate::tables::export::Table::add_dynamic_fields`),
+/// it lets your plugin read and write the field in entries of the table. The values are checked
+/// against the field type, so e.g. a `u64` field cannot hold a string.
+///
+/// Fields start out unset in every entry, until a value is written (by your plugin or by others).
+#[derive(Debug, Clone)]
+pub struct DynamicField {
+    field_type: DynamicFieldType,
+    descriptor: Arc<FieldDescriptor>,
+}
+
+impl DynamicField {
+    pub(crate) fn new(field_type: DynamicFieldType, descriptor: Arc<FieldDescriptor>) -> Self {
+        Self {
+            field_type,
+            descriptor,
+        }
+    }
+
+    /// Return the type of the field
+    pub fn field_type(&self) -> DynamicFieldType {
+        self.field_type
+    }
+
+    /// Get the value of the field in an entry
+    ///
+    /// Returns `None` if the field has not been set in this entry yet.
+    pub fn get<'a, E>(&self, entry: &'a ExtensibleEntry<E>) -> Option<&'a DynamicFieldValue> {
+        let value = entry.custom_field(self.descriptor.index)?;
+        match value {
+            DynamicFieldValue::None => None,
+            value => Some(value),
+        }
+    }
+
+    /// Set the value of the field in an entry
+    ///
+    /// Fails if the value does not match the field type.
+    pub fn set<E: Entry>(
+        &self,
+        entry: &mut ExtensibleEntry<E>,
+        value: DynamicFieldValue,
+    ) -> Result<(), anyhow::Error> {
+        if value.type_id() != Some(self.descriptor.type_id) {
+            anyhow::bail!(
+                "Type mismatch, expected {:?}, got {:?}",
+                self.field_type,
+                value
+            );
+        }
+        entry.set(self.descriptor.index, value)
+    }
+}
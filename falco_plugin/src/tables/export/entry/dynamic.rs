@@ -17,9 +17,9 @@ impl HasMetadata for DynamicEntry {
 
     fn new_with_metadata(
         _tag: &'static CStr,
-        _meta: &Self::Metadata,
+        meta: &Self::Metadata,
     ) -> Result<Self, anyhow::Error> {
-        Ok(Self::default())
+        meta.read_arc().new_entry()
     }
 }
 
@@ -17,6 +17,20 @@ pub struct ExtensibleEntry<E> {
     custom_fields: DynamicEntry,
 }
 
+impl<E> ExtensibleEntry<E> {
+    pub(crate) fn custom_fields(&self) -> &DynamicEntry {
+        &self.custom_fields
+    }
+
+    /// Return the value of a dynamic field, if it has been stored in this entry
+    pub(crate) fn custom_field(&self, key: FieldId) -> Option<&DynamicFieldValue> {
+        match key {
+            FieldId::Static(_) => None,
+            FieldId::Dynamic(index) => self.custom_fields.as_slice().get(index),
+        }
+    }
+}
+
 impl<E> Deref for ExtensibleEntry<E> {
     type Target = E;
 
@@ -38,9 +52,10 @@ where
     type Metadata = RefShared<ExtensibleEntryMetadata<E::Metadata>>;
 
     fn new_with_metadata(tag: &'static CStr, meta: &Self::Metadata) -> Result<Self, Error> {
+        let meta = meta.read_arc();
         Ok(Self {
-            inner: E::new_with_metadata(tag, &meta.read_arc().inner)?,
-            custom_fields: Default::default(),
+            inner: E::new_with_metadata(tag, &meta.inner)?,
+            custom_fields: meta.new_custom_fields()?,
         })
     }
 }
@@ -1,6 +1,10 @@
+use crate::tables::export::entry::dynamic::DynamicEntry;
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
+use crate::tables::export::entry::traits::Entry;
 use crate::tables::export::field_descriptor::FieldDescriptor;
 use crate::tables::export::field_descriptor::{FieldId, FieldRef};
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::export::field_value::dynamic_table::{DynamicTable, DynamicTableSpec};
 use crate::tables::export::metadata::Metadata;
 use crate::tables::FieldTypeId;
 use falco_plugin_api::{ss_plugin_bool, ss_plugin_state_type, ss_plugin_table_fieldinfo};
@@ -12,12 +16,42 @@ use std::sync::Arc;
 #[derive(Debug)]
 pub struct DynamicFieldsOnly {
     pub(crate) fields: BTreeMap<CString, Arc<FieldDescriptor>>,
+    tables: Vec<(FieldId, DynamicTableSpec)>,
+}
+
+impl DynamicFieldsOnly {
+    /// Add a table-valued field, holding a nested table created from `spec` in each entry
+    pub(crate) fn add_table_field(
+        &mut self,
+        name: &CStr,
+        spec: DynamicTableSpec,
+    ) -> Option<FieldRef> {
+        if self.fields.contains_key(name) {
+            return None;
+        }
+
+        let field = self.add_field(name, FieldTypeId::Table, true)?;
+        self.tables.push((field.as_ref().index, spec));
+        Some(field)
+    }
+
+    /// Create the dynamic fields for a new entry
+    ///
+    /// Plain fields start out unset, but table-valued fields need a nested table in each entry.
+    pub(crate) fn new_entry(&self) -> Result<DynamicEntry, anyhow::Error> {
+        let mut entry = DynamicEntry::new();
+        for (index, spec) in &self.tables {
+            entry.set(*index, DynamicFieldValue::Table(DynamicTable::new(spec)?))?;
+        }
+        Ok(entry)
+    }
 }
 
 impl Metadata for DynamicFieldsOnly {
     fn new() -> Result<Self, anyhow::Error> {
         Ok(Self {
             fields: Default::default(),
+            tables: Vec::new(),
         })
     }
 }
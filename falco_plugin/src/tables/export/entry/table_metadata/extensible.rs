@@ -1,6 +1,8 @@
+use crate::tables::export::entry::dynamic::DynamicEntry;
 use crate::tables::export::entry::table_metadata::dynamic::DynamicFieldsOnly;
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::field_descriptor::FieldRef;
+use crate::tables::export::field_value::dynamic_table::DynamicTableSpec;
 use crate::tables::export::metadata::Metadata;
 use crate::tables::FieldTypeId;
 use anyhow::Error;
@@ -13,6 +15,32 @@ pub struct ExtensibleEntryMetadata<M> {
     custom_fields: DynamicFieldsOnly,
 }
 
+impl<M> ExtensibleEntryMetadata<M> {
+    /// Return the number of fields added by other plugins
+    pub(crate) fn num_dynamic_fields(&self) -> usize {
+        self.custom_fields.fields.len()
+    }
+
+    /// Create the dynamic fields for a new entry
+    pub(crate) fn new_custom_fields(&self) -> Result<DynamicEntry, Error> {
+        self.custom_fields.new_entry()
+    }
+}
+
+impl<M: TableMetadata> ExtensibleEntryMetadata<M> {
+    /// Add a table-valued field, see [`DynamicFieldsOnly::add_table_field`]
+    pub(crate) fn add_table_field(
+        &mut self,
+        name: &CStr,
+        spec: DynamicTableSpec,
+    ) -> Option<FieldRef> {
+        if self.inner.get_field(name).is_some() {
+            return None;
+        }
+        self.custom_fields.add_table_field(name, spec)
+    }
+}
+
 impl<M> Metadata for ExtensibleEntryMetadata<M>
 where
     M: Metadata,
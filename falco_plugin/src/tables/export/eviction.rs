@@ -0,0 +1,242 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::time::{Duration, Instant};
+
+/// # Which entry to evict when a bounded table is full
+///
+/// See [`Table::with_max_entries`](`crate::tables::export::Table::with_max_entries`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EvictionPolicy {
+    /// Evict the entry that was inserted the longest time ago
+    Fifo,
+    /// Evict the entry that was inserted or looked up the longest time ago
+    Lru,
+}
+
+pub(crate) type EvictionCallback<K, E> = Box<dyn FnMut(&K, &mut E)>;
+
+#[derive(Debug)]
+struct Tracked {
+    insert_seq: u64,
+    use_seq: u64,
+    touched: Instant,
+}
+
+/// The bookkeeping for bounded tables and tables with a TTL
+///
+/// Every tracked key gets two sequence numbers: one bumped on each insert and one bumped
+/// on each insert or lookup, so the eviction candidates are simply the keys in sequence order
+/// (insertion order for FIFO, usage order for LRU and expiry).
+pub(crate) struct Eviction<K, E> {
+    max_entries: Option<(usize, EvictionPolicy)>,
+    ttl: Option<Duration>,
+    next_seq: u64,
+    by_insert: BTreeMap<u64, K>,
+    by_use: BTreeMap<u64, K>,
+    by_key: BTreeMap<K, Tracked>,
+    pub(crate) callback: Option<EvictionCallback<K, E>>,
+}
+
+impl<K: Debug, E> Debug for Eviction<K, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Eviction")
+            .field("max_entries", &self.max_entries)
+            .field("ttl", &self.ttl)
+            .field("tracked", &self.by_key.len())
+            .finish()
+    }
+}
+
+impl<K: Ord, E> Eviction<K, E> {
+    pub(crate) fn new() -> Self {
+        Self {
+            max_entries: None,
+            ttl: None,
+            next_seq: 0,
+            by_insert: BTreeMap::new(),
+            by_use: BTreeMap::new(),
+            by_key: BTreeMap::new(),
+            callback: None,
+        }
+    }
+
+    pub(crate) fn set_max_entries(&mut self, max_entries: usize, policy: EvictionPolicy) {
+        self.max_entries = Some((max_entries, policy));
+    }
+
+    pub(crate) fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = Some(ttl);
+    }
+
+    pub(crate) fn max_entries(&self) -> Option<usize> {
+        self.max_entries.map(|(max_entries, _)| max_entries)
+    }
+
+    fn next_seq(&mut self) -> u64 {
+        let seq = self.next_seq;
+        self.next_seq += 1;
+        seq
+    }
+
+    fn bump_use<Q>(&mut self, key: &Q, now: Instant) -> Option<&mut Tracked>
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let new_seq = self.next_seq();
+        let tracked = self.by_key.get_mut(key)?;
+        if let Some(key) = self.by_use.remove(&tracked.use_seq) {
+            self.by_use.insert(new_seq, key);
+        }
+        tracked.use_seq = new_seq;
+        tracked.touched = now;
+        Some(tracked)
+    }
+
+    /// Record an entry being inserted (or replaced)
+    pub(crate) fn inserted<Q>(&mut self, key: &Q, now: Instant)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ToOwned<Owned = K> + ?Sized,
+    {
+        let new_seq = self.next_seq();
+        match self.bump_use(key, now) {
+            Some(tracked) => {
+                let old_seq = std::mem::replace(&mut tracked.insert_seq, new_seq);
+                if let Some(key) = self.by_insert.remove(&old_seq) {
+                    self.by_insert.insert(new_seq, key);
+                }
+            }
+            None => {
+                let tracked = Tracked {
+                    insert_seq: new_seq,
+                    use_seq: new_seq,
+                    touched: now,
+                };
+                self.by_key.insert(key.to_owned(), tracked);
+                self.by_insert.insert(new_seq, key.to_owned());
+                self.by_use.insert(new_seq, key.to_owned());
+            }
+        }
+    }
+
+    /// Record an entry being looked up
+    pub(crate) fn used<Q>(&mut self, key: &Q, now: Instant)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.bump_use(key, now);
+    }
+
+    /// Record an entry being removed
+    pub(crate) fn removed<Q>(&mut self, key: &Q)
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        if let Some(tracked) = self.by_key.remove(key) {
+            self.by_insert.remove(&tracked.insert_seq);
+            self.by_use.remove(&tracked.use_seq);
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.by_insert.clear();
+        self.by_use.clear();
+        self.by_key.clear();
+    }
+
+    /// Check whether an entry has not been touched for longer than the TTL
+    pub(crate) fn is_expired<Q>(&self, key: &Q, now: Instant) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let (Some(ttl), Some(tracked)) = (self.ttl, self.by_key.get(key)) else {
+            return false;
+        };
+        now.saturating_duration_since(tracked.touched) > ttl
+    }
+
+    /// The keys of expired entries, oldest first
+    pub(crate) fn expired(&self, now: Instant) -> impl Iterator<Item = &K> {
+        self.by_use
+            .values()
+            .take_while(move |key| self.is_expired(*key, now))
+    }
+
+    /// The tracked keys, in eviction order for the capacity limit
+    pub(crate) fn candidates(&self) -> impl Iterator<Item = &K> {
+        match self.max_entries {
+            Some((_, EvictionPolicy::Fifo)) => self.by_insert.values(),
+            _ => self.by_use.values(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(eviction: &Eviction<u64, ()>) -> Vec<u64> {
+        eviction.candidates().copied().collect()
+    }
+
+    fn new_eviction(policy: EvictionPolicy) -> Eviction<u64, ()> {
+        let mut eviction = Eviction::new();
+        eviction.set_max_entries(2, policy);
+        eviction
+    }
+
+    #[test]
+    fn test_fifo_order() {
+        let now = Instant::now();
+        let mut eviction = new_eviction(EvictionPolicy::Fifo);
+        eviction.inserted(&1, now);
+        eviction.inserted(&2, now);
+        eviction.inserted(&3, now);
+        eviction.used(&1, now);
+        assert_eq!(candidates(&eviction), [1, 2, 3]);
+
+        eviction.inserted(&1, now);
+        eviction.removed(&2);
+        assert_eq!(candidates(&eviction), [3, 1]);
+    }
+
+    #[test]
+    fn test_lru_order() {
+        let now = Instant::now();
+        let mut eviction = new_eviction(EvictionPolicy::Lru);
+        eviction.inserted(&1, now);
+        eviction.inserted(&2, now);
+        eviction.inserted(&3, now);
+        eviction.used(&1, now);
+        eviction.used(&4, now);
+        assert_eq!(candidates(&eviction), [2, 3, 1]);
+
+        eviction.clear();
+        assert!(candidates(&eviction).is_empty());
+    }
+
+    #[test]
+    fn test_expiry() {
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+
+        let mut eviction = Eviction::<u64, ()>::new();
+        eviction.set_ttl(Duration::from_secs(10));
+        eviction.inserted(&1, at(0));
+        eviction.inserted(&2, at(5));
+        eviction.inserted(&3, at(8));
+        eviction.used(&1, at(9));
+
+        let expired = |now| eviction.expired(now).copied().collect::<Vec<_>>();
+        assert!(expired(at(10)).is_empty());
+        assert_eq!(expired(at(16)), [2]);
+        assert_eq!(expired(at(20)), [2, 3, 1]);
+        assert!(eviction.is_expired(&3, at(19)));
+        assert!(!eviction.is_expired(&1, at(19)));
+    }
+}
@@ -14,7 +14,8 @@ use std::ops::{Deref, DerefMut};
 ///
 /// **Note**: the wrapped type must implement [`Default`] as entries may be created
 /// over the plugin API without any interaction with your plugin code.
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
 pub struct Private<T>(T);
 
 impl<T> Deref for Private<T> {
@@ -1,6 +1,6 @@
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
 use crate::tables::export::field_value::traits::FieldValue;
-use crate::tables::export::field_value::traits::{seal, StaticField};
+use crate::tables::export::field_value::traits::{seal, FromDynamicFieldValue, StaticField};
 use crate::tables::export::metadata::HasMetadata;
 use crate::tables::FieldTypeId;
 use anyhow::Error;
@@ -15,7 +15,8 @@ use std::ops::{Deref, DerefMut};
 ///
 /// This type implements [`Deref`] and [`DerefMut`], so you do not need any extra
 /// code when accessing the actual data.
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
 pub struct Public<T>(T);
 
 impl<T: FieldValue + Default> HasMetadata for Public<T> {
@@ -60,3 +61,9 @@ impl<T: TryFrom<DynamicFieldValue>> TryFrom<DynamicFieldValue> for Public<T> {
         Ok(Self(T::try_from(value)?))
     }
 }
+
+impl<T: FromDynamicFieldValue> FromDynamicFieldValue for Public<T> {
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, Error> {
+        Ok(Self(T::from_dynamic_field_value(value)?))
+    }
+}
@@ -1,6 +1,6 @@
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
 use crate::tables::export::field_value::traits::FieldValue;
-use crate::tables::export::field_value::traits::{seal, StaticField};
+use crate::tables::export::field_value::traits::{seal, FromDynamicFieldValue, StaticField};
 use crate::tables::export::metadata::HasMetadata;
 use crate::tables::FieldTypeId;
 use anyhow::Error;
@@ -16,7 +16,8 @@ use std::ops::{Deref, DerefMut};
 ///
 /// This type implements [`Deref`] and [`DerefMut`], so you do not need any extra
 /// code when accessing the actual data.
-#[derive(Debug)]
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+#[serde(transparent)]
 pub struct Readonly<T>(T);
 
 impl<T: FieldValue + Default> HasMetadata for Readonly<T> {
@@ -61,3 +62,9 @@ impl<T: TryFrom<DynamicFieldValue>> TryFrom<DynamicFieldValue> for Readonly<T> {
         Ok(Self(T::try_from(value)?))
     }
 }
+
+impl<T: FromDynamicFieldValue> FromDynamicFieldValue for Readonly<T> {
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, Error> {
+        Ok(Self(T::from_dynamic_field_value(value)?))
+    }
+}
@@ -1,21 +1,25 @@
 use crate::tables::export::entry::table_metadata::extensible::ExtensibleEntryMetadata;
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::map::TableMap;
 use crate::tables::export::metadata::HasMetadata;
 use crate::tables::export::ref_shared::RefShared;
 use crate::tables::export::table::Table;
+use crate::tables::export::table::TableValue;
 use crate::tables::Key;
 use anyhow::Error;
 use std::borrow::Borrow;
 use std::ffi::CStr;
+use std::hash::Hash;
 
-impl<K, E> HasMetadata for Box<Table<K, E>>
+impl<K, E, M> HasMetadata for Box<Table<K, E, M>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     type Metadata = RefShared<ExtensibleEntryMetadata<E::Metadata>>;
 
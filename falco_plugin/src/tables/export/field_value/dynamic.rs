@@ -1,3 +1,4 @@
+use crate::tables::export::field_value::dynamic_table::DynamicTable;
 use crate::tables::export::field_value::traits::seal;
 use crate::tables::export::field_value::traits::FieldValue;
 use crate::tables::FieldTypeId;
@@ -22,9 +23,28 @@ pub enum DynamicFieldValue {
     I64(i64),
     Bool(bool),
     String(CString),
+    Table(DynamicTable),
 }
 
 impl DynamicFieldValue {
+    /// Return the type of the stored value, or `None` for an unset value
+    pub(crate) fn type_id(&self) -> Option<FieldTypeId> {
+        match self {
+            Self::None => None,
+            Self::U8(_) => Some(FieldTypeId::U8),
+            Self::I8(_) => Some(FieldTypeId::I8),
+            Self::U16(_) => Some(FieldTypeId::U16),
+            Self::I16(_) => Some(FieldTypeId::I16),
+            Self::U32(_) => Some(FieldTypeId::U32),
+            Self::I32(_) => Some(FieldTypeId::I32),
+            Self::U64(_) => Some(FieldTypeId::U64),
+            Self::I64(_) => Some(FieldTypeId::I64),
+            Self::Bool(_) => Some(FieldTypeId::Bool),
+            Self::String(_) => Some(FieldTypeId::String),
+            Self::Table(_) => Some(FieldTypeId::Table),
+        }
+    }
+
     pub(crate) unsafe fn from_data(
         value: &ss_plugin_state_data,
         type_id: FieldTypeId,
@@ -70,6 +90,9 @@ impl FieldValue for DynamicFieldValue {
             DynamicFieldValue::String(v) if type_id == FieldTypeId::String => {
                 out.str_ = v.as_c_str().as_ptr()
             }
+            DynamicFieldValue::Table(v) if type_id == FieldTypeId::Table => {
+                out.table = v.table_input().cast()
+            }
             _ => anyhow::bail!("Type mismatch, requested {:?}, got {:?}", type_id, self),
         };
 
@@ -0,0 +1,130 @@
+use crate::tables::data::TableData;
+use crate::tables::export::entry::dynamic::DynamicEntry;
+use crate::tables::export::entry::table_metadata::dynamic::DynamicFieldsOnly;
+use crate::tables::export::entry::table_metadata::extensible::ExtensibleEntryMetadata;
+use crate::tables::export::ref_shared::RefShared;
+use crate::tables::export::table::Table;
+use crate::tables::import::Bool;
+use crate::tables::{FieldTypeId, Key};
+use falco_plugin_api::ss_plugin_table_input;
+use std::borrow::Borrow;
+use std::ffi::{CStr, CString};
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+
+pub(crate) mod seal {
+    pub trait Sealed {}
+}
+
+type NestedMetadata = RefShared<ExtensibleEntryMetadata<RefShared<DynamicFieldsOnly>>>;
+
+/// # A nested table held in a field added at runtime
+///
+/// See [`Table::add_table_field`](`crate::tables::export::Table::add_table_field`)
+pub struct DynamicTable(DynamicTableInner);
+
+/// The recipe for the nested tables of a runtime-added table field
+///
+/// All the nested tables in a particular field share the same metadata, so fields added
+/// to one of them (e.g. by other plugins) show up in all of them.
+#[derive(Debug, Clone)]
+pub(crate) struct DynamicTableSpec {
+    pub(crate) name: &'static CStr,
+    pub(crate) key_type: FieldTypeId,
+    pub(crate) metadata: NestedMetadata,
+}
+
+/// # A key type usable in tables added at runtime
+///
+/// This trait is sealed and implemented for all the key types supported by exported tables.
+pub trait DynamicTableKey:
+    seal::Sealed
+    + Key<Borrowed: Ord + Hash + ToOwned<Owned = Self>>
+    + Ord
+    + Borrow<<Self as Key>::Borrowed>
+    + Sized
+{
+    #[doc(hidden)]
+    fn table(table: &DynamicTable) -> Option<&Table<Self, DynamicEntry>>;
+}
+
+macro_rules! impl_dynamic_table {
+    ($($variant:ident($ty:ty)),*) => {
+        enum DynamicTableInner {
+            $($variant(Box<Table<$ty, DynamicEntry>>),)*
+        }
+
+        $(
+            impl seal::Sealed for $ty {}
+
+            impl DynamicTableKey for $ty {
+                fn table(table: &DynamicTable) -> Option<&Table<Self, DynamicEntry>> {
+                    match &table.0 {
+                        DynamicTableInner::$variant(table) => Some(table),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        )*
+
+        impl DynamicTable {
+            pub(crate) fn new(spec: &DynamicTableSpec) -> Result<Self, anyhow::Error> {
+                $(
+                    if spec.key_type == <$ty as TableData>::TYPE_ID {
+                        let table = Table::new_with_metadata(spec.name, &spec.metadata)?;
+                        return Ok(Self(DynamicTableInner::$variant(Box::new(table))));
+                    }
+                )*
+                anyhow::bail!("Unsupported key type {:?} for table {:?}", spec.key_type, spec.name)
+            }
+
+            pub(crate) fn table_input(&self) -> *mut ss_plugin_table_input {
+                match &self.0 {
+                    $(DynamicTableInner::$variant(table) => table.get_boxed_vtable(),)*
+                }
+            }
+
+            fn name_and_size(&self) -> (&'static CStr, usize) {
+                match &self.0 {
+                    $(DynamicTableInner::$variant(table) => (table.name(), table.size()),)*
+                }
+            }
+        }
+    };
+}
+
+impl_dynamic_table!(
+    U8(u8),
+    I8(i8),
+    U16(u16),
+    I16(i16),
+    U32(u32),
+    I32(i32),
+    U64(u64),
+    I64(i64),
+    Bool(Bool),
+    String(CString)
+);
+
+impl Debug for DynamicTable {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (name, size) = self.name_and_size();
+        f.debug_struct("DynamicTable")
+            .field("name", &name)
+            .field("size", &size)
+            .finish()
+    }
+}
+
+// SAFETY: the parts of `Table` that are not thread safe are the eviction and lifecycle
+// callbacks and the raw pointers in the cached FFI structures. The nested tables are created
+// by the SDK without any callbacks and only handed out by shared reference, so the callbacks
+// can never be set. The raw pointers only point to data owned by the table itself (or static
+// data), so they can be sent along with it.
+#[cfg(feature = "thread-safe-tables")]
+unsafe impl Send for DynamicTable {}
+
+// SAFETY: see above
+#[cfg(feature = "thread-safe-tables")]
+unsafe impl Sync for DynamicTable {}
@@ -1,4 +1,5 @@
 pub mod dynamic;
+pub mod dynamic_table;
 pub mod scalar;
 pub mod table;
 pub mod traits;
@@ -1,5 +1,7 @@
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
-use crate::tables::export::field_value::traits::{seal, FieldValue, StaticField};
+use crate::tables::export::field_value::traits::{
+    seal, FieldValue, FromDynamicFieldValue, StaticField,
+};
 use crate::tables::FieldTypeId;
 use falco_plugin_api::ss_plugin_state_data;
 use std::ffi::CString;
@@ -63,6 +65,12 @@ macro_rules! impl_scalar_field {
                 }
             }
         }
+
+        impl FromDynamicFieldValue for $ty {
+            fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, anyhow::Error> {
+                value.try_into()
+            }
+        }
     };
 }
 
@@ -2,29 +2,34 @@ use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::entry::traits::Entry;
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
 use crate::tables::export::field_value::traits::FieldValue;
-use crate::tables::export::field_value::traits::{seal, StaticField};
+use crate::tables::export::field_value::traits::{seal, FromDynamicFieldValue, StaticField};
+use crate::tables::export::map::TableMap;
 use crate::tables::export::table::Table;
+use crate::tables::export::table::TableValue;
 use crate::tables::{FieldTypeId, Key};
 use falco_plugin_api::ss_plugin_state_data;
 use std::borrow::Borrow;
+use std::hash::Hash;
 
-impl<K, E> seal::Sealed for Box<Table<K, E>>
+impl<K, E, M> seal::Sealed for Box<Table<K, E, M>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata + Clone,
+    M: TableMap<K, TableValue<E>>,
 {
 }
 
-impl<K, E> FieldValue for Box<Table<K, E>>
+impl<K, E, M> FieldValue for Box<Table<K, E, M>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata + Clone,
+    M: TableMap<K, TableValue<E>>,
 {
     fn to_data(
         &self,
@@ -41,25 +46,27 @@ where
     }
 }
 
-impl<K, E> StaticField for Box<Table<K, E>>
+impl<K, E, M> StaticField for Box<Table<K, E, M>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata + Clone,
+    M: TableMap<K, TableValue<E>>,
 {
     const TYPE_ID: FieldTypeId = FieldTypeId::Table;
     const READONLY: bool = true;
 }
 
-impl<K, E> TryFrom<DynamicFieldValue> for Box<Table<K, E>>
+impl<K, E, M> TryFrom<DynamicFieldValue> for Box<Table<K, E, M>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata + Clone,
+    M: TableMap<K, TableValue<E>>,
 {
     type Error = anyhow::Error;
 
@@ -67,3 +74,17 @@ where
         anyhow::bail!("Table-valued fields cannot be set")
     }
 }
+
+impl<K, E, M> FromDynamicFieldValue for Box<Table<K, E, M>>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata + Clone,
+    M: TableMap<K, TableValue<E>>,
+{
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, anyhow::Error> {
+        value.try_into()
+    }
+}
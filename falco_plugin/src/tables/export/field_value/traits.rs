@@ -1,3 +1,4 @@
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
 use crate::tables::FieldTypeId;
 use falco_plugin_api::ss_plugin_state_data;
 
@@ -9,6 +10,12 @@ pub(crate) mod seal {
 ///
 /// This trait is sealed, meaning you cannot add new implementations (the list is limited
 /// by the Falco plugin API)
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be stored in a table field",
+    label = "unsupported table field type",
+    note = "table fields can only hold integers, `bool`, `CString`, `TableEnum` types, `Encoded` values and nested tables",
+    note = "use `export::Private<_>` to store other types, invisible to other plugins"
+)]
 pub trait FieldValue: seal::Sealed + Sized {
     /// Store a C representation of `&self` in `out`
     ///
@@ -31,3 +38,12 @@ pub trait StaticField: FieldValue {
 
     const READONLY: bool;
 }
+
+/// Trait implemented for types that can be set from a [`DynamicFieldValue`]
+///
+/// This is equivalent to `TryFrom<DynamicFieldValue>`, except that it can be implemented
+/// for all [`TableEnum`](`crate::tables::TableEnum`) types at once.
+pub trait FromDynamicFieldValue: Sized {
+    /// Convert the value, failing on a type mismatch
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, anyhow::Error>;
+}
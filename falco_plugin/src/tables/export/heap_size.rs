@@ -0,0 +1,162 @@
+use crate::tables::export::entry::extensible::ExtensibleEntry;
+use crate::tables::export::entry::table_metadata::traits::TableMetadata;
+use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::TableValue;
+use crate::tables::export::{Private, Public, Readonly, Table};
+use crate::tables::import::Bool;
+use crate::tables::{Encoded, Key};
+use std::borrow::Borrow;
+use std::ffi::CString;
+use std::hash::Hash;
+
+/// # Estimate the heap memory owned by a value
+///
+/// This is used by [`Table::approx_memory_usage`] to attribute memory to exported tables.
+/// The [`Entry`](`crate::tables::export::Entry`) derive macro implements it for entry types,
+/// summing up the heap usage of all fields whose types implement `HeapSize`. Fields of other
+/// types (e.g. custom types in [`Private`] fields) only count towards the size of the entry
+/// itself, so if such a type owns significant amounts of memory, you should implement
+/// `HeapSize` for it:
+///
+/// ```
+/// use std::ffi::CString;
+/// use falco_plugin::tables::export;
+/// use falco_plugin::tables::export::HeapSize;
+///
+/// #[derive(Default)]
+/// struct Connections {
+///     peers: Vec<String>,
+/// }
+///
+/// impl HeapSize for Connections {
+///     fn heap_size(&self) -> usize {
+///         self.peers.heap_size()
+///     }
+/// }
+///
+/// #[derive(export::Entry)]
+/// struct Process {
+///     comm: export::Public<CString>,
+///     connections: export::Private<Connections>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut table = export::Table::<u64, Process>::new(c"processes")?;
+/// let empty_size = table.approx_memory_usage();
+/// assert_eq!(empty_size, 0);
+///
+/// let mut entry = table.create_entry()?;
+/// entry.connections.peers.push(String::from("127.0.0.1:8080"));
+/// table.insert(&1, entry);
+/// assert!(table.approx_memory_usage() > size_of::<Process>() + "127.0.0.1:8080".len());
+/// # Ok(())
+/// # }
+/// ```
+///
+/// The numbers are estimates: e.g. allocator overhead is not included.
+pub trait HeapSize {
+    /// Return the number of bytes allocated on the heap by this value
+    ///
+    /// This does not include the size of the value itself (`size_of_val(self)`).
+    fn heap_size(&self) -> usize;
+}
+
+macro_rules! impl_no_heap {
+    ($($ty:ty)*) => {
+        $(
+            impl HeapSize for $ty {
+                fn heap_size(&self) -> usize {
+                    0
+                }
+            }
+        )*
+    };
+}
+
+impl_no_heap!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize f32 f64 bool char Bool);
+
+impl HeapSize for CString {
+    fn heap_size(&self) -> usize {
+        self.as_bytes_with_nul().len()
+    }
+}
+
+impl HeapSize for String {
+    fn heap_size(&self) -> usize {
+        self.capacity()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Vec<T> {
+    fn heap_size(&self) -> usize {
+        self.capacity() * size_of::<T>() + self.iter().map(HeapSize::heap_size).sum::<usize>()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Box<T> {
+    fn heap_size(&self) -> usize {
+        size_of::<T>() + self.as_ref().heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Option<T> {
+    fn heap_size(&self) -> usize {
+        self.as_ref().map_or(0, HeapSize::heap_size)
+    }
+}
+
+impl<T: HeapSize> HeapSize for Encoded<T> {
+    fn heap_size(&self) -> usize {
+        // the cached encoding is transient, so only count the value itself
+        (**self).heap_size()
+    }
+}
+
+impl HeapSize for DynamicFieldValue {
+    fn heap_size(&self) -> usize {
+        match self {
+            DynamicFieldValue::String(s) => s.heap_size(),
+            _ => 0,
+        }
+    }
+}
+
+impl<T: HeapSize> HeapSize for Public<T> {
+    fn heap_size(&self) -> usize {
+        (**self).heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Readonly<T> {
+    fn heap_size(&self) -> usize {
+        (**self).heap_size()
+    }
+}
+
+impl<T: HeapSize> HeapSize for Private<T> {
+    fn heap_size(&self) -> usize {
+        (**self).heap_size()
+    }
+}
+
+impl<E: HeapSize> HeapSize for ExtensibleEntry<E> {
+    fn heap_size(&self) -> usize {
+        (**self).heap_size() + self.custom_fields().heap_size()
+    }
+}
+
+impl<K, E, M> HeapSize for Table<K, E, M>
+where
+    K: Key + Ord + HeapSize,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry + HeapSize,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    fn heap_size(&self) -> usize {
+        self.approx_memory_usage()
+    }
+}
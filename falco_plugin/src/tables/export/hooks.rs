@@ -0,0 +1,70 @@
+use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::table::TableValue;
+use std::fmt::{Debug, Formatter};
+
+pub(crate) type CreateCallback<K, E> = Box<dyn FnMut(&K, &mut E)>;
+pub(crate) type EraseCallback<K, E> = Box<dyn FnMut(&K, Option<&mut E>)>;
+pub(crate) type ClearCallback = Box<dyn FnMut()>;
+
+/// The lifecycle callbacks of a [`Table`](`crate::tables::export::Table`)
+pub(crate) struct TableHooks<K, E> {
+    pub(crate) on_create: Option<CreateCallback<K, E>>,
+    pub(crate) on_erase: Option<EraseCallback<K, E>>,
+    pub(crate) on_clear: Option<ClearCallback>,
+}
+
+impl<K, E> Default for TableHooks<K, E> {
+    fn default() -> Self {
+        Self {
+            on_create: None,
+            on_erase: None,
+            on_clear: None,
+        }
+    }
+}
+
+impl<K, E> Debug for TableHooks<K, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableHooks")
+            .field("on_create", &self.on_create.is_some())
+            .field("on_erase", &self.on_erase.is_some())
+            .field("on_clear", &self.on_clear.is_some())
+            .finish()
+    }
+}
+
+impl<K, E: Entry> TableHooks<K, E> {
+    pub(crate) fn has_create(&self) -> bool {
+        self.on_create.is_some()
+    }
+
+    pub(crate) fn has_erase(&self) -> bool {
+        self.on_erase.is_some()
+    }
+
+    /// Run the `on_create` callback for an entry just inserted under `key`
+    pub(crate) fn created(&mut self, key: &K, entry: &mut E) {
+        if let Some(callback) = self.on_create.as_mut() {
+            callback(key, entry);
+        }
+    }
+
+    /// Run the `on_erase` callback for an entry just removed from under `key`
+    ///
+    /// The entry is only passed to the callback if it is not locked by anyone else.
+    pub(crate) fn erased(&mut self, key: &K, value: &TableValue<E>) {
+        if let Some(callback) = self.on_erase.as_mut() {
+            match value.try_write() {
+                Some(mut entry) => callback(key, Some(&mut entry)),
+                None => callback(key, None),
+            }
+        }
+    }
+
+    /// Run the `on_clear` callback
+    pub(crate) fn cleared(&mut self) {
+        if let Some(callback) = self.on_clear.as_mut() {
+            callback();
+        }
+    }
+}
@@ -39,11 +39,31 @@ macro_rules! impl_export_table_set {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! impl_export_field_init {
+    (skip) => {
+        ::std::default::Default::default()
+    };
+    (skip, $default:expr) => {
+        $default
+    };
+    ($field_type:ty, $field_tag:literal, $meta:expr) => {
+        <$field_type as $crate::tables::export::HasMetadata>::new_with_metadata($field_tag, $meta)?
+    };
+    ($field_type:ty, $field_tag:literal, $meta:expr, $default:expr) => {{
+        let mut field = $crate::impl_export_field_init!($field_type, $field_tag, $meta);
+        *field = $default;
+        field
+    }};
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_export_table {
     (for $name:ident {
-        $([$i:literal] $field_tag:literal ($field_name_bstr:literal) as $field_name:ident: $field_type:ty)*
+        $([$i:literal] $field_tag:literal ($field_name_bstr:literal) as $field_name:ident: $field_type:ty $(= $default:expr)?;)*
+        $(skip $skipped_name:ident $(= $skipped_default:expr)?;)*
     }) => {
         const _: () = {
             use $crate::tables::export::traits::TableMetadata;
@@ -58,6 +78,8 @@ macro_rules! impl_export_table {
             use $crate::tables::export::StaticFieldFallback;
             use $crate::tables::export::StaticFieldGet;
             use $crate::tables::export::StaticFieldGetFallback;
+            use $crate::tables::export::StaticFieldHeapSize;
+            use $crate::tables::export::StaticFieldHeapSizeFallback;
             use $crate::tables::export::StaticFieldSet;
             use $crate::tables::export::StaticFieldSetFallback;
             use $crate::tables::FieldTypeId;
@@ -122,11 +144,20 @@ macro_rules! impl_export_table {
 
                 fn new_with_metadata(tag: &'static std::ffi::CStr, meta: &Self::Metadata) -> ::std::result::Result<Self, $crate::anyhow::Error> {
                     Ok(Self {
-                       $($field_name: HasMetadata::new_with_metadata($field_tag, &meta.read().$field_name)?,)*
+                       $($field_name: $crate::impl_export_field_init!(
+                           $field_type, $field_tag, &meta.read().$field_name $(, $default)?
+                       ),)*
+                       $($skipped_name: $crate::impl_export_field_init!(skip $(, $skipped_default)?),)*
                     })
                 }
             }
 
+            impl $crate::tables::export::HeapSize for $name {
+                fn heap_size(&self) -> usize {
+                    0 $(+ StaticFieldHeapSize(&self.$field_name).heap_size())*
+                }
+            }
+
             impl $crate::tables::export::traits::Entry for $name {
                 $crate::impl_export_table_get!(
                     self,
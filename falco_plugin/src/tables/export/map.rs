@@ -0,0 +1,227 @@
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::hash::{BuildHasher, Hash};
+
+/// # A map type usable as the storage of an exported table
+///
+/// Exported tables keep their entries in a map, which is a [`BTreeMap`] by default.
+/// This trait lets you choose a different one (via the last generic parameter of
+/// [`Table`](`crate::tables::export::Table`)), depending on the needs of your plugin:
+/// - [`BTreeMap`] iterates over the entries in key order and has predictable performance
+/// - [`HashMap`](`std::collections::HashMap`) has faster lookups, especially for large tables
+///   with keys spread over the whole key space, but iterates in arbitrary order. You can also
+///   choose the hasher (the `S` generic parameter), e.g. to trade DoS resistance for speed
+/// - `hashbrown::HashMap` (with the `hashbrown` feature enabled) is similar to the standard
+///   `HashMap`, but uses a faster hasher by default
+///
+/// The keys are always owned, so the key type must also be hashable for the hash-based maps
+/// (all the supported key types are).
+pub trait TableMap<K, V>: Default {
+    /// Return the number of entries in the map
+    fn len(&self) -> usize;
+
+    /// Check whether the map is empty
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the value corresponding to a key
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized;
+
+    /// Insert a value, returning the one previously stored under the same key
+    fn insert(&mut self, key: K, value: V) -> Option<V>;
+
+    /// Remove an entry, returning the stored key and value (if it was present)
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized;
+
+    /// Remove a value, returning it (if it was present)
+    fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        self.remove_entry(key).map(|(_, value)| value)
+    }
+
+    /// Remove all entries from the map
+    fn clear(&mut self);
+
+    /// Reserve space for at least `additional` more entries
+    ///
+    /// The default implementation does nothing, which is the right choice for maps
+    /// that do not preallocate (like [`BTreeMap`])
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Iterate over all entries in the map
+    ///
+    /// The iteration order is up to the map implementation.
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a;
+
+    /// Iterate over all values in the map
+    ///
+    /// The iteration order is up to the map implementation.
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V>
+    where
+        V: 'a;
+}
+
+impl<K: Ord, V> TableMap<K, V> for BTreeMap<K, V> {
+    fn len(&self) -> usize {
+        BTreeMap::len(self)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        BTreeMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        BTreeMap::insert(self, key, value)
+    }
+
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        BTreeMap::remove_entry(self, key)
+    }
+
+    fn clear(&mut self) {
+        BTreeMap::clear(self)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        BTreeMap::iter(self)
+    }
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V>
+    where
+        V: 'a,
+    {
+        BTreeMap::values_mut(self)
+    }
+}
+
+impl<K, V, S> TableMap<K, V> for std::collections::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn len(&self) -> usize {
+        std::collections::HashMap::len(self)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        std::collections::HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        std::collections::HashMap::insert(self, key, value)
+    }
+
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        std::collections::HashMap::remove_entry(self, key)
+    }
+
+    fn clear(&mut self) {
+        std::collections::HashMap::clear(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        std::collections::HashMap::reserve(self, additional)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        std::collections::HashMap::iter(self)
+    }
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V>
+    where
+        V: 'a,
+    {
+        std::collections::HashMap::values_mut(self)
+    }
+}
+
+#[cfg(feature = "hashbrown")]
+impl<K, V, S> TableMap<K, V> for hashbrown::HashMap<K, V, S>
+where
+    K: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    fn len(&self) -> usize {
+        hashbrown::HashMap::len(self)
+    }
+
+    fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        hashbrown::HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) -> Option<V> {
+        hashbrown::HashMap::insert(self, key, value)
+    }
+
+    fn remove_entry<Q>(&mut self, key: &Q) -> Option<(K, V)>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + Eq + ?Sized,
+    {
+        hashbrown::HashMap::remove_entry(self, key)
+    }
+
+    fn clear(&mut self) {
+        hashbrown::HashMap::clear(self)
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        hashbrown::HashMap::reserve(self, additional)
+    }
+
+    fn iter<'a>(&'a self) -> impl Iterator<Item = (&'a K, &'a V)>
+    where
+        K: 'a,
+        V: 'a,
+    {
+        hashbrown::HashMap::iter(self)
+    }
+
+    fn values_mut<'a>(&'a mut self) -> impl Iterator<Item = &'a mut V>
+    where
+        V: 'a,
+    {
+        hashbrown::HashMap::values_mut(self)
+    }
+}
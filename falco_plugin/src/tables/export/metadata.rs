@@ -30,6 +30,12 @@ impl<M: Metadata> Metadata for RefShared<M> {
 /// For almost all types, their metadata is `()`, but for tables it's a type that implements
 /// `TableMetadata` and can be used to create a new instance of a table, using the same list
 /// of dynamic fields.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be used as a field in an exported table entry",
+    label = "unsupported field type",
+    note = "wrap the value in `export::Public<_>`, `export::Readonly<_>` or `export::Private<_>`, \
+        use `Box<export::Table<K, E>>` for a nested table, or mark the field `#[skip]`"
+)]
 pub trait HasMetadata: Sized {
     /// The metadata type
     type Metadata;
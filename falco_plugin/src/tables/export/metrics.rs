@@ -0,0 +1,71 @@
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// The counters behind [`Table::metrics`](`crate::tables::export::Table::metrics`)
+#[derive(Debug)]
+pub(crate) struct TableMetrics {
+    entries: MetricLabel,
+    dynamic_fields: MetricLabel,
+    inserts: (MetricLabel, AtomicU64),
+    erases: (MetricLabel, AtomicU64),
+    evictions: (MetricLabel, AtomicU64),
+}
+
+fn label(table_name: &CStr, metric: &str, metric_type: MetricType) -> MetricLabel {
+    let mut name = table_name.to_bytes().to_vec();
+    name.push(b'.');
+    name.extend_from_slice(metric.as_bytes());
+    // the table name is a valid C string and the suffix has no NUL bytes
+    MetricLabel::new(CString::new(name).unwrap(), metric_type)
+}
+
+impl TableMetrics {
+    pub(crate) fn new(table_name: &CStr) -> Self {
+        let counter = |metric| (label(table_name, metric, MetricType::Monotonic), 0.into());
+        Self {
+            entries: label(table_name, "entries", MetricType::NonMonotonic),
+            dynamic_fields: label(table_name, "dynamic_fields", MetricType::NonMonotonic),
+            inserts: counter("inserts"),
+            erases: counter("erases"),
+            evictions: counter("evictions"),
+        }
+    }
+
+    pub(crate) fn inserted(&self, count: usize) {
+        self.inserts.1.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn erased(&self, count: usize) {
+        self.erases.1.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    pub(crate) fn evicted(&self, count: usize) {
+        self.evictions.1.fetch_add(count as u64, Ordering::Relaxed);
+    }
+
+    /// Build the metrics, given the current table size and number of dynamic fields
+    pub(crate) fn collect(
+        &self,
+        entries: usize,
+        dynamic_fields: usize,
+        with_evictions: bool,
+    ) -> Vec<Metric> {
+        let gauge =
+            |label: &MetricLabel, value: usize| label.with_value(MetricValue::U64(value as u64));
+        let counter = |(label, value): &(MetricLabel, AtomicU64)| {
+            label.with_value(MetricValue::U64(value.load(Ordering::Relaxed)))
+        };
+
+        let mut metrics = vec![
+            gauge(&self.entries, entries),
+            gauge(&self.dynamic_fields, dynamic_fields),
+            counter(&self.inserts),
+            counter(&self.erases),
+        ];
+        if with_evictions {
+            metrics.push(counter(&self.evictions));
+        }
+        metrics
+    }
+}
@@ -9,7 +9,12 @@
 //! except for nested tables. These just need to be a `Box<Table<K, E>>`, as it makes no sense
 //! to have a private nested table and the distinction between writable and readonly is meaningless
 //! for tables (they have no setter to replace the whole table and you can always add/remove
-//! entries from the nested table).
+//! entries from the nested table). Fields that should not be part of the table at all can also
+//! be marked `#[skip]` instead, see [below](#field-attributes).
+//!
+//! Integer fields with a fixed set of values can use a Rust enum implementing
+//! [`TableEnum`](`crate::tables::TableEnum`) (and [`Default`]), e.g. `Public<L4Proto>`.
+//! Other plugins see a plain integer field and cannot write values that do not map to a variant.
 //!
 //! # Example
 //!
@@ -61,24 +66,185 @@
 //! }
 //!# plugin!(#[no_capabilities] MyPlugin);
 //! ```
+//!
+//! # Field attributes
+//!
+//! By default, fields are exported under their Rust names and start out with their
+//! [`Default`] value. This can be changed per field:
+//!
+//! - `#[name = "..."]` exports the field under a different name, e.g. one that is not
+//!   a valid Rust identifier
+//! - `#[default(expr)]` sets the initial value used for new entries, including the ones created
+//!   by other plugins. For wrapped fields, `expr` is the value of the inner type
+//! - `#[skip]` leaves the field out of the table schema entirely. Skipped fields do not need
+//!   a wrapper type and can hold anything implementing [`Default`] (or having a `#[default]`)
+//!
+//! ```
+//! use falco_plugin::tables::export;
+//!
+//! #[derive(export::Entry)]
+//! struct Connection {
+//!     #[name = "bytes.sent"]
+//!     bytes_sent: export::Public<u64>,
+//!     #[default(1500)]
+//!     mtu: export::Readonly<u64>,
+//!     #[skip]
+//!     #[default(Vec::with_capacity(16))]
+//!     recent_packets: Vec<u64>,
+//! }
+//! ```
+//!
+//! # Accessing the table from your plugin
+//!
+//! Besides the lower-level [`Table::lookup`], [`Table::insert`] and [`Table::erase`], tables
+//! offer a map-like API for use within your plugin: [`Table::get`], [`Table::get_mut`],
+//! [`Table::iter`], [`Table::retain`] and [`Table::entry`], which returns a [`TableEntry`]
+//! similar to the one from [`HashMap::entry`](`std::collections::HashMap::entry`):
+//!
+//! ```ignore
+//! let conn = self.connections.entry(key).or_insert_with(|conn| *conn.mtu = 1500)?;
+//! ```
+//!
+//! # Saving table contents in capture files
+//!
+//! If the entry type also derives `serde::Serialize` and `serde::Deserialize`, the table
+//! contents can be saved as async events using [`Table::dump_state`] (from
+//! [`AsyncEventPlugin::dump_state`](`crate::async_event::AsyncEventPlugin::dump_state`))
+//! and loaded back using [`Table::restore_state`] (from a parse plugin), so the state survives
+//! writing and replaying a capture file.
+//!
+//! For debugging, [`Table::to_json`] returns the table contents as a JSON object mapping
+//! keys to entries and [`Table::from_json`] builds a table back from such an object.
+//!
+//! # Limiting the table size
+//!
+//! Tables holding e.g. per-connection state can grow without bound in long-running plugins.
+//! To cap their size, use [`Table::with_max_entries`] and choose an [`EvictionPolicy`],
+//! and/or expire entries that have not been used for a while with [`Table::with_ttl`]:
+//!
+//! ```
+//! use falco_plugin::tables::export;
+//!
+//! #[derive(export::Entry)]
+//! struct Connection {
+//!     bytes: export::Public<u64>,
+//! }
+//!
+//! # fn main() -> anyhow::Result<()> {
+//! let table = export::Table::<u64, Connection>::new(c"connections")?
+//!     .with_max_entries(10000, export::EvictionPolicy::Lru)
+//!     .with_ttl(std::time::Duration::from_secs(300))
+//!     .with_eviction_callback(|key, conn| println!("evicting {key} ({} bytes)", *conn.bytes));
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! # Lifecycle callbacks
+//!
+//! Other plugins can insert, erase and clear entries in your tables at any time. To keep
+//! e.g. an auxiliary index in sync or to emit async events on changes, set callbacks using
+//! [`Table::with_create_callback`], [`Table::with_erase_callback`] and
+//! [`Table::with_clear_callback`]. They run for changes made both by your plugin and by others.
+//!
+//! # Fields listed in the plugin config
+//!
+//! To let users expose extra fields on your table (e.g. for other plugins to fill in), put
+//! a list of [`DynamicFieldSpec`] values in your config type and pass it to
+//! [`Table::add_dynamic_fields`] when creating the table. You get back a [`DynamicField`]
+//! handle for each field name, to read and write the field in table entries:
+//!
+//! ```ignore
+//! let mut table = export::Table::new(c"connections")?;
+//! let fields = table.add_dynamic_fields(&config.extra_fields)?;
+//! // ...
+//! fields["score"].set(&mut entry, export::DynamicFieldValue::U64(10))?;
+//! ```
+//!
+//! # Nested tables added at runtime
+//!
+//! When the schema of a nested table is only known at runtime (e.g. it comes from the plugin
+//! config), use [`Table::add_table_field`] instead of a `Box<Table<K, E>>` field. Every entry
+//! (including ones created later) gets its own nested table with the given key type and fields,
+//! which your plugin can reach with [`Table::get_table_field`] and other plugins import
+//! like any other nested table.
+//!
+//! # Table metrics
+//!
+//! Tables created with [`Table::with_metrics`] keep track of their size and the number
+//! of inserted, erased and evicted entries. Return [`Table::metrics`] from
+//! [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`) to report them to Falco:
+//!
+//! ```ignore
+//! fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+//!     self.connections.metrics()
+//! }
+//! ```
+//!
+//! # Choosing the map type
+//!
+//! By default, table entries are stored in a [`BTreeMap`](`std::collections::BTreeMap`),
+//! so iterating over the table visits the entries in key order. If you don't need that,
+//! a hash map may be a better fit, especially for large tables. The map type is the last
+//! generic parameter of [`Table`] and can be any type implementing [`TableMap`]:
+//!
+//! ```
+//! use std::collections::HashMap;
+//! use std::hash::RandomState;
+//! use falco_plugin::tables::export;
+//!
+//! #[derive(export::Entry)]
+//! struct ExportedTable {
+//!     int_field: export::Readonly<u64>,
+//! }
+//!
+//! type ExportedMap = HashMap<u64, export::TableValue<ExportedTable>, RandomState>;
+//!
+//! struct MyPlugin {
+//!     exported_table: Box<export::Table<u64, ExportedTable, ExportedMap>>,
+//! }
+//! ```
+//!
+//! Tables written to from multiple threads (with the `thread-safe-tables` feature enabled)
+//! can also be split into several separately locked maps using [`Table::with_shards`],
+//! to reduce lock contention. Threads that only need to read the table can get
+//! a [`TableReadView`] from [`Table::read_view`] instead.
 
+mod dump;
+mod dynamic_field;
 mod entry;
+mod eviction;
 mod field;
 mod field_descriptor;
-mod field_value;
+pub(crate) mod field_value;
+mod heap_size;
+mod hooks;
 mod macros;
+mod map;
 mod metadata;
+mod metrics;
 mod ref_shared;
+mod shards;
 mod static_field_specialization;
 mod table;
+mod table_entry;
 mod tables_input;
+mod view;
 mod vtable;
 mod wrappers;
 
+pub use dynamic_field::{DynamicField, DynamicFieldSpec, DynamicFieldType};
+pub use eviction::EvictionPolicy;
 pub use field::private::Private;
 pub use field::public::Public;
 pub use field::readonly::Readonly;
-pub use table::Table;
+pub use field_value::dynamic::DynamicFieldValue;
+pub use field_value::dynamic_table::DynamicTableKey;
+pub use heap_size::HeapSize;
+pub use map::TableMap;
+pub use shards::TableShards;
+pub use table::{Table, TableValue};
+pub use table_entry::{OccupiedEntry, TableEntry, VacantEntry};
+pub use view::TableReadView;
 
 // for macro use only
 #[doc(hidden)]
@@ -91,10 +257,6 @@ pub mod traits {
 #[doc(hidden)]
 pub use field_descriptor::{FieldDescriptor, FieldId, FieldRef};
 
-// for macro use only
-#[doc(hidden)]
-pub use field_value::dynamic::DynamicFieldValue;
-
 // for macro use only
 #[doc(hidden)]
 pub use metadata::{HasMetadata, Metadata};
@@ -106,8 +268,8 @@ pub use ref_shared::RefShared;
 // for macro use only
 #[doc(hidden)]
 pub use static_field_specialization::{
-    StaticFieldCheck, StaticFieldFallback, StaticFieldGet, StaticFieldGetFallback, StaticFieldSet,
-    StaticFieldSetFallback,
+    StaticFieldCheck, StaticFieldFallback, StaticFieldGet, StaticFieldGetFallback,
+    StaticFieldHeapSize, StaticFieldHeapSizeFallback, StaticFieldSet, StaticFieldSetFallback,
 };
 
 /// Mark a struct type as a table value
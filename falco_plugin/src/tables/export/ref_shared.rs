@@ -15,6 +15,9 @@ pub type RefShared<T> = Arc<RefCounted<T>>;
 /// like `Rc<RefCell<T>> + RefMut<T>`
 pub type RefGuard<T> = lock_api::ArcRwLockWriteGuard<LockImpl, T>;
 
+/// like `Rc<RefCell<T>> + Ref<T>`
+pub type RefReadGuard<T> = lock_api::ArcRwLockReadGuard<LockImpl, T>;
+
 pub fn new_shared_ref<T>(inner: T) -> RefShared<T> {
     Arc::new(RefCounted::new(inner))
 }
@@ -0,0 +1,62 @@
+use crate::tables::export::ref_shared::{new_shared_ref, RefShared};
+use std::hash::{BuildHasher, BuildHasherDefault, DefaultHasher, Hash};
+use std::sync::Arc;
+
+/// # The maps underlying a [`Table`](`crate::tables::export::Table`)
+///
+/// A table keeps its entries in one or more maps (shards), each behind its own lock.
+/// By default, there's just one shard, but tables written to from multiple threads
+/// can be split into several using [`Table::with_shards`](`crate::tables::export::Table::with_shards`),
+/// so that threads working on different keys do not have to wait for each other.
+///
+/// Each key always lives in the same shard, returned by [`TableShards::shard`].
+/// This type is cheap to clone and can be sent to other threads (with the `thread-safe-tables`
+/// feature enabled), like the map returned from [`Table::data`](`crate::tables::export::Table::data`).
+#[derive(Debug)]
+pub struct TableShards<M> {
+    shards: Arc<[RefShared<M>]>,
+}
+
+impl<M> Clone for TableShards<M> {
+    fn clone(&self) -> Self {
+        Self {
+            shards: Arc::clone(&self.shards),
+        }
+    }
+}
+
+impl<M: Default> TableShards<M> {
+    pub(crate) fn new(count: usize) -> Self {
+        Self {
+            shards: (0..count.max(1))
+                .map(|_| new_shared_ref(M::default()))
+                .collect(),
+        }
+    }
+}
+
+impl<M> TableShards<M> {
+    /// Return all the shards
+    pub fn shards(&self) -> &[RefShared<M>] {
+        &self.shards
+    }
+
+    /// Return the index of the shard holding a particular key
+    pub fn shard_index<Q: Hash + ?Sized>(&self, key: &Q) -> usize {
+        match self.shards.len() {
+            1 => 0,
+            n => (BuildHasherDefault::<DefaultHasher>::default().hash_one(key) % n as u64) as usize,
+        }
+    }
+
+    /// Return the shard holding a particular key
+    ///
+    /// The key can be passed either as the owned key type, or its borrowed form (e.g. `CStr`
+    /// for `CString` keys), as they hash the same.
+    ///
+    /// To actually access the map, you first need to lock the returned object for reading
+    /// (`shard.read()`) or writing (`shard.write()`).
+    pub fn shard<Q: Hash + ?Sized>(&self, key: &Q) -> &RefShared<M> {
+        &self.shards[self.shard_index(key)]
+    }
+}
@@ -1,5 +1,6 @@
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
-use crate::tables::export::field_value::traits::StaticField;
+use crate::tables::export::field_value::traits::{FromDynamicFieldValue, StaticField};
+use crate::tables::export::heap_size::HeapSize;
 use crate::tables::FieldTypeId;
 use falco_plugin_api::ss_plugin_state_data;
 use std::marker::PhantomData;
@@ -23,7 +24,7 @@ pub struct StaticFieldCheck<T>(PhantomData<T>);
 
 impl<T> StaticFieldCheck<T>
 where
-    T: StaticField + TryFrom<DynamicFieldValue, Error = anyhow::Error>,
+    T: StaticField + FromDynamicFieldValue,
 {
     /// get the type id from the actual StaticField implementation
     pub const MAYBE_TYPE_ID: Option<FieldTypeId> = Some(T::TYPE_ID);
@@ -54,7 +55,7 @@ pub struct StaticFieldGet<'a, T>(pub &'a T);
 
 impl<T> StaticFieldGet<'_, T>
 where
-    T: StaticField + TryFrom<DynamicFieldValue, Error = anyhow::Error>,
+    T: StaticField + FromDynamicFieldValue,
 {
     /// get a static field value
     pub fn static_field_get(
@@ -84,11 +85,37 @@ pub struct StaticFieldSet<'a, T>(pub &'a mut T);
 
 impl<T> StaticFieldSet<'_, T>
 where
-    T: StaticField + TryFrom<DynamicFieldValue, Error = anyhow::Error>,
+    T: StaticField + FromDynamicFieldValue,
 {
     /// get a static field value
     pub fn static_field_set(&mut self, value: DynamicFieldValue) -> Result<(), anyhow::Error> {
-        *self.0 = value.try_into()?;
+        *self.0 = T::from_dynamic_field_value(value)?;
         Ok(())
     }
 }
+
+/// A compile-time check for types implementing HeapSize, providing the heap size
+///
+/// See <https://github.com/nvzqz/impls?tab=readme-ov-file#how-it-works> for how it works
+pub trait StaticFieldHeapSizeFallback {
+    /// get the heap size of a field (dummy implementation for types not implementing HeapSize)
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl<T> StaticFieldHeapSizeFallback for T {}
+
+#[allow(missing_docs)]
+#[allow(missing_debug_implementations)]
+pub struct StaticFieldHeapSize<'a, T>(pub &'a T);
+
+impl<T> StaticFieldHeapSize<'_, T>
+where
+    T: HeapSize,
+{
+    /// get the heap size of a field
+    pub fn heap_size(&self) -> usize {
+        self.0.heap_size()
+    }
+}
@@ -1,31 +1,50 @@
+use crate::base::{Metric, MetricLabel, MetricType, MetricValue};
+use crate::tables::export::dynamic_field::{DynamicField, DynamicFieldSpec};
+use crate::tables::export::entry::dynamic::DynamicEntry;
 use crate::tables::export::entry::extensible::ExtensibleEntry;
 use crate::tables::export::entry::table_metadata::extensible::ExtensibleEntryMetadata;
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::entry::traits::Entry;
-use crate::tables::export::field_descriptor::{FieldDescriptor, FieldRef};
+use crate::tables::export::eviction::{Eviction, EvictionPolicy};
+use crate::tables::export::field_descriptor::{FieldDescriptor, FieldId, FieldRef};
 use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::export::field_value::dynamic_table::{
+    DynamicTable, DynamicTableKey, DynamicTableSpec,
+};
+use crate::tables::export::heap_size::HeapSize;
+use crate::tables::export::hooks::TableHooks;
+use crate::tables::export::map::TableMap;
 use crate::tables::export::metadata::HasMetadata;
 use crate::tables::export::metadata::Metadata;
+use crate::tables::export::metrics::TableMetrics;
 use crate::tables::export::ref_shared::{
-    new_counted_ref, new_shared_ref, RefCounted, RefGuard, RefShared,
+    new_counted_ref, new_shared_ref, RefCounted, RefGuard, RefReadGuard, RefShared,
 };
+use crate::tables::export::shards::TableShards;
+use crate::tables::export::table_entry::TableEntry;
+use crate::tables::export::view::TableReadView;
 use crate::tables::export::vtable::Vtable;
 use crate::tables::{FieldTypeId, Key};
 use crate::FailureReason;
 use falco_plugin_api::{ss_plugin_state_data, ss_plugin_table_fieldinfo};
 use std::borrow::Borrow;
 use std::collections::BTreeMap;
-use std::ffi::CStr;
+use std::ffi::{CStr, CString};
 use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
+use std::time::{Duration, Instant};
 
 /// # A table exported to other plugins
 ///
 /// An instance of this type can be exposed to other plugins via
 /// [`tables::TablesInput::add_table`](`crate::tables::TablesInput::add_table`)
 ///
-/// The generic parameters are: key type and entry type. The key type is anything
+/// The generic parameters are: key type, entry type and map type. The key type is anything
 /// usable as a table key, while the entry type is a type that can be stored in the table.
 /// You can obtain such a type by `#[derive]`ing Entry on a struct describing all the table fields.
+/// The map type is the container holding the entries and defaults to a [`BTreeMap`].
+/// See [`TableMap`] for the alternatives.
 ///
 /// Supported key types include:
 /// - integer types (u8/i8, u16/i16, u32/i32, u64/i64)
@@ -36,29 +55,36 @@ use std::fmt::{Debug, Formatter};
 ///
 /// The implementation is thread-safe when the `thread-safe-tables` feature is enabled.
 #[must_use]
-pub struct Table<K, E>
+pub struct Table<K, E, M = BTreeMap<K, TableValue<E>>>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     name: &'static CStr,
     field_descriptors: Vec<ss_plugin_table_fieldinfo>,
     metadata: RefShared<ExtensibleEntryMetadata<E::Metadata>>,
-    data: RefShared<BTreeMap<K, RefShared<ExtensibleEntry<E>>>>,
+    data: TableShards<M>,
+    keys: PhantomData<K>,
+    eviction: RefCounted<Option<Eviction<K, E>>>,
+    has_eviction: bool,
+    hooks: RefCounted<TableHooks<K, E>>,
+    metrics: Option<TableMetrics>,
 
     pub(crate) vtable: RefCounted<Option<Box<Vtable>>>,
 }
 
-impl<K, E> Debug for Table<K, E>
+impl<K, E, M> Debug for Table<K, E, M>
 where
     K: Key + Ord + Debug,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry + Debug,
     E::Metadata: TableMetadata + Debug,
+    M: TableMap<K, TableValue<E>> + Debug,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Table")
@@ -72,13 +98,17 @@ where
 type TableMetadataType<E> = RefShared<ExtensibleEntryMetadata<<E as HasMetadata>::Metadata>>;
 pub(crate) type TableEntryType<E> = RefGuard<ExtensibleEntry<E>>;
 
-impl<K, E> Table<K, E>
+/// # The type of values stored in the map underlying a [`Table`]
+pub type TableValue<E> = RefShared<ExtensibleEntry<E>>;
+
+impl<K, E, M> Table<K, E, M>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     /// Create a new table using provided metadata
     ///
@@ -91,7 +121,12 @@ where
             name: tag,
             field_descriptors: vec![],
             metadata: metadata.clone(),
-            data: new_shared_ref(BTreeMap::new()),
+            data: TableShards::new(1),
+            keys: PhantomData,
+            eviction: new_counted_ref(None),
+            has_eviction: false,
+            hooks: new_counted_ref(TableHooks::default()),
+            metrics: None,
 
             vtable: new_counted_ref(None),
         };
@@ -105,25 +140,296 @@ where
             name,
             field_descriptors: vec![],
             metadata: new_shared_ref(ExtensibleEntryMetadata::new()?),
-            data: new_shared_ref(BTreeMap::new()),
+            data: TableShards::new(1),
+            keys: PhantomData,
+            eviction: new_counted_ref(None),
+            has_eviction: false,
+            hooks: new_counted_ref(TableHooks::default()),
+            metrics: None,
 
             vtable: new_counted_ref(None),
         })
     }
 
+    /// Create a new table with space for at least `capacity` entries
+    ///
+    /// This avoids reallocating the map while populating a large table, e.g. when loading it
+    /// using [`Table::extend`]. Map types that do not preallocate (like the default [`BTreeMap`])
+    /// ignore the capacity, see [`TableMap::reserve`].
+    pub fn with_capacity(name: &'static CStr, capacity: usize) -> Result<Self, anyhow::Error> {
+        let table = Self::new(name)?;
+        table.data.shards()[0].write().reserve(capacity);
+        Ok(table)
+    }
+
+    /// Split the table into `shards` separately locked maps
+    ///
+    /// By default, all entries live in a single map, which has to be locked for writing
+    /// to insert or erase an entry. When multiple threads (e.g. background tasks and the main
+    /// event loop) write to the table at the same time, they end up waiting for each other.
+    /// Splitting the table into shards lets threads working on keys from different shards
+    /// proceed in parallel. This is only useful with the `thread-safe-tables` feature enabled.
+    ///
+    /// Sharded tables cannot hand out the whole map ([`Table::data`] returns `None`), so use
+    /// [`Table::shards`] to access the data from other threads instead. Iterating over
+    /// the table visits the shards one after another, so the entries are no longer visited
+    /// in key order, even with the default [`BTreeMap`].
+    ///
+    /// Existing entries are moved to their new shards, but preallocated capacity
+    /// (see [`Table::with_capacity`]) is lost, so call this before populating the table.
+    pub fn with_shards(mut self, shards: usize) -> Self {
+        let data = TableShards::<M>::new(shards);
+        for shard in self.data.shards() {
+            let mut shard = shard.write();
+            for (key, value) in shard.iter() {
+                let key = key.borrow().to_owned();
+                data.shard(key.borrow())
+                    .write()
+                    .insert(key, std::sync::Arc::clone(value));
+            }
+            shard.clear();
+        }
+        self.data = data;
+        self
+    }
+
+    fn configure_eviction(&mut self, func: impl FnOnce(&mut Eviction<K, E>)) {
+        self.has_eviction = true;
+        let mut eviction = self.eviction.write();
+        let eviction = eviction.get_or_insert_with(|| {
+            let mut eviction = Eviction::new();
+            let now = Instant::now();
+            for shard in self.data.shards() {
+                for (key, _) in shard.read().iter() {
+                    eviction.inserted(key.borrow(), now);
+                }
+            }
+            eviction
+        });
+        func(eviction);
+    }
+
+    /// Update the eviction bookkeeping, skipping the lock for tables without eviction
+    fn update_eviction(&self, func: impl FnOnce(&mut Eviction<K, E>)) {
+        if !self.has_eviction {
+            return;
+        }
+        if let Some(eviction) = self.eviction.write().as_mut() {
+            func(eviction);
+        }
+    }
+
+    /// Limit the number of entries in the table
+    ///
+    /// Whenever an insert makes the table grow beyond `max_entries` entries, the oldest entries
+    /// (according to `policy`) are removed from the table. This applies to inserts both via
+    /// [`Table::insert`] and from other plugins, which simply see the evicted entries disappear,
+    /// as if they had been erased.
+    ///
+    /// Entries that are currently locked (e.g. because someone holds a reference to them)
+    /// are never evicted, so the table may temporarily exceed the limit.
+    ///
+    /// **Note**: changes made directly to the map returned from [`Table::data`] bypass
+    /// the bookkeeping, so entries inserted that way are never evicted.
+    pub fn with_max_entries(mut self, max_entries: usize, policy: EvictionPolicy) -> Self {
+        self.configure_eviction(|eviction| eviction.set_max_entries(max_entries, policy));
+        self.evict_entries();
+        self
+    }
+
+    /// Expire entries that have not been used for a while
+    ///
+    /// Entries that have not been inserted or looked up (via [`Table::lookup`] or by other
+    /// plugins) for longer than `ttl` are removed from the table. The expired entries are removed
+    /// when the table is accessed (on every lookup, insert and iteration), so they never show up
+    /// to the table users. In a table that is rarely accessed, call [`Table::expire_entries`]
+    /// periodically to release the memory.
+    ///
+    /// As with [`Table::with_max_entries`], locked entries are never removed and the map
+    /// returned from [`Table::data`] bypasses the bookkeeping.
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.configure_eviction(|eviction| eviction.set_ttl(ttl));
+        self
+    }
+
+    /// Set a callback to run for every evicted or expired entry
+    ///
+    /// This only has an effect on tables with a limit set using [`Table::with_max_entries`]
+    /// or [`Table::with_ttl`]. The callback runs with the table locked, so it must not try
+    /// to access the table. It can e.g. queue an async event describing the removed state.
+    pub fn with_eviction_callback(mut self, callback: impl FnMut(&K, &mut E) + 'static) -> Self {
+        self.configure_eviction(|eviction| eviction.callback = Some(Box::new(callback)));
+        self
+    }
+
+    /// Set a callback to run for every entry inserted into the table
+    ///
+    /// The callback runs for entries inserted both via [`Table::insert`] (or [`Table::extend`])
+    /// and by other plugins, right after the entry is added to the table. Replacing an existing
+    /// entry counts as erasing the old one (see [`Table::with_erase_callback`]) and creating
+    /// the new one.
+    ///
+    /// Note that other plugins typically set the fields of a new entry only after inserting it,
+    /// so the callback sees the entry with default values in that case.
+    ///
+    /// As with all the lifecycle callbacks, it must not try to access the table.
+    pub fn with_create_callback(self, callback: impl FnMut(&K, &mut E) + 'static) -> Self {
+        self.hooks.write().on_create = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a callback to run for every entry erased from the table
+    ///
+    /// The callback runs for entries erased both via [`Table::erase`] and by other plugins,
+    /// as well as for entries replaced by [`Table::insert`]. It does not run for evicted entries
+    /// (see [`Table::with_eviction_callback`]), nor when the whole table is cleared (see
+    /// [`Table::with_clear_callback`]).
+    ///
+    /// The erased entry is passed to the callback, unless it is still locked (e.g. because
+    /// the plugin erasing it holds a reference to it), in which case the callback gets `None`.
+    pub fn with_erase_callback(self, callback: impl FnMut(&K, Option<&mut E>) + 'static) -> Self {
+        self.hooks.write().on_erase = Some(Box::new(callback));
+        self
+    }
+
+    /// Set a callback to run whenever the table is cleared
+    ///
+    /// The callback runs (once, after all entries are removed) when the table is cleared
+    /// either via [`Table::clear`] or by another plugin.
+    pub fn with_clear_callback(self, callback: impl FnMut() + 'static) -> Self {
+        self.hooks.write().on_clear = Some(Box::new(callback));
+        self
+    }
+
+    /// Remove all expired entries from the table
+    ///
+    /// See [`Table::with_ttl`] for details. Returns the number of removed entries.
+    pub fn expire_entries(&mut self) -> usize {
+        self.evict_entries()
+    }
+
+    /// Remove expired entries and entries over the capacity limit
+    fn evict_entries(&self) -> usize {
+        if !self.has_eviction {
+            return 0;
+        }
+        let mut eviction = self.eviction.write();
+        let Some(eviction) = eviction.as_mut() else {
+            return 0;
+        };
+
+        let now = Instant::now();
+        let over_limit =
+            |eviction: &Eviction<K, E>, len| eviction.max_entries().is_some_and(|max| len > max);
+        if eviction.expired(now).next().is_none() && !over_limit(eviction, self.size()) {
+            return 0;
+        }
+
+        let mut evicted = 0;
+
+        let expired: Vec<K> = eviction
+            .expired(now)
+            .map(|key| key.borrow().to_owned())
+            .collect();
+        for key in expired {
+            evicted += self.evict_entry(eviction, key) as usize;
+        }
+
+        while over_limit(eviction, self.size()) {
+            let mut stale = Vec::new();
+            let mut victim = None;
+            for key in eviction.candidates() {
+                let value = self
+                    .data
+                    .shard(key.borrow())
+                    .read()
+                    .get(key.borrow())
+                    .cloned();
+                match value {
+                    None => stale.push(key.borrow().to_owned()),
+                    Some(value) if !value.is_locked() => {
+                        victim = Some(key.borrow().to_owned());
+                        break;
+                    }
+                    Some(_) => {}
+                }
+            }
+
+            for key in stale {
+                eviction.removed(key.borrow());
+            }
+
+            let Some(key) = victim else {
+                break;
+            };
+            evicted += self.evict_entry(eviction, key) as usize;
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.evicted(evicted);
+        }
+        evicted
+    }
+
+    fn evict_entry(&self, eviction: &mut Eviction<K, E>, key: K) -> bool {
+        let mut data = self.data.shard(key.borrow()).write();
+        let Some(value) = data.get(key.borrow()) else {
+            eviction.removed(key.borrow());
+            return false;
+        };
+        let Some(mut entry) = value.try_write_arc() else {
+            return false;
+        };
+
+        data.remove(key.borrow());
+        drop(data);
+        eviction.removed(key.borrow());
+        if let Some(callback) = eviction.callback.as_mut() {
+            callback(&key, &mut entry);
+        }
+        true
+    }
+
     /// Get an accessor to the underlying data
     ///
-    /// This method returns a reference to the underlying BTreeMap, containing all the table's data.
+    /// This method returns a reference to the underlying map (a [`BTreeMap`] unless you chose
+    /// a different [`TableMap`]), containing all the table's data.
     /// It can be useful for:
     /// - accessing the table from a different thread (with the `thread-safe-tables` feature enabled)
     /// - bypassing the table API for convenience or more control over locking
     ///
-    /// To actually access the BTreeMap, you first need to lock the returned object for reading
+    /// To actually access the map, you first need to lock the returned object for reading
     /// (`data.read()`) or writing (`data.write()`).
-    pub fn data(&self) -> RefShared<BTreeMap<K, RefShared<ExtensibleEntry<E>>>> {
+    ///
+    /// Returns `None` if the table has been split into multiple shards using
+    /// [`Table::with_shards`]. Use [`Table::shards`] for sharded tables.
+    pub fn data(&self) -> Option<RefShared<M>> {
+        match self.data.shards() {
+            [data] => Some(data.clone()),
+            _ => None,
+        }
+    }
+
+    /// Get an accessor to the underlying shards
+    ///
+    /// This is the equivalent of [`Table::data`] for tables split into multiple maps using
+    /// [`Table::with_shards`] (but it works for all tables). See [`TableShards`] for details.
+    pub fn shards(&self) -> TableShards<M> {
         self.data.clone()
     }
 
+    /// Get a read-only view of the table
+    ///
+    /// The returned [`TableReadView`] can be cloned and sent to other threads (with
+    /// the `thread-safe-tables` feature enabled) to look up and iterate over entries,
+    /// without giving them the ability to modify the table.
+    ///
+    /// The view keeps referring to the maps that hold the data when it was created, so get it
+    /// after [`Table::with_shards`], if you split the table.
+    pub fn read_view(&self) -> TableReadView<K, E, M> {
+        TableReadView::new(self.data.clone())
+    }
+
     /// Return the table name.
     pub fn name(&self) -> &'static CStr {
         self.name
@@ -131,16 +437,216 @@ where
 
     /// Return the number of entries in the table.
     pub fn size(&self) -> usize {
-        self.data.read().len()
+        self.data
+            .shards()
+            .iter()
+            .map(|shard| shard.read().len())
+            .sum()
+    }
+
+    /// Estimate the memory used by the table
+    ///
+    /// This adds up the size of all keys and entries, including the heap memory they own
+    /// (as reported by [`HeapSize`]), and of the fields added by other plugins. The overhead
+    /// of the map itself (e.g. tree nodes or empty hash buckets) is not included, so the actual
+    /// memory usage is somewhat higher.
+    ///
+    /// Entries that are currently locked for writing (e.g. because you're holding on to one
+    /// while calling this method) only count with their size, without any heap data.
+    pub fn approx_memory_usage(&self) -> usize
+    where
+        K: HeapSize,
+        E: HeapSize,
+    {
+        let entry_size =
+            size_of::<K>() + size_of::<TableValue<E>>() + size_of::<ExtensibleEntry<E>>();
+
+        self.data
+            .shards()
+            .iter()
+            .map(|shard| {
+                shard
+                    .read()
+                    .iter()
+                    .map(|(key, entry)| {
+                        let entry_heap_size = entry.try_read().map_or(0, |entry| entry.heap_size());
+                        entry_size + key.heap_size() + entry_heap_size
+                    })
+                    .sum::<usize>()
+            })
+            .sum()
+    }
+
+    /// Report the estimated memory usage of the table as a metric
+    ///
+    /// This is a convenience wrapper around [`Table::approx_memory_usage`], meant to be
+    /// returned from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`), so that
+    /// operators can attribute memory growth to specific tables.
+    pub fn memory_usage_metric(&self, name: &'static CStr) -> Metric
+    where
+        K: HeapSize,
+        E: HeapSize,
+    {
+        Metric::new(
+            MetricLabel::new(name, MetricType::NonMonotonic),
+            MetricValue::U64(self.approx_memory_usage() as u64),
+        )
+    }
+
+    /// Keep track of table usage, to be reported as metrics
+    ///
+    /// See [`Table::metrics`] for details.
+    pub fn with_metrics(mut self) -> Self {
+        self.metrics = Some(TableMetrics::new(self.name));
+        self
+    }
+
+    /// Report the table usage as metrics
+    ///
+    /// This is meant to be returned from [`Plugin::get_metrics`](`crate::base::Plugin::get_metrics`)
+    /// and only reports anything for tables created with [`Table::with_metrics`].
+    /// The metric names start with the table name, followed by:
+    /// - `entries`: the current number of entries
+    /// - `dynamic_fields`: the number of fields added by other plugins
+    /// - `inserts`: the total number of entries inserted (including replaced ones)
+    /// - `erases`: the total number of entries erased or removed by clearing the table
+    /// - `evictions`: the total number of entries evicted or expired (only for tables
+    ///   with [`Table::with_max_entries`] or [`Table::with_ttl`])
+    ///
+    /// The counts include changes made both via the [`Table`] methods and by other plugins,
+    /// but not the ones made directly to the map returned from [`Table::data`].
+    pub fn metrics(&self) -> Vec<Metric> {
+        let Some(metrics) = &self.metrics else {
+            return Vec::new();
+        };
+
+        metrics.collect(
+            self.size(),
+            self.metadata.read().num_dynamic_fields(),
+            self.eviction.read().is_some(),
+        )
     }
 
     /// Get an entry corresponding to a particular key.
     pub fn lookup<Q>(&self, key: &Q) -> Option<TableEntryType<E>>
     where
         K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Ord + Hash + ?Sized,
     {
-        Some(self.data.read().get(key)?.write_arc())
+        self.evict_entries();
+        let entry = self.data.shard(key).read().get(key)?.clone();
+        self.update_eviction(|eviction| eviction.used(key, Instant::now()));
+        Some(entry.write_arc())
+    }
+
+    /// Get an entry corresponding to a particular key, locked for reading.
+    ///
+    /// Like [`Table::lookup`], this counts as using the entry for [`Table::with_max_entries`]
+    /// and [`Table::with_ttl`].
+    pub fn get<Q>(&self, key: &Q) -> Option<RefReadGuard<ExtensibleEntry<E>>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.evict_entries();
+        let entry = self.data.shard(key).read().get(key)?.clone();
+        self.update_eviction(|eviction| eviction.used(key, Instant::now()));
+        Some(entry.read_arc())
+    }
+
+    /// Get an entry corresponding to a particular key, locked for writing.
+    ///
+    /// This is the same as [`Table::lookup`], spelled like [`HashMap::get_mut`](`std::collections::HashMap::get_mut`).
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<TableEntryType<E>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.lookup(key)
+    }
+
+    /// Get the entry for a particular key for in-place manipulation
+    ///
+    /// See [`TableEntry`] for details.
+    pub fn entry(&mut self, key: K) -> TableEntry<'_, K, E, M> {
+        TableEntry::new(self, key)
+    }
+
+    /// Iterate over all entries in the table, locked for reading
+    ///
+    /// This follows the same rules as [`Table::iterate_entries`]: the iterator visits the entries
+    /// present when it was created (and not erased since) and each entry is locked only while
+    /// its guard is alive. Entries locked for writing are skipped without the `thread-safe-tables`
+    /// feature and waited for with it.
+    pub fn iter(
+        &self,
+    ) -> impl Iterator<Item = (K, RefReadGuard<ExtensibleEntry<E>>)> + use<'_, K, E, M> {
+        self.evict_entries();
+        self.snapshot().into_iter().filter_map(|(key, value)| {
+            if !self.is_current(&key, &value) {
+                return None;
+            }
+
+            #[cfg(feature = "thread-safe-tables")]
+            let entry = Some(value.read_arc());
+            #[cfg(not(feature = "thread-safe-tables"))]
+            let entry = value.try_read_arc();
+
+            Some((key, entry?))
+        })
+    }
+
+    /// Keep only the entries for which the closure returns true
+    ///
+    /// The other entries are erased (running the callback set with
+    /// [`Table::with_erase_callback`]). Entries skipped due to locking (see [`Table::iter`])
+    /// are kept.
+    pub fn retain<F>(&mut self, mut func: F)
+    where
+        F: FnMut(&K, &mut E) -> bool,
+    {
+        self.evict_entries();
+        for (key, value) in self.snapshot() {
+            if !self.is_current(&key, &value) {
+                continue;
+            }
+
+            #[cfg(feature = "thread-safe-tables")]
+            let entry = Some(value.write());
+            #[cfg(not(feature = "thread-safe-tables"))]
+            let entry = value.try_write();
+
+            let keep = match entry {
+                Some(mut entry) => func(&key, &mut entry),
+                None => true,
+            };
+            if !keep {
+                self.remove(key.borrow());
+            }
+        }
+    }
+
+    /// Collect all the entries in the table, without locking them
+    fn snapshot(&self) -> Vec<(K, TableValue<E>)> {
+        let mut snapshot = Vec::with_capacity(self.size());
+        for shard in self.data.shards() {
+            snapshot.extend(
+                shard
+                    .read()
+                    .iter()
+                    .map(|(key, value)| (key.borrow().to_owned(), value.clone())),
+            );
+        }
+        snapshot
+    }
+
+    /// Check whether an entry from a snapshot is still in the table
+    fn is_current(&self, key: &K, value: &TableValue<E>) -> bool {
+        self.data
+            .shard(key.borrow())
+            .read()
+            .get(key.borrow())
+            .is_some_and(|current| std::sync::Arc::ptr_eq(current, value))
     }
 
     /// Get the value for a field in an entry.
@@ -155,34 +661,86 @@ where
         entry.get(index, type_id, out)
     }
 
-    /// Execute a closure on all entries in the table with read-only access.
+    /// Execute a closure on all entries in the table.
     ///
     /// The iteration continues until all entries are visited or the closure returns false.
-    // TODO(upstream) the closure cannot store away the entry but we could use explicit docs
-    pub fn iterate_entries<F>(&mut self, mut func: F) -> bool
+    /// Returns true if all entries were visited.
+    ///
+    /// The entries are visited in the iteration order of the underlying map (i.e. in key order
+    /// for the default [`BTreeMap`]), as of the start of the iteration. The table itself is not
+    /// locked while the closure runs, so it may freely look up, insert and erase entries
+    /// (also via the plugin API, when another plugin is iterating over the table):
+    /// - entries inserted during the iteration are not visited
+    /// - entries erased during the iteration are not visited (unless they already have been)
+    ///
+    /// Each entry is locked while the closure runs for it. Without the `thread-safe-tables`
+    /// feature, entries that are already locked (e.g. because you're holding on to one while
+    /// calling this method) are skipped. With the feature enabled, the iteration waits for them
+    /// to be released, so holding an entry of the table you're iterating over will deadlock.
+    ///
+    /// The closure must not store away the entry it receives.
+    pub fn iterate_entries<F>(&self, mut func: F) -> bool
     where
         F: FnMut(&mut TableEntryType<E>) -> bool,
     {
-        for value in &mut self.data.write().values_mut() {
-            if !func(&mut value.write_arc()) {
-                return false;
+        self.evict_entries();
+        for (key, value) in self.snapshot() {
+            if !self.is_current(&key, &value) {
+                continue;
+            }
+
+            #[cfg(feature = "thread-safe-tables")]
+            let entry = Some(value.write_arc());
+            #[cfg(not(feature = "thread-safe-tables"))]
+            let entry = value.try_write_arc();
+
+            if let Some(mut entry) = entry {
+                if !func(&mut entry) {
+                    return false;
+                }
             }
         }
         true
     }
 
     /// Remove all entries from the table.
-    pub fn clear(&mut self) {
-        self.data.write().clear()
+    pub fn clear(&self) {
+        for shard in self.data.shards() {
+            let mut data = shard.write();
+            if let Some(metrics) = &self.metrics {
+                metrics.erased(data.len());
+            }
+            data.clear();
+        }
+        self.update_eviction(|eviction| eviction.clear());
+        self.hooks.write().cleared();
     }
 
     /// Erase an entry by key.
-    pub fn erase<Q>(&mut self, key: &Q) -> Option<TableEntryType<E>>
+    pub fn erase<Q>(&self, key: &Q) -> Option<TableEntryType<E>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        Some(self.remove(key)?.write_arc())
+    }
+
+    /// Remove an entry by key, without locking it
+    pub(crate) fn remove<Q>(&self, key: &Q) -> Option<TableValue<E>>
     where
         K: Borrow<Q>,
-        Q: Ord + ?Sized,
+        Q: Ord + Hash + ?Sized,
     {
-        Some(self.data.write().remove(key)?.write_arc())
+        let (key, removed) = self.data.shard(key).write().remove_entry(key)?;
+        self.update_eviction(|eviction| eviction.removed(&key));
+        if let Some(metrics) = &self.metrics {
+            metrics.erased(1);
+        }
+        let mut hooks = self.hooks.write();
+        if hooks.has_erase() {
+            hooks.erased(&key, &removed);
+        }
+        Some(removed)
     }
 
     /// Create a new table entry.
@@ -201,20 +759,20 @@ where
     /// The `Table` object itself cannot be shared between threads safely even with
     /// the `thread-safe-tables` feature enabled, but almost full functionality can be achieved
     /// using two objects that can:
-    /// 1. The underlying BTreeMap, obtained from [Table::data]
+    /// 1. The underlying map, obtained from [Table::data] (or [Table::shards] for sharded tables)
     /// 2. A closure capable of creating a new entry (returned from this function)
     ///
     /// The only functionality missing is listing table fields, and until a use case comes along,
     /// it's likely to remain unimplemented.
     ///
     /// The entry obtained by calling the closure returned from `create_entry_fn` can be later
-    /// inserted into the table e.g. by calling [BTreeMap::insert].
+    /// inserted into the table e.g. by calling [TableMap::insert].
     ///
     /// To actually access the entry's fields, you first need to lock the returned object for reading
     /// (`data.read()`) or writing (`data.write()`).
     pub fn create_entry_fn(
         &self,
-    ) -> impl Fn() -> Result<RefShared<ExtensibleEntry<E>>, anyhow::Error> + use<K, E> {
+    ) -> impl Fn() -> Result<TableValue<E>, anyhow::Error> + use<K, E, M> {
         let name = self.name;
         let metadata = self.metadata.clone();
 
@@ -226,19 +784,99 @@ where
     }
 
     /// Attach an entry to a table key
-    pub fn insert<Q>(&mut self, key: &Q, entry: TableEntryType<E>) -> Option<TableEntryType<E>>
+    pub fn insert<Q>(&self, key: &Q, entry: TableEntryType<E>) -> Option<TableEntryType<E>>
     where
         K: Borrow<Q>,
-        Q: Ord + ToOwned<Owned = K> + ?Sized,
+        Q: Ord + Hash + ToOwned<Owned = K> + ?Sized,
     {
         // note: different semantics from data.insert: we return the *new* entry
         let new_entry = std::sync::Arc::clone(RefGuard::rwlock(&entry));
 
-        self.data
+        let replaced = self
+            .data
+            .shard(key)
             .write()
             .insert(key.to_owned(), std::sync::Arc::clone(&new_entry));
         drop(entry);
-        Some(new_entry.write_arc())
+        let mut new_entry = new_entry.write_arc();
+
+        if let Some(metrics) = &self.metrics {
+            metrics.inserted(1);
+        }
+        self.update_eviction(|eviction| eviction.inserted(key, Instant::now()));
+        {
+            let mut hooks = self.hooks.write();
+            if hooks.has_create() || hooks.has_erase() {
+                let key = key.to_owned();
+                if let Some(replaced) = replaced {
+                    hooks.erased(&key, &replaced);
+                }
+                hooks.created(&key, &mut new_entry);
+            }
+        }
+        // the new entry is locked, so it won't get evicted right away
+        self.evict_entries();
+
+        Some(new_entry)
+    }
+
+    /// Attach multiple entries to their table keys
+    ///
+    /// This is equivalent to calling [`Table::insert`] for each entry, but it locks the table
+    /// (each shard, see [`Table::with_shards`]) only once and reserves space for the new entries up front (based on the iterator's
+    /// [`size_hint`](`Iterator::size_hint`)), so it's the preferred way to populate a table
+    /// with many entries at once.
+    ///
+    /// The size limit set with [`Table::with_max_entries`] is enforced after all the entries
+    /// are inserted, so inserting more entries than the limit evicts some of the new ones.
+    pub fn extend<I>(&mut self, entries: I)
+    where
+        I: IntoIterator<Item = (K, TableEntryType<E>)>,
+    {
+        let entries = entries.into_iter();
+        let shards = self.data.shards();
+        let mut by_shard: Vec<Vec<(K, TableEntryType<E>)>> = (0..shards.len())
+            .map(|_| Vec::with_capacity(entries.size_hint().0 / shards.len()))
+            .collect();
+        for (key, entry) in entries {
+            by_shard[self.data.shard_index(key.borrow())].push((key, entry));
+        }
+
+        let now = Instant::now();
+        let mut inserted = Vec::new();
+        let mut replaced = Vec::new();
+        {
+            let mut eviction = self.has_eviction.then(|| self.eviction.write());
+            for (shard, entries) in shards.iter().zip(by_shard) {
+                let mut data = shard.write();
+                data.reserve(entries.len());
+                for (key, entry) in entries {
+                    if let Some(eviction) = eviction.as_mut().and_then(|e| e.as_mut()) {
+                        eviction.inserted(key.borrow(), now);
+                    }
+                    let value = std::sync::Arc::clone(RefGuard::rwlock(&entry));
+                    let key_copy = key.borrow().to_owned();
+                    if let Some(old) = data.insert(key_copy, value) {
+                        replaced.push((key.borrow().to_owned(), old));
+                    }
+                    inserted.push((key, entry));
+                }
+            }
+        }
+
+        if let Some(metrics) = &self.metrics {
+            metrics.inserted(inserted.len());
+        }
+        {
+            let mut hooks = self.hooks.write();
+            for (key, old) in replaced {
+                hooks.erased(&key, &old);
+            }
+            for (key, mut entry) in inserted {
+                hooks.created(&key, &mut entry);
+            }
+        }
+        self.evict_entries();
     }
 
     /// Write a value to a field of an entry
@@ -279,6 +917,86 @@ where
             .filter(|f| f.as_ref().type_id == field_type)
     }
 
+    /// Add a nested table field to the table
+    ///
+    /// Nested tables are usually declared statically in the entry struct, but when the schema
+    /// is only known at runtime (e.g. from the plugin config), a table-valued field can be added
+    /// here instead. Every entry (existing and new, including the ones created by other plugins)
+    /// gets its own nested table, keyed by `NK` and with the fields listed in `fields` (other
+    /// plugins can add more fields, shared by all the nested tables in this field).
+    ///
+    /// Other plugins see the new field just like a statically declared nested table. Your plugin
+    /// can access the nested table of an entry using [`Table::get_table_field`].
+    ///
+    /// Ideally, add the fields before registering the table with
+    /// [`TablesInput::add_table`](`crate::tables::TablesInput::add_table`), so that all other
+    /// plugins can see them. Adding a field to a populated table locks all the entries in turn,
+    /// so you must not hold on to any of them while calling this method.
+    pub fn add_table_field<NK: DynamicTableKey>(
+        &mut self,
+        name: &CStr,
+        fields: &[(&CStr, FieldTypeId)],
+    ) -> Result<FieldRef, anyhow::Error> {
+        if self.metadata.get_field(name).is_some() {
+            anyhow::bail!("Field {:?} already exists in table {:?}", name, self.name);
+        }
+
+        let metadata = new_shared_ref(ExtensibleEntryMetadata::new()?);
+        for (field_name, field_type) in fields {
+            if *field_type == FieldTypeId::Table {
+                anyhow::bail!("Nested table fields cannot hold tables ({:?})", field_name);
+            }
+            metadata
+                .write()
+                .add_field(field_name, *field_type, false)
+                .ok_or_else(|| anyhow::anyhow!("Duplicate nested table field {:?}", field_name))?;
+        }
+
+        // the nested tables are named after the field, which (like all table fields)
+        // stays around for as long as the plugin is loaded
+        let spec = DynamicTableSpec {
+            name: Box::leak(name.to_owned().into_boxed_c_str()),
+            key_type: NK::TYPE_ID,
+            metadata,
+        };
+        let field = self
+            .metadata
+            .write()
+            .add_table_field(name, spec.clone())
+            .ok_or_else(|| anyhow::anyhow!("Cannot add field {:?}", name))?;
+
+        for shard in self.data.shards() {
+            for (_, entry) in shard.read().iter() {
+                let table = DynamicFieldValue::Table(DynamicTable::new(&spec)?);
+                entry.write().set(field.as_ref().index, table)?;
+            }
+        }
+
+        Ok(field)
+    }
+
+    /// Get the nested table in a field added using [`Table::add_table_field`]
+    ///
+    /// `NK` must be the same key type the field was added with.
+    pub fn get_table_field<'a, NK: DynamicTableKey>(
+        &self,
+        entry: &'a TableEntryType<E>,
+        field: &FieldRef,
+    ) -> Result<&'a Table<NK, DynamicEntry>, anyhow::Error> {
+        let FieldId::Dynamic(index) = field.as_ref().index else {
+            anyhow::bail!("Field {:?} was not added at runtime", field);
+        };
+        match entry.custom_fields().as_slice().get(index) {
+            Some(DynamicFieldValue::Table(table)) => NK::table(table).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Bad key type, requested {:?}, table has a different one",
+                    NK::TYPE_ID
+                )
+            }),
+            _ => anyhow::bail!("Field {:?} does not hold a table", field),
+        }
+    }
+
     /// Add a new field to the table
     pub fn add_field(
         &mut self,
@@ -288,15 +1006,59 @@ where
     ) -> Option<FieldRef> {
         self.metadata.add_field(name, field_type, read_only)
     }
+
+    /// Add the fields listed in the plugin config to the table
+    ///
+    /// This is meant to be called from [`Plugin::new`](`crate::base::Plugin::new`), with a list
+    /// of [`DynamicFieldSpec`] values deserialized from the config. It returns a map from field
+    /// names to [`DynamicField`] handles, which your plugin can use to read and write the fields
+    /// in table entries.
+    ///
+    /// Fails if a name is not a valid C string or is already taken by a field of a different
+    /// type (including the fields of the entry type itself). Listing the same field twice
+    /// with the same type is fine.
+    pub fn add_dynamic_fields(
+        &mut self,
+        fields: &[DynamicFieldSpec],
+    ) -> Result<BTreeMap<String, DynamicField>, anyhow::Error> {
+        let mut handles = BTreeMap::new();
+        for spec in fields {
+            let name = CString::new(spec.name.as_str())?;
+            if self.metadata.read().inner.get_field(&name).is_some() {
+                anyhow::bail!("Field {:?} already exists in table {:?}", name, self.name);
+            }
+
+            let field = self.add_field(&name, spec.field_type.into(), spec.readonly);
+            let Some(FieldRef::Dynamic(descriptor)) = field else {
+                anyhow::bail!(
+                    "Field {:?} already exists in table {:?} with a different type",
+                    name,
+                    self.name
+                );
+            };
+            handles.insert(
+                spec.name.clone(),
+                DynamicField::new(spec.field_type, descriptor),
+            );
+        }
+        Ok(handles)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use crate::tables::export::entry::dynamic::DynamicEntry;
-    use crate::tables::export::Table;
+    use crate::tables::export::{EvictionPolicy, Table, TableValue};
     use crate::tables::import::Bool;
-    use crate::tables::TablesInput;
+    use crate::tables::{FieldTypeId, TablesInput};
+    use falco_plugin_api::ss_plugin_state_data;
+    use std::cell::RefCell;
+    use std::collections::HashMap;
     use std::ffi::CString;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    type HashTable<K> = Table<K, DynamicEntry, HashMap<K, TableValue<DynamicEntry>>>;
 
     // Just a compile test
     #[allow(unused)]
@@ -312,6 +1074,314 @@ mod tests {
         input.add_table(Table::<Bool, DynamicEntry>::new(c"exported")?)?;
         input.add_table(Table::<CString, DynamicEntry>::new(c"exported")?)?;
 
+        input.add_table(HashTable::<u64>::new(c"exported")?)?;
+        input.add_table(HashTable::<Bool>::new(c"exported")?)?;
+        input.add_table(HashTable::<CString>::new(c"exported")?)?;
+
         Ok(())
     }
+
+    #[test]
+    fn test_hash_map_backend() {
+        let table = HashTable::<CString>::new(c"hashed").unwrap();
+
+        for key in [c"foo", c"bar", c"baz"] {
+            let entry = table.create_entry().unwrap();
+            table.insert(key, entry);
+        }
+        assert_eq!(table.size(), 3);
+        assert!(table.lookup(c"bar").is_some());
+        assert!(table.lookup(c"qux").is_none());
+
+        let mut visited = 0;
+        table.iterate_entries(|_| {
+            visited += 1;
+            true
+        });
+        assert_eq!(visited, 3);
+
+        assert!(table.erase(c"bar").is_some());
+        assert!(table.lookup(c"bar").is_none());
+        assert_eq!(table.size(), 2);
+
+        table.clear();
+        assert_eq!(table.size(), 0);
+    }
+
+    #[test]
+    fn test_hooks() {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let (created, erased, cleared) = (events.clone(), events.clone(), events.clone());
+        let mut table = Table::<u64, DynamicEntry>::new(c"hooked")
+            .unwrap()
+            .with_create_callback(move |key, _| created.borrow_mut().push(format!("create {key}")))
+            .with_erase_callback(move |key, entry| {
+                erased
+                    .borrow_mut()
+                    .push(format!("erase {key} {}", entry.is_some()))
+            })
+            .with_clear_callback(move || cleared.borrow_mut().push(String::from("clear")));
+
+        table.insert(&1, table.create_entry().unwrap());
+        table.insert(&1, table.create_entry().unwrap());
+        let entries = (2..4).map(|key| (key, table.create_entry().unwrap()));
+        table.extend(entries.collect::<Vec<_>>());
+        assert!(table.erase(&2).is_some());
+        let held = table.lookup(&3).unwrap();
+        assert!(table.remove(&3).is_some());
+        drop(held);
+        table.clear();
+
+        assert_eq!(
+            *events.borrow(),
+            [
+                "create 1",
+                "erase 1 true",
+                "create 1",
+                "create 2",
+                "create 3",
+                "erase 2 true",
+                "erase 3 false",
+                "clear"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_read_view() {
+        let table = HashTable::<u64>::new(c"viewed").unwrap();
+        let view = table.read_view();
+        assert!(view.is_empty());
+
+        for key in 0..3 {
+            table.insert(&key, table.create_entry().unwrap());
+        }
+        let view2 = view.clone();
+        assert_eq!(view2.len(), 3);
+        assert!(view2.contains_key(&1));
+        assert!(view2.get(&2).is_some());
+        assert!(view2.get(&3).is_none());
+
+        let mut keys = Vec::new();
+        assert!(!view.for_each(|key, _| {
+            keys.push(*key);
+            keys.len() < 2
+        }));
+        assert_eq!(keys.len(), 2);
+
+        table.erase(&1);
+        assert!(!view.contains_key(&1));
+    }
+
+    #[test]
+    fn test_add_table_field() {
+        let mut table = Table::<u64, DynamicEntry>::new(c"parent").unwrap();
+        table.insert(&1, table.create_entry().unwrap());
+
+        let field = table
+            .add_table_field::<u64>(c"children", &[(c"count", FieldTypeId::U64)])
+            .unwrap();
+        assert!(table.add_table_field::<u64>(c"children", &[]).is_err());
+        assert!(table
+            .add_table_field::<u64>(c"nested", &[(c"table", FieldTypeId::Table)])
+            .is_err());
+        assert!(table.get_field(c"children", FieldTypeId::Table).is_some());
+
+        // existing entries get a nested table too
+        let entry = table.lookup(&1).unwrap();
+        let nested = table.get_table_field::<u64>(&entry, &field).unwrap();
+        assert_eq!(nested.name(), c"children");
+        assert!(nested.get_field(c"count", FieldTypeId::U64).is_some());
+        assert!(table.get_table_field::<u32>(&entry, &field).is_err());
+
+        // each entry has a separate nested table
+        let other = table.create_entry().unwrap();
+        let other_nested = table.get_table_field::<u64>(&other, &field).unwrap();
+        nested
+            .shards()
+            .shard(&5)
+            .write()
+            .insert(5, nested.create_entry_fn()().unwrap());
+        assert_eq!(nested.size(), 1);
+        assert_eq!(other_nested.size(), 0);
+
+        let mut out = ss_plugin_state_data { u64_: 0 };
+        table
+            .get_field_value(&entry, field.as_ref(), &mut out)
+            .unwrap();
+        assert!(!unsafe { out.table }.is_null());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut table = HashTable::<u64>::with_capacity(c"bulk", 1000).unwrap();
+        assert!(table.data().unwrap().read().capacity() >= 1000);
+
+        let entries = (0..1000)
+            .map(|key| (key, table.create_entry().unwrap()))
+            .collect::<Vec<_>>();
+        table.extend(entries);
+        assert_eq!(table.size(), 1000);
+        assert!(table.lookup(&999).is_some());
+
+        // size limits apply to the whole batch
+        let mut table = Table::<u64, DynamicEntry>::new(c"bounded")
+            .unwrap()
+            .with_max_entries(10, EvictionPolicy::Fifo);
+        let entries = (0..100)
+            .map(|key| (key, table.create_entry().unwrap()))
+            .collect::<Vec<_>>();
+        table.extend(entries);
+        assert_eq!(table.size(), 10);
+        assert!(table.lookup(&89).is_none());
+        assert!(table.lookup(&90).is_some());
+    }
+
+    #[test]
+    fn test_shards() {
+        let table = HashTable::<u64>::new(c"sharded").unwrap().with_shards(8);
+        for key in 0..100 {
+            let entry = table.create_entry().unwrap();
+            table.insert(&key, entry);
+        }
+        assert_eq!(table.size(), 100);
+
+        let shards = table.shards();
+        assert_eq!(shards.shards().len(), 8);
+        assert!(shards.shards().iter().all(|shard| shard.read().len() < 100));
+        for key in 0..100 {
+            assert!(shards.shard(&key).read().get(&key).is_some());
+        }
+
+        assert!(table.erase(&42).is_some());
+        assert!(table.lookup(&42).is_none());
+        let mut visited = 0;
+        table.iterate_entries(|_| {
+            visited += 1;
+            true
+        });
+        assert_eq!(visited, 99);
+
+        // entries are moved over when resharding
+        let table = table.with_shards(3);
+        assert_eq!(table.size(), 99);
+        assert!(table.lookup(&41).is_some());
+
+        table.clear();
+        assert_eq!(table.size(), 0);
+    }
+
+    #[test]
+    fn test_sharded_data() {
+        let table = HashTable::<u64>::new(c"sharded").unwrap().with_shards(2);
+        assert!(table.data().is_none());
+        assert_eq!(table.shards().shards().len(), 2);
+    }
+
+    #[test]
+    fn test_max_entries() {
+        let evicted = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let evicted_in_callback = evicted.clone();
+        let table = Table::<u64, DynamicEntry>::new(c"bounded")
+            .unwrap()
+            .with_max_entries(3, EvictionPolicy::Lru)
+            .with_eviction_callback(move |key, _| evicted_in_callback.borrow_mut().push(*key));
+
+        for key in 1..=3 {
+            let entry = table.create_entry().unwrap();
+            table.insert(&key, entry);
+        }
+        assert_eq!(table.size(), 3);
+
+        // 1 is now the most recently used entry, so 2 gets evicted first
+        drop(table.lookup(&1));
+        for key in 4..=5 {
+            let entry = table.create_entry().unwrap();
+            table.insert(&key, entry);
+        }
+        assert_eq!(table.size(), 3);
+        assert_eq!(*evicted.borrow(), [2, 3]);
+        assert!(table.lookup(&1).is_some());
+
+        // entries in use are not evicted
+        let held = table.lookup(&1).unwrap();
+        let held_too = table.lookup(&4).unwrap();
+        let entry = table.create_entry().unwrap();
+        table.insert(&6, entry);
+        assert_eq!(*evicted.borrow(), [2, 3, 5]);
+        drop((held, held_too));
+
+        assert!(table.erase(&6).is_some());
+        table.clear();
+        let entry = table.create_entry().unwrap();
+        table.insert(&7, entry);
+        assert_eq!(table.size(), 1);
+        assert_eq!(*evicted.borrow(), [2, 3, 5]);
+    }
+
+    #[test]
+    fn test_ttl() {
+        // generous enough not to expire anything between the first accesses on a busy machine
+        const TTL: Duration = Duration::from_millis(250);
+
+        let expired = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let expired_in_callback = expired.clone();
+        let mut table = Table::<u64, DynamicEntry>::new(c"expiring")
+            .unwrap()
+            .with_ttl(TTL)
+            .with_eviction_callback(move |key, _| expired_in_callback.borrow_mut().push(*key));
+
+        for key in 1..=2 {
+            let entry = table.create_entry().unwrap();
+            table.insert(&key, entry);
+        }
+        assert!(table.lookup(&1).is_some());
+        assert_eq!(table.expire_entries(), 0);
+
+        std::thread::sleep(2 * TTL);
+        assert!(table.lookup(&1).is_none());
+        assert_eq!(*expired.borrow(), [2, 1]);
+        assert_eq!(table.size(), 0);
+
+        for key in 3..=4 {
+            let entry = table.create_entry().unwrap();
+            table.insert(&key, entry);
+        }
+        std::thread::sleep(2 * TTL);
+        assert_eq!(table.expire_entries(), 2);
+        assert_eq!(*expired.borrow(), [2, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_approx_memory_usage() {
+        let mut table = Table::<CString, DynamicEntry>::new(c"sized").unwrap();
+        let field = table
+            .add_field(c"name", FieldTypeId::String, false)
+            .unwrap();
+        let field = field.as_ref();
+        assert_eq!(table.approx_memory_usage(), 0);
+
+        let entry = table.create_entry().unwrap();
+        table.insert(c"key", entry);
+        let empty_entry_usage = table.approx_memory_usage();
+        assert!(empty_entry_usage >= c"key".count_bytes());
+
+        let mut entry = table.lookup(c"key").unwrap();
+        let name = c"a fairly long process name";
+        let value = ss_plugin_state_data {
+            str_: name.as_ptr(),
+        };
+        table.write(&mut entry, field, &value).unwrap();
+        drop(entry);
+
+        let usage = table.approx_memory_usage();
+        assert!(usage >= empty_entry_usage + name.count_bytes());
+        assert_eq!(
+            table
+                .memory_usage_metric(c"sized_memory")
+                .as_raw()
+                .value_type,
+            falco_plugin_api::ss_plugin_metric_value_type_SS_PLUGIN_METRIC_VALUE_TYPE_U64
+        );
+    }
 }
@@ -0,0 +1,227 @@
+use crate::tables::export::entry::table_metadata::traits::TableMetadata;
+use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::{Table, TableEntryType, TableValue};
+use crate::tables::Key;
+use std::borrow::Borrow;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+
+/// # A view into a single entry of a [`Table`], which may either be vacant or occupied
+///
+/// Returned from [`Table::entry`], this mirrors [`std::collections::hash_map::Entry`]:
+///
+/// ```
+/// use falco_plugin::tables::export;
+///
+/// #[derive(export::Entry)]
+/// struct Connection {
+///     packets: export::Public<u64>,
+/// }
+///
+/// # fn main() -> anyhow::Result<()> {
+/// let mut table = export::Table::<u64, Connection>::new(c"connections")?;
+/// for _ in 0..3 {
+///     let conn = table
+///         .entry(1)
+///         .and_modify(|conn| *conn.packets += 1)
+///         .or_insert_with(|conn| *conn.packets = 1)?;
+///     println!("{} packets", *conn.packets);
+/// }
+/// assert_eq!(*table.get(&1).unwrap().packets, 3);
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Entries are always created by the table (so that fields added by other plugins get
+/// initialized), which is why, unlike in the standard library, the closures passed to
+/// [`TableEntry::or_insert_with`] and [`VacantEntry::insert_with`] initialize a freshly created
+/// entry instead of returning a value. Creating an entry can fail, so these methods return
+/// a `Result`.
+pub enum TableEntry<'a, K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    /// An entry present in the table
+    Occupied(OccupiedEntry<K, E>),
+    /// A key not present in the table
+    Vacant(VacantEntry<'a, K, E, M>),
+}
+
+/// # An entry present in a [`Table`]
+///
+/// The entry stays locked while this object is alive.
+pub struct OccupiedEntry<K, E> {
+    key: K,
+    entry: TableEntryType<E>,
+}
+
+/// # A key not present in a [`Table`]
+pub struct VacantEntry<'a, K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    key: K,
+    table: &'a mut Table<K, E, M>,
+}
+
+impl<'a, K, E, M> TableEntry<'a, K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    pub(crate) fn new(table: &'a mut Table<K, E, M>, key: K) -> Self {
+        match table.lookup(key.borrow()) {
+            Some(entry) => Self::Occupied(OccupiedEntry { key, entry }),
+            None => Self::Vacant(VacantEntry { key, table }),
+        }
+    }
+
+    /// Return the key of this entry
+    pub fn key(&self) -> &K {
+        match self {
+            Self::Occupied(entry) => entry.key(),
+            Self::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Modify the entry in place, if it is present in the table
+    pub fn and_modify<F>(mut self, func: F) -> Self
+    where
+        F: FnOnce(&mut E),
+    {
+        if let Self::Occupied(entry) = &mut self {
+            func(entry.get_mut());
+        }
+        self
+    }
+
+    /// Return the entry, inserting a new one first if it's not present
+    ///
+    /// The closure is called to initialize the new entry (with all fields set to their
+    /// default values) before it is inserted.
+    pub fn or_insert_with<F>(self, init: F) -> Result<TableEntryType<E>, anyhow::Error>
+    where
+        F: FnOnce(&mut E),
+    {
+        match self {
+            Self::Occupied(entry) => Ok(entry.into_mut()),
+            Self::Vacant(entry) => entry.insert_with(init),
+        }
+    }
+
+    /// Return the entry, inserting a new one with default field values if it's not present
+    pub fn or_default(self) -> Result<TableEntryType<E>, anyhow::Error> {
+        self.or_insert_with(|_| {})
+    }
+}
+
+impl<K, E> OccupiedEntry<K, E> {
+    /// Return the key of this entry
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Return a reference to the entry
+    pub fn get(&self) -> &E {
+        &self.entry
+    }
+
+    /// Return a mutable reference to the entry
+    pub fn get_mut(&mut self) -> &mut E {
+        &mut self.entry
+    }
+
+    /// Return the locked entry
+    pub fn into_mut(self) -> TableEntryType<E> {
+        self.entry
+    }
+}
+
+impl<'a, K, E, M> VacantEntry<'a, K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    /// Return the key of this entry
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Take ownership of the key
+    pub fn into_key(self) -> K {
+        self.key
+    }
+
+    /// Create a new entry, initialize it using the closure and insert it into the table
+    pub fn insert_with<F>(self, init: F) -> Result<TableEntryType<E>, anyhow::Error>
+    where
+        F: FnOnce(&mut E),
+    {
+        let mut entry = self.table.create_entry()?;
+        init(&mut entry);
+        self.table
+            .insert(self.key.borrow(), entry)
+            .ok_or_else(|| anyhow::anyhow!("Failed to insert entry"))
+    }
+}
+
+impl<K, E, M> Debug for TableEntry<'_, K, E, M>
+where
+    K: Key + Ord + Debug,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry + Debug,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Occupied(entry) => f.debug_tuple("Occupied").field(entry).finish(),
+            Self::Vacant(entry) => f.debug_tuple("Vacant").field(entry).finish(),
+        }
+    }
+}
+
+impl<K: Debug, E: Debug> Debug for OccupiedEntry<K, E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("OccupiedEntry")
+            .field("key", &self.key)
+            .field("entry", self.get())
+            .finish()
+    }
+}
+
+impl<K, E, M> Debug for VacantEntry<'_, K, E, M>
+where
+    K: Key + Ord + Debug,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    E: Entry,
+    E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VacantEntry")
+            .field("key", &self.key)
+            .finish()
+    }
+}
@@ -1,4 +1,6 @@
 use crate::error::as_result::{AsResult, WithLastError};
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::TableValue;
 use crate::tables::export::traits::{Entry, TableMetadata};
 use crate::tables::export::wrappers::{fields_vtable, reader_vtable, writer_vtable};
 use crate::tables::export::Table;
@@ -8,6 +10,7 @@ use falco_plugin_api::{
     ss_plugin_table_reader_vtable, ss_plugin_table_writer_vtable,
 };
 use std::borrow::Borrow;
+use std::hash::Hash;
 
 impl TablesInput<'_> {
     /// # Export a table to the Falco plugin API
@@ -15,20 +18,24 @@ impl TablesInput<'_> {
     /// This method returns a Box, which you need to store in your plugin instance
     /// even if you don't intend to use the table yourself (the table is destroyed when
     /// going out of scope, which will lead to crashes in plugins using your table).
-    pub fn add_table<K, E>(&self, table: Table<K, E>) -> Result<Box<Table<K, E>>, anyhow::Error>
+    pub fn add_table<K, E, M>(
+        &self,
+        table: Table<K, E, M>,
+    ) -> Result<Box<Table<K, E, M>>, anyhow::Error>
     where
         K: Key + Ord,
         K: Borrow<<K as Key>::Borrowed>,
-        <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+        <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
         E: Entry,
         E::Metadata: TableMetadata,
+        M: TableMap<K, TableValue<E>>,
     {
-        let mut reader_vtable_ext = reader_vtable::<K, E>();
-        let mut writer_vtable_ext = writer_vtable::<K, E>();
-        let mut fields_vtable_ext = fields_vtable::<K, E>();
+        let mut reader_vtable_ext = reader_vtable::<K, E, M>();
+        let mut writer_vtable_ext = writer_vtable::<K, E, M>();
+        let mut fields_vtable_ext = fields_vtable::<K, E, M>();
 
         let mut table = Box::new(table);
-        let table_ptr = table.as_mut() as *mut Table<K, E>;
+        let table_ptr = table.as_mut() as *mut Table<K, E, M>;
 
         // Note: we lend the ss_plugin_table_input to the FFI api and do not need
         // to hold on to it (everything is copied out), but the name field is copied
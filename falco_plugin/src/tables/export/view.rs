@@ -0,0 +1,148 @@
+use crate::tables::export::entry::extensible::ExtensibleEntry;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::ref_shared::RefReadGuard;
+use crate::tables::export::shards::TableShards;
+use crate::tables::export::table::TableValue;
+use crate::tables::Key;
+use std::borrow::Borrow;
+use std::collections::BTreeMap;
+use std::fmt::{Debug, Formatter};
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// # A read-only view of a [`Table`](`crate::tables::export::Table`)
+///
+/// Returned from [`Table::read_view`](`crate::tables::export::Table::read_view`), this is a cheap
+/// handle to the table data that can be cloned and moved into background threads or async tasks
+/// (with the `thread-safe-tables` feature enabled), without handing them the whole table.
+/// It only allows reading the entries: inserting, erasing and modifying entries still goes
+/// through the table itself.
+///
+/// Every access takes the same locks as the table does, so a view never sees a partially
+/// updated entry, but an entry may change (or disappear) between two calls.
+///
+/// Reads through a view bypass the bookkeeping for [`Table::with_max_entries`] and
+/// [`Table::with_ttl`], so they do not count as using an entry, and expired entries stay
+/// visible until the table removes them.
+///
+/// [`Table::with_max_entries`]: `crate::tables::export::Table::with_max_entries`
+/// [`Table::with_ttl`]: `crate::tables::export::Table::with_ttl`
+pub struct TableReadView<K, E, M = BTreeMap<K, TableValue<E>>> {
+    data: TableShards<M>,
+    types: PhantomData<fn() -> (K, E)>,
+}
+
+impl<K, E, M> Clone for TableReadView<K, E, M> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            types: PhantomData,
+        }
+    }
+}
+
+impl<K, E, M: Debug> Debug for TableReadView<K, E, M> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TableReadView")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<K, E, M> TableReadView<K, E, M>
+where
+    K: Key + Ord,
+    K: Borrow<<K as Key>::Borrowed>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
+    M: TableMap<K, TableValue<E>>,
+{
+    pub(crate) fn new(data: TableShards<M>) -> Self {
+        Self {
+            data,
+            types: PhantomData,
+        }
+    }
+
+    /// Get an entry corresponding to a particular key
+    ///
+    /// The entry stays locked for reading until the returned guard is dropped, so the table
+    /// cannot modify it in the meantime (with the `thread-safe-tables` feature enabled,
+    /// the writer waits for the guard to be released).
+    pub fn get<Q>(&self, key: &Q) -> Option<RefReadGuard<ExtensibleEntry<E>>>
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        // don't hold the map lock while waiting for the entry
+        let entry = self.data.shard(key).read().get(key)?.clone();
+        Some(entry.read_arc())
+    }
+
+    /// Check whether the table contains a particular key
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Ord + Hash + ?Sized,
+    {
+        self.data.shard(key).read().get(key).is_some()
+    }
+
+    /// Return the number of entries in the table
+    pub fn len(&self) -> usize {
+        self.data
+            .shards()
+            .iter()
+            .map(|shard| shard.read().len())
+            .sum()
+    }
+
+    /// Check whether the table is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Execute a closure on all entries in the table
+    ///
+    /// The iteration continues until all entries are visited or the closure returns false.
+    /// Returns true if all entries were visited.
+    ///
+    /// As with [`Table::iterate_entries`](`crate::tables::export::Table::iterate_entries`),
+    /// the entries present at the start of the iteration are visited one at a time, without
+    /// keeping the table locked, so the table can be modified while the iteration is running.
+    /// Entries locked for writing are skipped without the `thread-safe-tables` feature and waited
+    /// for with it.
+    pub fn for_each<F>(&self, mut func: F) -> bool
+    where
+        F: FnMut(&K, &E) -> bool,
+    {
+        for shard in self.data.shards() {
+            let snapshot: Vec<(K, TableValue<E>)> = shard
+                .read()
+                .iter()
+                .map(|(key, value)| (key.borrow().to_owned(), value.clone()))
+                .collect();
+
+            for (key, value) in snapshot {
+                let still_present = shard
+                    .read()
+                    .get(key.borrow())
+                    .is_some_and(|current| std::sync::Arc::ptr_eq(current, &value));
+                if !still_present {
+                    continue;
+                }
+
+                #[cfg(feature = "thread-safe-tables")]
+                let entry = Some(value.read());
+                #[cfg(not(feature = "thread-safe-tables"))]
+                let entry = value.try_read();
+
+                if let Some(entry) = entry {
+                    if !func(&key, &entry) {
+                        return false;
+                    }
+                }
+            }
+        }
+        true
+    }
+}
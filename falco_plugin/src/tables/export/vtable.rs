@@ -1,5 +1,7 @@
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::entry::traits::Entry;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::TableValue;
 use crate::tables::export::wrappers::{fields_vtable, reader_vtable, writer_vtable};
 use crate::tables::export::Table;
 use crate::tables::Key;
@@ -9,6 +11,7 @@ use falco_plugin_api::{
     ss_plugin_table_writer_vtable, ss_plugin_table_writer_vtable_ext,
 };
 use std::borrow::Borrow;
+use std::hash::Hash;
 
 pub(crate) struct Vtable {
     pub(crate) input: ss_plugin_table_input,
@@ -17,17 +20,18 @@ pub(crate) struct Vtable {
     fields_ext: ss_plugin_table_fields_vtable_ext,
 }
 
-impl<K, E> Table<K, E>
+impl<K, E, M> Table<K, E, M>
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     #[allow(clippy::borrowed_box)]
     pub(crate) fn get_boxed_vtable(self: &Box<Self>) -> *mut ss_plugin_table_input {
-        let table_ptr = self.as_ref() as *const Table<K, E> as *mut Table<K, E>;
+        let table_ptr = self.as_ref() as *const Table<K, E, M> as *mut Table<K, E, M>;
         let mut vtable_place = self.vtable.write();
 
         if let Some(ref mut vtable) = *vtable_place {
@@ -36,9 +40,9 @@ where
             return &mut vtable.input as *mut _;
         }
 
-        let reader_vtable_ext = reader_vtable::<K, E>();
-        let writer_vtable_ext = writer_vtable::<K, E>();
-        let fields_vtable_ext = fields_vtable::<K, E>();
+        let reader_vtable_ext = reader_vtable::<K, E, M>();
+        let writer_vtable_ext = writer_vtable::<K, E, M>();
+        let fields_vtable_ext = fields_vtable::<K, E, M>();
 
         let table_input = ss_plugin_table_input {
             name: self.name().as_ptr(),
@@ -2,6 +2,8 @@ use crate::error::ffi_result::FfiResult;
 use crate::tables::export::entry::table_metadata::traits::TableMetadata;
 use crate::tables::export::entry::traits::Entry;
 use crate::tables::export::field_descriptor::FieldDescriptor;
+use crate::tables::export::map::TableMap;
+use crate::tables::export::table::TableValue;
 use crate::tables::export::table::{Table, TableEntryType};
 use crate::tables::{FieldTypeId, Key};
 use falco_plugin_api::{
@@ -14,18 +16,20 @@ use falco_plugin_api::{
 use num_traits::FromPrimitive;
 use std::borrow::Borrow;
 use std::ffi::{c_char, CStr};
+use std::hash::Hash;
 
 // SAFETY: `table` must be a valid pointer to Table<K,E>
-unsafe extern "C-unwind" fn get_table_name<K, E>(table: *mut ss_plugin_table_t) -> *const c_char
+unsafe extern "C-unwind" fn get_table_name<K, E, M>(table: *mut ss_plugin_table_t) -> *const c_char
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return std::ptr::null_mut();
         };
         table.name().as_ptr()
@@ -33,16 +37,17 @@ where
 }
 
 // SAFETY: `table` must be a valid pointer to Table<K,E>
-unsafe extern "C-unwind" fn get_table_size<K, E>(table: *mut ss_plugin_table_t) -> u64
+unsafe extern "C-unwind" fn get_table_size<K, E, M>(table: *mut ss_plugin_table_t) -> u64
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return 0;
         };
         table.size() as u64
@@ -51,19 +56,20 @@ where
 
 // SAFETY: `table` must be a valid pointer to Table<K,E>
 // SAFETY: `key` must be a valid pointer to ss_plugin_state_data
-unsafe extern "C-unwind" fn get_table_entry<K, E>(
+unsafe extern "C-unwind" fn get_table_entry<K, E, M>(
     table: *mut ss_plugin_table_t,
     key: *const ss_plugin_state_data,
 ) -> *mut ss_plugin_table_entry_t
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return std::ptr::null_mut();
         };
         let Some(key) = key.as_ref() else {
@@ -79,7 +85,7 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn read_entry_field<K, E>(
+unsafe extern "C-unwind" fn read_entry_field<K, E, M>(
     table: *mut ss_plugin_table_t,
     entry: *mut ss_plugin_table_entry_t,
     field: *const ss_plugin_table_field_t,
@@ -88,12 +94,13 @@ unsafe extern "C-unwind" fn read_entry_field<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
         let Some(entry) = (entry as *mut TableEntryType<E>).as_mut() else {
@@ -126,7 +133,7 @@ unsafe extern "C-unwind" fn release_table_entry<E>(
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn iterate_entries<K, E>(
+unsafe extern "C-unwind" fn iterate_entries<K, E, M>(
     table: *mut ss_plugin_table_t,
     func: ss_plugin_table_iterator_func_t,
     state: *mut ss_plugin_table_iterator_state_t,
@@ -134,38 +141,39 @@ unsafe extern "C-unwind" fn iterate_entries<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     let Some(func) = func else {
         return 0;
     };
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return 0;
         };
 
-        table.iterate_entries(|e| {
+        let finished = table.iterate_entries(|e| {
             let entry = e as *mut _ as *mut ss_plugin_table_entry_t;
             func(state, entry) != 0
         });
+        finished as ss_plugin_bool
     }
-
-    1
 }
 
 // SAFETY: `table` must be a valid pointer to Table<K,E>
-unsafe extern "C-unwind" fn clear_table<K, E>(table: *mut ss_plugin_table_t) -> ss_plugin_rc
+unsafe extern "C-unwind" fn clear_table<K, E, M>(table: *mut ss_plugin_table_t) -> ss_plugin_rc
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
         table.clear();
@@ -174,26 +182,30 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn erase_table_entry<K, E>(
+unsafe extern "C-unwind" fn erase_table_entry<K, E, M>(
     table: *mut ss_plugin_table_t,
     key: *const ss_plugin_state_data,
 ) -> ss_plugin_rc
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
         let Some(key) = key.as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
         let key = K::from_data(key);
-        match table.erase(key) {
+        // don't lock the removed entry like `Table::erase` does: the caller may still hold
+        // a reference to it (obtained via `get_table_entry`), which would deadlock
+        let removed = table.remove(key);
+        match removed {
             None => ss_plugin_rc_SS_PLUGIN_FAILURE,
             Some(_) => ss_plugin_rc_SS_PLUGIN_SUCCESS,
         }
@@ -201,18 +213,19 @@ where
 }
 
 // SAFETY: `table` must be a valid pointer to Table<K,E>
-unsafe extern "C-unwind" fn create_table_entry<K, E>(
+unsafe extern "C-unwind" fn create_table_entry<K, E, M>(
     table: *mut ss_plugin_table_t,
 ) -> *mut ss_plugin_table_entry_t
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return std::ptr::null_mut();
         };
 
@@ -224,7 +237,7 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn add_table_entry<K, E>(
+unsafe extern "C-unwind" fn add_table_entry<K, E, M>(
     table: *mut ss_plugin_table_t,
     key: *const ss_plugin_state_data,
     entry: *mut ss_plugin_table_entry_t,
@@ -232,16 +245,17 @@ unsafe extern "C-unwind" fn add_table_entry<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     if entry.is_null() {
         return std::ptr::null_mut();
     }
 
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return std::ptr::null_mut();
         };
         let Some(key) = key.as_ref() else {
@@ -258,7 +272,7 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn write_entry_field<K, E>(
+unsafe extern "C-unwind" fn write_entry_field<K, E, M>(
     table: *mut ss_plugin_table_t,
     entry: *mut ss_plugin_table_entry_t,
     field: *const ss_plugin_table_field_t,
@@ -267,12 +281,13 @@ unsafe extern "C-unwind" fn write_entry_field<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return ss_plugin_rc_SS_PLUGIN_FAILURE;
         };
         let Some(entry) = (entry as *mut TableEntryType<E>).as_mut() else {
@@ -289,19 +304,20 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn list_table_fields<K, E>(
+unsafe extern "C-unwind" fn list_table_fields<K, E, M>(
     table: *mut ss_plugin_table_t,
     nfields: *mut u32,
 ) -> *const ss_plugin_table_fieldinfo
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_mut() else {
             return std::ptr::null_mut();
         };
         let fields = table.list_fields();
@@ -311,7 +327,7 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn get_table_field<K, E>(
+unsafe extern "C-unwind" fn get_table_field<K, E, M>(
     table: *mut ss_plugin_table_t,
     name: *const c_char,
     data_type: ss_plugin_state_type,
@@ -319,12 +335,13 @@ unsafe extern "C-unwind" fn get_table_field<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_ref() else {
             return std::ptr::null_mut();
         };
         let Some(data_type) = FieldTypeId::from_usize(data_type as usize) else {
@@ -343,7 +360,7 @@ where
 }
 
 // SAFETY: all pointers must be valid
-unsafe extern "C-unwind" fn add_table_field<K, E>(
+unsafe extern "C-unwind" fn add_table_field<K, E, M>(
     table: *mut ss_plugin_table_t,
     name: *const c_char,
     data_type: ss_plugin_state_type,
@@ -351,12 +368,13 @@ unsafe extern "C-unwind" fn add_table_field<K, E>(
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     unsafe {
-        let Some(table) = (table as *mut Table<K, E>).as_mut() else {
+        let Some(table) = (table as *mut Table<K, E, M>).as_mut() else {
             return std::ptr::null_mut();
         };
         let Some(data_type) = FieldTypeId::from_usize(data_type as usize) else {
@@ -374,53 +392,56 @@ where
     }
 }
 
-pub(crate) fn reader_vtable<K, E>() -> ss_plugin_table_reader_vtable_ext
+pub(crate) fn reader_vtable<K, E, M>() -> ss_plugin_table_reader_vtable_ext
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     ss_plugin_table_reader_vtable_ext {
-        get_table_name: Some(get_table_name::<K, E>),
-        get_table_size: Some(get_table_size::<K, E>),
-        get_table_entry: Some(get_table_entry::<K, E>),
-        read_entry_field: Some(read_entry_field::<K, E>),
+        get_table_name: Some(get_table_name::<K, E, M>),
+        get_table_size: Some(get_table_size::<K, E, M>),
+        get_table_entry: Some(get_table_entry::<K, E, M>),
+        read_entry_field: Some(read_entry_field::<K, E, M>),
         release_table_entry: Some(release_table_entry::<E>),
-        iterate_entries: Some(iterate_entries::<K, E>),
+        iterate_entries: Some(iterate_entries::<K, E, M>),
     }
 }
 
-pub(crate) fn writer_vtable<K, E>() -> ss_plugin_table_writer_vtable_ext
+pub(crate) fn writer_vtable<K, E, M>() -> ss_plugin_table_writer_vtable_ext
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     ss_plugin_table_writer_vtable_ext {
-        clear_table: Some(clear_table::<K, E>),
-        erase_table_entry: Some(erase_table_entry::<K, E>),
-        create_table_entry: Some(create_table_entry::<K, E>),
+        clear_table: Some(clear_table::<K, E, M>),
+        erase_table_entry: Some(erase_table_entry::<K, E, M>),
+        create_table_entry: Some(create_table_entry::<K, E, M>),
         destroy_table_entry: Some(release_table_entry::<E>), // same as release_table_entry
-        add_table_entry: Some(add_table_entry::<K, E>),
-        write_entry_field: Some(write_entry_field::<K, E>),
+        add_table_entry: Some(add_table_entry::<K, E, M>),
+        write_entry_field: Some(write_entry_field::<K, E, M>),
     }
 }
 
-pub(crate) fn fields_vtable<K, E>() -> ss_plugin_table_fields_vtable_ext
+pub(crate) fn fields_vtable<K, E, M>() -> ss_plugin_table_fields_vtable_ext
 where
     K: Key + Ord,
     K: Borrow<<K as Key>::Borrowed>,
-    <K as Key>::Borrowed: Ord + ToOwned<Owned = K>,
+    <K as Key>::Borrowed: Ord + Hash + ToOwned<Owned = K>,
     E: Entry,
     E::Metadata: TableMetadata,
+    M: TableMap<K, TableValue<E>>,
 {
     ss_plugin_table_fields_vtable_ext {
-        list_table_fields: Some(list_table_fields::<K, E>),
-        get_table_field: Some(get_table_field::<K, E>),
-        add_table_field: Some(add_table_field::<K, E>),
+        list_table_fields: Some(list_table_fields::<K, E, M>),
+        get_table_field: Some(get_table_field::<K, E, M>),
+        add_table_field: Some(add_table_field::<K, E, M>),
     }
 }
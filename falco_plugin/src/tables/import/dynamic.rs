@@ -0,0 +1,450 @@
+use crate::error::as_result::{AsResult, WithLastError};
+use crate::tables::data::FieldTypeId;
+use crate::tables::import::entry::raw::RawEntry;
+use crate::tables::import::field::TableFieldInfo;
+use crate::tables::import::table::raw::{IterationResult, RawTable};
+use crate::tables::TableReader;
+use crate::tables::TableWriter;
+use crate::tables::TablesInput;
+use falco_plugin_api::{
+    ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED, ss_plugin_state_data, ss_plugin_state_type,
+    ss_plugin_table_field_t, ss_plugin_table_t,
+};
+use std::ffi::{CStr, CString};
+use std::fmt::{Display, Formatter};
+use std::ops::ControlFlow;
+
+/// # A table key or field value with a type only known at runtime
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DynamicValue {
+    /// 8-bit signed int
+    I8(i8),
+    /// 16-bit signed int
+    I16(i16),
+    /// 32-bit signed int
+    I32(i32),
+    /// 64-bit signed int
+    I64(i64),
+    /// 8-bit unsigned int
+    U8(u8),
+    /// 16-bit unsigned int
+    U16(u16),
+    /// 32-bit unsigned int
+    U32(u32),
+    /// 64-bit unsigned int
+    U64(u64),
+    /// A string
+    String(CString),
+    /// A boolean value
+    Bool(bool),
+}
+
+impl DynamicValue {
+    /// Get the type of the value
+    pub fn type_id(&self) -> FieldTypeId {
+        match self {
+            DynamicValue::I8(_) => FieldTypeId::I8,
+            DynamicValue::I16(_) => FieldTypeId::I16,
+            DynamicValue::I32(_) => FieldTypeId::I32,
+            DynamicValue::I64(_) => FieldTypeId::I64,
+            DynamicValue::U8(_) => FieldTypeId::U8,
+            DynamicValue::U16(_) => FieldTypeId::U16,
+            DynamicValue::U32(_) => FieldTypeId::U32,
+            DynamicValue::U64(_) => FieldTypeId::U64,
+            DynamicValue::String(_) => FieldTypeId::String,
+            DynamicValue::Bool(_) => FieldTypeId::Bool,
+        }
+    }
+
+    /// # Convert to the raw FFI representation
+    ///
+    /// The returned value borrows from `self` for string values
+    fn to_data(&self) -> ss_plugin_state_data {
+        match self {
+            DynamicValue::I8(v) => ss_plugin_state_data { s8: *v },
+            DynamicValue::I16(v) => ss_plugin_state_data { s16: *v },
+            DynamicValue::I32(v) => ss_plugin_state_data { s32: *v },
+            DynamicValue::I64(v) => ss_plugin_state_data { s64: *v },
+            DynamicValue::U8(v) => ss_plugin_state_data { u8_: *v },
+            DynamicValue::U16(v) => ss_plugin_state_data { u16_: *v },
+            DynamicValue::U32(v) => ss_plugin_state_data { u32_: *v },
+            DynamicValue::U64(v) => ss_plugin_state_data { u64_: *v },
+            DynamicValue::String(v) => ss_plugin_state_data { str_: v.as_ptr() },
+            DynamicValue::Bool(v) => ss_plugin_state_data { b: *v as _ },
+        }
+    }
+
+    /// # Convert from the raw FFI representation
+    ///
+    /// Returns `None` for types that cannot be represented as a [`DynamicValue`] (tables)
+    ///
+    /// # Safety
+    /// `data` must hold a valid value of type `type_id`
+    unsafe fn from_data(data: &ss_plugin_state_data, type_id: FieldTypeId) -> Option<Self> {
+        let value = unsafe {
+            match type_id {
+                FieldTypeId::I8 => DynamicValue::I8(data.s8),
+                FieldTypeId::I16 => DynamicValue::I16(data.s16),
+                FieldTypeId::I32 => DynamicValue::I32(data.s32),
+                FieldTypeId::I64 => DynamicValue::I64(data.s64),
+                FieldTypeId::U8 => DynamicValue::U8(data.u8_),
+                FieldTypeId::U16 => DynamicValue::U16(data.u16_),
+                FieldTypeId::U32 => DynamicValue::U32(data.u32_),
+                FieldTypeId::U64 => DynamicValue::U64(data.u64_),
+                FieldTypeId::String if data.str_.is_null() => {
+                    DynamicValue::String(CString::default())
+                }
+                FieldTypeId::String => DynamicValue::String(CStr::from_ptr(data.str_).to_owned()),
+                FieldTypeId::Bool => DynamicValue::Bool(data.b != 0),
+                FieldTypeId::Table => return None,
+            }
+        };
+        Some(value)
+    }
+}
+
+impl Display for DynamicValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DynamicValue::I8(v) => v.fmt(f),
+            DynamicValue::I16(v) => v.fmt(f),
+            DynamicValue::I32(v) => v.fmt(f),
+            DynamicValue::I64(v) => v.fmt(f),
+            DynamicValue::U8(v) => v.fmt(f),
+            DynamicValue::U16(v) => v.fmt(f),
+            DynamicValue::U32(v) => v.fmt(f),
+            DynamicValue::U64(v) => v.fmt(f),
+            DynamicValue::String(v) => v.to_string_lossy().fmt(f),
+            DynamicValue::Bool(v) => v.fmt(f),
+        }
+    }
+}
+
+macro_rules! impl_dynamic_value_from {
+    ($ty:ty => $variant:ident) => {
+        impl From<$ty> for DynamicValue {
+            fn from(value: $ty) -> Self {
+                DynamicValue::$variant(value)
+            }
+        }
+    };
+}
+
+impl_dynamic_value_from!(i8 => I8);
+impl_dynamic_value_from!(i16 => I16);
+impl_dynamic_value_from!(i32 => I32);
+impl_dynamic_value_from!(i64 => I64);
+impl_dynamic_value_from!(u8 => U8);
+impl_dynamic_value_from!(u16 => U16);
+impl_dynamic_value_from!(u32 => U32);
+impl_dynamic_value_from!(u64 => U64);
+impl_dynamic_value_from!(CString => String);
+impl_dynamic_value_from!(bool => Bool);
+
+impl From<&CStr> for DynamicValue {
+    fn from(value: &CStr) -> Self {
+        DynamicValue::String(value.to_owned())
+    }
+}
+
+/// # A field of a [`DynamicTable`]
+#[derive(Debug)]
+pub struct DynamicField {
+    info: TableFieldInfo,
+    table: *mut ss_plugin_table_t,
+    field: *mut ss_plugin_table_field_t,
+}
+
+impl DynamicField {
+    /// Get the field name
+    pub fn name(&self) -> &CStr {
+        &self.info.name
+    }
+
+    /// Get the field type
+    pub fn type_id(&self) -> FieldTypeId {
+        self.info.field_type
+    }
+
+    /// Check whether the field is read-only
+    pub fn is_read_only(&self) -> bool {
+        self.info.read_only
+    }
+
+    /// Get the full field description
+    pub fn info(&self) -> &TableFieldInfo {
+        &self.info
+    }
+}
+
+/// # An entry of a [`DynamicTable`]
+#[derive(Debug)]
+pub struct DynamicEntry {
+    raw_entry: RawEntry,
+}
+
+impl DynamicEntry {
+    fn check_field(&self, field: &DynamicField) -> Result<(), anyhow::Error> {
+        if field.table != self.raw_entry.table {
+            anyhow::bail!(
+                "Field {:?} does not belong to the table of this entry",
+                field.info.name
+            );
+        }
+        Ok(())
+    }
+
+    /// # Get a field value for this entry
+    ///
+    /// Returns an error for table-valued fields, which cannot be accessed dynamically
+    pub fn read_field(
+        &self,
+        reader: &impl TableReader,
+        field: &DynamicField,
+    ) -> Result<DynamicValue, anyhow::Error> {
+        self.check_field(field)?;
+
+        let mut data = ss_plugin_state_data { u64_: 0 };
+        unsafe {
+            reader
+                .read_entry_field(
+                    self.raw_entry.table,
+                    self.raw_entry.entry,
+                    field.field,
+                    &mut data as *mut _,
+                )
+                .unwrap_or(ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED)
+                .as_result()
+                .with_last_error(reader.last_error())?;
+
+            DynamicValue::from_data(&data, field.info.field_type).ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Field {:?} has unsupported type {:?}",
+                    field.info.name,
+                    field.info.field_type
+                )
+            })
+        }
+    }
+
+    /// # Set a field value for this entry
+    ///
+    /// The value must be of the same type as the field
+    pub fn write_field(
+        &self,
+        writer: &impl TableWriter,
+        field: &DynamicField,
+        value: &DynamicValue,
+    ) -> Result<(), anyhow::Error> {
+        self.check_field(field)?;
+        if field.info.read_only {
+            anyhow::bail!("Field {:?} is read-only", field.info.name);
+        }
+        if value.type_id() != field.info.field_type {
+            anyhow::bail!(
+                "Bad value type for field {:?}, got {:?}, field has {:?}",
+                field.info.name,
+                value.type_id(),
+                field.info.field_type
+            );
+        }
+
+        unsafe {
+            self.raw_entry
+                .write_field(writer, field.field, &value.to_data())
+                .as_result()
+                .with_last_error(writer.last_error())
+        }
+    }
+}
+
+/// # A table with fields discovered at runtime
+///
+/// Importing a [`Table`](`crate::tables::import::Table`) requires knowing the key type and
+/// the fields you want to access at compile time. This type instead lists the fields (and their
+/// types) when the table is imported and represents all keys and values as [`DynamicValue`]s,
+/// so it can be used to write generic tools (like table dumpers) that work with any table.
+///
+/// Get one using [`TablesInput::get_dynamic_table`]:
+///
+/// ```ignore
+/// let table = input.get_dynamic_table(c"threads")?;
+/// for field in table.fields() {
+///     println!("{:?}: {:?}", field.name(), field.type_id());
+/// }
+///
+/// // later, e.g. in `parse_event`
+/// let reader = &parse_input.reader;
+/// table.iter_entries_mut(reader, |entry| {
+///     for field in table.fields() {
+///         if let Ok(value) = entry.read_field(reader, field) {
+///             println!("{:?} = {}", field.name(), value);
+///         }
+///     }
+///     ControlFlow::Continue(())
+/// })?;
+/// ```
+///
+/// All keys and values are type-checked at runtime. Table-valued (nested table) fields
+/// are listed, but their values cannot be accessed.
+#[derive(Debug)]
+pub struct DynamicTable {
+    name: CString,
+    key_type: FieldTypeId,
+    raw_table: RawTable,
+    fields: Vec<DynamicField>,
+}
+
+impl DynamicTable {
+    pub(crate) fn new(
+        name: &CStr,
+        key_type: FieldTypeId,
+        raw_table: RawTable,
+        tables_input: &TablesInput,
+    ) -> Result<Self, anyhow::Error> {
+        let mut fields = Vec::new();
+        for info in raw_table.list_field_info(&tables_input.fields_ext) {
+            let field = tables_input.fields_ext.get_table_field(
+                raw_table.table,
+                info.name.as_ptr(),
+                info.field_type as ss_plugin_state_type,
+            )?;
+            if field.is_null() {
+                return Err(anyhow::anyhow!("Failed to get table field {:?}", info.name))
+                    .with_last_error(&tables_input.last_error);
+            }
+
+            fields.push(DynamicField {
+                info,
+                table: raw_table.table,
+                field,
+            });
+        }
+
+        Ok(Self {
+            name: name.to_owned(),
+            key_type,
+            raw_table,
+            fields,
+        })
+    }
+
+    /// Get the table name
+    pub fn name(&self) -> &CStr {
+        &self.name
+    }
+
+    /// Get the key type of the table
+    pub fn key_type(&self) -> FieldTypeId {
+        self.key_type
+    }
+
+    /// Get all the fields of the table, as they were when the table was imported
+    pub fn fields(&self) -> &[DynamicField] {
+        &self.fields
+    }
+
+    /// Get a table field by name
+    pub fn field(&self, name: &CStr) -> Option<&DynamicField> {
+        self.fields.iter().find(|f| f.name() == name)
+    }
+
+    fn check_key(&self, key: &DynamicValue) -> Result<(), anyhow::Error> {
+        if key.type_id() != self.key_type {
+            anyhow::bail!(
+                "Bad key type for table {:?}, got {:?}, table has {:?}",
+                self.name,
+                key.type_id(),
+                self.key_type
+            );
+        }
+        Ok(())
+    }
+
+    /// # Get the table size
+    ///
+    /// Return the number of entries in the table
+    pub fn get_size(&self, reader_vtable: &impl TableReader) -> anyhow::Result<usize> {
+        self.raw_table.get_size(reader_vtable)
+    }
+
+    /// Look up an entry corresponding to `key`
+    pub fn get_entry(
+        &self,
+        reader_vtable: &impl TableReader,
+        key: &DynamicValue,
+    ) -> Result<DynamicEntry, anyhow::Error> {
+        self.check_key(key)?;
+        let raw_entry = unsafe {
+            self.raw_table
+                .get_entry_by_data(reader_vtable, &key.to_data())
+        }?;
+        Ok(DynamicEntry { raw_entry })
+    }
+
+    /// Create a new table entry (not yet attached to a key)
+    pub fn create_entry(
+        &self,
+        writer_vtable: &impl TableWriter,
+    ) -> Result<DynamicEntry, anyhow::Error> {
+        let raw_entry = self.raw_table.create_entry(writer_vtable)?;
+        Ok(DynamicEntry { raw_entry })
+    }
+
+    /// Attach an entry to a table key (insert an entry to the table)
+    pub fn insert(
+        &self,
+        reader_vtable: &impl TableReader,
+        writer_vtable: &impl TableWriter,
+        key: &DynamicValue,
+        entry: DynamicEntry,
+    ) -> Result<DynamicEntry, anyhow::Error> {
+        self.check_key(key)?;
+        let raw_entry = unsafe {
+            self.raw_table.insert_by_data(
+                reader_vtable,
+                writer_vtable,
+                &key.to_data(),
+                entry.raw_entry,
+            )
+        }?;
+        Ok(DynamicEntry { raw_entry })
+    }
+
+    /// Erase a table entry by key
+    pub fn erase(
+        &self,
+        writer_vtable: &impl TableWriter,
+        key: &DynamicValue,
+    ) -> Result<(), anyhow::Error> {
+        self.check_key(key)?;
+        unsafe { self.raw_table.erase_by_data(writer_vtable, &key.to_data()) }
+    }
+
+    /// Remove all entries from the table
+    pub fn clear(&self, writer_vtable: &impl TableWriter) -> Result<(), anyhow::Error> {
+        self.raw_table.clear(writer_vtable)
+    }
+
+    /// # Iterate over all entries in a table with mutable access
+    ///
+    /// The closure is called once for each table entry with a corresponding entry
+    /// object as a parameter.
+    ///
+    /// The iteration stops when either all entries have been processed or the closure returns
+    /// [`ControlFlow::Break`].
+    pub fn iter_entries_mut<F>(
+        &self,
+        reader_vtable: &impl TableReader,
+        mut func: F,
+    ) -> anyhow::Result<IterationResult>
+    where
+        F: FnMut(&mut DynamicEntry) -> ControlFlow<()>,
+    {
+        self.raw_table
+            .iter_entries_mut(reader_vtable, move |raw_entry| {
+                func(&mut DynamicEntry { raw_entry })
+            })
+    }
+}
@@ -1,10 +1,10 @@
 use crate::error::as_result::{AsResult, WithLastError};
 use crate::tables::data::Value;
 use crate::tables::import::field::Field;
-use crate::tables::import::traits::{EntryWrite, TableMetadata};
+use crate::tables::import::traits::{EntryWrite, ReadFields, TableMetadata};
 use crate::tables::TableReader;
 use crate::tables::TableWriter;
-use falco_plugin_api::ss_plugin_table_t;
+use falco_plugin_api::{ss_plugin_state_data, ss_plugin_table_t};
 
 pub(crate) mod raw;
 use raw::RawEntry;
@@ -52,13 +52,36 @@ impl<M> Entry<M> {
     ) -> Result<V::Value<'_>, anyhow::Error> {
         field.validator.check(self.table)?;
         unsafe {
-            self.raw_entry
-                .read_field_with_assoc::<V>(reader, field.field.field, &field.field.assoc_data)
+            let data = self
+                .raw_entry
+                .read_field_raw(reader, field.field.field)
                 .ok_or_else(|| anyhow::anyhow!("Could not read field value"))
-                .with_last_error(reader.last_error())
+                .with_last_error(reader.last_error())?;
+            V::validate_data(&data)?;
+            Ok(V::from_data_with_assoc(&data, &field.field.assoc_data))
         }
     }
 
+    /// Get several field values for this entry at once
+    ///
+    /// Pass a tuple of field references and get a tuple of values back, e.g.
+    ///
+    /// ```ignore
+    /// let (pid, comm) = entry.read_fields(reader, (&metadata.pid, &metadata.comm))?;
+    /// ```
+    ///
+    /// All fields are validated up front and then read in a single tight loop, so this is
+    /// cheaper than calling [`Entry::read_field`] for each field on hot paths that need
+    /// many fields of the same entry. Note that the plugin API has no bulk read call, so there
+    /// is still one call across the API boundary per field.
+    pub fn read_fields<'a, F: ReadFields<'a, Self>>(
+        &'a self,
+        reader: &impl TableReader,
+        fields: F,
+    ) -> Result<F::Values, anyhow::Error> {
+        fields.read_fields(self, reader)
+    }
+
     /// Set a field value for this entry
     pub fn write_field<V: Value<AssocData = ()> + ?Sized>(
         &self,
@@ -76,6 +99,63 @@ impl<M> Entry<M> {
     }
 }
 
+macro_rules! impl_read_fields {
+    ($($v:ident $field:ident $data:ident),+) => {
+        impl<'a, M, $($v: Value + ?Sized + 'a),+> ReadFields<'a, Entry<M>>
+            for ($(&Field<$v, Entry<M>>,)+)
+        {
+            type Values = ($($v::Value<'a>,)+);
+
+            fn read_fields(
+                self,
+                entry: &'a Entry<M>,
+                reader: &impl TableReader,
+            ) -> Result<Self::Values, anyhow::Error> {
+                let ($($field,)+) = self;
+                $($field.validator.check(entry.table)?;)+
+
+                let fields = [$($field.field.field as *const _),+];
+                let mut data = fields.map(|_| ss_plugin_state_data { u64_: 0 });
+                unsafe {
+                    entry
+                        .raw_entry
+                        .read_fields_raw(reader, &fields, &mut data)
+                        .map_err(|i| anyhow::anyhow!("Could not read value of field #{i}"))
+                        .with_last_error(reader.last_error())?;
+                }
+
+                let [$($data),+] = data;
+                $(unsafe { $v::validate_data(&$data)?; })+
+                Ok(($(unsafe { $v::from_data_with_assoc(&$data, &$field.field.assoc_data) },)+))
+            }
+        }
+    };
+}
+
+impl_read_fields!(V1 f1 d1);
+impl_read_fields!(V1 f1 d1, V2 f2 d2);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7);
+impl_read_fields!(V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7, V8 f8 d8);
+impl_read_fields!(
+    V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7, V8 f8 d8, V9 f9 d9
+);
+impl_read_fields!(
+    V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7, V8 f8 d8, V9 f9 d9,
+    V10 f10 d10
+);
+impl_read_fields!(
+    V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7, V8 f8 d8, V9 f9 d9,
+    V10 f10 d10, V11 f11 d11
+);
+impl_read_fields!(
+    V1 f1 d1, V2 f2 d2, V3 f3 d3, V4 f4 d4, V5 f5 d5, V6 f6 d6, V7 f7 d7, V8 f8 d8, V9 f9 d9,
+    V10 f10 d10, V11 f11 d11, V12 f12 d12
+);
+
 impl<M, V: Value<AssocData = ()> + ?Sized> EntryWrite<&Field<V, Entry<M>>, V> for Entry<M> {
     fn write_field(
         &self,
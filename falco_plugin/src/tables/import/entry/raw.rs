@@ -1,10 +1,10 @@
-use crate::tables::data::Value;
 use crate::tables::TableReader;
 use crate::tables::TableWriter;
 use falco_plugin_api::{
     ss_plugin_rc, ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED, ss_plugin_rc_SS_PLUGIN_SUCCESS,
     ss_plugin_state_data, ss_plugin_table_entry_t, ss_plugin_table_field_t, ss_plugin_table_t,
 };
+use std::rc::Rc;
 
 #[derive(Debug)]
 pub struct RawEntry {
@@ -13,15 +13,17 @@ pub struct RawEntry {
     pub(crate) destructor: Option<
         unsafe extern "C-unwind" fn(t: *mut ss_plugin_table_t, e: *mut ss_plugin_table_entry_t),
     >,
+    /// the entry actually owning the handle, for handles shared via the entry cache
+    #[allow(dead_code)] // only held to delay dropping the owner
+    pub(crate) keepalive: Option<Rc<RawEntry>>,
 }
 
 impl RawEntry {
-    pub unsafe fn read_field_with_assoc<'a, T: Value + ?Sized>(
+    pub unsafe fn read_field_raw(
         &self,
         reader: &impl TableReader,
         field: *const ss_plugin_table_field_t,
-        assoc: &T::AssocData,
-    ) -> Option<T::Value<'a>> {
+    ) -> Option<ss_plugin_state_data> {
         let mut data = ss_plugin_state_data { u64_: 0 };
         if unsafe { reader.read_entry_field(self.table, self.entry, field, &mut data as *mut _) }
             .unwrap_or(ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED)
@@ -29,10 +31,31 @@ impl RawEntry {
         {
             None
         } else {
-            Some(unsafe { T::from_data_with_assoc(&data, assoc) })
+            Some(data)
         }
     }
 
+    /// Read several fields in a row, stopping at the first failure
+    ///
+    /// `out` must be at least as long as `fields`. Returns the index of the failed field
+    /// as the error.
+    pub unsafe fn read_fields_raw(
+        &self,
+        reader: &impl TableReader,
+        fields: &[*const ss_plugin_table_field_t],
+        out: &mut [ss_plugin_state_data],
+    ) -> Result<(), usize> {
+        for (i, (field, data)) in fields.iter().zip(out.iter_mut()).enumerate() {
+            let rc = unsafe { reader.read_entry_field(self.table, self.entry, *field, data) }
+                .unwrap_or(ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED);
+            if rc != ss_plugin_rc_SS_PLUGIN_SUCCESS {
+                return Err(i);
+            }
+        }
+
+        Ok(())
+    }
+
     pub unsafe fn write_field(
         &self,
         writer: &impl TableWriter,
@@ -1,13 +1,27 @@
-use crate::tables::data::Value;
+use crate::tables::data::{FieldTypeId, Value};
 use crate::tables::import::field::raw::RawField;
 use crate::tables::import::runtime::RuntimeEntry;
 use crate::tables::import::runtime_table_validator::RuntimeTableValidator;
 use crate::tables::import::traits::RawFieldValueType;
+use std::ffi::CString;
 use std::fmt::{Debug, Formatter};
 use std::marker::PhantomData;
 
 pub(crate) mod raw;
 
+/// # Description of a table field
+///
+/// Returned from [`crate::tables::import::Table::list_table_fields`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableFieldInfo {
+    /// The field name
+    pub name: CString,
+    /// The type of data stored in the field
+    pub field_type: FieldTypeId,
+    /// Whether the field can be written to
+    pub read_only: bool,
+}
+
 /// # Table field descriptor
 ///
 /// This struct wraps an opaque pointer from the Falco plugin API, representing a particular
@@ -54,6 +68,14 @@ impl<V: Value + ?Sized, T> RawFieldValueType for Field<V, T> {
         Self: 'a;
 }
 
+impl<V: Value + ?Sized, T> RawFieldValueType for Option<Field<V, T>> {
+    type TableValue = V;
+    type EntryValue<'a>
+        = <V as Value>::Value<'a>
+    where
+        Self: 'a;
+}
+
 impl<V: Value + ?Sized, E> From<RawField<V>> for Field<V, E> {
     fn from(raw_field: RawField<V>) -> Self {
         let validator = RuntimeTableValidator::new(std::ptr::null_mut());
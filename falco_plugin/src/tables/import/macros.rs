@@ -1,18 +1,48 @@
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_import_table_metadata {
-    (for $meta:ident => { $($access_fn:ident($field:ident, $field_cstr:literal);)* }) => {
+    (for $meta:ident => { $($access_fn:ident($field:ident $(, $field_cstr:literal)?);)* }) => {
         impl $crate::tables::import::traits::TableMetadata for $meta {
             fn new(
                 raw_table: &$crate::tables::import::RawTable,
                 tables_input: &$crate::tables::TablesInput)
             -> $crate::anyhow::Result<Self> {
                 Ok(Self {
-                    $($field: raw_table.$access_fn(tables_input, $field_cstr)?.into(),)*
+                    $($field: $crate::impl_import_table_metadata!(
+                        @init raw_table, tables_input, $access_fn $(, $field_cstr)?),)*
                 })
             }
         }
-    }
+    };
+    (for $meta:ident => key $key:ty; { $($access_fn:ident($field:ident $(, $field_cstr:literal)?);)* }) => {
+        impl $crate::tables::import::traits::TableMetadata for $meta {
+            fn new(
+                raw_table: &$crate::tables::import::RawTable,
+                tables_input: &$crate::tables::TablesInput)
+            -> $crate::anyhow::Result<Self> {
+                raw_table.check_key_type::<$key>(stringify!($meta))?;
+                Ok(Self {
+                    $($field: $crate::impl_import_table_metadata!(
+                        @init raw_table, tables_input, $access_fn $(, $field_cstr)?),)*
+                })
+            }
+        }
+
+        impl $crate::tables::import::traits::KeyedTableMetadata for $meta {
+            type Key = $key;
+        }
+    };
+    (@init $raw_table:ident, $tables_input:ident, skip) => {
+        ::std::default::Default::default()
+    };
+    (@init $raw_table:ident, $tables_input:ident, get_optional_field, $field_cstr:literal) => {
+        $raw_table
+            .get_optional_field($tables_input, $field_cstr)?
+            .map(::std::convert::Into::into)
+    };
+    (@init $raw_table:ident, $tables_input:ident, $access_fn:ident, $field_cstr:literal) => {
+        $raw_table.$access_fn($tables_input, $field_cstr)?.into()
+    };
 }
 
 #[doc(hidden)]
@@ -20,12 +50,17 @@ macro_rules! impl_import_table_metadata {
 macro_rules! impl_import_table_accessor_traits {
     ($m:ident: $getter:ident,$table_getter:ident,$setter:ident) => {
         #[allow(non_snake_case)]
+        #[doc = concat!("Accessors for the `", stringify!($m), "` field")]
         pub mod $m {
             #[allow(non_camel_case_types)]
+            #[doc = concat!("Read the `", stringify!($m), "` field")]
             pub trait $getter<'a> {
+                /// The type of the field, as stored in the table
                 type TableValue: $crate::tables::Value + ?Sized;
+                /// The type returned by the getter
                 type EntryValue: 'a;
 
+                #[doc = concat!("Get the value of the `", stringify!($m), "` field")]
                 fn $getter(
                     &'a self,
                     reader: &impl $crate::tables::TableReader,
@@ -33,10 +68,14 @@ macro_rules! impl_import_table_accessor_traits {
             }
 
             #[allow(non_camel_case_types)]
+            #[doc = concat!("Look up entries in the `", stringify!($m), "` nested table")]
             pub trait $table_getter<'a> {
+                /// The key type of the nested table
                 type Key;
+                /// The entry type of the nested table
                 type Entry;
 
+                #[doc = concat!("Get an entry from the `", stringify!($m), "` nested table")]
                 fn $table_getter(
                     &'a self,
                     reader: &impl $crate::tables::TableReader,
@@ -45,9 +84,12 @@ macro_rules! impl_import_table_accessor_traits {
             }
 
             #[allow(non_camel_case_types)]
+            #[doc = concat!("Write the `", stringify!($m), "` field")]
             pub trait $setter<'a> {
+                /// The type of the field
                 type ScalarValue: $crate::tables::Value<AssocData = ()> + ?Sized;
 
+                #[doc = concat!("Set the value of the `", stringify!($m), "` field")]
                 fn $setter(
                     &'a self,
                     writer: &impl $crate::tables::TableWriter,
@@ -74,6 +116,7 @@ macro_rules! impl_import_table_accessor_impls {
         const _: () = {
             use $crate::tables::import::traits::Entry;
             use $crate::tables::import::traits::EntryWrite;
+            use $crate::tables::import::traits::MaybeField;
             use $crate::tables::import::traits::RawFieldValueType;
             use $crate::tables::import::traits::TableAccess;
             use $crate::tables::Key;
@@ -89,7 +132,7 @@ macro_rules! impl_import_table_accessor_impls {
                     reader: &impl $crate::tables::TableReader,
                 ) -> $crate::anyhow::Result<Self::EntryValue> {
                     let metadata = self.get_metadata();
-                    self.read_field(reader, &metadata.$field)
+                    self.read_field(reader, metadata.$field.field()?)
                 }
             }
 
@@ -118,7 +161,7 @@ macro_rules! impl_import_table_accessor_impls {
                 E: 'a,
                 E: $getter<'a>,
                 E::TableValue: Value<AssocData = ()>,
-                E: EntryWrite<&'a $field_ty, E::TableValue>,
+                E: EntryWrite<&'a <$field_ty as MaybeField>::Field, E::TableValue>,
                 E: Entry<Metadata = std::sync::Arc<$meta_ty>>,
             {
                 type ScalarValue = E::TableValue;
@@ -129,7 +172,7 @@ macro_rules! impl_import_table_accessor_impls {
                     value: &Self::ScalarValue,
                 ) -> $crate::anyhow::Result<()> {
                     let metadata = self.get_metadata();
-                    self.write_field(writer, &metadata.$field, value)
+                    self.write_field(writer, metadata.$field.field()?, value)
                 }
             }
         };
@@ -147,6 +190,8 @@ mod tests {
     struct ImportedMeta {
         u64_field: Field<u64, ImportedEntry>,
         string_field: Field<CStr, ImportedEntry>,
+        #[allow(dead_code)]
+        local_field: u64,
     }
 
     type ImportedEntry = Entry<Arc<ImportedMeta>>;
@@ -154,6 +199,7 @@ mod tests {
     impl_import_table_metadata!(for ImportedMeta => {
         get_field(u64_field, c"u64_field");
         add_field(string_field, c"string_field");
+        skip(local_field);
     });
 
     mod private {
@@ -50,6 +50,32 @@
 //! using the plugin API. This means that each read/write is fairly expensive (involves
 //! method calls), so you should probably cache the values in local variables.
 //!
+//! ## Declaring the key type
+//!
+//! Instead of repeating the key type in each [`Table`] alias, you can declare it once, with
+//! a `#[key_type(...)]` attribute on the metadata struct, and use [`KeyedTable`], which takes
+//! the key type from there:
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use falco_plugin::tables::import::{Entry, Field, KeyedTable, TableMetadata};
+//! #
+//! type Thing = Entry<Arc<ThingMetadata>>;
+//! type ThingTable = KeyedTable<Thing>; // same as Table<u64, Thing>
+//!
+//! #[derive(TableMetadata)]
+//! #[entry_type(Thing)]
+//! #[key_type(u64)]
+//! struct ThingMetadata {
+//!     number: Field<u64, Thing>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! The declared key type is verified against the actual table when the metadata is created,
+//! so a mismatch results in an error naming both types (for both top-level and nested tables).
+//!
 //! ## Declaring fields
 //!
 //! You need to declare each field you're going to use in a particular table, by providing
@@ -67,6 +93,61 @@
 //! all use the same field (they will share the data). Adding a field multiple times
 //! with different types is not allowed and will cause an error at initialization time.
 //!
+//! Fields tagged with `#[skip]` are not looked up in the table at all. They are initialized
+//! with [`Default::default`] and get no generated accessors, so you can keep data that is not
+//! stored in the table (e.g. optional fields, looked up manually) in the metadata struct:
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+//! #
+//! type Thread = Entry<Arc<ThreadMetadata>>;
+//! type ThreadTable = Table<i64, Thread>;
+//!
+//! #[derive(TableMetadata)]
+//! #[entry_type(Thread)]
+//! struct ThreadMetadata {
+//!     #[name(c"exe_path")]
+//!     path: Field<std::ffi::CStr, Thread>,
+//!
+//!     #[skip]
+//!     loginuid: Option<Field<u32, Thread>>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! Fields that only exist in some versions of the table (e.g. ones added in a newer Falco
+//! release) can be declared as `Option<Field<...>>`. If the table does not have such a field,
+//! it is set to `None` instead of failing the whole import, and its generated getter and setter
+//! return an error. Check `entry.get_metadata().field_name.is_some()` (using
+//! [`traits::Entry::get_metadata`]) to choose a fallback
+//! up front:
+//!
+//! ```
+//! # use std::sync::Arc;
+//! # use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+//! #
+//! type Thread = Entry<Arc<ThreadMetadata>>;
+//! type ThreadTable = Table<i64, Thread>;
+//!
+//! #[derive(TableMetadata)]
+//! #[entry_type(Thread)]
+//! struct ThreadMetadata {
+//!     comm: Field<std::ffi::CStr, Thread>,
+//!     pidns_init_start_ts: Option<Field<u64, Thread>>,
+//! }
+//!
+//! # fn main() {}
+//! ```
+//!
+//! A field that exists but has a different type is still an error. Optional fields cannot be
+//! combined with `#[custom]`, since custom fields are always added to the table.
+//!
+//! Integer fields with a fixed set of values can be declared using a Rust enum implementing
+//! [`TableEnum`](`crate::tables::TableEnum`), e.g. `Field<L4Proto, Thing>`. Reading a value
+//! that does not map to any variant returns an error.
+//!
 //! ## Generated methods
 //!
 //! Each scalar field gets a getter and setter method, e.g. declaring a metadata struct like
@@ -93,9 +174,22 @@
 //!     -> Result<NestedThing, anyhow::Error>;
 //! ```
 //!
+//! To reach a value nested several tables deep, you can use the [`table_path!`] macro instead
+//! of chaining the lookups manually, e.g.
+//! `table_path!(reader, table => [key].nested[nested_key].number)` returns an `Option<u64>`.
+//!
+//! The returned nested table is a regular [`Table`], so besides reading it, you can also modify
+//! it using the writer vtable you use for the parent table: create and insert entries
+//! ([`Table::create_entry`], [`Table::insert`]), write their fields and remove them
+//! ([`Table::erase`], [`Table::clear`]). The changes are visible to the plugin owning the table.
+//!
 //! **Note**: setters do not take `&mut self` as all the mutation happens on the other side
 //! of the API (presumably in another plugin).
 //!
+//! To read several fields of the same entry, e.g. in a hot event parsing path, you can also
+//! use [`Entry::read_fields`] with a tuple of fields from the metadata struct instead of calling
+//! each getter separately.
+//!
 //! ### Visibility of generated methods
 //!
 //! The generated methods are actually trait implementations, not inherent impls (due to proc
@@ -320,14 +414,37 @@
 //!
 //! See the [`Table`] type for additional methods on tables, to e.g. iterate
 //! over entries or clear the whole table.
+//!
+//! # Optional tables
+//!
+//! [`TablesInput::get_table`](`crate::tables::TablesInput::get_table`) fails if the table
+//! does not exist. If your plugin can work without a table exported by another plugin, use
+//! [`TablesInput::try_get_table`](`crate::tables::TablesInput::try_get_table`) instead, which returns
+//! `Ok(None)` for missing tables, or check up front with
+//! [`TablesInput::table_exists`](`crate::tables::TablesInput::table_exists`).
+//!
+//! # Tables with fields unknown at compile time
+//!
+//! If you need to access tables without knowing their structure in advance (e.g. to dump
+//! the contents of an arbitrary table), use [`TablesInput::get_dynamic_table`](`crate::tables::TablesInput::get_dynamic_table`)
+//! to get a [`DynamicTable`], which discovers the fields at runtime.
+//!
+//! # libsinsp tables
+//!
+//! Bindings for the thread and file descriptor tables maintained by libsinsp (and the container
+//! table exported by the container plugin) are available in the [`sinsp`] module.
 
+mod dynamic;
 mod entry;
 mod field;
 mod macros;
 mod runtime;
 mod runtime_table_validator;
+pub mod sinsp;
 mod table;
 mod table_input;
+#[cfg(feature = "thread-safe-tables")]
+mod thread_safe;
 
 // for macro use only
 #[doc(hidden)]
@@ -335,10 +452,18 @@ pub mod traits;
 
 pub use crate::tables::data::Bool;
 pub use crate::tables::data::TableData;
+pub use dynamic::{DynamicEntry, DynamicField, DynamicTable, DynamicValue};
 pub use entry::Entry;
 pub use field::Field;
+pub use field::TableFieldInfo;
 pub use runtime::RuntimeEntry;
+pub use table::raw::IterationResult;
+pub(crate) use table::EntryCacheScope;
+pub use table::KeyedTable;
 pub use table::Table;
+pub use table_input::TableInfo;
+#[cfg(feature = "thread-safe-tables")]
+pub use thread_safe::{ImportedTables, ThreadSafeTables};
 
 // for macro use only
 #[doc(hidden)]
@@ -348,3 +473,21 @@ pub use table::raw::RawTable;
 ///
 /// See the [module documentation](`crate::tables::import`) for details.
 pub use falco_plugin_derive::TableMetadata;
+
+/// Look up a value in (nested) imported tables in a single expression
+///
+/// ```ignore
+/// let fd_type: Option<u8> =
+///     table_path!(reader, self.threads => [tid].file_descriptors[fd].fd_type);
+/// ```
+///
+/// The path starts with a table and the key to look up in it (in brackets), followed by
+/// any number of `.field[key]` steps, descending into nested tables, and optionally ends with
+/// a `.field` to read a field value. Without the final field, the innermost entry is returned.
+///
+/// The macro uses the methods generated by `#[derive(TableMetadata)]` (`get_field_by_key`
+/// and `get_field`), so their traits must be in scope. It evaluates to `None` if any lookup fails
+/// (e.g. an entry does not exist), and to `Some(value)` otherwise. Borrowed values (like strings)
+/// are converted to their owned counterparts (e.g. [`CString`](`std::ffi::CString`)), since
+/// the intermediate entries don't outlive the expression.
+pub use falco_plugin_derive::table_path;
@@ -0,0 +1,185 @@
+//! # Ready-made bindings for the libsinsp tables
+//!
+//! Most plugins running inside Falco want to look at the thread table maintained by libsinsp
+//! (and the file descriptor tables nested inside it). Instead of declaring the metadata
+//! structs for these tables in every plugin, you can use the types from this module:
+//!
+//! ```
+//! # use std::ffi::CStr;
+//! # use falco_plugin::anyhow::{self, Error};
+//! # use falco_plugin::base::Plugin;
+//! # use falco_plugin::tables::TablesInput;
+//! # use falco_plugin::{parse_plugin, plugin};
+//! use falco_event::events::RawEvent;
+//! use falco_plugin::parse::{EventInput, ParseInput, ParsePlugin};
+//! use falco_plugin::tables::import::sinsp::{ThreadTable, THREAD_TABLE};
+//! use falco_plugin::tables::import::sinsp::thread_accessors::*;
+//!
+//! struct MyPlugin {
+//!     threads: ThreadTable,
+//! }
+//!
+//! impl Plugin for MyPlugin {
+//!     // ...
+//! #     const NAME: &'static CStr = c"dummy";
+//! #     const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+//! #     const DESCRIPTION: &'static CStr = c"test plugin";
+//! #     const CONTACT: &'static CStr = c"rust@localdomain.pl";
+//! #     type ConfigType = ();
+//!
+//!     fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+//!         let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+//!         let threads = input.get_table(THREAD_TABLE)?;
+//!
+//!         Ok(Self { threads })
+//!     }
+//! }
+//!
+//! impl ParsePlugin for MyPlugin {
+//!     type Event<'a> = RawEvent<'a>;
+//!
+//!     fn parse_event(&mut self, event: &EventInput<RawEvent>, parse_input: &ParseInput)
+//!         -> anyhow::Result<()> {
+//!         let reader = &parse_input.reader;
+//!         let tid = event.event()?.metadata.tid;
+//!
+//!         let thread = self.threads.get_entry(reader, &tid)?;
+//!         let comm = thread.get_comm(reader)?;
+//!         let parent = thread.get_ptid(reader)?;
+//!
+//!         Ok(())
+//!     }
+//! }
+//! # plugin!(MyPlugin);
+//! # parse_plugin!(MyPlugin);
+//! # fn main() {}
+//! ```
+//!
+//! The accessor methods are generated as described in the [module documentation](`crate::tables::import`)
+//! and live in the [`thread_accessors`] and [`fd_accessors`] modules, so you need to `use` them
+//! (e.g. `use falco_plugin::tables::import::sinsp::thread_accessors::*;`) before calling them.
+//!
+//! The metadata structs only declare fields that libsinsp provides for every thread (or file
+//! descriptor), as importing a table fails if any declared field is missing. If you need
+//! other fields, or want to add your own, declare your own metadata struct instead
+//! (you can still use [`FdTable`] for the `file_descriptors` field).
+//!
+//! ## Container IDs
+//!
+//! Recent versions of libsinsp no longer track containers themselves. The `container_id`
+//! field is added to the thread table by the container plugin instead, so it's only available
+//! when that plugin is loaded (before yours). It's declared in a separate metadata struct,
+//! [`ThreadContainerMetadata`], so that you can import the thread table a second time,
+//! as a [`ThreadContainerTable`], only when you need it.
+//!
+//! The container plugin also exports a table with the metadata of each container, indexed
+//! by the container id. You can import it as a [`ContainerTable`] and look up containers
+//! by the id found in the thread table:
+//!
+//! ```ignore
+//! use falco_plugin::tables::import::sinsp::container_accessors::*;
+//! use falco_plugin::tables::import::sinsp::thread_container_accessors::*;
+//!
+//! // in Plugin::new
+//! let threads: ThreadContainerTable = input.get_table(THREAD_TABLE)?;
+//! let containers: ContainerTable = input.get_table(CONTAINER_TABLE)?;
+//!
+//! // in e.g. ExtractPlugin methods
+//! let container_id = threads.get_entry(reader, &tid)?.get_container_id(reader)?;
+//! let container = containers.get_entry(reader, &container_id.to_owned())?;
+//! let image = container.get_image(reader)?;
+//! ```
+
+use crate::tables::import::{Entry, Field, Table, TableMetadata};
+use std::ffi::{CStr, CString};
+use std::sync::Arc;
+
+/// The name of the libsinsp thread table
+pub const THREAD_TABLE: &CStr = c"threads";
+
+/// # A single entry in the libsinsp thread table
+pub type Thread = Entry<Arc<ThreadMetadata>>;
+
+/// # The libsinsp thread table, indexed by thread id
+pub type ThreadTable = Table<i64, Thread>;
+
+/// # Metadata for the libsinsp thread table
+///
+/// See [`thread_accessors`] for the generated methods.
+#[derive(Debug, TableMetadata)]
+#[entry_type(Thread)]
+#[key_type(i64)]
+#[accessors_mod(thread_accessors)]
+pub struct ThreadMetadata {
+    tid: Field<i64, Thread>,
+    pid: Field<i64, Thread>,
+    ptid: Field<i64, Thread>,
+    sid: Field<i64, Thread>,
+    vtid: Field<i64, Thread>,
+    vpid: Field<i64, Thread>,
+    comm: Field<CStr, Thread>,
+    exe: Field<CStr, Thread>,
+    exepath: Field<CStr, Thread>,
+    file_descriptors: Field<FdTable, Thread>,
+}
+
+/// # A single entry in a libsinsp file descriptor table
+pub type Fd = Entry<Arc<FdMetadata>>;
+
+/// # A libsinsp file descriptor table, indexed by the fd number
+///
+/// Each thread has one of these, available via the `file_descriptors` field.
+pub type FdTable = Table<i64, Fd>;
+
+/// # Metadata for the libsinsp file descriptor tables
+///
+/// See [`fd_accessors`] for the generated methods.
+#[derive(Debug, TableMetadata)]
+#[entry_type(Fd)]
+#[key_type(i64)]
+#[accessors_mod(fd_accessors)]
+pub struct FdMetadata {
+    fd: Field<i64, Fd>,
+    #[name(c"type")]
+    fd_type: Field<u8, Fd>,
+    name: Field<CStr, Fd>,
+}
+
+/// # A thread table entry, as seen by the container plugin
+pub type ThreadContainer = Entry<Arc<ThreadContainerMetadata>>;
+
+/// # The libsinsp thread table, with the fields added by the container plugin
+pub type ThreadContainerTable = Table<i64, ThreadContainer>;
+
+/// # Metadata for the fields the container plugin adds to the thread table
+///
+/// See [`thread_container_accessors`] for the generated methods.
+#[derive(Debug, TableMetadata)]
+#[entry_type(ThreadContainer)]
+#[key_type(i64)]
+#[accessors_mod(thread_container_accessors)]
+pub struct ThreadContainerMetadata {
+    container_id: Field<CStr, ThreadContainer>,
+}
+
+/// The name of the container table exported by the container plugin
+pub const CONTAINER_TABLE: &CStr = c"containers";
+
+/// # A single entry in the container table
+pub type Container = Entry<Arc<ContainerMetadata>>;
+
+/// # The container table exported by the container plugin, indexed by container id
+pub type ContainerTable = Table<CString, Container>;
+
+/// # Metadata for the container table
+///
+/// See [`container_accessors`] for the generated methods.
+#[derive(Debug, TableMetadata)]
+#[entry_type(Container)]
+#[key_type(CString)]
+#[accessors_mod(container_accessors)]
+pub struct ContainerMetadata {
+    id: Field<CStr, Container>,
+    name: Field<CStr, Container>,
+    image: Field<CStr, Container>,
+}
@@ -0,0 +1,325 @@
+use crate::tables::data::{FieldTypeId, Key};
+use crate::tables::import::entry::raw::RawEntry;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::ffi::{CStr, CString};
+use std::rc::{Rc, Weak};
+
+thread_local! {
+    /// The entries cached during the current event callback, if any
+    ///
+    /// Entries evicted from their cache before the end of the scope are replaced with `None`,
+    /// so that the indices of nested scopes remain valid.
+    static SCOPE_ENTRIES: RefCell<Option<Vec<Option<Rc<RawEntry>>>>> = const { RefCell::new(None) };
+
+    /// Incremented for every (outermost) event callback
+    static SCOPE_GENERATION: Cell<u64> = const { Cell::new(0) };
+}
+
+/// # The lifetime of cached entries
+///
+/// Entry caches only hold on to entries while one of these is alive, i.e. for the duration
+/// of a single event callback (the SDK enters a scope in the parse, extract and source
+/// wrappers, the latter covering a whole batch). When the scope ends, all entries cached
+/// on the current thread are released, so that no entry stays referenced (and, for tables
+/// exported by Rust plugins, locked) between events.
+///
+/// Outside a scope (e.g. in background threads), the caches are not used at all.
+#[must_use]
+pub(crate) struct EntryCacheScope {
+    prev_len: Option<usize>,
+}
+
+impl EntryCacheScope {
+    pub(crate) fn enter() -> Self {
+        let prev_len = SCOPE_ENTRIES.with_borrow_mut(|entries| match entries {
+            Some(entries) => Some(entries.len()),
+            None => {
+                SCOPE_GENERATION.set(SCOPE_GENERATION.get().wrapping_add(1));
+                *entries = Some(Vec::new());
+                None
+            }
+        });
+
+        Self { prev_len }
+    }
+}
+
+impl Drop for EntryCacheScope {
+    fn drop(&mut self) {
+        let released = SCOPE_ENTRIES.with_borrow_mut(|entries| match self.prev_len {
+            Some(len) => entries
+                .as_mut()
+                .map(|entries| entries.split_off(len))
+                .unwrap_or_default(),
+            None => entries.take().unwrap_or_default(),
+        });
+
+        // release the entries outside the borrow, as this calls into the plugin API
+        drop(released);
+    }
+}
+
+/// Release the scope's references to entries evicted from a cache
+///
+/// The entries stay alive as long as somebody still uses them (through the handles returned
+/// from [`EntryCache::get_or_insert`]).
+fn release_evicted(evicted: impl IntoIterator<Item = Weak<RawEntry>>) {
+    let released = SCOPE_ENTRIES.with_borrow_mut(|scope_entries| {
+        let mut released = Vec::new();
+        let Some(scope_entries) = scope_entries else {
+            return released;
+        };
+        for entry in evicted {
+            let slot = scope_entries.iter_mut().find(|slot| {
+                slot.as_ref()
+                    .is_some_and(|scope_entry| Rc::as_ptr(scope_entry) == entry.as_ptr())
+            });
+            if let Some(slot) = slot {
+                released.extend(slot.take());
+            }
+        }
+        released
+    });
+
+    // release the entries outside the borrow, as this calls into the plugin API
+    drop(released);
+}
+
+/// A table key, detached from its Rust type
+///
+/// This lets us compare keys of any [`Key`] type without requiring extra trait bounds
+#[derive(Debug, PartialEq, Eq)]
+enum CachedKey {
+    Int(i128),
+    String(CString),
+}
+
+impl CachedKey {
+    fn new<K: Key>(key: &K) -> Self {
+        let data = key.to_data();
+        unsafe {
+            match K::TYPE_ID {
+                FieldTypeId::I8 => Self::Int(data.s8 as i128),
+                FieldTypeId::I16 => Self::Int(data.s16 as i128),
+                FieldTypeId::I32 => Self::Int(data.s32 as i128),
+                FieldTypeId::I64 => Self::Int(data.s64 as i128),
+                FieldTypeId::U8 => Self::Int(data.u8_ as i128),
+                FieldTypeId::U16 => Self::Int(data.u16_ as i128),
+                FieldTypeId::U32 => Self::Int(data.u32_ as i128),
+                FieldTypeId::U64 => Self::Int(data.u64_ as i128),
+                FieldTypeId::Bool => Self::Int(data.b as i128),
+                FieldTypeId::String => Self::String(CStr::from_ptr(data.str_).to_owned()),
+                FieldTypeId::Table => unreachable!("tables cannot be used as keys"),
+            }
+        }
+    }
+}
+
+/// The most recently used entries of an imported table
+///
+/// The entry handles are owned by the current [`EntryCacheScope`] and shared with
+/// the [`RawEntry`] objects the cache hands out, so evicting an entry that is still in use
+/// does not release it under its user's feet. The cache itself only keeps weak references,
+/// valid for the scope they were created in. Evicting an entry also drops the scope's
+/// reference, so the cache keeps at most `capacity` entries alive.
+#[derive(Debug)]
+pub(crate) struct EntryCache {
+    capacity: usize,
+    entries: RefCell<VecDeque<(CachedKey, u64, Weak<RawEntry>)>>,
+}
+
+impl EntryCache {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: RefCell::new(VecDeque::with_capacity(capacity)),
+        }
+    }
+
+    /// Get a cached entry for `key` or look it up using `get_entry` (and cache it)
+    pub(crate) fn get_or_insert<K: Key>(
+        &self,
+        key: &K,
+        get_entry: impl FnOnce() -> Result<RawEntry, anyhow::Error>,
+    ) -> Result<RawEntry, anyhow::Error> {
+        let Some(generation) =
+            SCOPE_ENTRIES.with_borrow(|entries| entries.as_ref().map(|_| SCOPE_GENERATION.get()))
+        else {
+            return get_entry();
+        };
+
+        let key = CachedKey::new(key);
+        let mut entries = self.entries.borrow_mut();
+        entries.retain(|(_, entry_generation, entry)| {
+            *entry_generation == generation && entry.strong_count() > 0
+        });
+
+        let cached = entries
+            .iter()
+            .position(|(k, _, _)| *k == key)
+            .and_then(|pos| {
+                // move the entry to the front, as the most recently used one
+                let cached = entries.remove(pos)?;
+                let entry = cached.2.upgrade()?;
+                entries.push_front(cached);
+                Some(entry)
+            });
+
+        let entry = match cached {
+            Some(entry) => entry,
+            None => {
+                let entry = Rc::new(get_entry()?);
+                SCOPE_ENTRIES.with_borrow_mut(|scope_entries| {
+                    if let Some(scope_entries) = scope_entries {
+                        scope_entries.push(Some(Rc::clone(&entry)));
+                    }
+                });
+                entries.push_front((key, generation, Rc::downgrade(&entry)));
+                if entries.len() > self.capacity {
+                    release_evicted(entries.drain(self.capacity..).map(|(_, _, entry)| entry));
+                }
+                entry
+            }
+        };
+
+        Ok(RawEntry {
+            table: entry.table,
+            entry: entry.entry,
+            destructor: None,
+            keepalive: Some(entry),
+        })
+    }
+
+    /// Drop all the cached entries
+    pub(crate) fn clear(&self) {
+        let evicted = self.entries.borrow_mut().drain(..).collect::<Vec<_>>();
+        release_evicted(evicted.into_iter().map(|(_, _, entry)| entry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    fn fake_entry(id: usize) -> RawEntry {
+        RawEntry {
+            table: std::ptr::null_mut(),
+            entry: id as *mut _,
+            destructor: None,
+            keepalive: None,
+        }
+    }
+
+    #[test]
+    fn test_entry_cache() {
+        let _scope = EntryCacheScope::enter();
+        let cache = EntryCache::new(2);
+        let lookups = Cell::new(0);
+        let get = |key: u64| {
+            cache
+                .get_or_insert(&key, || {
+                    lookups.set(lookups.get() + 1);
+                    Ok(fake_entry(key as usize))
+                })
+                .unwrap()
+                .entry as usize
+        };
+
+        assert_eq!(get(1), 1);
+        assert_eq!(get(2), 2);
+        assert_eq!(get(1), 1);
+        assert_eq!(lookups.get(), 2);
+
+        // 2 is the least recently used entry, so it gets evicted
+        assert_eq!(get(3), 3);
+        assert_eq!(get(1), 1);
+        assert_eq!(lookups.get(), 3);
+        assert_eq!(get(2), 2);
+        assert_eq!(lookups.get(), 4);
+
+        cache.clear();
+        assert_eq!(get(1), 1);
+        assert_eq!(lookups.get(), 5);
+    }
+
+    #[test]
+    fn test_entry_cache_string_keys() {
+        let _scope = EntryCacheScope::enter();
+        let cache = EntryCache::new(2);
+        let lookups = Cell::new(0);
+        let get = |key: &CStr| {
+            cache
+                .get_or_insert(&key.to_owned(), || {
+                    lookups.set(lookups.get() + 1);
+                    Ok(fake_entry(key.count_bytes()))
+                })
+                .unwrap()
+                .entry as usize
+        };
+
+        assert_eq!(get(c"a"), 1);
+        assert_eq!(get(c"bb"), 2);
+        assert_eq!(get(c"a"), 1);
+        assert_eq!(lookups.get(), 2);
+    }
+
+    #[test]
+    fn test_entry_cache_scope() {
+        let cache = EntryCache::new(2);
+        let lookups = Cell::new(0);
+        let get = |key: u64| {
+            cache
+                .get_or_insert(&key, || {
+                    lookups.set(lookups.get() + 1);
+                    Ok(fake_entry(key as usize))
+                })
+                .unwrap()
+        };
+
+        // no caching outside a scope
+        get(1);
+        get(1);
+        assert_eq!(lookups.get(), 2);
+
+        let scope = EntryCacheScope::enter();
+        get(1);
+        let held = get(1);
+        assert_eq!(lookups.get(), 3);
+
+        // the entry is released with the scope, apart from the handle still held
+        drop(scope);
+        let keepalive = Rc::downgrade(held.keepalive.as_ref().unwrap());
+        drop(held);
+        assert_eq!(keepalive.strong_count(), 0);
+
+        // the next scope does not see entries from the previous one
+        let _scope = EntryCacheScope::enter();
+        get(1);
+        assert_eq!(lookups.get(), 4);
+    }
+
+    #[test]
+    fn test_entry_cache_eviction_releases() {
+        let _scope = EntryCacheScope::enter();
+        let cache = EntryCache::new(1);
+        let get = |key: u64| cache.get_or_insert(&key, || Ok(fake_entry(key as usize)));
+
+        let handle = get(1).unwrap();
+        let first = Rc::downgrade(handle.keepalive.as_ref().unwrap());
+        drop(handle);
+        assert_eq!(first.strong_count(), 1);
+
+        // evicting the entry releases it, even though the scope is still alive
+        let second = get(2).unwrap();
+        assert_eq!(first.strong_count(), 0);
+
+        // unless it's still in use
+        let second_weak = Rc::downgrade(second.keepalive.as_ref().unwrap());
+        cache.clear();
+        assert_eq!(second_weak.strong_count(), 1);
+        drop(second);
+        assert_eq!(second_weak.strong_count(), 0);
+    }
+}
@@ -1,10 +1,10 @@
 use crate::tables::data::{seal, FieldTypeId, Key, TableData, Value};
 use crate::tables::import::entry;
-use crate::tables::import::field::Field;
+use crate::tables::import::field::{Field, TableFieldInfo};
 use crate::tables::import::runtime::NoMetadata;
 use crate::tables::import::runtime_table_validator::RuntimeTableValidator;
 use crate::tables::import::table::raw::{IterationResult, RawTable};
-use crate::tables::import::traits::{Entry, TableAccess, TableMetadata};
+use crate::tables::import::traits::{Entry, KeyedTableMetadata, TableAccess, TableMetadata};
 use crate::tables::TableFields;
 use crate::tables::TableReader;
 use crate::tables::TableWriter;
@@ -15,18 +15,29 @@ use std::ffi::CStr;
 use std::marker::PhantomData;
 use std::ops::ControlFlow;
 
+mod cache;
 pub(crate) mod raw;
 
+use cache::EntryCache;
+pub(crate) use cache::EntryCacheScope;
+
 /// # A table imported via the Falco plugin API
 #[derive(Debug)]
 pub struct Table<K, E = entry::Entry<NoMetadata<()>>, M = <E as Entry>::Metadata> {
     pub(crate) raw_table: RawTable,
     pub(crate) metadata: M,
     pub(crate) is_nested: bool,
+    pub(crate) entry_cache: Option<EntryCache>,
     pub(crate) key_type: PhantomData<K>,
     pub(crate) entry_type: PhantomData<E>,
 }
 
+/// # An imported table, with the key type declared by the entry metadata
+///
+/// This is [`Table`] with the key type taken from the `#[key_type(...)]` attribute
+/// of the metadata struct, so that it only needs to be spelled out once.
+pub type KeyedTable<E> = Table<<<E as Entry>::Metadata as KeyedTableMetadata>::Key, E>;
+
 impl<K, E, M> TableAccess for Table<K, E, M>
 where
     K: Key,
@@ -42,6 +53,7 @@ where
             raw_table,
             metadata,
             is_nested,
+            entry_cache: None,
             key_type: PhantomData,
             entry_type: PhantomData,
         }
@@ -67,8 +79,16 @@ where
     M: TableMetadata + Clone,
 {
     /// Look up an entry in `table` corresponding to `key`
+    ///
+    /// If the entry cache is enabled (see [`Table::with_entry_cache`]), entries already looked up
+    /// in the current event callback are returned from the cache instead of being looked up again.
     pub fn get_entry(&self, reader_vtable: &impl TableReader, key: &K) -> Result<E, Error> {
-        let raw_entry = self.raw_table.get_entry(reader_vtable, key)?;
+        let raw_entry = match &self.entry_cache {
+            Some(cache) => {
+                cache.get_or_insert(key, || self.raw_table.get_entry(reader_vtable, key))?
+            }
+            None => self.raw_table.get_entry(reader_vtable, key)?,
+        };
         Ok(E::new(
             raw_entry,
             self.raw_table.table,
@@ -78,6 +98,7 @@ where
 
     /// Erase a table entry by key
     pub fn erase(&self, writer_vtable: &impl TableWriter, key: &K) -> Result<(), Error> {
+        self.clear_entry_cache();
         unsafe { self.raw_table.erase(writer_vtable, key) }
     }
 
@@ -89,6 +110,7 @@ where
         key: &K,
         entry: E,
     ) -> Result<E, Error> {
+        self.clear_entry_cache();
         let raw_entry = unsafe {
             self.raw_table
                 .insert(reader_vtable, writer_vtable, key, entry.into_raw())
@@ -115,11 +137,47 @@ where
             raw_table,
             metadata,
             is_nested,
+            entry_cache: None,
             key_type: PhantomData,
             entry_type: PhantomData,
         }
     }
 
+    /// # Cache recently used entries
+    ///
+    /// Plugins often look up the same few entries (e.g. the current thread) several times
+    /// while handling a single event. With the cache enabled, [`Table::get_entry`] keeps
+    /// the handles of up to `capacity` most recently used entries and returns them without
+    /// calling into the plugin API again.
+    ///
+    /// This only deduplicates lookups within a single event callback (parsing an event,
+    /// extracting fields or generating a whole batch of events): all cached entries are released
+    /// when the callback returns, so the next event always sees the current table contents
+    /// and no entries stay referenced (and, for tables exported by Rust plugins, locked)
+    /// between events. An entry needed for every event (e.g. the thread that generated it)
+    /// is still looked up once per event. Outside event callbacks (e.g. in background threads),
+    /// the cache is not used at all.
+    ///
+    /// The cache keeps at most `capacity` entries alive: evicted entries are released right away,
+    /// unless you still hold on to them.
+    ///
+    /// Within a callback, the cache is dropped whenever you modify the table through this object
+    /// ([`Table::insert`], [`Table::erase`], [`Table::clear`]), but it has no way to notice
+    /// changes made by anybody else. Call [`Table::clear_entry_cache`] if that's a concern.
+    pub fn with_entry_cache(mut self, capacity: usize) -> Self {
+        self.entry_cache = Some(EntryCache::new(capacity));
+        self
+    }
+
+    /// # Drop all entries from the entry cache
+    ///
+    /// This is a no-op if the cache is not enabled (see [`Table::with_entry_cache`]).
+    pub fn clear_entry_cache(&self) {
+        if let Some(cache) = &self.entry_cache {
+            cache.clear();
+        }
+    }
+
     pub(crate) fn table_validator(&self) -> RuntimeTableValidator {
         let ptr = if self.is_nested {
             std::ptr::null_mut()
@@ -141,6 +199,7 @@ where
 
     /// Remove all entries from the table
     pub fn clear(&self, writer_vtable: &impl TableWriter) -> Result<(), Error> {
+        self.clear_entry_cache();
         self.raw_table.clear(writer_vtable)
     }
 
@@ -148,11 +207,20 @@ where
     ///
     /// **Note**: this method is of limited utility in actual plugin code (you know the fields you
     /// want to access), so it returns the unmodified structure from the plugin API, including
-    /// raw pointers to C-style strings. This may change later.
+    /// raw pointers to C-style strings. See [`Table::list_table_fields`] for a safe alternative.
     pub fn list_fields(&self, fields_vtable: &TableFields) -> &[ss_plugin_table_fieldinfo] {
         self.raw_table.list_fields(fields_vtable)
     }
 
+    /// # List the available fields
+    ///
+    /// This lets you check which fields a table offers before calling [`Table::get_field`]
+    /// (e.g. to degrade gracefully when an optional field is missing). Fields of types
+    /// unsupported by the SDK are skipped.
+    pub fn list_table_fields(&self, tables_input: &TablesInput) -> Vec<TableFieldInfo> {
+        self.raw_table.list_field_info(&tables_input.fields_ext)
+    }
+
     /// # Get a table field by name
     ///
     /// The field must exist in the table and must be of the type `V`, otherwise an error
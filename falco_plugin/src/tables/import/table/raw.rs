@@ -3,6 +3,7 @@ use crate::strings::from_ptr::try_str_from_ptr_with_lifetime;
 use crate::tables::data::{FieldTypeId, Key, Value};
 use crate::tables::import::entry::raw::RawEntry;
 use crate::tables::import::field::raw::RawField;
+use crate::tables::import::field::TableFieldInfo;
 use crate::tables::import::traits::TableMetadata;
 use crate::tables::TableFields;
 use crate::tables::TableReader;
@@ -64,6 +65,33 @@ pub struct RawTable {
 }
 
 impl RawTable {
+    /// # Get the key type of the table
+    ///
+    /// Returns `None` if the table has a key type unknown to the SDK
+    pub fn key_type(&self) -> Option<FieldTypeId> {
+        let input = unsafe { &*(self.table as *mut falco_plugin_api::ss_plugin_table_input) };
+        FieldTypeId::from_u32(input.key_type)
+    }
+
+    /// # Verify the key type of the table
+    ///
+    /// Returns an error naming both types if the table key is not of type `K`,
+    /// as declared by the metadata struct `metadata`.
+    pub fn check_key_type<K: Key>(&self, metadata: &str) -> Result<(), anyhow::Error> {
+        let key_type = self.key_type();
+        if key_type != Some(K::TYPE_ID) {
+            anyhow::bail!(
+                "Bad key type, {} declares {} ({:?}), table has {:?}",
+                metadata,
+                std::any::type_name::<K>(),
+                K::TYPE_ID,
+                key_type,
+            );
+        }
+
+        Ok(())
+    }
+
     /// # List the available fields
     ///
     /// **Note**: this method is of limited utility in actual plugin code (you know the fields you
@@ -81,6 +109,23 @@ impl RawTable {
         }
     }
 
+    /// # List the available fields, skipping ones with unsupported types
+    pub(crate) fn list_field_info(&self, fields_vtable: &TableFields) -> Vec<TableFieldInfo> {
+        self.list_fields(fields_vtable)
+            .iter()
+            .filter_map(|info| {
+                if info.name.is_null() {
+                    return None;
+                }
+                Some(TableFieldInfo {
+                    name: unsafe { CStr::from_ptr(info.name) }.to_owned(),
+                    field_type: FieldTypeId::from_u32(info.field_type)?,
+                    read_only: info.read_only != 0,
+                })
+            })
+            .collect()
+    }
+
     /// # Get a table field by name
     ///
     /// The field must exist in the table and must be of the type `V`, otherwise an error
@@ -114,6 +159,26 @@ impl RawTable {
         })
     }
 
+    /// # Get a table field by name, if it exists
+    ///
+    /// Returns `None` if the table does not have a field called `name`. If the field exists
+    /// but is not of type `V`, an error is returned, just like from [`RawTable::get_field`].
+    pub fn get_optional_field<V: Value + ?Sized>(
+        &self,
+        tables_input: &TablesInput,
+        name: &CStr,
+    ) -> Result<Option<RawField<V>>, anyhow::Error> {
+        let exists = self
+            .list_fields(&tables_input.fields_ext)
+            .iter()
+            .any(|info| !info.name.is_null() && unsafe { CStr::from_ptr(info.name) } == name);
+        if !exists {
+            return Ok(None);
+        }
+
+        self.get_field(tables_input, name).map(Some)
+    }
+
     /// # Add a table field
     ///
     /// The field will have the specified name and the type is derived from the generic argument.
@@ -159,8 +224,19 @@ impl RawTable {
             );
         }
 
-        let entry =
-            unsafe { reader_vtable.get_table_entry(self.table, &key.to_data() as *const _) }?;
+        unsafe { self.get_entry_by_data(reader_vtable, &key.to_data()) }
+    }
+
+    /// # Look up an entry by a key in its raw FFI representation
+    ///
+    /// # Safety
+    /// `key` must hold a value of the table's key type
+    pub(crate) unsafe fn get_entry_by_data(
+        &self,
+        reader_vtable: &impl TableReader,
+        key: &ss_plugin_state_data,
+    ) -> Result<RawEntry, anyhow::Error> {
+        let entry = unsafe { reader_vtable.get_table_entry(self.table, key as *const _) }?;
 
         if entry.is_null() {
             Err(anyhow::anyhow!("table entry not found"))
@@ -169,6 +245,7 @@ impl RawTable {
                 table: self.table,
                 entry: entry as *mut _,
                 destructor: reader_vtable.release_table_entry_fn(),
+                keepalive: None,
             })
         }
     }
@@ -182,10 +259,22 @@ impl RawTable {
         &self,
         writer_vtable: &impl TableWriter,
         key: &K,
+    ) -> Result<(), anyhow::Error> {
+        unsafe { self.erase_by_data(writer_vtable, &key.to_data()) }
+    }
+
+    /// # Erase a table entry by a key in its raw FFI representation
+    ///
+    /// # Safety
+    /// `key` must hold a value of the table's key type
+    pub(crate) unsafe fn erase_by_data(
+        &self,
+        writer_vtable: &impl TableWriter,
+        key: &ss_plugin_state_data,
     ) -> Result<(), anyhow::Error> {
         unsafe {
             writer_vtable
-                .erase_table_entry(self.table, &key.to_data() as *const _)?
+                .erase_table_entry(self.table, key as *const _)?
                 .as_result()?
         };
         Ok(())
@@ -208,6 +297,7 @@ impl RawTable {
                 table: self.table,
                 entry,
                 destructor: writer_vtable.destroy_table_entry_fn(),
+                keepalive: None,
             })
         }
     }
@@ -224,11 +314,24 @@ impl RawTable {
         reader_vtable: &impl TableReader,
         writer_vtable: &impl TableWriter,
         key: &K,
+        entry: RawEntry,
+    ) -> Result<RawEntry, anyhow::Error> {
+        unsafe { self.insert_by_data(reader_vtable, writer_vtable, &key.to_data(), entry) }
+    }
+
+    /// # Insert an entry into the table, using a key in its raw FFI representation
+    ///
+    /// # Safety
+    /// `key` must hold a value of the table's key type
+    pub(crate) unsafe fn insert_by_data(
+        &self,
+        reader_vtable: &impl TableReader,
+        writer_vtable: &impl TableWriter,
+        key: &ss_plugin_state_data,
         mut entry: RawEntry,
     ) -> Result<RawEntry, anyhow::Error> {
-        let ret = unsafe {
-            writer_vtable.add_table_entry(self.table, &key.to_data() as *const _, entry.entry)?
-        };
+        let ret =
+            unsafe { writer_vtable.add_table_entry(self.table, key as *const _, entry.entry)? };
 
         if ret.is_null() {
             Err(anyhow::anyhow!("Failed to attach entry"))
@@ -238,6 +341,7 @@ impl RawTable {
                 table: self.table,
                 entry: ret,
                 destructor: reader_vtable.release_table_entry_fn(),
+                keepalive: None,
             })
         }
     }
@@ -286,6 +390,7 @@ impl RawTable {
                     table: self.table,
                     entry: s,
                     destructor: None,
+                    keepalive: None,
                 };
                 func(raw_entry).is_continue()
             },
@@ -359,9 +464,12 @@ impl RawTable {
     }
 }
 
+/// # The outcome of iterating over table entries
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub enum IterationResult {
+    /// All entries have been visited
     Finished,
+    /// The iteration was stopped early by the closure
     Exited,
 }
 
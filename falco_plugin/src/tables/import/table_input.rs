@@ -1,11 +1,42 @@
 use crate::error::as_result::WithLastError;
+use crate::tables::data::FieldTypeId;
 use crate::tables::import::traits::{TableAccess, TableMetadata};
-use crate::tables::import::RawTable;
+use crate::tables::import::{DynamicTable, RawTable};
 use crate::tables::{Key, TablesInput};
 use falco_plugin_api::ss_plugin_state_type;
-use std::ffi::CStr;
+use num_traits::FromPrimitive;
+use std::ffi::{CStr, CString};
+
+/// # Description of a table
+///
+/// Returned from [`TablesInput::list_table_info`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableInfo {
+    /// The table name
+    pub name: CString,
+    /// The type of the table key
+    pub key_type: FieldTypeId,
+}
 
 impl TablesInput<'_> {
+    /// # List the available tables, skipping ones with unsupported key types
+    ///
+    /// Unlike [`TablesInput::list_tables`], this method returns owned, safe data.
+    pub fn list_table_info(&self) -> Vec<TableInfo> {
+        self.list_tables()
+            .iter()
+            .filter_map(|info| {
+                if info.name.is_null() {
+                    return None;
+                }
+                Some(TableInfo {
+                    name: unsafe { CStr::from_ptr(info.name) }.to_owned(),
+                    key_type: FieldTypeId::from_u32(info.key_type)?,
+                })
+            })
+            .collect()
+    }
+
     /// # Import a table from the Falco plugin API
     ///
     /// The key type is verified by the plugin API, so this method will return
@@ -15,20 +46,75 @@ impl TablesInput<'_> {
         T: TableAccess<Key = K>,
         K: Key,
     {
+        // check the key type up front, to report both types on mismatch
+        if let Some(info) = self.find_table_info(name) {
+            if info.key_type != K::TYPE_ID as ss_plugin_state_type {
+                anyhow::bail!(
+                    "Bad key type for table {:?}, requested {} ({:?}), table has {:?}",
+                    name,
+                    std::any::type_name::<K>(),
+                    K::TYPE_ID,
+                    FieldTypeId::from_u32(info.key_type),
+                );
+            }
+        }
+
+        let table = self.get_raw_table(name, K::TYPE_ID)?;
+        let metadata = T::Metadata::new(&table, self)?;
+        Ok(T::new(table, metadata, false))
+    }
+
+    /// # Import a table if it exists
+    ///
+    /// This works like [`TablesInput::get_table`], except that a missing table results
+    /// in `Ok(None)` instead of an error. Use it for optional dependencies on tables
+    /// exported by other plugins. Other errors (e.g. a key or field type mismatch)
+    /// are still reported.
+    pub fn try_get_table<T, K>(&self, name: &CStr) -> Result<Option<T>, anyhow::Error>
+    where
+        T: TableAccess<Key = K>,
+        K: Key,
+    {
+        if !self.table_exists(name) {
+            return Ok(None);
+        }
+
+        self.get_table(name).map(Some)
+    }
+
+    /// # Import a table with fields discovered at runtime
+    ///
+    /// Unlike [`TablesInput::get_table`], this method does not need the key type or any
+    /// fields to be known at compile time. See [`DynamicTable`] for details.
+    pub fn get_dynamic_table(&self, name: &CStr) -> Result<DynamicTable, anyhow::Error> {
+        let info = self
+            .find_table_info(name)
+            .ok_or_else(|| anyhow::anyhow!("Table {:?} does not exist", name))?;
+        let key_type = FieldTypeId::from_u32(info.key_type).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Table {:?} has an unsupported key type {}",
+                name,
+                info.key_type
+            )
+        })?;
+
+        let table = self.get_raw_table(name, key_type)?;
+        DynamicTable::new(name, key_type, table, self)
+    }
+
+    fn get_raw_table(&self, name: &CStr, key_type: FieldTypeId) -> Result<RawTable, anyhow::Error> {
         let table = unsafe {
             (self.get_table)(
                 self.owner,
                 name.as_ptr().cast(),
-                K::TYPE_ID as ss_plugin_state_type,
+                key_type as ss_plugin_state_type,
             )
         };
         if table.is_null() {
             Err(anyhow::anyhow!("Could not get table {:?}", name)).with_last_error(&self.last_error)
         } else {
             // Safety: we pass the data directly from FFI, the framework would never lie to us, right?
-            let table = RawTable { table };
-            let metadata = T::Metadata::new(&table, self)?;
-            Ok(T::new(table, metadata, false))
+            Ok(RawTable { table })
         }
     }
 }
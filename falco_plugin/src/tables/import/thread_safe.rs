@@ -0,0 +1,121 @@
+use crate::tables::import::Table;
+use crate::tables::{TablesInput, ValidatedTableReader, ValidatedTableWriter};
+use parking_lot::Mutex;
+
+mod seal {
+    pub trait Sealed {}
+}
+
+/// # Types that can be wrapped in [`ThreadSafeTables`]
+///
+/// Imported tables are not [`Send`], as most of them must not be used from other threads,
+/// so [`ThreadSafeTables`] cannot simply require `T: Send`. Instead, it only accepts
+/// a [`Table`] or a tuple of up to four tables, so that nothing else (like an `Rc`
+/// or an entry) can be smuggled to another thread along with them.
+pub trait ImportedTables: seal::Sealed {}
+
+impl<K, E, M> seal::Sealed for Table<K, E, M> {}
+impl<K, E, M> ImportedTables for Table<K, E, M> {}
+
+macro_rules! impl_imported_tables_tuple {
+    ($($ty:ident),*) => {
+        impl<$($ty: ImportedTables),*> seal::Sealed for ($($ty,)*) {}
+        impl<$($ty: ImportedTables),*> ImportedTables for ($($ty,)*) {}
+    };
+}
+
+impl_imported_tables_tuple!(A, B);
+impl_imported_tables_tuple!(A, B, C);
+impl_imported_tables_tuple!(A, B, C, D);
+
+#[derive(Debug)]
+struct ThreadSafeTablesInner<T> {
+    tables: T,
+    reader: ValidatedTableReader<'static>,
+    writer: ValidatedTableWriter<'static>,
+}
+
+/// # Imported tables accessible from other threads
+///
+/// The plugin API only lets you access tables from within plugin callbacks (on the thread
+/// the framework calls you on), so imported tables, entries and the reader/writer objects
+/// cannot be sent to other threads. However, tables exported by Rust plugins built with
+/// the `thread-safe-tables` feature protect all their data with locks, so it is actually safe
+/// to access them from a background thread, concurrently to the framework and other plugins.
+///
+/// This type wraps a set of imported tables (`T`, a [`Table`] or a tuple of tables,
+/// see [`ImportedTables`]) together with a reader and a writer and lets you access them
+/// from any thread:
+///
+/// ```ignore
+/// // in Plugin::new
+/// let tables = unsafe { ThreadSafeTables::new(input, input.get_table::<MyTable>(c"my_table")?)? };
+/// let tables = Arc::new(tables);
+///
+/// // in a background thread
+/// tables.with(|table, reader, writer| {
+///     let entry = table.get_entry(reader, &key)?;
+///     entry.set_counter(writer, &(entry.get_counter(reader)? + 1))
+/// })?;
+/// ```
+///
+/// Calls to [`ThreadSafeTables::with`] are serialized with a mutex. Each table operation inside
+/// the closure goes straight to the exporting plugin, which takes its own locks, so other
+/// plugins (and the framework) can still access the tables in the meantime.
+///
+/// **Caveats**:
+/// - The SDK cannot tell who implements an imported table (the framework may wrap tables
+///   exported by other plugins), so it's up to you to only use this with tables exported
+///   by Rust plugins with the `thread-safe-tables` feature enabled. Tables owned by Falco
+///   itself (like the thread table) are *not* thread-safe and must not be used from other threads.
+/// - A table entry stays locked for as long as you hold it, so keep the closures short.
+///   Another thread (including the main one) trying to access a locked entry will block
+///   until you release it. Entries cannot escape the closure (neither the closure nor
+///   its result may hold anything that is not [`Send`]). The entry cache
+///   ([`Table::with_entry_cache`]) is not used in background threads.
+/// - The tables must not be used after the plugin is destroyed, so make sure to stop
+///   your background threads before that happens (e.g. in `Drop`).
+#[derive(Debug)]
+pub struct ThreadSafeTables<T> {
+    inner: Mutex<ThreadSafeTablesInner<T>>,
+}
+
+// SAFETY: `T` only contains raw pointers to tables exported (by contract) with
+// `thread-safe-tables`, all access to them is serialized with a mutex and no entries
+// can leave `ThreadSafeTables::with`
+unsafe impl<T: ImportedTables> Send for ThreadSafeTables<T> {}
+unsafe impl<T: ImportedTables> Sync for ThreadSafeTables<T> {}
+
+impl<T: ImportedTables> ThreadSafeTables<T> {
+    /// # Wrap imported tables for use from other threads
+    ///
+    /// # Safety
+    /// - all tables in `tables` must be exported by Rust plugins with the `thread-safe-tables`
+    ///   feature enabled
+    /// - the returned object must not outlive the plugin
+    pub unsafe fn new(tables_input: &TablesInput, tables: T) -> Result<Self, anyhow::Error> {
+        let reader = unsafe { tables_input.reader_ext.validate()?.into_static() };
+        let writer = unsafe { tables_input.writer_ext.validate()?.into_static() };
+
+        Ok(Self {
+            inner: Mutex::new(ThreadSafeTablesInner {
+                tables,
+                reader,
+                writer,
+            }),
+        })
+    }
+
+    /// # Access the tables
+    ///
+    /// The closure gets the wrapped tables along with a reader and a writer to use with them.
+    /// Both the closure and its result must be [`Send`], so that table entries (which are not)
+    /// cannot outlive the call, either returned or stored in a captured variable.
+    pub fn with<R: Send>(
+        &self,
+        func: impl FnOnce(&T, &ValidatedTableReader, &ValidatedTableWriter) -> R + Send,
+    ) -> R {
+        let inner = self.inner.lock();
+        func(&inner.tables, &inner.reader, &inner.writer)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::tables::data::{Key, Value};
 use crate::tables::import::entry::raw::RawEntry;
+use crate::tables::import::field::Field;
 use crate::tables::import::table::raw::RawTable;
 use crate::tables::TableReader;
 use crate::tables::TableWriter;
@@ -25,6 +26,18 @@ impl<M: TableMetadata> TableMetadata for Arc<M> {
     }
 }
 
+/// Metadata for tables with a declared key type
+///
+/// Implemented by `#[derive(TableMetadata)]` for metadata structs tagged with `#[key_type(...)]`
+pub trait KeyedTableMetadata: TableMetadata {
+    /// the type of the table key
+    type Key: Key;
+}
+
+impl<M: KeyedTableMetadata> KeyedTableMetadata for Arc<M> {
+    type Key = M::Key;
+}
+
 /// A trait describing structs that can be stored as table entries
 pub trait Entry {
     /// metadata for the entry (for each field)
@@ -56,6 +69,23 @@ pub trait EntryWrite<F, V: Value<AssocData = ()> + ?Sized> {
     ) -> Result<(), anyhow::Error>;
 }
 
+/// A trait describing a set of fields that can be read from an entry in one go
+///
+/// Implemented for tuples of (up to 12) field references, e.g. `(&Field<u64, E>, &Field<CStr, E>)`,
+/// where the values are returned as a tuple of the same arity.
+/// See [`Entry::read_fields`](`crate::tables::import::Entry::read_fields`) for details.
+pub trait ReadFields<'a, E> {
+    /// the values read from the entry
+    type Values;
+
+    /// read all the fields from `entry`
+    fn read_fields(
+        self,
+        entry: &'a E,
+        reader: &impl TableReader,
+    ) -> Result<Self::Values, anyhow::Error>;
+}
+
 /// A trait describing a table that can have its entries looked up
 ///
 /// This too only exists to please the elder gods awoken in the derive macro
@@ -99,3 +129,33 @@ pub trait RawFieldValueType {
     where
         Self: 'a;
 }
+
+/// A trait for metadata fields that may be missing from the table
+///
+/// Implemented for [`Field`] (always present) and `Option<Field>` (present only if the table
+/// had the field when the metadata was created), so that the derive macro can handle both
+/// the same way.
+pub trait MaybeField {
+    /// the actual field type
+    type Field;
+
+    /// get the field, or an error if the table does not have it
+    fn field(&self) -> Result<&Self::Field, anyhow::Error>;
+}
+
+impl<V: Value + ?Sized, T> MaybeField for Field<V, T> {
+    type Field = Self;
+
+    fn field(&self) -> Result<&Self::Field, anyhow::Error> {
+        Ok(self)
+    }
+}
+
+impl<V: Value + ?Sized, T> MaybeField for Option<Field<V, T>> {
+    type Field = Field<V, T>;
+
+    fn field(&self) -> Result<&Self::Field, anyhow::Error> {
+        self.as_ref()
+            .ok_or_else(|| anyhow::anyhow!("Optional field not available in this table"))
+    }
+}
@@ -48,7 +48,9 @@
 //! - CString
 //!
 //! Any other types are not supported, including in particular e.g. collections (`Vec<T>`),
-//! enums or any structs.
+//! enums or any structs. However, the SDK can map some Rust types onto the supported ones:
+//! - enums implementing [`TableEnum`] are stored as integers
+//! - serializable values wrapped in [`Encoded`] are stored as JSON strings
 //!
 //! # Nested tables
 //!
@@ -110,6 +112,10 @@
 //! Note that [`crate::base::Plugin::new`] receives an `Option<&TablesInput>` and the option
 //! is populated only for parsing and extraction plugins (source and async plugins receive `None`).
 //!
+//! In debug builds, the SDK also checks the rules at runtime: attempting a table write while
+//! extracting fields or generating events panics with a message naming the read-only phase,
+//! rather than failing with an opaque error code from the framework.
+//!
 //! # The flow of using tables
 //!
 //! The access controls described above push you into structuring your plugins in a specific way.
@@ -137,8 +143,17 @@
 //! the `thread-safe-tables` feature, tables exported from your plugin become thread-safe, so you
 //! can use them from your plugin (e.g. in a separate thread) concurrently to other plugins
 //! (in the main thread).
+//!
+//! Imported tables cannot be used from other threads by default, as the SDK has no way to know
+//! whether the plugin exporting the table did the same. If you know the tables you import come
+//! from Rust plugins built with `thread-safe-tables`, you can opt in to accessing them from
+//! background threads with `import::ThreadSafeTables` (see its documentation for the caveats).
 
+pub use encoded::Encoded;
+pub(crate) use import::EntryCacheScope;
+pub use table_enum::{TableEnum, TableEnumRepr};
 pub(crate) use vtable::fields::TableFields;
+pub(crate) use vtable::phase::ReadOnlyPhase;
 pub(crate) use vtable::reader::private::TableReaderImpl;
 pub use vtable::reader::LazyTableReader;
 pub use vtable::reader::TableReader;
@@ -150,8 +165,10 @@ pub use vtable::writer::ValidatedTableWriter;
 pub use vtable::TablesInput;
 
 mod data;
+mod encoded;
 pub mod export;
 pub mod import;
+mod table_enum;
 mod vtable;
 
 // for macro use only
@@ -0,0 +1,165 @@
+use crate::tables::data::{seal, FieldTypeId, Key, TableData, Value};
+use crate::tables::export::field_value::dynamic::DynamicFieldValue;
+use crate::tables::export::field_value::traits::{
+    seal as export_seal, FieldValue, FromDynamicFieldValue, StaticField,
+};
+use crate::tables::import::RawTable;
+use crate::tables::TablesInput;
+use falco_plugin_api::{ss_plugin_state_data, ss_plugin_table_field_t};
+use std::fmt::Debug;
+
+/// # An integer type usable as the representation of a [`TableEnum`]
+///
+/// This trait is sealed and implemented for all the integer types (`u8` through `u64`
+/// and `i8` through `i64`).
+pub trait TableEnumRepr:
+    Key<Borrowed = Self>
+    + FieldValue
+    + StaticField
+    + TryFrom<DynamicFieldValue, Error = anyhow::Error>
+    + Copy
+    + Debug
+{
+}
+
+impl TableEnumRepr for u8 {}
+impl TableEnumRepr for i8 {}
+impl TableEnumRepr for u16 {}
+impl TableEnumRepr for i16 {}
+impl TableEnumRepr for u32 {}
+impl TableEnumRepr for i32 {}
+impl TableEnumRepr for u64 {}
+impl TableEnumRepr for i64 {}
+
+/// # An enum stored in table fields as an integer
+///
+/// Many table fields are integers with a well-known set of values (e.g. the file descriptor
+/// type). Implementing this trait for a Rust enum lets you use it directly as the field type,
+/// both in imported tables (`import::Field<FdType, Fd>`) and in exported ones
+/// (`export::Public<FdType>`):
+///
+/// ```
+/// use falco_plugin::tables::TableEnum;
+///
+/// #[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+/// #[repr(u8)]
+/// enum L4Proto {
+///     #[default]
+///     Unknown = 0,
+///     Tcp = 3,
+///     Udp = 4,
+/// }
+///
+/// impl TableEnum for L4Proto {
+///     type Repr = u8;
+///
+///     fn to_repr(self) -> u8 {
+///         self as u8
+///     }
+///
+///     fn from_repr(repr: u8) -> Option<Self> {
+///         match repr {
+///             0 => Some(Self::Unknown),
+///             3 => Some(Self::Tcp),
+///             4 => Some(Self::Udp),
+///             _ => None,
+///         }
+///     }
+/// }
+/// ```
+///
+/// The field is visible to other plugins as a plain integer of type [`TableEnum::Repr`].
+/// Reading a value that does not correspond to any variant (from an imported table) returns
+/// an error, as does an attempt by another plugin to write such a value to an exported table.
+///
+/// **Note**: exported fields need a default value, so the enum must also implement [`Default`]
+/// to be used in an exported table.
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` cannot be stored in a table field",
+    label = "unsupported table field type",
+    note = "table fields can only hold integers, `bool`, `CString`, `TableEnum` types and nested tables"
+)]
+pub trait TableEnum: Copy + Debug + 'static {
+    /// The integer type used to store the enum
+    type Repr: TableEnumRepr;
+
+    /// Get the integer representation of the value
+    fn to_repr(self) -> Self::Repr;
+
+    /// Convert an integer back to the enum, returning `None` for unknown values
+    fn from_repr(repr: Self::Repr) -> Option<Self>;
+
+    /// Convert an integer back to the enum, returning an error for unknown values
+    fn try_from_repr(repr: Self::Repr) -> Result<Self, anyhow::Error> {
+        Self::from_repr(repr).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Invalid value {:?} for {}",
+                repr,
+                std::any::type_name::<Self>()
+            )
+        })
+    }
+}
+
+// imported tables
+
+impl<T: TableEnum> seal::Sealed for T {}
+
+impl<T: TableEnum> TableData for T {
+    const TYPE_ID: FieldTypeId = <T::Repr as TableData>::TYPE_ID;
+
+    fn to_data(&self) -> ss_plugin_state_data {
+        TableData::to_data(&self.to_repr())
+    }
+}
+
+impl<T: TableEnum> Value for T {
+    type AssocData = ();
+    type Value<'a> = T;
+
+    unsafe fn from_data_with_assoc<'a>(
+        data: &ss_plugin_state_data,
+        _assoc: &Self::AssocData,
+    ) -> Self::Value<'a> {
+        let repr = unsafe { *<T::Repr as Key>::from_data(data) };
+        T::from_repr(repr).expect("enum value should have been validated")
+    }
+
+    unsafe fn validate_data(data: &ss_plugin_state_data) -> Result<(), anyhow::Error> {
+        let repr = unsafe { *<T::Repr as Key>::from_data(data) };
+        T::try_from_repr(repr).map(|_| ())
+    }
+
+    unsafe fn get_assoc_from_raw_table(
+        _table: &RawTable,
+        _field: *mut ss_plugin_table_field_t,
+        _tables_input: &TablesInput,
+    ) -> Result<Self::AssocData, anyhow::Error> {
+        Ok(())
+    }
+}
+
+// exported tables
+
+impl<T: TableEnum> export_seal::Sealed for T {}
+
+impl<T: TableEnum> FieldValue for T {
+    fn to_data(
+        &self,
+        out: &mut ss_plugin_state_data,
+        type_id: FieldTypeId,
+    ) -> Result<(), anyhow::Error> {
+        FieldValue::to_data(&self.to_repr(), out, type_id)
+    }
+}
+
+impl<T: TableEnum> StaticField for T {
+    const TYPE_ID: FieldTypeId = <T::Repr as StaticField>::TYPE_ID;
+    const READONLY: bool = false;
+}
+
+impl<T: TableEnum> FromDynamicFieldValue for T {
+    fn from_dynamic_field_value(value: DynamicFieldValue) -> Result<Self, anyhow::Error> {
+        T::try_from_repr(T::Repr::try_from(value)?)
+    }
+}
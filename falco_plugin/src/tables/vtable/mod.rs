@@ -3,9 +3,11 @@ use falco_plugin_api::{
     ss_plugin_init_input, ss_plugin_owner_t, ss_plugin_rc, ss_plugin_state_type,
     ss_plugin_table_info, ss_plugin_table_input, ss_plugin_table_t,
 };
+use std::ffi::CStr;
 use thiserror::Error;
 
 pub mod fields;
+pub(crate) mod phase;
 pub mod reader;
 pub mod writer;
 
@@ -103,9 +105,9 @@ impl TablesInput<'_> {
 impl TablesInput<'_> {
     /// # List the available tables
     ///
-    /// **Note**: this method is of limited utility in actual plugin code (you know the tables you
-    /// want to access), so it returns the unmodified structure from the plugin API, including
-    /// raw pointers to C-style strings. This may change later.
+    /// **Note**: this method returns the unmodified structure from the plugin API, including
+    /// raw pointers to C-style strings. See [`TablesInput::list_table_info`] for a safe
+    /// alternative.
     pub fn list_tables(&self) -> &[ss_plugin_table_info] {
         let mut num_tables = 0u32;
         let tables = unsafe { (self.list_tables)(self.owner, &mut num_tables as *mut _) };
@@ -115,4 +117,17 @@ impl TablesInput<'_> {
             unsafe { std::slice::from_raw_parts(tables, num_tables as usize) }
         }
     }
+
+    /// # Check whether a table exists
+    ///
+    /// Use this to check for tables exported by other plugins that may or may not be loaded.
+    pub fn table_exists(&self, name: &CStr) -> bool {
+        self.find_table_info(name).is_some()
+    }
+
+    pub(crate) fn find_table_info(&self, name: &CStr) -> Option<&ss_plugin_table_info> {
+        self.list_tables()
+            .iter()
+            .find(|info| !info.name.is_null() && unsafe { CStr::from_ptr(info.name) } == name)
+    }
 }
@@ -0,0 +1,82 @@
+//! # Runtime checks for table access control
+//!
+//! The access rules are mostly enforced at compile time, by only handing out a
+//! [`TableWriter`](`super::writer::TableWriter`) where writes are allowed. In debug builds,
+//! the framework additionally marks the read-only phases (like field extraction), so that
+//! a table write that slips through (e.g. via a writer smuggled out of its callback with
+//! `unsafe` code) panics with a clear message instead of failing with an opaque error code.
+//!
+//! In release builds, all of this compiles to nothing.
+
+#[cfg(debug_assertions)]
+use std::cell::Cell;
+
+#[cfg(debug_assertions)]
+thread_local! {
+    static READ_ONLY_PHASE: Cell<Option<&'static str>> = const { Cell::new(None) };
+}
+
+/// Marks tables as read-only on the current thread, until dropped
+#[must_use]
+pub(crate) struct ReadOnlyPhase {
+    #[cfg(debug_assertions)]
+    prev: Option<&'static str>,
+}
+
+impl ReadOnlyPhase {
+    /// Enter a read-only phase, described as `phase` in the panic message
+    #[cfg_attr(not(debug_assertions), allow(unused_variables))]
+    pub(crate) fn enter(phase: &'static str) -> Self {
+        Self {
+            #[cfg(debug_assertions)]
+            prev: READ_ONLY_PHASE.replace(Some(phase)),
+        }
+    }
+}
+
+impl Drop for ReadOnlyPhase {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        READ_ONLY_PHASE.set(self.prev);
+    }
+}
+
+/// Panic if tables are read-only on the current thread
+#[inline]
+#[track_caller]
+pub(crate) fn assert_writable() {
+    #[cfg(debug_assertions)]
+    if let Some(phase) = READ_ONLY_PHASE.get() {
+        panic!("Attempted to write to a table during {phase}, where tables are read-only");
+    }
+}
+
+#[cfg(all(test, debug_assertions))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_only_phase() {
+        assert_writable();
+
+        {
+            let _outer = ReadOnlyPhase::enter("field extraction");
+            let err = std::panic::catch_unwind(assert_writable).unwrap_err();
+            let msg = err.downcast_ref::<String>().unwrap();
+            assert!(msg.contains("during field extraction"), "{msg}");
+
+            {
+                let _inner = ReadOnlyPhase::enter("event generation");
+                let err = std::panic::catch_unwind(assert_writable).unwrap_err();
+                let msg = err.downcast_ref::<String>().unwrap();
+                assert!(msg.contains("during event generation"), "{msg}");
+            }
+
+            let err = std::panic::catch_unwind(assert_writable).unwrap_err();
+            let msg = err.downcast_ref::<String>().unwrap();
+            assert!(msg.contains("during field extraction"), "{msg}");
+        }
+
+        assert_writable();
+    }
+}
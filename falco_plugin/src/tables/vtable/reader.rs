@@ -229,6 +229,29 @@ pub struct ValidatedTableReader<'t> {
     lifetime: PhantomData<&'t ()>,
 }
 
+impl ValidatedTableReader<'_> {
+    /// Drop the lifetime of the reader
+    ///
+    /// The reader only holds function pointers (and the plugin owner pointer),
+    /// which remain valid for as long as the plugin is loaded.
+    ///
+    /// # Safety
+    /// The returned reader must not outlive the plugin.
+    #[cfg(feature = "thread-safe-tables")]
+    pub(crate) unsafe fn into_static(self) -> ValidatedTableReader<'static> {
+        ValidatedTableReader {
+            get_table_name: self.get_table_name,
+            get_table_size: self.get_table_size,
+            get_table_entry: self.get_table_entry,
+            read_entry_field: self.read_entry_field,
+            release_table_entry: self.release_table_entry,
+            iterate_entries: self.iterate_entries,
+            last_error: self.last_error,
+            lifetime: PhantomData,
+        }
+    }
+}
+
 impl private::TableReaderImpl for ValidatedTableReader<'_> {
     type Error = std::convert::Infallible;
 
@@ -1,4 +1,5 @@
 use crate::error::last_error::LastError;
+use crate::tables::vtable::phase::assert_writable;
 use crate::tables::vtable::TableError;
 use crate::tables::vtable::TableError::BadVtable;
 use falco_plugin_api::{
@@ -131,6 +132,7 @@ impl private::TableWriterImpl for LazyTableWriter<'_> {
     type Error = TableError;
 
     unsafe fn clear_table(&self, t: *mut ss_plugin_table_t) -> Result<ss_plugin_rc, TableError> {
+        assert_writable();
         unsafe {
             Ok(self
                 .writer_ext
@@ -144,6 +146,7 @@ impl private::TableWriterImpl for LazyTableWriter<'_> {
         t: *mut ss_plugin_table_t,
         key: *const ss_plugin_state_data,
     ) -> Result<ss_plugin_rc, TableError> {
+        assert_writable();
         unsafe {
             Ok(self
                 .writer_ext
@@ -158,6 +161,7 @@ impl private::TableWriterImpl for LazyTableWriter<'_> {
         &self,
         t: *mut ss_plugin_table_t,
     ) -> Result<*mut ss_plugin_table_entry_t, TableError> {
+        assert_writable();
         unsafe {
             Ok(self
                 .writer_ext
@@ -192,6 +196,7 @@ impl private::TableWriterImpl for LazyTableWriter<'_> {
         key: *const ss_plugin_state_data,
         entry: *mut ss_plugin_table_entry_t,
     ) -> Result<*mut ss_plugin_table_entry_t, TableError> {
+        assert_writable();
         unsafe {
             Ok(self
                 .writer_ext
@@ -209,6 +214,7 @@ impl private::TableWriterImpl for LazyTableWriter<'_> {
         f: *const ss_plugin_table_field_t,
         in_: *const ss_plugin_state_data,
     ) -> Result<ss_plugin_rc, TableError> {
+        assert_writable();
         unsafe {
             Ok(self
                 .writer_ext
@@ -254,10 +260,34 @@ pub struct ValidatedTableWriter<'t> {
     lifetime: PhantomData<&'t ()>,
 }
 
+impl ValidatedTableWriter<'_> {
+    /// Drop the lifetime of the writer
+    ///
+    /// The writer only holds function pointers (and the plugin owner pointer),
+    /// which remain valid for as long as the plugin is loaded.
+    ///
+    /// # Safety
+    /// The returned writer must not outlive the plugin.
+    #[cfg(feature = "thread-safe-tables")]
+    pub(crate) unsafe fn into_static(self) -> ValidatedTableWriter<'static> {
+        ValidatedTableWriter {
+            clear_table: self.clear_table,
+            erase_table_entry: self.erase_table_entry,
+            create_table_entry: self.create_table_entry,
+            destroy_table_entry: self.destroy_table_entry,
+            add_table_entry: self.add_table_entry,
+            write_entry_field: self.write_entry_field,
+            last_error: self.last_error,
+            lifetime: PhantomData,
+        }
+    }
+}
+
 impl private::TableWriterImpl for ValidatedTableWriter<'_> {
     type Error = std::convert::Infallible;
 
     unsafe fn clear_table(&self, t: *mut ss_plugin_table_t) -> Result<ss_plugin_rc, Self::Error> {
+        assert_writable();
         unsafe { Ok((self.clear_table)(t)) }
     }
 
@@ -266,6 +296,7 @@ impl private::TableWriterImpl for ValidatedTableWriter<'_> {
         t: *mut ss_plugin_table_t,
         key: *const ss_plugin_state_data,
     ) -> Result<ss_plugin_rc, Self::Error> {
+        assert_writable();
         unsafe { Ok((self.erase_table_entry)(t, key)) }
     }
 
@@ -273,6 +304,7 @@ impl private::TableWriterImpl for ValidatedTableWriter<'_> {
         &self,
         t: *mut ss_plugin_table_t,
     ) -> Result<*mut ss_plugin_table_entry_t, Self::Error> {
+        assert_writable();
         unsafe { Ok((self.create_table_entry)(t)) }
     }
 
@@ -298,6 +330,7 @@ impl private::TableWriterImpl for ValidatedTableWriter<'_> {
         key: *const ss_plugin_state_data,
         entry: *mut ss_plugin_table_entry_t,
     ) -> Result<*mut ss_plugin_table_entry_t, Self::Error> {
+        assert_writable();
         unsafe { Ok((self.add_table_entry)(t, key, entry)) }
     }
 
@@ -308,6 +341,7 @@ impl private::TableWriterImpl for ValidatedTableWriter<'_> {
         f: *const ss_plugin_table_field_t,
         in_: *const ss_plugin_state_data,
     ) -> Result<ss_plugin_rc, Self::Error> {
+        assert_writable();
         unsafe { Ok((self.write_entry_field)(t, e, f, in_)) }
     }
 
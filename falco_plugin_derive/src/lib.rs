@@ -1,7 +1,8 @@
 #![doc = include_str!("../README.md")]
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::{parse_macro_input, DeriveInput};
 
 fn ident_to_cstr(ident: &Ident) -> syn::LitCStr {
@@ -13,13 +14,73 @@ fn ident_to_cstr(ident: &Ident) -> syn::LitCStr {
     )
 }
 
-fn ident_to_bstr(ident: &Ident) -> syn::LitByteStr {
-    let mut name = ident.to_string();
-    name.push('\0');
-    syn::LitByteStr::new(name.as_bytes(), ident.span())
+/// Parse the arguments of the `#[name(...)]` attribute, if present
+///
+/// `expected` describes the expected arguments in the error message for malformed ones.
+fn parse_attr_args<T: syn::parse::Parse>(
+    attrs: &[syn::Attribute],
+    name: &str,
+    expected: &str,
+) -> syn::Result<Option<T>> {
+    let Some(attr) = attrs.iter().find(|a| a.path().is_ident(name)) else {
+        return Ok(None);
+    };
+
+    attr.parse_args()
+        .map(Some)
+        .map_err(|_| syn::Error::new_spanned(attr, format!("expected {expected}")))
+}
+
+/// Check for a `#[name]` attribute without arguments
+fn has_flag_attr(attrs: &[syn::Attribute], name: &str) -> syn::Result<bool> {
+    let Some(attr) = attrs.iter().find(|a| a.path().is_ident(name)) else {
+        return Ok(false);
+    };
+
+    match attr.meta {
+        syn::Meta::Path(_) => Ok(true),
+        _ => Err(syn::Error::new_spanned(
+            attr,
+            format!("`#[{name}]` does not take any arguments"),
+        )),
+    }
+}
+
+/// Parse `#[name = "..."]` (or `#[name(c"...")]`, as used for imported tables) on an exported field
+fn parse_export_field_name(attr: &syn::Attribute) -> syn::Result<String> {
+    let name = match &attr.meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: syn::Lit::Str(name),
+                    ..
+                }),
+            ..
+        }) => name.value(),
+        syn::Meta::List(_) => attr
+            .parse_args::<syn::LitCStr>()?
+            .value()
+            .to_string_lossy()
+            .into_owned(),
+        _ => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "expected a string literal, e.g. `#[name = \"field_name\"]`",
+            ))
+        }
+    };
+
+    if name.is_empty() || name.contains('\0') {
+        return Err(syn::Error::new_spanned(
+            attr,
+            "field names must be non-empty and cannot contain NUL bytes",
+        ));
+    }
+
+    Ok(name)
 }
 
-#[proc_macro_derive(Entry)]
+#[proc_macro_derive(Entry, attributes(name, skip, default))]
 pub fn derive_entry(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -46,29 +107,111 @@ pub fn derive_entry(input: TokenStream) -> TokenStream {
 
     let fields = fields.named;
 
-    let static_fields = fields.iter().enumerate().map(|(i, f)| {
+    let mut static_fields = Vec::new();
+    let mut skipped_fields = Vec::new();
+    let mut exported_types = Vec::new();
+    let mut exported_names = std::collections::BTreeSet::new();
+    for f in &fields {
         let field_name = f.ident.as_ref().unwrap();
-        let field_name_bstr = ident_to_bstr(field_name);
+        let is_skipped = match has_flag_attr(&f.attrs, "skip") {
+            Ok(is_skipped) => is_skipped,
+            Err(e) => return TokenStream::from(e.to_compile_error()),
+        };
+        let name_attr = f.attrs.iter().find(|a| a.path().is_ident("name"));
+
+        let default = match f.attrs.iter().find(|a| a.path().is_ident("default")) {
+            Some(attr) => match attr.parse_args::<syn::Expr>() {
+                Ok(default) => Some(quote!(= #default)),
+                Err(_) => {
+                    return TokenStream::from(
+                        syn::Error::new_spanned(
+                            attr,
+                            "expected an expression, e.g. `#[default(42)]`",
+                        )
+                        .to_compile_error(),
+                    )
+                }
+            },
+            None => None,
+        };
+
+        if is_skipped {
+            if let Some(attr) = name_attr {
+                return TokenStream::from(
+                    syn::Error::new_spanned(
+                        attr,
+                        "`#[skip]` fields are not exported and cannot be renamed",
+                    )
+                    .to_compile_error(),
+                );
+            }
+            skipped_fields.push(quote!(skip #field_name #default;));
+            continue;
+        }
+
+        let exported_name = match name_attr.map(parse_export_field_name) {
+            Some(Ok(name)) => name,
+            Some(Err(e)) => return TokenStream::from(e.to_compile_error()),
+            None => field_name.to_string(),
+        };
+        if !exported_names.insert(exported_name.clone()) {
+            return TokenStream::from(
+                syn::Error::new_spanned(
+                    name_attr.map_or_else(|| quote!(#field_name), |attr| quote!(#attr)),
+                    format!("duplicate exported field name `{exported_name}`"),
+                )
+                .to_compile_error(),
+            );
+        }
+
+        let mut field_name_bstr = exported_name.into_bytes();
+        field_name_bstr.push(0);
+        let field_name_bstr = syn::LitByteStr::new(&field_name_bstr, field_name.span());
+
         let tag = format!("{}.{}\0", input.ident, field_name);
         let field_tag = syn::LitCStr::new(
             std::ffi::CStr::from_bytes_with_nul(tag.as_bytes()).unwrap(),
             field_name.span(),
         );
 
+        let i = static_fields.len();
         let ty = &f.ty;
-        quote!( [#i] #field_tag (#field_name_bstr) as #field_name: #ty)
-    });
+        static_fields
+            .push(quote!( [#i] #field_tag (#field_name_bstr) as #field_name: #ty #default;));
+        exported_types.push(ty);
+    }
 
-    quote!(::falco_plugin::impl_export_table!(
-        for #name
-        {
-            #(#static_fields)*
-        }
-    );)
+    // check the field types up front, so that unsupported types get reported
+    // at the offending field rather than somewhere deep in the generated code
+    let field_checks = exported_types
+        .iter()
+        .map(|ty| quote_spanned!(ty.span()=> check_field::<#ty>();));
+
+    quote!(
+        const _: () = {
+            fn check_field<T: ::falco_plugin::tables::export::HasMetadata>() {}
+
+            #[allow(dead_code)]
+            fn check_fields() {
+                #(#field_checks)*
+            }
+        };
+
+        ::falco_plugin::impl_export_table!(
+            for #name
+            {
+                #(#static_fields)*
+                #(#skipped_fields)*
+            }
+        );
+    )
     .into()
 }
 
-#[proc_macro_derive(TableMetadata, attributes(entry_type, accessors_mod, name, custom))]
+#[proc_macro_derive(
+    TableMetadata,
+    attributes(entry_type, key_type, accessors_mod, name, custom, skip)
+)]
 pub fn derive_table_metadata(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let syn::Data::Struct(data) = input.data else {
@@ -94,51 +237,126 @@ pub fn derive_table_metadata(input: TokenStream) -> TokenStream {
 
     let fields = fields.named;
 
-    let metadata_macro_args = fields.iter().filter_map(|f| {
-        let field = f.ident.as_ref()?;
-        let field_name = f
-            .attrs
-            .iter()
-            .filter(|a| a.path().is_ident("name"))
-            .filter_map(|a| a.parse_args::<syn::LitCStr>().ok())
-            .next()
-            .unwrap_or_else(|| ident_to_cstr(field));
+    let mut metadata_macro_args = Vec::new();
+    let mut accessor_fields = Vec::new();
+    for f in &fields {
+        let Some(field) = f.ident.as_ref() else {
+            continue;
+        };
 
-        let is_custom = f.attrs.iter().any(|f| f.path().is_ident("custom"));
+        let (is_skipped, is_custom) = match (
+            has_flag_attr(&f.attrs, "skip"),
+            has_flag_attr(&f.attrs, "custom"),
+        ) {
+            (Ok(is_skipped), Ok(is_custom)) => (is_skipped, is_custom),
+            (Err(e), _) | (_, Err(e)) => return TokenStream::from(e.to_compile_error()),
+        };
+        let name_attr = f.attrs.iter().find(|a| a.path().is_ident("name"));
+
+        if is_skipped {
+            if let Some(attr) = name_attr.or(f.attrs.iter().find(|a| a.path().is_ident("custom"))) {
+                return TokenStream::from(
+                    syn::Error::new_spanned(
+                        attr,
+                        "`#[skip]` fields are not stored in the table and cannot have other attributes",
+                    )
+                    .to_compile_error(),
+                );
+            }
+            metadata_macro_args.push(quote!(skip(#field)));
+            continue;
+        }
+
+        let field_name = match name_attr {
+            Some(attr) => match attr.parse_args::<syn::LitCStr>() {
+                Ok(name) => name,
+                Err(_) => {
+                    return TokenStream::from(
+                        syn::Error::new_spanned(
+                            attr,
+                            "expected a C string literal, e.g. `#[name(c\"field_name\")]`",
+                        )
+                        .to_compile_error(),
+                    )
+                }
+            },
+            None => ident_to_cstr(field),
+        };
+
+        let is_optional = match &f.ty {
+            syn::Type::Path(ty) => ty
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "Option"),
+            _ => false,
+        };
 
         if is_custom {
-            Some(quote!(add_field(#field, #field_name)))
+            if is_optional {
+                return TokenStream::from(
+                    syn::Error::new_spanned(
+                        &f.ty,
+                        "`#[custom]` fields are always added to the table and cannot be optional",
+                    )
+                    .to_compile_error(),
+                );
+            }
+            metadata_macro_args.push(quote!(add_field(#field, #field_name)));
+        } else if is_optional {
+            metadata_macro_args.push(quote!(get_optional_field(#field, #field_name)));
         } else {
-            Some(quote!(get_field(#field, #field_name)))
+            metadata_macro_args.push(quote!(get_field(#field, #field_name)));
         }
-    });
+        accessor_fields.push(f);
+    }
 
-    let impl_table_metadata = quote!(falco_plugin::impl_import_table_metadata!(
-        for #name => {
-            #(#metadata_macro_args;)*
-        }
-    ););
+    let key_type = match parse_attr_args::<syn::Type>(
+        &input.attrs,
+        "key_type",
+        "a type, e.g. `#[key_type(u64)]`",
+    ) {
+        Ok(key_type) => key_type,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
 
-    let entry_type = input
-        .attrs
-        .iter()
-        .filter(|a| a.path().is_ident("entry_type"))
-        .filter_map(|a| a.parse_args::<Ident>().ok())
-        .next();
+    let impl_table_metadata = match key_type {
+        Some(key_type) => quote!(falco_plugin::impl_import_table_metadata!(
+            for #name => key #key_type; {
+                #(#metadata_macro_args;)*
+            }
+        );),
+        None => quote!(falco_plugin::impl_import_table_metadata!(
+            for #name => {
+                #(#metadata_macro_args;)*
+            }
+        );),
+    };
 
-    let accessors_mod = input
-        .attrs
-        .iter()
-        .filter(|a| a.path().is_ident("accessors_mod"))
-        .filter_map(|a| a.parse_args::<Ident>().ok())
-        .next()
-        .unwrap_or_else(|| Ident::new(&format!("__falco_plugin_private_{name}"), name.span()));
+    let entry_type = match parse_attr_args::<Ident>(
+        &input.attrs,
+        "entry_type",
+        "a type name, e.g. `#[entry_type(ImportedEntry)]`",
+    ) {
+        Ok(entry_type) => entry_type,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    };
+
+    let accessors_mod = match parse_attr_args::<Ident>(
+        &input.attrs,
+        "accessors_mod",
+        "a module name, e.g. `#[accessors_mod(accessors)]`",
+    ) {
+        Ok(accessors_mod) => accessors_mod,
+        Err(e) => return TokenStream::from(e.to_compile_error()),
+    }
+    .unwrap_or_else(|| Ident::new(&format!("__falco_plugin_private_{name}"), name.span()));
 
     let mut field_traits = Vec::new();
     let mut field_trait_impls = vec![impl_table_metadata];
 
     if let Some(entry_type) = entry_type {
-        for f in fields {
+        for f in accessor_fields {
             let Some(field_name) = f.ident.as_ref() else {
                 continue;
             };
@@ -166,6 +384,7 @@ pub fn derive_table_metadata(input: TokenStream) -> TokenStream {
 
     quote!(
         #[allow(non_snake_case)]
+        #[doc = concat!("Field accessors generated from [`", stringify!(#name), "`]")]
         pub mod #accessors_mod {
             #(#field_traits)*
         }
@@ -177,3 +396,182 @@ pub fn derive_table_metadata(input: TokenStream) -> TokenStream {
     )
     .into()
 }
+
+fn camel_to_snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+    }
+    snake
+}
+
+#[proc_macro_derive(AsyncEventNames, attributes(name))]
+pub fn derive_async_event_names(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let syn::Data::Enum(data) = input.data else {
+        return TokenStream::from(
+            syn::Error::new(
+                input.ident.span(),
+                "Only enums with unit variants can derive `AsyncEventNames`",
+            )
+            .to_compile_error(),
+        );
+    };
+
+    let name = &input.ident;
+    let mut variants = Vec::new();
+    let mut names = Vec::new();
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, syn::Fields::Unit) {
+            return TokenStream::from(
+                syn::Error::new(
+                    variant.ident.span(),
+                    "Only enums with unit variants can derive `AsyncEventNames`",
+                )
+                .to_compile_error(),
+            );
+        }
+
+        let event_name = match parse_attr_args::<syn::LitCStr>(
+            &variant.attrs,
+            "name",
+            "a C string literal, e.g. `#[name(c\"event_name\")]`",
+        ) {
+            Ok(event_name) => event_name,
+            Err(e) => return TokenStream::from(e.to_compile_error()),
+        }
+        .unwrap_or_else(|| {
+            let mut event_name = camel_to_snake_case(&variant.ident.to_string());
+            event_name.push('\0');
+            syn::LitCStr::new(
+                std::ffi::CStr::from_bytes_with_nul(event_name.as_bytes()).unwrap(),
+                variant.ident.span(),
+            )
+        });
+        let event_name_str =
+            syn::LitStr::new(&event_name.value().to_string_lossy(), event_name.span());
+
+        let ident = &variant.ident;
+        variants.push(quote!(Self::#ident => #event_name));
+        names.push(event_name_str);
+    }
+
+    quote!(
+        impl ::falco_plugin::async_event::AsyncEventNames for #name {
+            const NAMES: &'static [&'static str] = &[#(#names),*];
+
+            fn name(self) -> &'static ::std::ffi::CStr {
+                match self {
+                    #(#variants,)*
+                }
+            }
+        }
+    )
+    .into()
+}
+
+/// A single step of a `table_path!` invocation: `.field` or `.field[key]`
+struct TablePathStep {
+    field: Ident,
+    key: Option<syn::Expr>,
+}
+
+/// The input to `table_path!`: `reader, table => [key].field[key].field`
+struct TablePath {
+    reader: syn::Expr,
+    table: syn::Expr,
+    key: syn::Expr,
+    steps: Vec<TablePathStep>,
+}
+
+fn parse_table_path_key(input: syn::parse::ParseStream) -> syn::Result<syn::Expr> {
+    let content;
+    syn::bracketed!(content in input);
+    content.parse()
+}
+
+impl syn::parse::Parse for TablePath {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
+        let reader = input.parse()?;
+        input.parse::<syn::Token![,]>()?;
+        let table = input.parse()?;
+        input.parse::<syn::Token![=>]>()?;
+        let key = parse_table_path_key(input)?;
+
+        let mut steps = Vec::new();
+        while !input.is_empty() {
+            if let Some(TablePathStep { field, key: None }) = steps.last() {
+                return Err(syn::Error::new(
+                    field.span(),
+                    "only the last field in the path can be read without a key",
+                ));
+            }
+
+            input.parse::<syn::Token![.]>()?;
+            let field = input.parse()?;
+            let key = if input.peek(syn::token::Bracket) {
+                Some(parse_table_path_key(input)?)
+            } else {
+                None
+            };
+            steps.push(TablePathStep { field, key });
+        }
+
+        Ok(Self {
+            reader,
+            table,
+            key,
+            steps,
+        })
+    }
+}
+
+#[proc_macro]
+pub fn table_path(input: TokenStream) -> TokenStream {
+    let TablePath {
+        reader,
+        table,
+        key,
+        steps,
+    } = parse_macro_input!(input as TablePath);
+
+    let span = proc_macro2::Span::mixed_site();
+    let reader_var = Ident::new("reader", span);
+    let entry_var = Ident::new("entry", span);
+
+    let mut lookups = Vec::new();
+    let mut result = quote!(#entry_var);
+    for TablePathStep { field, key } in steps {
+        match key {
+            Some(key) => {
+                let getter = Ident::new(&format!("get_{field}_by_key"), field.span());
+                lookups.push(quote!(
+                    let #entry_var = #entry_var.#getter(#reader_var, &(#key)).ok()?;
+                ));
+            }
+            None => {
+                let getter = Ident::new(&format!("get_{field}"), field.span());
+                result = quote!(#entry_var.#getter(#reader_var).ok()?.to_owned());
+            }
+        }
+    }
+
+    quote!(
+        (|| -> ::std::option::Option<_> {
+            let #reader_var = #reader;
+            let #entry_var = (#table).get_entry(#reader_var, &(#key)).ok()?;
+            #(#lookups)*
+            ::std::option::Option::Some(#result)
+        })()
+    )
+    .into()
+}
@@ -0,0 +1,78 @@
+use crate::event::Event;
+use crate::plugin::ExtractedField;
+use falco_plugin_api::ss_plugin_rc;
+use std::ffi::CStr;
+use std::ops::Range;
+
+/// # A single interaction between the runner and a plugin
+///
+/// Hooks registered with [`PluginRunner::add_hook`](`crate::PluginRunner::add_hook`) receive
+/// one of these for every call into a plugin made while processing events, in the order
+/// the calls happen.
+#[derive(Debug)]
+pub enum HookEvent<'a> {
+    /// A source or async plugin was asked for the next event
+    ///
+    /// Plugins that have no event available return a timeout, in which case the runner moves
+    /// on to the next plugin.
+    NextEvent {
+        /// The name of the plugin
+        plugin: &'a CStr,
+        /// The event (with the event number already assigned) or the error returned
+        result: &'a anyhow::Result<Event>,
+    },
+
+    /// An event is about to be passed to a parse plugin
+    ///
+    /// This is only emitted for plugins that actually get called, i.e. ones that support
+    /// parsing and have subscribed to the event type and source.
+    BeforeParse {
+        /// The name of the plugin
+        plugin: &'a CStr,
+        /// The event being parsed
+        event: &'a Event,
+    },
+
+    /// A parse plugin has processed an event
+    AfterParse {
+        /// The name of the plugin
+        plugin: &'a CStr,
+        /// The event that was parsed
+        event: &'a Event,
+        /// The result of the parse call
+        result: &'a anyhow::Result<()>,
+    },
+
+    /// A field was extracted from an event
+    ///
+    /// This is emitted once, for the plugin that provides the field.
+    Extract {
+        /// The name of the plugin
+        plugin: &'a CStr,
+        /// The event the field was extracted from
+        event: &'a Event,
+        /// The name of the field (including the argument, if any)
+        field: &'a str,
+        /// The extracted value or the error code
+        result: Result<&'a ExtractedField, ss_plugin_rc>,
+        /// The range of the event the value came from, if it was requested
+        range: Option<&'a Range<usize>>,
+    },
+}
+
+pub(crate) type Hook = Box<dyn FnMut(&HookEvent)>;
+
+#[derive(Default)]
+pub(crate) struct Hooks(Vec<Hook>);
+
+impl Hooks {
+    pub(crate) fn add(&mut self, hook: Hook) {
+        self.0.push(hook);
+    }
+
+    pub(crate) fn call(&mut self, event: &HookEvent) {
+        for hook in &mut self.0 {
+            hook(event)
+        }
+    }
+}
@@ -1,4 +1,5 @@
 mod event;
+mod hooks;
 mod plugin;
 mod tables;
 
@@ -8,7 +9,9 @@ pub use crate::plugin::MetricType;
 pub use crate::plugin::MetricValue;
 pub use crate::plugin::ScapStatus;
 pub use event::Event;
+pub use hooks::HookEvent;
 
+use crate::hooks::Hooks;
 use crate::tables::Tables;
 use plugin::Plugin;
 use plugin::INVALID_RANGE;
@@ -17,14 +20,19 @@ use std::ffi::{CStr, CString};
 use std::ops::Range;
 use std::rc::Rc;
 
+/// Fields extracted by the runner itself, not by any plugin
+const BUILTIN_FIELDS: &[&str] = &["evt.plugininfo"];
+
 pub struct PluginRunner {
     plugins: Vec<Plugin>,
     tables: Rc<RefCell<Tables>>,
+    hooks: Hooks,
 }
 
 pub struct CapturingPluginRunner {
     plugins: Vec<Plugin>,
     tables: Rc<RefCell<Tables>>,
+    hooks: Hooks,
     evtnum: u64,
 }
 
@@ -39,6 +47,7 @@ impl PluginRunner {
         Self {
             plugins: vec![],
             tables: Rc::new(RefCell::new(Tables::new())),
+            hooks: Hooks::default(),
         }
     }
 
@@ -48,21 +57,56 @@ impl PluginRunner {
         config: &CStr,
     ) -> anyhow::Result<()> {
         let plugin = Plugin::new(plugin, Rc::clone(&self.tables), config)?;
+
+        for field in plugin.extract_field_names() {
+            if BUILTIN_FIELDS.contains(&field) {
+                anyhow::bail!(
+                    "Field {field} from plugin {:?} collides with a built-in field",
+                    plugin.name_cstr()
+                );
+            }
+
+            if let Some(other) = self
+                .plugins
+                .iter()
+                .find(|p| p.extract_field_names().any(|f| f == field))
+            {
+                anyhow::bail!(
+                    "Field {field} from plugin {:?} is already provided by plugin {:?}",
+                    plugin.name_cstr(),
+                    other.name_cstr()
+                );
+            }
+        }
+
         self.plugins.push(plugin);
 
         Ok(())
     }
 
-    pub fn start_capture(mut self) -> anyhow::Result<CapturingPluginRunner> {
+    /// Register a hook called for every interaction with the plugins during a capture
+    ///
+    /// Hooks are called in the order they were added and see every event being read, parsed
+    /// and extracted from, with the exact results returned by the plugins. This makes them
+    /// useful for recording the calls or asserting on them in tests (a hook can simply panic
+    /// if it sees something unexpected).
+    ///
+    /// The hooks are kept when the capture is stopped.
+    pub fn add_hook(&mut self, hook: impl FnMut(&HookEvent) + 'static) {
+        self.hooks.add(Box::new(hook));
+    }
+
+    pub fn start_capture(mut self, open_params: &CStr) -> anyhow::Result<CapturingPluginRunner> {
         for plugin in &mut self.plugins {
             plugin
-                .on_capture_start()
+                .on_capture_start(open_params)
                 .map_err(|e| anyhow::anyhow!("Got API error {e}"))?;
         }
 
         Ok(CapturingPluginRunner {
             plugins: self.plugins,
             tables: self.tables,
+            hooks: self.hooks,
             evtnum: 0,
         })
     }
@@ -79,18 +123,23 @@ impl CapturingPluginRunner {
         Ok(PluginRunner {
             plugins: std::mem::take(&mut self.plugins),
             tables: std::mem::take(&mut self.tables),
+            hooks: std::mem::take(&mut self.hooks),
         })
     }
 
     fn get_next_event(&mut self) -> anyhow::Result<Event> {
         self.evtnum += 1;
         for plugin in &mut self.plugins {
-            let event = plugin.next_event();
+            let event = plugin.next_event().map(|mut event| {
+                event.evt_num = Some(self.evtnum);
+                event
+            });
+            self.hooks.call(&HookEvent::NextEvent {
+                plugin: plugin.name_cstr(),
+                result: &event,
+            });
             match event {
-                Ok(mut event) => {
-                    event.evt_num = Some(self.evtnum);
-                    return Ok(event);
-                }
+                Ok(event) => return Ok(event),
                 Err(e) => match e.downcast_ref::<ScapStatus>() {
                     Some(ScapStatus::Timeout) => continue,
                     _ => return Err(e),
@@ -105,7 +154,22 @@ impl CapturingPluginRunner {
         let event = self.get_next_event()?;
 
         for plugin in &mut self.plugins {
-            plugin.on_event(&event)?;
+            if !plugin.parses(&event) {
+                continue;
+            }
+
+            let name = plugin.name_cstr();
+            self.hooks.call(&HookEvent::BeforeParse {
+                plugin: name,
+                event: &event,
+            });
+            let result = plugin.on_event(&event);
+            self.hooks.call(&HookEvent::AfterParse {
+                plugin: name,
+                event: &event,
+                result: &result,
+            });
+            result?;
         }
 
         Ok(event)
@@ -133,6 +197,13 @@ impl CapturingPluginRunner {
 
         for plugin in &mut self.plugins {
             if let Some(res) = plugin.extract_field(event, field) {
+                self.hooks.call(&HookEvent::Extract {
+                    plugin: plugin.name_cstr(),
+                    event,
+                    field,
+                    result: res.as_ref().map_err(|e| *e),
+                    range: None,
+                });
                 return Some(res);
             }
         }
@@ -163,6 +234,17 @@ impl CapturingPluginRunner {
 
         for plugin in &mut self.plugins {
             if let Some(res) = plugin.extract_field_with_range(event, field) {
+                let (result, range) = match &res {
+                    Ok((value, range)) => (Ok(value), Some(range)),
+                    Err(e) => (Err(*e), None),
+                };
+                self.hooks.call(&HookEvent::Extract {
+                    plugin: plugin.name_cstr(),
+                    event,
+                    field,
+                    result,
+                    range,
+                });
                 return Some(res);
             }
         }
@@ -170,12 +252,28 @@ impl CapturingPluginRunner {
         None
     }
 
+    pub fn dump_state(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        let mut events = Vec::new();
+        for plugin in &mut self.plugins {
+            events.extend(plugin.dump_state()?);
+        }
+
+        Ok(events)
+    }
+
     pub fn get_metrics(&mut self) -> Vec<Metric> {
         self.plugins
             .iter_mut()
             .flat_map(|p| p.get_metrics())
             .collect()
     }
+
+    /// Get the capture progress (as a percentage) and an optional description
+    ///
+    /// The progress is reported by the first source plugin that supports it
+    pub fn get_progress(&mut self) -> Option<(f64, Option<CString>)> {
+        self.plugins.iter_mut().find_map(|p| p.get_progress())
+    }
 }
 
 impl Drop for CapturingPluginRunner {
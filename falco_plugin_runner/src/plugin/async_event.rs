@@ -77,6 +77,26 @@ impl AsyncPlugin {
         }
     }
 
+    pub fn dump_state(&mut self) -> Result<Vec<Vec<u8>>, ss_plugin_rc> {
+        let dump_state = self
+            .api()
+            .dump_state
+            .ok_or(ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED)?;
+
+        let mut collector = DumpStateCollector {
+            async_events: &self.async_events,
+            events: Vec::new(),
+        };
+
+        let owner = &mut collector as *mut _ as *mut ss_plugin_owner_t;
+        let rc = unsafe { dump_state(self.plugin, owner, Some(dump_state_handler)) };
+        if rc == ss_plugin_rc_SS_PLUGIN_SUCCESS {
+            Ok(collector.events)
+        } else {
+            Err(rc)
+        }
+    }
+
     pub fn next_event(&mut self) -> Result<*mut ss_plugin_event, ss_plugin_rc> {
         self.last_event = self.event_queue.lock().unwrap().pop_front();
         match &self.last_event {
@@ -106,6 +126,43 @@ pub struct AsyncEvent<'a> {
     pub data: &'a [u8],
 }
 
+/// Validate an async event submitted by the plugin
+///
+/// Returns the raw event bytes or an error message to report back to the plugin
+///
+/// # Safety
+///
+/// `event` must point to a valid event (at least `(*event).len` bytes long)
+unsafe fn validate_async_event<'a>(
+    event: *const ss_plugin_event,
+    async_events: &[String],
+) -> Result<&'a [u8], String> {
+    let evt_len = unsafe { (*event).len as usize };
+
+    let event = event as *const u8;
+    let event = unsafe { std::slice::from_raw_parts(event, evt_len) };
+
+    let raw_event = RawEvent::from(event).map_err(|e| format!("Failed to parse event: {e}"))?;
+
+    let async_event = raw_event
+        .load::<AsyncEvent>()
+        .map_err(|e| format!("Failed to parse async event: {e}"))?;
+
+    let async_event_name = async_event
+        .params
+        .name
+        .to_str()
+        .map_err(|e| format!("Failed to decode async event name as UTF-8: {e}"))?;
+
+    if !async_events.iter().any(|evt| evt == async_event_name) {
+        return Err(format!(
+            "Event name mismatch, got {async_event_name:?}, expected any of {async_events:?}"
+        ));
+    }
+
+    Ok(event)
+}
+
 unsafe extern "C-unwind" fn async_handler(
     owner: *mut ss_plugin_owner_t,
     event: *const ss_plugin_event,
@@ -113,52 +170,40 @@ unsafe extern "C-unwind" fn async_handler(
 ) -> i32 {
     let err = unsafe { std::slice::from_raw_parts_mut(err as *mut _, PLUGIN_MAX_ERRLEN as usize) };
     let owner = unsafe { &mut *(owner as *mut AsyncPlugin) };
-    let evt_len = unsafe { (*event).len as usize };
-
-    let event = event as *const u8;
-    let event = unsafe { std::slice::from_raw_parts(event, evt_len) };
 
-    let raw_event = match RawEvent::from(event) {
-        Ok(event) => event,
-        Err(e) => {
-            write_err_msg(err, &format!("Failed to parse event: {e}"));
-            return ss_plugin_rc_SS_PLUGIN_FAILURE;
+    match unsafe { validate_async_event(event, &owner.async_events) } {
+        Ok(event) => {
+            owner.event_queue.lock().unwrap().push_back(event.to_vec());
+            ss_plugin_rc_SS_PLUGIN_SUCCESS
         }
-    };
-
-    let async_event = match raw_event.load::<AsyncEvent>() {
-        Ok(event) => event,
-        Err(e) => {
-            write_err_msg(err, &format!("Failed to parse async event: {e}"));
-            return ss_plugin_rc_SS_PLUGIN_FAILURE;
+        Err(msg) => {
+            write_err_msg(err, &msg);
+            ss_plugin_rc_SS_PLUGIN_FAILURE
         }
-    };
-
-    let async_event_name = async_event.params.name;
-
-    let async_event_name = match async_event_name.to_str() {
-        Ok(name) => name,
-        Err(e) => {
-            write_err_msg(
-                err,
-                &format!("Failed to decode async event name as UTF-8: {e}"),
-            );
-            return ss_plugin_rc_SS_PLUGIN_FAILURE;
-        }
-    };
-
-    if !owner.async_events.iter().any(|evt| evt == async_event_name) {
-        write_err_msg(
-            err,
-            &format!(
-                "Event name mismatch, got {:?}, expected any of {:?}",
-                async_event_name, owner.async_events
-            ),
-        );
-        return ss_plugin_rc_SS_PLUGIN_FAILURE;
     }
+}
 
-    owner.event_queue.lock().unwrap().push_back(event.to_vec());
+struct DumpStateCollector<'a> {
+    async_events: &'a [String],
+    events: Vec<Vec<u8>>,
+}
+
+unsafe extern "C-unwind" fn dump_state_handler(
+    owner: *mut ss_plugin_owner_t,
+    event: *const ss_plugin_event,
+    err: *mut c_char,
+) -> i32 {
+    let err = unsafe { std::slice::from_raw_parts_mut(err as *mut _, PLUGIN_MAX_ERRLEN as usize) };
+    let owner = unsafe { &mut *(owner as *mut DumpStateCollector) };
 
-    ss_plugin_rc_SS_PLUGIN_SUCCESS
+    match unsafe { validate_async_event(event, owner.async_events) } {
+        Ok(event) => {
+            owner.events.push(event.to_vec());
+            ss_plugin_rc_SS_PLUGIN_SUCCESS
+        }
+        Err(msg) => {
+            write_err_msg(err, &msg);
+            ss_plugin_rc_SS_PLUGIN_FAILURE
+        }
+    }
 }
@@ -326,6 +326,10 @@ impl ExtractPlugin {
         unsafe { &*self.api }
     }
 
+    pub fn field_names(&self) -> impl Iterator<Item = &str> {
+        self.fields.iter().map(|f| f.name.as_str())
+    }
+
     fn extract_impl(
         &self,
         event: &Event,
@@ -137,6 +137,16 @@ impl Plugin {
         }
     }
 
+    pub fn name_cstr(&self) -> &'static CStr {
+        let name = self.name();
+        if name.is_null() {
+            c""
+        } else {
+            // the plugin API requires the name to be a static string
+            unsafe { CStr::from_ptr(name) }
+        }
+    }
+
     pub fn source_name(&self) -> *const c_char {
         if let Some(get_source) = self.api().__bindgen_anon_1.get_event_source {
             let ptr = unsafe { get_source() };
@@ -288,9 +298,9 @@ impl Plugin {
         Ok(())
     }
 
-    pub fn on_capture_start(&mut self) -> anyhow::Result<()> {
+    pub fn on_capture_start(&mut self, open_params: &CStr) -> anyhow::Result<()> {
         if let Some(ref mut source) = self.source {
-            source.on_capture_start().map_err(|e| {
+            source.on_capture_start(open_params).map_err(|e| {
                 anyhow!(
                     "failed to start capture, rc {e}, err {:?}",
                     self.last_error()
@@ -354,6 +364,19 @@ impl Plugin {
         Ok(())
     }
 
+    pub fn dump_state(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        let Some(ref mut async_event) = self.async_event else {
+            return Ok(Vec::new());
+        };
+
+        async_event.dump_state().map_err(|e| {
+            anyhow!(
+                "failed to dump async plugin state, rc {e}, err {:?}",
+                self.last_error()
+            )
+        })
+    }
+
     pub fn get_metrics(&mut self) -> Vec<Metric> {
         let Some(get_metrics) = self.api().get_metrics else {
             return Vec::new();
@@ -403,6 +426,11 @@ impl Plugin {
         }).collect()
     }
 
+    pub fn get_progress(&mut self) -> Option<(f64, Option<CString>)> {
+        let (progress_pct, detail) = self.source.as_mut()?.get_progress()?;
+        Some((progress_pct as f64 / 100.0, detail))
+    }
+
     fn decorate_event(&mut self, buf: *mut ss_plugin_event) -> Event {
         Event {
             source: self.source_name(),
@@ -451,6 +479,18 @@ impl Plugin {
         Err(anyhow::anyhow!("no source/async plugin here")).context(ScapStatus::Timeout)
     }
 
+    pub fn extract_field_names(&self) -> impl Iterator<Item = &str> {
+        self.extract
+            .iter()
+            .flat_map(|extract| extract.field_names())
+    }
+
+    pub fn parses(&self, event: &Event) -> bool {
+        self.parse
+            .as_ref()
+            .is_some_and(|parse| parse.accepts(event))
+    }
+
     pub fn on_event(&mut self, event: &Event) -> anyhow::Result<()> {
         if let Some(ref mut parse) = self.parse {
             parse.on_event(event).map_err(|e| {
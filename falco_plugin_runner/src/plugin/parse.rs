@@ -30,8 +30,12 @@ impl ParsePlugin {
         unsafe { &*self.api }
     }
 
+    pub fn accepts(&self, event: &Event) -> bool {
+        self.filter.matches(event)
+    }
+
     pub fn on_event(&mut self, event: &Event) -> Result<(), falco_plugin_api::ss_plugin_rc> {
-        if !self.filter.matches(event) {
+        if !self.accepts(event) {
             return Ok(());
         }
 
@@ -1,4 +1,5 @@
 use falco_plugin_api::{plugin_api__bindgen_ty_1, ss_plugin_event, ss_plugin_rc, ss_plugin_t};
+use std::ffi::{CStr, CString};
 
 pub struct SourcePlugin {
     plugin: *mut ss_plugin_t,
@@ -7,6 +8,8 @@ pub struct SourcePlugin {
     event_batch: *mut *mut ss_plugin_event,
     batch_size: usize,
     current_event: usize,
+    /// Return code of the last `next_batch` call, reported once its events are consumed
+    batch_rc: ss_plugin_rc,
 }
 
 impl SourcePlugin {
@@ -18,6 +21,7 @@ impl SourcePlugin {
             event_batch: std::ptr::null_mut(),
             batch_size: 0,
             current_event: 0,
+            batch_rc: falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS,
         }
     }
 
@@ -25,15 +29,14 @@ impl SourcePlugin {
         unsafe { &*self.api }
     }
 
-    pub fn on_capture_start(&mut self) -> Result<(), ss_plugin_rc> {
+    pub fn on_capture_start(&mut self, open_params: &CStr) -> Result<(), ss_plugin_rc> {
         let open = self
             .api()
             .open
             .ok_or(falco_plugin_api::ss_plugin_rc_SS_PLUGIN_NOT_SUPPORTED)?;
 
-        let config = std::ptr::null(); // TODO
         let mut rc = 0i32;
-        let instance = unsafe { open(self.plugin, config, &mut rc) };
+        let instance = unsafe { open(self.plugin, open_params.as_ptr(), &mut rc) };
         if rc == falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS {
             self.instance = instance;
             Ok(())
@@ -53,6 +56,14 @@ impl SourcePlugin {
 
     pub fn next_event(&mut self) -> Result<*mut ss_plugin_event, ss_plugin_rc> {
         if self.current_event >= self.batch_size {
+            let rc = std::mem::replace(
+                &mut self.batch_rc,
+                falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS,
+            );
+            if rc != falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS {
+                return Err(rc);
+            }
+
             let next = self
                 .api()
                 .next_batch
@@ -68,9 +79,20 @@ impl SourcePlugin {
             };
             self.batch_size = nevts as usize;
             self.current_event = 0;
-            if rc != falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS {
-                self.batch_size = 0;
-                return Err(rc);
+            match rc {
+                falco_plugin_api::ss_plugin_rc_SS_PLUGIN_SUCCESS => {}
+                // like libscap, deliver the events that came with a timeout or EOF
+                // before reporting the return code
+                falco_plugin_api::ss_plugin_rc_SS_PLUGIN_TIMEOUT
+                | falco_plugin_api::ss_plugin_rc_SS_PLUGIN_EOF
+                    if self.batch_size > 0 =>
+                {
+                    self.batch_rc = rc;
+                }
+                _ => {
+                    self.batch_size = 0;
+                    return Err(rc);
+                }
             }
         }
 
@@ -82,4 +104,19 @@ impl SourcePlugin {
             Err(falco_plugin_api::ss_plugin_rc_SS_PLUGIN_TIMEOUT)
         }
     }
+
+    /// Get the capture progress as a percentage (0-10000, i.e. in hundredths of a percent)
+    /// and an optional description
+    pub fn get_progress(&mut self) -> Option<(u32, Option<CString>)> {
+        let get_progress = self.api().get_progress?;
+        let mut progress_pct = 0u32;
+        let detail = unsafe { get_progress(self.plugin, self.instance, &mut progress_pct) };
+        let detail = if detail.is_null() {
+            None
+        } else {
+            Some(unsafe { CStr::from_ptr(detail) }.to_owned())
+        };
+
+        Some((progress_pct, detail))
+    }
 }
@@ -5,6 +5,7 @@ use falco_plugin::base::Plugin;
 use falco_plugin::event::events::Event;
 use falco_plugin::event::PluginEvent;
 use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::smallvec::{smallvec, SmallVec};
 use falco_plugin::static_plugin;
 use falco_plugin::tables::TablesInput;
 use falco_plugin_tests::plugin_collection::source::batched_empty_event::{
@@ -34,19 +35,37 @@ impl ExtractStaticPlugin {
     fn extract_static(&mut self, _req: ExtractRequest<Self>) -> Result<u64, Error> {
         Ok(5)
     }
+
+    fn extract_static_vec(&mut self, _req: ExtractRequest<Self>) -> Result<Vec<u64>, Error> {
+        Ok(vec![5, 6, 7])
+    }
+
+    fn extract_static_smallvec(
+        &mut self,
+        _req: ExtractRequest<Self>,
+    ) -> Result<SmallVec<[u64; 4]>, Error> {
+        Ok(smallvec![5, 6, 7])
+    }
 }
 
 impl ExtractPlugin for ExtractStaticPlugin {
     type Event<'a> = Event<PluginEvent<&'a [u8]>>;
     type ExtractContext = ();
-    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] =
-        &[field("static.field", &Self::extract_static)];
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("static.field", &Self::extract_static),
+        field("static.vec", &Self::extract_static_vec),
+        field("static.smallvec", &Self::extract_static_smallvec),
+    ];
 }
 
 static_plugin!(EXTRACT_STATIC_PLUGIN = ExtractStaticPlugin);
 
-fn plugin_extract_static_impl<D: TestDriver, M: Measurement>(g: &mut BenchmarkGroup<M>) {
-    g.bench_function(D::NAME, |b| {
+fn plugin_extract_static_impl<D: TestDriver, M: Measurement>(
+    g: &mut BenchmarkGroup<M>,
+    id: &str,
+    field: &CStr,
+) {
+    g.bench_function(id, |b| {
         let (mut driver, _plugin) = init_plugin::<D>(&BATCHED_EMPTY_EVENT, c"1").unwrap();
         let extract_plugin = driver.register_plugin(&EXTRACT_STATIC_PLUGIN, c"").unwrap();
         driver
@@ -59,7 +78,7 @@ fn plugin_extract_static_impl<D: TestDriver, M: Measurement>(g: &mut BenchmarkGr
 
         b.iter(|| {
             for _ in 0..NUM_EVENTS {
-                match black_box(driver.extract_field(c"static.field", &event)) {
+                match black_box(driver.extract_field(field, &event)) {
                     Ok(_) => (),
                     Err(e) => panic!("Unexpected error: {e}"),
                 }
@@ -72,12 +91,36 @@ fn plugin_extract_static(c: &mut Criterion) {
     let mut g = c.benchmark_group("plugin_extract_static");
     g.throughput(Throughput::Elements(NUM_EVENTS as u64));
 
-    plugin_extract_static_impl::<falco_plugin_tests::native::Driver, _>(&mut g);
+    plugin_extract_static_impl::<falco_plugin_tests::native::Driver, _>(
+        &mut g,
+        falco_plugin_tests::native::Driver::NAME,
+        c"static.field",
+    );
+    #[cfg(have_libsinsp)]
+    plugin_extract_static_impl::<falco_plugin_tests::ffi::Driver, _>(
+        &mut g,
+        falco_plugin_tests::ffi::Driver::NAME,
+        c"static.field",
+    );
+
+    g.finish();
+}
+
+fn plugin_extract_list_impl<D: TestDriver, M: Measurement>(g: &mut BenchmarkGroup<M>) {
+    plugin_extract_static_impl::<D, _>(g, &format!("{}/vec", D::NAME), c"static.vec");
+    plugin_extract_static_impl::<D, _>(g, &format!("{}/smallvec", D::NAME), c"static.smallvec");
+}
+
+fn plugin_extract_list(c: &mut Criterion) {
+    let mut g = c.benchmark_group("plugin_extract_list");
+    g.throughput(Throughput::Elements(NUM_EVENTS as u64));
+
+    plugin_extract_list_impl::<falco_plugin_tests::native::Driver, _>(&mut g);
     #[cfg(have_libsinsp)]
-    plugin_extract_static_impl::<falco_plugin_tests::ffi::Driver, _>(&mut g);
+    plugin_extract_list_impl::<falco_plugin_tests::ffi::Driver, _>(&mut g);
 
     g.finish();
 }
 
-criterion_group!(benches, plugin_extract_static);
+criterion_group!(benches, plugin_extract_static, plugin_extract_list);
 criterion_main!(benches);
@@ -67,6 +67,42 @@ SinspEvent SinspTestDriver::next() {
   return SinspEvent{rc, reinterpret_cast<char *>(evt)};
 }
 
+void SinspTestDriver::write_capture_file(const char *path) {
+  std::scoped_lock m(s_sinsp_lock);
+
+  // opening the dumper writes the initial state (threads, containers,
+  // async plugin state via dump_state) to the capture file
+  sinsp_dumper dumper;
+  dumper.open(&m_sinsp, path, false);
+  dumper.close();
+}
+
+uint64_t SinspTestDriver::dump_capture_file(const char *path) {
+  std::scoped_lock m(s_sinsp_lock);
+
+  sinsp_dumper dumper;
+  dumper.open(&m_sinsp, path, false);
+
+  uint64_t num_events = 0;
+  while (true) {
+    sinsp_evt *evt;
+    int rc = m_sinsp.next(&evt);
+    if (rc == SCAP_TIMEOUT) {
+      continue;
+    } else if (rc == SCAP_EOF) {
+      break;
+    } else if (rc != SCAP_SUCCESS) {
+      throw sinsp_exception(m_sinsp.getlasterr());
+    }
+
+    dumper.dump(evt);
+    num_events++;
+  }
+
+  dumper.close();
+  return num_events;
+}
+
 std::unique_ptr<std::string>
 SinspTestDriver::event_field_as_string(const char *field_name,
                                        const SinspEvent &event) {
@@ -215,3 +251,8 @@ std::unique_ptr<std::vector<SinspMetric>> SinspTestDriver::get_metrics() {
 
   return std::make_unique<std::vector<SinspMetric>>(std::move(metrics));
 }
+
+double SinspTestDriver::get_progress(std::string &detail) {
+  std::scoped_lock m(s_sinsp_lock);
+  return m_sinsp.get_read_progress_with_str(&detail);
+}
@@ -3,6 +3,7 @@
 class SinspTestDriver;
 struct SinspMetric;
 
+#include <libsinsp/dumper.h>
 #include <libsinsp/plugin.h>
 #include <libsinsp/sinsp.h>
 #include <memory>
@@ -21,6 +22,8 @@ public:
   void load_capture_file(const char *path);
   void start_capture(const char *name, const char *config, bool platform_data);
   SinspEvent next();
+  void write_capture_file(const char *path);
+  uint64_t dump_capture_file(const char *path);
   std::unique_ptr<std::string> event_field_as_string(const char *field_name,
                                                      const SinspEvent &event);
   std::unique_ptr<std::string>
@@ -32,6 +35,7 @@ public:
                                           const SinspEvent &event);
 
   std::unique_ptr<std::vector<SinspMetric>> get_metrics();
+  double get_progress(std::string &detail);
 
 private:
   sinsp m_sinsp;
@@ -0,0 +1,31 @@
+#[cfg(not(have_libsinsp))]
+fn main() {
+    panic!("libsinsp not available");
+}
+
+#[cfg(have_libsinsp)]
+fn main() {
+    use falco_plugin_tests::plugin_collection::source::random_events::{
+        write_random_capture, RandomEventsConfig,
+    };
+    use std::ffi::CString;
+
+    let mut args = std::env::args().skip(1);
+    let usage = "usage: write_random_events <output.scap> <seed> <count> <event type>...";
+
+    let output_path = args.next().expect(usage);
+    let output_path = CString::new(output_path).unwrap();
+    let seed = args.next().expect(usage).parse().expect(usage);
+    let count = args.next().expect(usage).parse().expect(usage);
+    let event_types = args.map(|arg| arg.parse().expect(usage)).collect();
+
+    let config = RandomEventsConfig {
+        seed,
+        count,
+        event_types,
+    };
+
+    let num_events =
+        write_random_capture::<falco_plugin_tests::ffi::Driver>(&output_path, &config).unwrap();
+    eprintln!("{num_events} events written");
+}
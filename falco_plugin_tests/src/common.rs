@@ -29,11 +29,19 @@ unsafe impl ExternType for RawExtractedValue {
     type Kind = cxx::kind::Trivial;
 }
 
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct SinspMetric {
     pub name: String,
     pub value: u64, // TODO: this is... taking shortcuts
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaptureProgress {
+    /// Progress percentage (0.0-100.0)
+    pub value: f64,
+    pub detail: Option<String>,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum PlatformData {
     Enabled,
@@ -46,6 +54,9 @@ pub trait TestDriver: Debug + Sized {
 
     const NAME: &'static str;
 
+    /// Whether [`TestDriver::start_capture`] supports [`PlatformData::Enabled`]
+    const SUPPORTS_PLATFORM_DATA: bool;
+
     fn new() -> anyhow::Result<Self>;
 
     fn register_plugin(
@@ -73,9 +84,29 @@ pub trait TestDriver: Debug + Sized {
 }
 
 pub trait SavefileTestDriver: TestDriver {
+    /// Start replaying a capture file
+    ///
+    /// The returned driver supports the whole [`CapturingTestDriver`] API, including polling
+    /// plugin metrics (e.g. using [`CapturingTestDriver::metrics_history`]) during the replay.
     fn load_capture_file(self, path: &CStr) -> anyhow::Result<Self::Capturing>;
 }
 
+pub trait DumpStateTestDriver: CapturingTestDriver {
+    /// Invoke `dump_state` on all async plugins and return the raw events they emitted
+    fn dump_state(&mut self) -> anyhow::Result<Vec<Vec<u8>>>;
+}
+
+pub trait SavefileWriterTestDriver: CapturingTestDriver {
+    /// Write a capture file at `path`, including the state dumped by all async plugins
+    fn write_capture_file(&mut self, path: &CStr) -> anyhow::Result<()>;
+
+    /// Write a capture file at `path`, including the initial state and all the remaining
+    /// events from the live capture
+    ///
+    /// Returns the number of events written
+    fn dump_capture_file(&mut self, path: &CStr) -> anyhow::Result<usize>;
+}
+
 pub trait AsPtr {
     fn as_ptr(&self) -> *const u8;
 }
@@ -108,6 +139,37 @@ pub trait CapturingTestDriver {
 
     fn get_metrics(&mut self) -> anyhow::Result<Vec<SinspMetric>>;
 
+    fn get_progress(&mut self) -> anyhow::Result<CaptureProgress>;
+
+    /// Read all the remaining events, collecting metrics along the way
+    ///
+    /// Only metrics with names starting with `prefix` (e.g. the plugin name) are included.
+    /// A snapshot is taken after each event, but only snapshots different from the previous one
+    /// are returned, so that the history does not depend on events that did not affect
+    /// the metrics (e.g. the state events at the start of a capture file).
+    fn metrics_history(&mut self, prefix: &str) -> anyhow::Result<Vec<Vec<SinspMetric>>> {
+        let mut history: Vec<Vec<SinspMetric>> = Vec::new();
+        loop {
+            match self.next_event() {
+                Ok(_) => {}
+                Err(ScapStatus::Eof) => break,
+                Err(ScapStatus::Timeout) | Err(ScapStatus::Filtered) => continue,
+                Err(e) => return Err(anyhow::anyhow!("{:?}", e)).context(e),
+            }
+
+            let metrics = self
+                .get_metrics()?
+                .into_iter()
+                .filter(|m| m.name.starts_with(prefix))
+                .collect::<Vec<_>>();
+            if history.last() != Some(&metrics) {
+                history.push(metrics);
+            }
+        }
+
+        Ok(history)
+    }
+
     fn next_event_as_str(&mut self) -> anyhow::Result<Option<String>> {
         let event = match self.next_event() {
             Ok(event) => event,
@@ -1,8 +1,8 @@
 use super::{
-    AsPtr, CapturingTestDriver, PlatformData, RawExtractedValue, SavefileTestDriver, ScapStatus,
-    TestDriver,
+    AsPtr, CapturingTestDriver, PlatformData, RawExtractedValue, SavefileTestDriver,
+    SavefileWriterTestDriver, ScapStatus, TestDriver,
 };
-use crate::common::{Api, CaptureNotStarted, CaptureStarted, SinspMetric};
+use crate::common::{Api, CaptureNotStarted, CaptureProgress, CaptureStarted, SinspMetric};
 use cxx;
 use cxx::UniquePtr;
 use falco_event_schema::fields::types::PT_IPNET;
@@ -86,6 +86,16 @@ mod ffi {
 
         fn next(self: Pin<&mut SinspTestDriver>) -> SinspEvent;
 
+        unsafe fn write_capture_file(
+            self: Pin<&mut SinspTestDriver>,
+            path: *const c_char,
+        ) -> Result<()>;
+
+        unsafe fn dump_capture_file(
+            self: Pin<&mut SinspTestDriver>,
+            path: *const c_char,
+        ) -> Result<u64>;
+
         unsafe fn event_field_as_string(
             self: Pin<&mut SinspTestDriver>,
             field_name: *const c_char,
@@ -109,6 +119,8 @@ mod ffi {
         fn get_metrics(
             self: Pin<&mut SinspTestDriver>,
         ) -> Result<UniquePtr<CxxVector<SinspMetric>>>;
+
+        fn get_progress(self: Pin<&mut SinspTestDriver>, detail: Pin<&mut CxxString>) -> f64;
     }
 
     impl CxxVector<extract_value_t> {}
@@ -150,6 +162,7 @@ impl TestDriver for SinspTestDriver<CaptureNotStarted> {
     type Plugin = SinspPlugin;
 
     const NAME: &'static str = "sinsp";
+    const SUPPORTS_PLATFORM_DATA: bool = true;
 
     fn new() -> anyhow::Result<Self> {
         let driver = ffi::new_test_driver();
@@ -414,6 +427,38 @@ impl CapturingTestDriver for SinspTestDriver<CaptureStarted> {
 
         Ok(out)
     }
+
+    fn get_progress(&mut self) -> anyhow::Result<CaptureProgress> {
+        cxx::let_cxx_string!(detail = "");
+        let value = self.driver.as_mut().unwrap().get_progress(detail.as_mut());
+        let detail = (!detail.is_empty()).then(|| detail.to_string_lossy().into_owned());
+
+        Ok(CaptureProgress { value, detail })
+    }
+}
+
+impl SavefileWriterTestDriver for SinspTestDriver<CaptureStarted> {
+    fn write_capture_file(&mut self, path: &CStr) -> anyhow::Result<()> {
+        unsafe {
+            self.driver
+                .as_mut()
+                .unwrap()
+                .write_capture_file(path.as_ptr())?;
+        }
+
+        Ok(())
+    }
+
+    fn dump_capture_file(&mut self, path: &CStr) -> anyhow::Result<usize> {
+        let num_events = unsafe {
+            self.driver
+                .as_mut()
+                .unwrap()
+                .dump_capture_file(path.as_ptr())?
+        };
+
+        Ok(num_events as usize)
+    }
 }
 
 pub type Driver = SinspTestDriver<CaptureNotStarted>;
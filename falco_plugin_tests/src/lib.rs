@@ -11,6 +11,7 @@ pub mod native;
 
 pub mod common;
 pub mod plugin_collection;
+pub mod random_events;
 
 pub use common::*;
 
@@ -24,8 +25,34 @@ pub fn init_plugin<D: TestDriver>(
     Ok((driver, plugin))
 }
 
+/// Instantiate tests for all available test drivers
+///
+/// Each test is a function generic over [`TestDriver`], e.g. `fn test_foo<D: TestDriver>()`.
+///
+/// Tests prefixed with `platform_data:` additionally take a [`PlatformData`] argument
+/// and are instantiated both with platform data enabled and disabled. The combinations
+/// not supported by a particular driver (see [`TestDriver::SUPPORTS_PLATFORM_DATA`])
+/// are generated as `#[ignore]`d tests:
+///
+/// ```ignore
+/// instantiate_tests!(platform_data: test_foo; test_bar);
+/// ```
 #[macro_export]
 macro_rules! instantiate_tests {
+    (platform_data: $($func:ident);*) => {
+        mod native {
+            $crate::instantiate_platform_data_tests!(
+                $crate::native::Driver, unsupported; $($func);*
+            );
+        }
+
+        #[cfg(have_libsinsp)]
+        mod ffi {
+            $crate::instantiate_platform_data_tests!(
+                $crate::ffi::Driver, supported; $($func);*
+            );
+        }
+    };
     ($($func:ident);*) => {
         mod native {
             $(
@@ -48,6 +75,50 @@ macro_rules! instantiate_tests {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! instantiate_platform_data_tests {
+    ($driver:ty, supported; $($func:ident);*) => {
+        const _: () = assert!(<$driver as $crate::TestDriver>::SUPPORTS_PLATFORM_DATA);
+
+        mod platform_data_enabled {
+            $(
+            #[test]
+            fn $func() {
+                super::super::$func::<$driver>($crate::PlatformData::Enabled)
+            }
+            )*
+        }
+
+        $crate::instantiate_platform_data_tests!(@disabled $driver; $($func);*);
+    };
+    ($driver:ty, unsupported; $($func:ident);*) => {
+        const _: () = assert!(!<$driver as $crate::TestDriver>::SUPPORTS_PLATFORM_DATA);
+
+        mod platform_data_enabled {
+            $(
+            #[test]
+            #[ignore = "the driver does not support platform data"]
+            fn $func() {
+                super::super::$func::<$driver>($crate::PlatformData::Enabled)
+            }
+            )*
+        }
+
+        $crate::instantiate_platform_data_tests!(@disabled $driver; $($func);*);
+    };
+    (@disabled $driver:ty; $($func:ident);*) => {
+        mod platform_data_disabled {
+            $(
+            #[test]
+            fn $func() {
+                super::super::$func::<$driver>($crate::PlatformData::Disabled)
+            }
+            )*
+        }
+    };
+}
+
 #[macro_export]
 macro_rules! instantiate_sinsp_tests {
     ($($func:ident);*) => {
@@ -63,6 +134,20 @@ macro_rules! instantiate_sinsp_tests {
     };
 }
 
+#[macro_export]
+macro_rules! instantiate_native_tests {
+    ($($func:ident);*) => {
+        mod native {
+            $(
+            #[test]
+            fn $func() {
+                super::$func::<$crate::native::Driver>()
+            }
+            )*
+        }
+    };
+}
+
 #[cfg(test)]
 mod tests {
     use crate::TestDriver;
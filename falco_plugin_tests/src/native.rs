@@ -1,4 +1,7 @@
-use crate::{AsPtr, CapturingTestDriver, PlatformData, ScapStatus, SinspMetric, TestDriver};
+use crate::{
+    AsPtr, CaptureProgress, CapturingTestDriver, DumpStateTestDriver, PlatformData, ScapStatus,
+    SinspMetric, TestDriver,
+};
 use falco_plugin_runner::{CapturingPluginRunner, ExtractedField, MetricValue, PluginRunner};
 use std::ffi::CStr;
 use std::fmt::{Debug, Formatter};
@@ -14,6 +17,18 @@ impl Debug for NativePlugin {
 
 pub struct NativeTestDriver(PluginRunner);
 
+/// A field extraction failure, carrying the return code from the plugin
+#[derive(Debug)]
+pub struct ExtractError(pub falco_plugin::api::ss_plugin_rc);
+
+impl std::fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "failed to extract field: {}", self.0)
+    }
+}
+
+impl std::error::Error for ExtractError {}
+
 pub struct NativeCapturingTestDriver(CapturingPluginRunner);
 
 impl Debug for NativeTestDriver {
@@ -33,6 +48,7 @@ impl TestDriver for NativeTestDriver {
     type Plugin = NativePlugin;
 
     const NAME: &'static str = "native";
+    const SUPPORTS_PLATFORM_DATA: bool = false;
 
     fn new() -> anyhow::Result<Self> {
         Ok(Self(PluginRunner::new()))
@@ -67,14 +83,14 @@ impl TestDriver for NativeTestDriver {
     fn start_capture(
         self,
         _name: &CStr,
-        _config: &CStr,
+        config: &CStr,
         platform_data: PlatformData,
     ) -> anyhow::Result<Self::Capturing> {
         anyhow::ensure!(
             platform_data == PlatformData::Disabled,
             "Platform data is not supported"
         );
-        let capturing = self.0.start_capture()?;
+        let capturing = self.0.start_capture(config)?;
         Ok(NativeCapturingTestDriver(capturing))
     }
 }
@@ -107,7 +123,7 @@ impl CapturingTestDriver for NativeCapturingTestDriver {
         let s = std::str::from_utf8(field_name.to_bytes())?;
         match self.0.extract_field(event, s) {
             None => Ok(None),
-            Some(Err(e)) => Err(anyhow::anyhow!("failed to extract field: {}", e)),
+            Some(Err(rc)) => Err(ExtractError(rc).into()),
             Some(Ok(s)) => Ok(Some(s.to_string())),
         }
     }
@@ -120,7 +136,7 @@ impl CapturingTestDriver for NativeCapturingTestDriver {
         let s = std::str::from_utf8(field_name.to_bytes())?;
         match self.0.extract_field_with_range(event, s) {
             None => Ok(None),
-            Some(Err(e)) => Err(anyhow::anyhow!("failed to extract field: {}", e)),
+            Some(Err(rc)) => Err(ExtractError(rc).into()),
             Some(Ok((s, range))) => Ok(Some((s.to_string(), range))),
         }
     }
@@ -143,7 +159,7 @@ impl CapturingTestDriver for NativeCapturingTestDriver {
         let s = std::str::from_utf8(field_name.to_bytes())?;
         match self.0.extract_field(event, s) {
             None => Ok(None),
-            Some(Err(e)) => Err(anyhow::anyhow!("failed to extract field: {}", e)),
+            Some(Err(rc)) => Err(ExtractError(rc).into()),
             Some(Ok(s)) => Ok(Some(s)),
         }
     }
@@ -170,6 +186,20 @@ impl CapturingTestDriver for NativeCapturingTestDriver {
             })
             .collect())
     }
+
+    fn get_progress(&mut self) -> anyhow::Result<CaptureProgress> {
+        let (value, detail) = self.0.get_progress().unwrap_or_default();
+        Ok(CaptureProgress {
+            value,
+            detail: detail.map(|d| d.to_string_lossy().into_owned()),
+        })
+    }
+}
+
+impl DumpStateTestDriver for NativeCapturingTestDriver {
+    fn dump_state(&mut self) -> anyhow::Result<Vec<Vec<u8>>> {
+        self.0.dump_state()
+    }
 }
 
 pub type Driver = NativeTestDriver;
@@ -1,15 +1,16 @@
 use crate::plugin_collection::events::countdown::Countdown;
 use anyhow::Error;
 use falco_plugin::base::Plugin;
+use falco_plugin::bumpalo;
 use falco_plugin::event::events::Event;
 use falco_plugin::event::fields::{FromBytes, ToBytes};
 use falco_plugin::event::PluginEvent;
 use falco_plugin::extract::{
     field, ExtractByteRange, ExtractFieldInfo, ExtractPlugin, ExtractRequest,
 };
-use falco_plugin::static_plugin;
 use falco_plugin::strings::WriteIntoCString;
 use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
 use std::ffi::{CStr, CString};
 
 struct ExtractRemainingFromPayload;
@@ -47,6 +48,18 @@ impl ExtractRemainingFromPayload {
         Ok(out)
     }
 
+    fn extract_payload_uppercase<'c>(
+        &mut self,
+        req: ExtractRequest<'c, '_, '_, '_, Self>,
+    ) -> Result<&'c CStr, Error> {
+        let event = req.event.event()?;
+        let mut buf = bumpalo::collections::Vec::new_in(req.storage);
+        event.params.event_data.write(&mut buf)?;
+        buf.make_ascii_uppercase();
+        buf.push(0);
+        Ok(CStr::from_bytes_with_nul(buf.into_bump_slice())?)
+    }
+
     fn extract_payload_repeated(
         &mut self,
         req: ExtractRequest<Self>,
@@ -59,7 +72,7 @@ impl ExtractRemainingFromPayload {
     }
 
     fn extract_events_remaining(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
-        let event = req.event.event()?;
+        let event = req.parsed_event()?;
         let remaining = event.params.event_data.remaining() as u64;
         Ok(remaining)
     }
@@ -69,8 +82,7 @@ impl ExtractRemainingFromPayload {
         req: ExtractRequest<Self>,
         reps: u64,
     ) -> Result<Vec<u64>, Error> {
-        let event = req.event.event()?;
-        let remaining: u64 = event.params.event_data.remaining() as u64;
+        let remaining: u64 = req.event_payload()?.remaining() as u64;
         Ok(vec![remaining; reps as usize])
     }
 
@@ -88,6 +100,26 @@ impl ExtractRemainingFromPayload {
             None => Ok(req.event.event()?.params.event_data.remaining() as u64),
         }
     }
+
+    fn extract_source(&mut self, req: ExtractRequest<Self>) -> Result<Option<CString>, Error> {
+        Ok(req.event_source().map(CStr::to_owned))
+    }
+
+    fn extract_plugin_id(&mut self, req: ExtractRequest<Self>) -> Result<Option<u64>, Error> {
+        Ok(req.event_plugin_id()?.map(u64::from))
+    }
+
+    fn extract_num_fields(&mut self, req: ExtractRequest<Self>) -> Result<Option<u64>, Error> {
+        Ok(req.raw_extract_input().map(|input| input.num_fields as u64))
+    }
+
+    fn extract_not_applicable(&mut self, _req: ExtractRequest<Self>) -> Result<Option<u64>, Error> {
+        Ok(None)
+    }
+
+    fn extract_unavailable(&mut self, _req: ExtractRequest<Self>) -> Result<Option<u64>, Error> {
+        Err(anyhow::anyhow!("value not available yet").context(FailureReason::Timeout))
+    }
 }
 
 impl ExtractPlugin for ExtractRemainingFromPayload {
@@ -99,6 +131,7 @@ impl ExtractPlugin for ExtractRemainingFromPayload {
             "dummy.payload_with_range",
             &Self::extract_payload_with_range,
         ),
+        field("dummy.payload_uppercase", &Self::extract_payload_uppercase),
         field("dummy.payload_repeated", &Self::extract_payload_repeated),
         field("dummy.remaining", &Self::extract_events_remaining),
         field("dummy.remaining_repeated", &Self::events_remaining_repeated),
@@ -106,6 +139,11 @@ impl ExtractPlugin for ExtractRemainingFromPayload {
             "dummy.remaining_with_maybe_override",
             &Self::extract_events_remaining_with_maybe_override,
         ),
+        field("dummy.source", &Self::extract_source),
+        field("dummy.plugin_id", &Self::extract_plugin_id),
+        field("dummy.num_fields", &Self::extract_num_fields),
+        field("dummy.not_applicable", &Self::extract_not_applicable),
+        field("dummy.unavailable", &Self::extract_unavailable),
     ];
 }
 
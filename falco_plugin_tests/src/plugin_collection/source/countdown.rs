@@ -6,7 +6,9 @@ use falco_plugin::event::events::Event;
 use falco_plugin::event::fields::ToBytes;
 use falco_plugin::event::PluginEvent;
 use falco_plugin::extract::EventInput;
-use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::source::{
+    parse_open_params, EventBatch, ProgressInfo, SourcePlugin, SourcePluginInstance,
+};
 use falco_plugin::strings::CStringWriter;
 use falco_plugin::tables::TablesInput;
 use falco_plugin::{static_plugin, FailureReason};
@@ -19,6 +21,13 @@ pub struct CountdownConfig {
     batch_size: usize,
 }
 
+/// Open parameters, overriding the plugin config for a single capture
+#[derive(Debug, serde::Deserialize, falco_plugin::schemars::JsonSchema)]
+#[schemars(crate = "falco_plugin::schemars")]
+pub struct CountdownOpenParams {
+    remaining: usize,
+}
+
 pub struct CountdownPlugin {
     num_batches: usize,
     num_events: usize,
@@ -59,8 +68,10 @@ impl Plugin for CountdownPlugin {
 }
 
 pub struct CountdownPluginInstance {
+    total: usize,
     remaining: usize,
     batch_size: usize,
+    progress: CString,
 }
 
 impl SourcePluginInstance for CountdownPluginInstance {
@@ -86,6 +97,20 @@ impl SourcePluginInstance for CountdownPluginInstance {
             Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof))
         }
     }
+
+    fn get_progress(&mut self) -> ProgressInfo<'_> {
+        let produced = self.total - self.remaining;
+        self.progress = CString::new(format!("{produced}/{} events", self.total)).unwrap();
+
+        ProgressInfo {
+            value: if self.total == 0 {
+                100.0
+            } else {
+                produced as f64 * 100.0 / self.total as f64
+            },
+            detail: Some(&self.progress),
+        }
+    }
 }
 
 impl SourcePlugin for CountdownPlugin {
@@ -94,10 +119,14 @@ impl SourcePlugin for CountdownPlugin {
     const PLUGIN_ID: u32 = 1111;
     type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
 
-    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+    fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, Error> {
+        let params = parse_open_params::<Json<CountdownOpenParams>>(params)?;
+        let remaining = params.map_or(self.remaining, |Json(params)| params.remaining);
         Ok(CountdownPluginInstance {
-            remaining: self.remaining,
+            total: remaining,
+            remaining,
             batch_size: self.batch_size,
+            progress: CString::default(),
         })
     }
 
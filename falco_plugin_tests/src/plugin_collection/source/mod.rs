@@ -1,2 +1,3 @@
 pub mod batched_empty_event;
 pub mod countdown;
+pub mod random_events;
@@ -0,0 +1,134 @@
+use crate::random_events::{random_event, Rng};
+use crate::{init_plugin, PlatformData, SavefileWriterTestDriver, TestDriver};
+use anyhow::Error;
+use falco_event_schema::events::AnyEvent;
+use falco_plugin::base::{Json, Plugin};
+use falco_plugin::bumpalo::Bump;
+use falco_plugin::event::events::{AnyEventPayload, EventMetadata, RawEvent};
+use falco_plugin::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+
+/// The maximum number of events generated in a single batch
+const BATCH_SIZE: usize = 64;
+
+#[derive(
+    Debug, Clone, serde::Serialize, serde::Deserialize, falco_plugin::schemars::JsonSchema,
+)]
+#[schemars(crate = "falco_plugin::schemars")]
+pub struct RandomEventsConfig {
+    /// Seed for the random number generator
+    pub seed: u64,
+    /// The total number of events to generate
+    pub count: usize,
+    /// Event types to pick from (at random, for each event)
+    pub event_types: Vec<u16>,
+}
+
+/// A syscall source plugin emitting random events of selected types
+pub struct RandomEventsPlugin {
+    config: RandomEventsConfig,
+}
+
+impl Plugin for RandomEventsPlugin {
+    const NAME: &'static CStr = c"random_events";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"generate random syscall events";
+    const CONTACT: &'static CStr = c"https://github.com/falcosecurity/plugin-sdk-rs";
+    type ConfigType = Json<RandomEventsConfig>;
+
+    fn new(_input: Option<&TablesInput>, Json(config): Self::ConfigType) -> Result<Self, Error> {
+        anyhow::ensure!(!config.event_types.is_empty(), "No event types given");
+        if let Some(event_type) = config
+            .event_types
+            .iter()
+            .find(|t| !AnyEvent::EVENT_TYPES.contains(t))
+        {
+            anyhow::bail!("Unknown event type {event_type}");
+        }
+
+        Ok(Self { config })
+    }
+}
+
+pub struct RandomEventsPluginInstance {
+    rng: Rng,
+    remaining: usize,
+    ts: u64,
+    arena: Bump,
+}
+
+impl SourcePluginInstance for RandomEventsPluginInstance {
+    type Plugin = RandomEventsPlugin;
+
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        if self.remaining == 0 {
+            return Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof));
+        }
+
+        let batch_size = std::cmp::min(self.remaining, BATCH_SIZE);
+        for _ in 0..batch_size {
+            let event_types = &plugin.config.event_types;
+            let event_type = event_types[self.rng.below(event_types.len())];
+
+            // keep the timestamps increasing, like in a real capture
+            self.ts += 1 + self.rng.below(1_000_000) as u64;
+            let metadata = EventMetadata {
+                ts: self.ts,
+                tid: self.rng.below(65536) as i64,
+            };
+
+            self.arena.reset();
+            let event = random_event(&mut self.rng, event_type, metadata, &self.arena)
+                .ok_or_else(|| anyhow::anyhow!("Unknown event type {event_type}"))?;
+            batch.add(event)?;
+            self.remaining -= 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl SourcePlugin for RandomEventsPlugin {
+    type Instance = RandomEventsPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"";
+    const PLUGIN_ID: u32 = 0;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(RandomEventsPluginInstance {
+            rng: Rng::new(self.config.seed),
+            remaining: self.config.count,
+            ts: 1_700_000_000_000_000_000,
+            arena: Bump::new(),
+        })
+    }
+
+    fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+        let event = event.event()?.load::<AnyEvent>()?;
+        Ok(CString::new(format!("{event:?}"))?)
+    }
+}
+
+static_plugin!(pub RANDOM_EVENTS_PLUGIN_API = RandomEventsPlugin);
+
+/// Write a capture file at `path` with random events, as described by `config`
+///
+/// Returns the number of events written.
+pub fn write_random_capture<D: TestDriver>(
+    path: &CStr,
+    config: &RandomEventsConfig,
+) -> anyhow::Result<usize>
+where
+    D::Capturing: SavefileWriterTestDriver,
+{
+    let config = CString::new(serde_json::to_string(config)?)?;
+    let (driver, _plugin) = init_plugin::<D>(&RANDOM_EVENTS_PLUGIN_API, &config)?;
+    let mut driver = driver.start_capture(RandomEventsPlugin::NAME, c"", PlatformData::Disabled)?;
+    driver.dump_capture_file(path)
+}
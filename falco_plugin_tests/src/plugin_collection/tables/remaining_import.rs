@@ -1,11 +1,12 @@
 use falco_plugin::tables::import;
 use std::sync::Arc;
 
-pub type RemainingCounterImportTable = import::Table<u64, RemainingCounterImport>;
+pub type RemainingCounterImportTable = import::KeyedTable<RemainingCounterImport>;
 pub type RemainingCounterImport = import::Entry<Arc<RemainingCounterImportMetadata>>;
 
 #[derive(import::TableMetadata)]
 #[entry_type(RemainingCounterImport)]
+#[key_type(u64)]
 #[accessors_mod(accessors)]
 pub struct RemainingCounterImportMetadata {
     remaining: import::Field<u64, RemainingCounterImport>,
@@ -1,6 +1,6 @@
 use falco_plugin::anyhow::{self, Error};
-use falco_plugin::async_event::{AsyncEventPlugin, AsyncHandler, BackgroundTask};
-use falco_plugin::base::Plugin;
+use falco_plugin::async_event::{AsyncEvent, AsyncEventPlugin, AsyncHandler, BackgroundTask};
+use falco_plugin::base::{Metric, Plugin};
 use falco_plugin::event::events::RawEvent;
 use falco_plugin::extract::EventInput;
 use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
@@ -28,6 +28,10 @@ impl Plugin for DummyPlugin {
     fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
         Ok(Default::default())
     }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        self.task.stats().metrics()
+    }
 }
 
 struct DummyPluginInstance;
@@ -81,6 +85,15 @@ impl AsyncEventPlugin for DummyPlugin {
         Ok(())
     }
 
+    fn dump_state(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        handler.emit(Self::async_event(c"dummy_async", b"state"))?;
+        handler.emit(Self::async_event(c"dummy_async", b"more state"))?;
+        assert!(handler
+            .emit(Self::async_event(c"invalid_event_name", b"state"))
+            .is_err());
+        Ok(())
+    }
+
     fn stop_async(&mut self) -> Result<(), Error> {
         dbg!("requesting shutdown");
         self.task.request_stop_and_notify()?;
@@ -98,6 +111,15 @@ impl AsyncEventPlugin for DummyPlugin {
 
 static_plugin!(DUMMY_PLUGIN_API = DummyPlugin);
 
+fn async_payloads<'a>(events: impl IntoIterator<Item = RawEvent<'a>>) -> Vec<Vec<u8>> {
+    events
+        .into_iter()
+        .filter_map(|event| event.load::<AsyncEvent<&[u8]>>().ok())
+        .filter(|event| event.params.name == c"dummy_async")
+        .map(|event| event.params.data.to_vec())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use falco_plugin::base::Plugin;
@@ -119,7 +141,95 @@ mod tests {
                 nevts += 1;
             }
         }
+
+        let metrics = driver.get_metrics().unwrap();
+        let metric = |name: &str| {
+            metrics
+                .iter()
+                .find(|m| m.name == name)
+                .unwrap_or_else(|| panic!("missing metric {name}"))
+                .value
+        };
+        assert_eq!(metric("dummy.background_task_threads"), 1);
+        assert!(metric("dummy.background_task_iterations") > 0);
     }
 
     instantiate_tests!(test_async);
 }
+
+#[cfg(test)]
+mod dump_state_tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, DumpStateTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_dump_state<D: TestDriver>()
+    where
+        D::Capturing: DumpStateTestDriver,
+    {
+        let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::DummyPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let events = driver.dump_state().unwrap();
+        let events = events.iter().map(|buf| RawEvent::from(buf).unwrap());
+        assert_eq!(
+            super::async_payloads(events),
+            [b"state".to_vec(), b"more state".to_vec()]
+        );
+    }
+
+    instantiate_native_tests!(test_dump_state);
+}
+
+#[cfg(all(test, have_libsinsp))]
+mod sinsp_tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_sinsp_tests, AsPtr, CapturingTestDriver, PlatformData,
+        SavefileTestDriver, SavefileWriterTestDriver, ScapStatus, TestDriver,
+    };
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn test_dump_state_savefile<D: SavefileTestDriver>()
+    where
+        D::Capturing: SavefileWriterTestDriver,
+    {
+        let path = std::env::temp_dir().join(format!("dump_state_{}.scap", std::process::id()));
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+
+        {
+            let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+            let mut driver = driver
+                .start_capture(super::DummyPlugin::NAME, c"", PlatformData::Disabled)
+                .unwrap();
+            driver.write_capture_file(&path).unwrap();
+        }
+
+        let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+        let mut driver = driver.load_capture_file(&path).unwrap();
+
+        let mut events = Vec::new();
+        loop {
+            match driver.next_event() {
+                Ok(event) => {
+                    let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+                    events.extend(super::async_payloads([event]));
+                }
+                Err(ScapStatus::Eof) => break,
+                Err(ScapStatus::Timeout) => continue,
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+        std::fs::remove_file(path.to_str().unwrap()).ok();
+
+        assert_eq!(events, [b"state".to_vec(), b"more state".to_vec()]);
+    }
+
+    instantiate_sinsp_tests!(test_dump_state_savefile);
+}
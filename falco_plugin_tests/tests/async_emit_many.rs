@@ -0,0 +1,120 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{EventToBytes, RawEvent};
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::time::Duration;
+
+struct EmitManyPlugin;
+
+impl Plugin for EmitManyPlugin {
+    const NAME: &'static CStr = c"emit_many";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct EmitManyInstance;
+
+impl SourcePluginInstance for EmitManyInstance {
+    type Plugin = EmitManyPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for EmitManyPlugin {
+    type Instance = EmitManyInstance;
+    const EVENT_SOURCE: &'static CStr = c"emit_many";
+    const PLUGIN_ID: u32 = 1116;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(EmitManyInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for EmitManyPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["many"];
+    const EVENT_SOURCES: &'static [&'static str] = &["emit_many"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        let payloads = ["one", "two", "three"];
+        handler.emit_many(
+            payloads
+                .iter()
+                .map(|p| Self::async_event(c"many", p.as_bytes())),
+        )?;
+
+        let mut buf = Vec::new();
+        Self::async_event(c"many", b"raw").write(&mut buf)?;
+        handler.emit_raw(&buf)?;
+
+        // truncated events and other event types are rejected before reaching the framework
+        anyhow::ensure!(handler.emit_raw(&buf[..buf.len() - 1]).is_err());
+        anyhow::ensure!(handler.emit_raw(&buf[..10]).is_err());
+        let mut buf = Vec::new();
+        EmitManyInstance::plugin_event(b"plugin event").write(&mut buf)?;
+        anyhow::ensure!(handler.emit_raw(&buf).is_err());
+
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+static_plugin!(EMIT_MANY_API = EmitManyPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_emit_many<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::EMIT_MANY_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::EmitManyPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut payloads = Vec::new();
+        while payloads.len() < 4 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            payloads.push(String::from_utf8(event.params.data.to_vec()).unwrap());
+        }
+
+        assert_eq!(payloads, ["one", "two", "three", "raw"]);
+    }
+
+    instantiate_tests!(test_emit_many);
+}
@@ -0,0 +1,126 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{AsyncEventNames, AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::time::Duration;
+
+#[derive(Clone, Copy, AsyncEventNames)]
+enum MyAsyncEvents {
+    ContainerAdded,
+    ContainerRemoved,
+    #[name(c"image-pulled")]
+    ImagePulled,
+}
+
+struct NamesPlugin;
+
+impl Plugin for NamesPlugin {
+    const NAME: &'static CStr = c"async_names";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct NamesInstance;
+
+impl SourcePluginInstance for NamesInstance {
+    type Plugin = NamesPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for NamesPlugin {
+    type Instance = NamesInstance;
+    const EVENT_SOURCE: &'static CStr = c"async_names";
+    const PLUGIN_ID: u32 = 1117;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(NamesInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for NamesPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = MyAsyncEvents::NAMES;
+    const EVENT_SOURCES: &'static [&'static str] = &["async_names"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        handler.emit(MyAsyncEvents::ContainerAdded.event(b"added"))?;
+        handler.emit(MyAsyncEvents::ContainerRemoved.event(b"removed"))?;
+        handler.emit(Self::async_event(
+            MyAsyncEvents::ImagePulled.name(),
+            b"pulled",
+        ))?;
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+static_plugin!(NAMES_API = NamesPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_async_event_names<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::NAMES_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::NamesPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut payloads = Vec::new();
+        while payloads.len() < 3 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            payloads.push((
+                event.params.name.to_owned(),
+                String::from_utf8(event.params.data.to_vec()).unwrap(),
+            ));
+        }
+
+        assert_eq!(
+            payloads,
+            [
+                (c"container_added".to_owned(), "added".to_string()),
+                (c"container_removed".to_owned(), "removed".to_string()),
+                (c"image-pulled".to_owned(), "pulled".to_string()),
+            ]
+        );
+    }
+
+    instantiate_tests!(test_async_event_names);
+}
@@ -0,0 +1,170 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{
+    AsyncEventPlugin, AsyncHandler, AsyncHandlerQueue, AsyncHandlerQueueStats, OverflowPolicy,
+};
+use falco_plugin::base::{Metric, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::panic;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+#[derive(Default)]
+struct QueuePlugin {
+    queue: Option<AsyncHandlerQueue>,
+    thread: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl Plugin for QueuePlugin {
+    const NAME: &'static CStr = c"queue";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"async queue plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        match &self.queue {
+            Some(queue) => queue.stats(),
+            None => AsyncHandlerQueueStats::default(),
+        }
+        .metrics()
+    }
+}
+
+struct QueuePluginInstance;
+
+impl SourcePluginInstance for QueuePluginInstance {
+    type Plugin = QueuePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for QueuePlugin {
+    type Instance = QueuePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"queue";
+    const PLUGIN_ID: u32 = 1113;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(QueuePluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for QueuePlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["queue_async"];
+    const EVENT_SOURCES: &'static [&'static str] = &["queue"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        self.stop_async()?;
+
+        let queue = AsyncHandlerQueue::new(handler, 8, OverflowPolicy::Block)?;
+        let sender = queue.sender();
+        self.queue = Some(queue);
+
+        // a burst much larger than the queue, followed by an event the framework rejects
+        self.thread = Some(std::thread::spawn(move || {
+            for i in 0..100u32 {
+                sender.emit(Self::async_event(c"queue_async", i.to_string().as_bytes()))?;
+            }
+            sender.emit(Self::async_event(c"invalid_event_name", b"oops"))
+        }));
+
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        if let Some(handle) = self.thread.take() {
+            match handle.join() {
+                Ok(res) => res?,
+                Err(e) => panic::resume_unwind(e),
+            }
+        }
+
+        // the invalid event is expected to fail
+        if let Some(mut queue) = self.queue.take() {
+            anyhow::ensure!(queue.stop().is_err());
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(QUEUE_PLUGIN_API = QueuePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_async_queue<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::QUEUE_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::QueuePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut payloads = Vec::new();
+        while payloads.len() < 100 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            if event.params.name == c"queue_async" {
+                payloads.push(String::from_utf8(event.params.data.to_vec()).unwrap());
+            }
+        }
+
+        // all events arrive in order, nothing gets dropped while blocking
+        let expected = (0..100).map(|i| i.to_string()).collect::<Vec<_>>();
+        assert_eq!(payloads, expected);
+
+        // wait for the drain thread to get to the invalid event
+        let metric = |driver: &mut D::Capturing, name: &str| {
+            driver
+                .get_metrics()
+                .unwrap()
+                .into_iter()
+                .find(|m| m.name == name)
+                .unwrap_or_else(|| panic!("missing metric {name}"))
+                .value
+        };
+        for _ in 0..1000 {
+            if metric(&mut driver, "queue.async_queue_errors") == 1 {
+                break;
+            }
+            let _ = driver.next_event();
+        }
+
+        assert_eq!(metric(&mut driver, "queue.async_queue_errors"), 1);
+        assert_eq!(metric(&mut driver, "queue.async_queue_emitted"), 100);
+        assert_eq!(metric(&mut driver, "queue.async_queue_dropped"), 0);
+        assert_eq!(metric(&mut driver, "queue.async_queue_queued"), 0);
+    }
+
+    instantiate_tests!(test_async_queue);
+}
@@ -0,0 +1,138 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::{
+    AsyncSourceInstance, AsyncSourcePluginInstance, EventBatch, SourcePlugin, SourcePluginInstance,
+};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::tokio::time::{sleep_until, Instant};
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::CStr;
+use std::time::Duration;
+
+/// The number of events to generate
+const NUM_EVENTS: usize = 3;
+
+/// The delay between events, longer than the batch timeout
+const EVENT_INTERVAL: Duration = Duration::from_millis(30);
+
+struct AsyncPlugin;
+
+impl Plugin for AsyncPlugin {
+    const NAME: &'static CStr = c"async_source";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct AsyncPluginInstance {
+    next: usize,
+    deadline: Instant,
+}
+
+impl AsyncSourcePluginInstance for AsyncPluginInstance {
+    type Plugin = AsyncPlugin;
+    const TIMEOUT: Duration = Duration::from_millis(10);
+
+    async fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch<'_>,
+    ) -> Result<(), Error> {
+        if self.next == NUM_EVENTS {
+            return Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof));
+        }
+
+        // the deadline is stored in the instance, so this is cancel safe
+        sleep_until(self.deadline).await;
+
+        let payload = format!("event {}", self.next);
+        batch.add(AsyncSourceInstance::<Self>::plugin_event(
+            payload.as_bytes(),
+        ))?;
+        self.next += 1;
+        self.deadline += EVENT_INTERVAL;
+        Ok(())
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [Metric::new(
+            MetricLabel::new(c"events_read", MetricType::Monotonic),
+            MetricValue::U64(self.next as u64),
+        )]
+    }
+}
+
+impl SourcePlugin for AsyncPlugin {
+    type Instance = AsyncSourceInstance<AsyncPluginInstance>;
+    const EVENT_SOURCE: &'static CStr = c"async_source";
+    const PLUGIN_ID: u32 = 1114;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        let instance = AsyncSourceInstance::new(AsyncPluginInstance {
+            next: 0,
+            deadline: Instant::now() + EVENT_INTERVAL,
+        })?;
+        Ok(instance)
+    }
+}
+
+static_plugin!(ASYNC_SOURCE_API = AsyncPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_async_source<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::ASYNC_SOURCE_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::AsyncPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut events = Vec::new();
+        let mut timeouts = 0;
+        loop {
+            let event = match driver.next_event() {
+                Ok(event) => event,
+                Err(ScapStatus::Eof) => break,
+                Err(ScapStatus::Timeout) => {
+                    timeouts += 1;
+                    continue;
+                }
+                Err(e) => panic!("{e:?}"),
+            };
+            events.push(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(events, ["event 0", "event 1", "event 2"]);
+        assert!(timeouts > 0);
+
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metrics,
+            [("async_source.events_read.instance_0".to_string(), 3)]
+        );
+    }
+
+    instantiate_tests!(test_async_source);
+}
@@ -74,7 +74,7 @@ impl AsyncEventPlugin for DummyAsyncPlugin {
             self.stop_async()?;
         }
 
-        let data = self.table.data();
+        let data = self.table.data().unwrap();
         let create_entry_fn = self.table.create_entry_fn();
         let mut counter = 0;
 
@@ -0,0 +1,150 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{
+    AsyncEventPlugin, AsyncHandler, ThrottledHandler, ThrottledHandlerStats,
+};
+use falco_plugin::base::{Metric, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::time::Duration;
+
+#[derive(Default)]
+struct ThrottlePlugin {
+    handler: Option<ThrottledHandler>,
+}
+
+impl Plugin for ThrottlePlugin {
+    const NAME: &'static CStr = c"throttle";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"async throttle plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        match &self.handler {
+            Some(handler) => handler.stats(),
+            None => ThrottledHandlerStats::default(),
+        }
+        .metrics()
+    }
+}
+
+struct ThrottlePluginInstance;
+
+impl SourcePluginInstance for ThrottlePluginInstance {
+    type Plugin = ThrottlePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for ThrottlePlugin {
+    type Instance = ThrottlePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"throttle";
+    const PLUGIN_ID: u32 = 1121;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(ThrottlePluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for ThrottlePlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["throttle_async"];
+    const EVENT_SOURCES: &'static [&'static str] = &["throttle"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        // a very slow refill rate, so that only the initial burst gets through
+        let handler = ThrottledHandler::new(handler)
+            .with_rate_limit(1, 3)
+            .with_dedup(Duration::from_secs(60));
+
+        for _ in 0..5 {
+            handler.emit(Self::async_event(c"throttle_async", b"same"))?;
+        }
+        for payload in [b"one", b"two", b"six"] {
+            handler.emit(Self::async_event(c"throttle_async", payload))?;
+        }
+
+        self.handler = Some(handler);
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+static_plugin!(THROTTLE_PLUGIN_API = ThrottlePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_async_throttle<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::THROTTLE_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::ThrottlePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut payloads = Vec::new();
+        while payloads.len() < 3 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            if event.params.name == c"throttle_async" {
+                payloads.push(String::from_utf8(event.params.data.to_vec()).unwrap());
+            }
+        }
+
+        // the duplicates don't use up the burst, but the last event exceeds it
+        assert_eq!(payloads, ["same", "one", "two"]);
+
+        let metric = |driver: &mut D::Capturing, name: &str| {
+            driver
+                .get_metrics()
+                .unwrap()
+                .into_iter()
+                .find(|m| m.name == name)
+                .unwrap_or_else(|| panic!("missing metric {name}"))
+                .value
+        };
+        assert_eq!(metric(&mut driver, "throttle.async_throttle_emitted"), 3);
+        assert_eq!(
+            metric(&mut driver, "throttle.async_throttle_deduplicated"),
+            4
+        );
+        assert_eq!(
+            metric(&mut driver, "throttle.async_throttle_rate_limited"),
+            1
+        );
+    }
+
+    instantiate_tests!(test_async_throttle);
+}
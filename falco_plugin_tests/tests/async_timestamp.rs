@@ -0,0 +1,125 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+struct TimestampPlugin;
+
+impl Plugin for TimestampPlugin {
+    const NAME: &'static CStr = c"timestamp";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"async timestamp plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct TimestampPluginInstance;
+
+impl SourcePluginInstance for TimestampPluginInstance {
+    type Plugin = TimestampPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for TimestampPlugin {
+    type Instance = TimestampPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"timestamp";
+    const PLUGIN_ID: u32 = 1122;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(TimestampPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for TimestampPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["timestamp_async"];
+    const EVENT_SOURCES: &'static [&'static str] = &["timestamp"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        handler.emit(Self::async_event(c"timestamp_async", b"now"))?;
+        handler.emit(
+            Self::async_event(c"timestamp_async", b"backfill")
+                .with_timestamp(backfill_time())
+                .with_tid(1234),
+        )?;
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn backfill_time() -> SystemTime {
+    UNIX_EPOCH + Duration::from_secs(1_600_000_000)
+}
+
+static_plugin!(TIMESTAMP_PLUGIN_API = TimestampPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_async_timestamp<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::TIMESTAMP_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::TimestampPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut events = Vec::new();
+        while events.len() < 2 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            if event.params.name == c"timestamp_async" {
+                events.push((
+                    String::from_utf8(event.params.data.to_vec()).unwrap(),
+                    event.metadata,
+                ));
+            }
+        }
+
+        let (payload, metadata) = &events[0];
+        assert_eq!(payload, "now");
+        assert_ne!(metadata.timestamp(), Some(super::backfill_time()));
+
+        // an explicit timestamp and tid are passed through unchanged
+        let (payload, metadata) = &events[1];
+        assert_eq!(payload, "backfill");
+        assert_eq!(metadata.timestamp(), Some(super::backfill_time()));
+        assert_eq!(metadata.tid, 1234);
+    }
+
+    instantiate_tests!(test_async_timestamp);
+}
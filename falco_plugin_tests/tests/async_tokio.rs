@@ -0,0 +1,124 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::tokio::AsyncTasks;
+use falco_plugin::async_event::{AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::time::Duration;
+
+struct TokioPlugin {
+    tasks: AsyncTasks,
+}
+
+impl Plugin for TokioPlugin {
+    const NAME: &'static CStr = c"tokio";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"tokio async plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let mut tasks = AsyncTasks::new()?;
+        tasks.register(|handler: AsyncHandler| async move {
+            let mut counter = 0u32;
+            loop {
+                falco_plugin::tokio::time::sleep(Duration::from_millis(10)).await;
+                handler.emit(Self::async_event(
+                    c"tokio_async",
+                    counter.to_string().as_bytes(),
+                ))?;
+                counter += 1;
+            }
+        });
+
+        Ok(Self { tasks })
+    }
+}
+
+struct TokioPluginInstance;
+
+impl SourcePluginInstance for TokioPluginInstance {
+    type Plugin = TokioPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(20));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for TokioPlugin {
+    type Instance = TokioPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"tokio";
+    const PLUGIN_ID: u32 = 1112;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(TokioPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for TokioPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["tokio_async"];
+    const EVENT_SOURCES: &'static [&'static str] = &["tokio"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        self.tasks.start(handler)
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        self.tasks.stop()
+    }
+}
+
+static_plugin!(TOKIO_PLUGIN_API = TokioPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_tokio_async<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::TOKIO_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::TokioPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut payloads = Vec::new();
+        while payloads.len() < 5 {
+            let Ok(event) = driver.next_event() else {
+                continue;
+            };
+            let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+            let Ok(event) = event.load::<AsyncEvent<&[u8]>>() else {
+                continue;
+            };
+            if event.params.name == c"tokio_async" {
+                payloads.push(event.params.data.to_vec());
+            }
+        }
+
+        // the task emits events in order
+        assert_eq!(
+            payloads,
+            ["0", "1", "2", "3", "4"].map(|p| p.as_bytes().to_vec())
+        );
+    }
+
+    instantiate_tests!(test_tokio_async);
+}
@@ -0,0 +1,181 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{AsyncEvent, AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{Event, RawEvent};
+use falco_plugin::event::PayloadFormat;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::panic;
+use std::sync::Mutex;
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+struct Reading {
+    sensor: String,
+    value: u64,
+}
+
+static RECEIVED: Mutex<Vec<(PayloadFormat, Reading)>> = Mutex::new(Vec::new());
+
+#[derive(Default)]
+struct TypedPlugin {
+    thread: Option<JoinHandle<Result<(), Error>>>,
+}
+
+impl Plugin for TypedPlugin {
+    const NAME: &'static CStr = c"typed";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"typed async event plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Default::default())
+    }
+}
+
+struct TypedPluginInstance;
+
+impl SourcePluginInstance for TypedPluginInstance {
+    type Plugin = TypedPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for TypedPlugin {
+    type Instance = TypedPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"typed";
+    const PLUGIN_ID: u32 = 1114;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(TypedPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for TypedPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["typed_json", "typed_bincode"];
+    const EVENT_SOURCES: &'static [&'static str] = &["typed"];
+
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        self.stop_async()?;
+
+        self.thread = Some(std::thread::spawn(move || {
+            let reading = Reading {
+                sensor: "json".to_string(),
+                value: 1,
+            };
+            handler.emit(Self::async_event_typed(
+                c"typed_json",
+                &reading,
+                PayloadFormat::Json,
+            )?)?;
+
+            let reading = Reading {
+                sensor: "bincode".to_string(),
+                value: 2,
+            };
+            handler.emit(Self::async_event_typed(
+                c"typed_bincode",
+                &reading,
+                PayloadFormat::Bincode,
+            )?)
+        }));
+
+        Ok(())
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        let Some(handle) = self.thread.take() else {
+            return Ok(());
+        };
+
+        match handle.join() {
+            Ok(res) => res,
+            Err(e) => panic::resume_unwind(e),
+        }
+    }
+}
+
+impl ParsePlugin for TypedPlugin {
+    type Event<'a> = Event<AsyncEvent<'a, &'a [u8]>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        for (name, format) in [
+            (c"typed_json", PayloadFormat::Json),
+            (c"typed_bincode", PayloadFormat::Bincode),
+        ] {
+            if let Some(reading) = event.parse_async_event::<Reading>(name, format)? {
+                RECEIVED.lock().unwrap().push((format, reading));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(TYPED_PLUGIN_API = TypedPlugin);
+
+#[cfg(test)]
+mod tests {
+    use super::{PayloadFormat, Reading, RECEIVED};
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_typed_async_events<D: TestDriver>() {
+        RECEIVED.lock().unwrap().clear();
+
+        let (driver, _plugin) = init_plugin::<D>(&super::TYPED_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::TypedPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        while RECEIVED.lock().unwrap().len() < 2 {
+            let _ = driver.next_event();
+        }
+
+        assert_eq!(
+            *RECEIVED.lock().unwrap(),
+            [
+                (
+                    PayloadFormat::Json,
+                    Reading {
+                        sensor: "json".to_string(),
+                        value: 1
+                    }
+                ),
+                (
+                    PayloadFormat::Bincode,
+                    Reading {
+                        sensor: "bincode".to_string(),
+                        value: 2
+                    }
+                ),
+            ]
+        );
+    }
+
+    instantiate_tests!(test_typed_async_events);
+}
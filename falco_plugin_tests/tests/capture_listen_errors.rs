@@ -0,0 +1,144 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::listen::{CaptureListenInput, CaptureListenPlugin, RoutineHandle};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+static RUNS: AtomicUsize = AtomicUsize::new(0);
+static CLOSE_ERRORS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct DummyPlugin {
+    start_time: std::time::Instant,
+    tasks: Vec<RoutineHandle>,
+}
+
+impl Plugin for DummyPlugin {
+    const NAME: &'static CStr = c"dummy";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"dummy async plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self {
+            start_time: std::time::Instant::now(),
+            tasks: Vec::new(),
+        })
+    }
+}
+
+struct DummyPluginInstance;
+
+impl SourcePluginInstance for DummyPluginInstance {
+    type Plugin = DummyPlugin;
+
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(20));
+        if plugin.tasks.iter().all(|t| t.is_finished()) {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Eof))
+        } else if plugin.start_time.elapsed() > Duration::from_millis(1200) {
+            Err(anyhow::anyhow!("routines did not finish").context(FailureReason::Failure))
+        } else {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+        }
+    }
+}
+
+impl SourcePlugin for DummyPlugin {
+    type Instance = DummyPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"dummy";
+    const PLUGIN_ID: u32 = 1111;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(DummyPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl CaptureListenPlugin for DummyPlugin {
+    fn capture_open(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+        self.tasks
+            .push(listen_input.thread_pool.subscribe_fallible(|_| {
+                let run = RUNS.fetch_add(1, Ordering::Relaxed) + 1;
+                if run == 3 {
+                    ControlFlow::Break(Err(anyhow::anyhow!("failed in run {run}")))
+                } else {
+                    ControlFlow::Continue(())
+                }
+            })?);
+
+        self.tasks.push(
+            listen_input
+                .thread_pool
+                .subscribe_fallible(|_| ControlFlow::Break(Ok(())))?,
+        );
+
+        Ok(())
+    }
+
+    fn capture_close(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+        let mut errors = CLOSE_ERRORS.lock().unwrap();
+        for task in self.tasks.drain(..) {
+            let res = task.stop_and_join(&listen_input.thread_pool);
+            match res {
+                Ok(()) => errors.push("ok".to_string()),
+                Err(e) => errors.push(format!("{e:#}")),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(DUMMY_PLUGIN_API = DummyPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_listen_errors<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::DummyPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            let event = driver.next_event();
+            match event {
+                Ok(_) => continue,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        drop(driver);
+
+        assert_eq!(super::RUNS.load(Ordering::Relaxed), 3);
+        assert_eq!(
+            *super::CLOSE_ERRORS.lock().unwrap(),
+            ["failed in run 3", "ok"]
+        );
+    }
+
+    instantiate_tests!(test_listen_errors);
+}
@@ -0,0 +1,121 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::listen::{CaptureListenInput, CaptureListenPlugin};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+static TICKS: AtomicUsize = AtomicUsize::new(0);
+
+struct DummyPlugin {
+    start_time: std::time::Instant,
+}
+
+impl Plugin for DummyPlugin {
+    const NAME: &'static CStr = c"dummy";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"dummy async plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self {
+            start_time: std::time::Instant::now(),
+        })
+    }
+}
+
+struct DummyPluginInstance;
+
+impl SourcePluginInstance for DummyPluginInstance {
+    type Plugin = DummyPlugin;
+
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(20));
+        let count = TICKS.load(Ordering::Relaxed);
+        if count >= 10 {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Eof))
+        } else if plugin.start_time.elapsed() > Duration::from_millis(1200) {
+            Err(anyhow::anyhow!("did not get 10 pings from background task")
+                .context(FailureReason::Failure))
+        } else {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+        }
+    }
+}
+
+impl SourcePlugin for DummyPlugin {
+    type Instance = DummyPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"dummy";
+    const PLUGIN_ID: u32 = 1111;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(DummyPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+// no `capture_close`: the routine is stopped by the SDK
+impl CaptureListenPlugin for DummyPlugin {
+    fn capture_open(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+        listen_input.spawn(|| {
+            TICKS.fetch_add(1, Ordering::Relaxed);
+            std::thread::sleep(Duration::from_millis(10));
+            ControlFlow::Continue(())
+        })?;
+
+        Ok(())
+    }
+}
+
+static_plugin!(DUMMY_PLUGIN_API = DummyPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    fn test_listen_managed<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::DummyPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            let event = driver.next_event();
+            match event {
+                Ok(_) => continue,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        drop(driver);
+
+        // the routine does not run after the capture is closed
+        let ticks_at_close = super::TICKS.load(Ordering::Relaxed);
+        assert!(ticks_at_close >= 10);
+        std::thread::sleep(Duration::from_millis(50));
+        assert_eq!(super::TICKS.load(Ordering::Relaxed), ticks_at_close);
+    }
+
+    instantiate_tests!(test_listen_managed);
+}
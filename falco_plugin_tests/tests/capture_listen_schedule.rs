@@ -0,0 +1,142 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::listen::{CaptureListenInput, CaptureListenPlugin, RoutineHandle};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+static DELAYED_RUNS: AtomicUsize = AtomicUsize::new(0);
+static DELAYED_AT_MS: AtomicUsize = AtomicUsize::new(0);
+static PERIODIC_RUNS: AtomicUsize = AtomicUsize::new(0);
+
+struct DummyPlugin {
+    start_time: std::time::Instant,
+    tasks: Vec<RoutineHandle>,
+}
+
+impl Plugin for DummyPlugin {
+    const NAME: &'static CStr = c"dummy";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"dummy async plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self {
+            start_time: std::time::Instant::now(),
+            tasks: Vec::new(),
+        })
+    }
+}
+
+struct DummyPluginInstance;
+
+impl SourcePluginInstance for DummyPluginInstance {
+    type Plugin = DummyPlugin;
+
+    fn next_batch(
+        &mut self,
+        plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(20));
+        if plugin.tasks.iter().all(|t| t.is_finished()) {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Eof))
+        } else if plugin.start_time.elapsed() > Duration::from_millis(1200) {
+            Err(anyhow::anyhow!("scheduled routines did not finish")
+                .context(FailureReason::Failure))
+        } else {
+            Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+        }
+    }
+}
+
+impl SourcePlugin for DummyPlugin {
+    type Instance = DummyPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"dummy";
+    const PLUGIN_ID: u32 = 1111;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(DummyPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl CaptureListenPlugin for DummyPlugin {
+    fn capture_open(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+        let start_time = self.start_time;
+        self.tasks.push(listen_input.thread_pool.subscribe_after(
+            Duration::from_millis(100),
+            move || {
+                DELAYED_RUNS.fetch_add(1, Ordering::Relaxed);
+                DELAYED_AT_MS.store(start_time.elapsed().as_millis() as usize, Ordering::Relaxed);
+            },
+        )?);
+
+        self.tasks.push(listen_input.thread_pool.subscribe_every(
+            Duration::from_millis(20),
+            || {
+                if PERIODIC_RUNS.fetch_add(1, Ordering::Relaxed) == 4 {
+                    ControlFlow::Break(())
+                } else {
+                    ControlFlow::Continue(())
+                }
+            },
+        )?);
+
+        Ok(())
+    }
+
+    fn capture_close(&mut self, listen_input: &CaptureListenInput) -> Result<(), Error> {
+        for task in self.tasks.drain(..) {
+            task.stop_and_join(&listen_input.thread_pool)?;
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(DUMMY_PLUGIN_API = DummyPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_listen_schedule<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::DUMMY_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::DummyPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            let event = driver.next_event();
+            match event {
+                Ok(_) => continue,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        drop(driver);
+
+        assert_eq!(super::DELAYED_RUNS.load(Ordering::Relaxed), 1);
+        assert!(super::DELAYED_AT_MS.load(Ordering::Relaxed) >= 100);
+        assert_eq!(super::PERIODIC_RUNS.load(Ordering::Relaxed), 5);
+    }
+
+    instantiate_tests!(test_listen_schedule);
+}
@@ -0,0 +1,190 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::compression::{CompressedPayload, Compression};
+use falco_plugin::event::events::{Event, EventMetadata};
+use falco_plugin::event::{EventSource, JsonPayload, PluginEvent};
+use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{anyhow, static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+struct Document {
+    name: String,
+    body: String,
+}
+
+impl EventSource for Document {
+    const SOURCE: Option<&'static str> = Some("compressed");
+}
+
+type Payload = CompressedPayload<JsonPayload<Document>>;
+
+const ALGORITHMS: &[Compression] = &[
+    Compression::None,
+    Compression::Zstd { level: 0 },
+    Compression::Lz4,
+];
+
+struct CompressedSourcePlugin;
+
+impl Plugin for CompressedSourcePlugin {
+    const NAME: &'static CStr = c"compressed";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct CompressedSourcePluginInstance {
+    next: usize,
+}
+
+impl SourcePluginInstance for CompressedSourcePluginInstance {
+    type Plugin = CompressedSourcePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        let Some(compression) = ALGORITHMS.get(self.next) else {
+            return Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof));
+        };
+
+        let document = Document {
+            name: format!("document {}", self.next),
+            body: "lorem ipsum ".repeat(1000),
+        };
+        self.next += 1;
+
+        batch.add(Event {
+            metadata: EventMetadata::default(),
+            params: PluginEvent {
+                plugin_id: CompressedSourcePlugin::PLUGIN_ID,
+                event_data: Payload::new(JsonPayload::new(document), *compression),
+            },
+        })?;
+        Ok(())
+    }
+}
+
+impl SourcePlugin for CompressedSourcePlugin {
+    type Instance = CompressedSourcePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"compressed";
+    const PLUGIN_ID: u32 = 1113;
+    type Event<'a> = Event<PluginEvent<Payload>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(CompressedSourcePluginInstance { next: 0 })
+    }
+
+    fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+        let event = event.event()?;
+        Ok(CString::new(
+            event.params.event_data.get_ref().get_ref().name.as_str(),
+        )?)
+    }
+}
+
+struct CompressedExtractPlugin;
+
+impl Plugin for CompressedExtractPlugin {
+    const NAME: &'static CStr = c"compressed_extract";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl CompressedExtractPlugin {
+    fn extract_body_len(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+        let event = req.parsed_event()?;
+        Ok(event.params.event_data.get_ref().get_ref().body.len() as u64)
+    }
+
+    fn extract_algorithm(&mut self, req: ExtractRequest<Self>) -> Result<CString, Error> {
+        let event = req.parsed_event()?;
+        let algorithm = match event.params.event_data.compression() {
+            Compression::None => c"none",
+            Compression::Zstd { .. } => c"zstd",
+            Compression::Lz4 => c"lz4",
+            _ => c"unknown",
+        };
+        Ok(algorithm.to_owned())
+    }
+}
+
+impl ExtractPlugin for CompressedExtractPlugin {
+    type Event<'a> = Event<PluginEvent<Payload>>;
+    type ExtractContext = ();
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("compressed.body_len", &Self::extract_body_len),
+        field("compressed.algorithm", &Self::extract_algorithm),
+    ];
+}
+
+static_plugin!(COMPRESSED_SOURCE_API = CompressedSourcePlugin);
+static_plugin!(COMPRESSED_EXTRACT_API = CompressedExtractPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_compressed_payload<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(&super::COMPRESSED_SOURCE_API, c"").unwrap();
+        let plugin = driver
+            .register_plugin(&super::COMPRESSED_EXTRACT_API, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"compressed").unwrap();
+        let mut driver = driver
+            .start_capture(
+                super::CompressedSourcePlugin::NAME,
+                c"",
+                PlatformData::Disabled,
+            )
+            .unwrap();
+
+        for (n, algorithm) in ["none", "zstd", "lz4"].into_iter().enumerate() {
+            let event = driver.next_event().unwrap();
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+                format!("document {n}")
+            );
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"compressed.body_len", &event)
+                    .unwrap()
+                    .unwrap(),
+                "12000"
+            );
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"compressed.algorithm", &event)
+                    .unwrap()
+                    .unwrap(),
+                algorithm
+            );
+        }
+
+        let event = driver.next_event();
+        assert!(matches!(event, Err(ScapStatus::Eof)))
+    }
+
+    instantiate_tests!(test_compressed_payload);
+}
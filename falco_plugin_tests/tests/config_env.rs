@@ -0,0 +1,112 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Env, Json, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::schemars::JsonSchema;
+use falco_plugin::serde::Deserialize;
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{anyhow, static_plugin};
+use std::ffi::CStr;
+
+#[derive(JsonSchema, Deserialize)]
+#[schemars(crate = "falco_plugin::schemars")]
+#[serde(crate = "falco_plugin::serde")]
+struct EnvConfig {
+    token: String,
+    greeting: String,
+    #[serde(default = "default_port")]
+    port: u16,
+}
+
+fn default_port() -> u16 {
+    8080
+}
+
+struct EnvConfigPlugin;
+
+impl Plugin for EnvConfigPlugin {
+    const NAME: &'static CStr = c"env_config";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = Env<Json<EnvConfig>>;
+
+    fn new(
+        _input: Option<&TablesInput>,
+        Env(Json(config)): Self::ConfigType,
+    ) -> Result<Self, Error> {
+        if config.token != "s3cr3t" {
+            anyhow::bail!("bad token {:?}", config.token);
+        }
+        if config.greeting != "hello" {
+            anyhow::bail!("bad greeting {:?}", config.greeting);
+        }
+        if config.port != 8080 {
+            anyhow::bail!("bad port {}", config.port);
+        }
+        Ok(Self)
+    }
+}
+
+impl ParsePlugin for EnvConfigPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(ENV_CONFIG_PLUGIN_API = EnvConfigPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::{init_plugin, instantiate_tests, TestDriver};
+    use std::sync::Once;
+
+    static SET_ENV: Once = Once::new();
+
+    fn test_env_config<D: TestDriver>() {
+        SET_ENV.call_once(|| {
+            // SAFETY: no other code in this test binary reads or modifies these variables
+            unsafe {
+                std::env::set_var("FALCO_PLUGIN_TEST_TOKEN", "s3cr3t");
+                std::env::set_var("FALCO_PLUGIN_TEST_QUOTED", r#"s3cr3t", "greeting": "evil"#);
+                std::env::remove_var("FALCO_PLUGIN_TEST_GREETING");
+            }
+        });
+
+        init_plugin::<D>(
+            &super::ENV_CONFIG_PLUGIN_API,
+            cr#"{"token": "${FALCO_PLUGIN_TEST_TOKEN}", "greeting": "${FALCO_PLUGIN_TEST_GREETING:-hello}"}"#,
+        )
+        .unwrap();
+
+        // the value is not spliced into the JSON text, so it cannot inject other fields
+        let res = init_plugin::<D>(
+            &super::ENV_CONFIG_PLUGIN_API,
+            cr#"{"token": "${FALCO_PLUGIN_TEST_QUOTED}", "greeting": "hello"}"#,
+        );
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.contains(r#"bad token "s3cr3t\", \"greeting\": \"evil""#),
+            "{err}"
+        );
+
+        let res = init_plugin::<D>(
+            &super::ENV_CONFIG_PLUGIN_API,
+            cr#"{"token": "${FALCO_PLUGIN_TEST_GREETING}", "greeting": "hello"}"#,
+        );
+        let err = res.unwrap_err().to_string();
+        assert!(
+            err.contains("environment variable FALCO_PLUGIN_TEST_GREETING is not set"),
+            "{err}"
+        );
+    }
+
+    instantiate_tests!(test_env_config);
+}
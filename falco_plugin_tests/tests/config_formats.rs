@@ -0,0 +1,114 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{AnyConfig, Plugin, Toml, Yaml};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::serde::Deserialize;
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{anyhow, static_plugin};
+use std::ffi::CStr;
+
+#[derive(Deserialize)]
+#[serde(crate = "falco_plugin::serde")]
+struct Config {
+    five: u64,
+    names: Vec<String>,
+}
+
+fn check_config(config: &Config) -> Result<(), Error> {
+    if config.five != 5 {
+        anyhow::bail!("I wanted five");
+    }
+    if config.names != ["a", "b"] {
+        anyhow::bail!("I wanted a and b");
+    }
+    Ok(())
+}
+
+macro_rules! config_plugin {
+    ($plugin:ident($wrapper:ident) => $api:ident, $name:literal) => {
+        struct $plugin;
+
+        impl Plugin for $plugin {
+            const NAME: &'static CStr = $name;
+            const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+            const DESCRIPTION: &'static CStr = c"test plugin";
+            const CONTACT: &'static CStr = c"rust@localdomain.pl";
+            type ConfigType = $wrapper<Config>;
+
+            fn new(
+                _input: Option<&TablesInput>,
+                $wrapper(config): Self::ConfigType,
+            ) -> Result<Self, Error> {
+                check_config(&config)?;
+                Ok(Self)
+            }
+        }
+
+        impl ParsePlugin for $plugin {
+            type Event<'a> = RawEvent<'a>;
+
+            fn parse_event(
+                &mut self,
+                _event: &EventInput<RawEvent>,
+                _parse_input: &ParseInput,
+            ) -> anyhow::Result<()> {
+                Ok(())
+            }
+        }
+
+        static_plugin!($api = $plugin);
+    };
+}
+
+config_plugin!(YamlPlugin(Yaml) => YAML_PLUGIN_API, c"yaml_config");
+config_plugin!(TomlPlugin(Toml) => TOML_PLUGIN_API, c"toml_config");
+config_plugin!(AnyConfigPlugin(AnyConfig) => ANY_CONFIG_PLUGIN_API, c"any_config");
+
+const YAML_CONFIG: &CStr = c"five: 5\nnames:\n  - a\n  - b\n";
+const TOML_CONFIG: &CStr = c"five = 5\nnames = [\"a\", \"b\"]\n";
+const JSON_CONFIG: &CStr = cr#"{"five": 5, "names": ["a", "b"]}"#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use falco_plugin_tests::{init_plugin, instantiate_tests, TestDriver};
+
+    fn test_yaml_config<D: TestDriver>() {
+        init_plugin::<D>(&YAML_PLUGIN_API, YAML_CONFIG).unwrap();
+
+        let res = init_plugin::<D>(&YAML_PLUGIN_API, c"five: 5\nnames: [a, b]\nfive: 6\n");
+        assert!(res.is_err());
+
+        let res = init_plugin::<D>(&YAML_PLUGIN_API, c"five: 5\nnames: 3\n");
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains("line 2 column 8"), "{err}");
+    }
+
+    fn test_toml_config<D: TestDriver>() {
+        init_plugin::<D>(&TOML_PLUGIN_API, TOML_CONFIG).unwrap();
+
+        let res = init_plugin::<D>(&TOML_PLUGIN_API, c"five = 5\nnames = 3\n");
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains("line 2, column 9"), "{err}");
+
+        let res = init_plugin::<D>(&TOML_PLUGIN_API, c"five = 6\nnames = [\"a\", \"b\"]\n");
+        assert!(res.unwrap_err().to_string().contains("I wanted five"));
+    }
+
+    fn test_any_config<D: TestDriver>() {
+        init_plugin::<D>(&ANY_CONFIG_PLUGIN_API, JSON_CONFIG).unwrap();
+        init_plugin::<D>(&ANY_CONFIG_PLUGIN_API, YAML_CONFIG).unwrap();
+        init_plugin::<D>(&ANY_CONFIG_PLUGIN_API, TOML_CONFIG).unwrap();
+
+        let res = init_plugin::<D>(&ANY_CONFIG_PLUGIN_API, c"{\"five\": 5}");
+        let err = res.unwrap_err().to_string();
+        assert!(err.contains("missing field `names`"), "{err}");
+    }
+
+    instantiate_tests!(
+        test_yaml_config;
+        test_toml_config;
+        test_any_config
+    );
+}
@@ -0,0 +1,201 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, Table, TableFieldInfo, TableMetadata};
+use falco_plugin::tables::{export, FieldTypeId, TablesInput};
+use std::ffi::CStr;
+use std::sync::Arc;
+
+// not a valid table field type, so it has to be skipped
+#[derive(Default)]
+struct ConnCache {
+    seen: Vec<u64>,
+}
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    #[name = "bytes.sent"]
+    bytes_sent: export::Public<u64>,
+    #[default(1500)]
+    mtu: export::Readonly<u64>,
+    #[name(c"bytes.received")]
+    #[default(1)]
+    bytes_received: export::Public<u64>,
+    #[skip]
+    cache: ConnCache,
+    #[skip]
+    #[default(7)]
+    generation: u32,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+struct ConnExportPlugin {
+    conns: Box<ExportedConnTable>,
+    last_key: Option<u64>,
+}
+
+impl Plugin for ConnExportPlugin {
+    const NAME: &'static CStr = c"conn_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.add_table(ExportedConnTable::new(c"conns")?)?;
+
+        Ok(Self {
+            conns,
+            last_key: None,
+        })
+    }
+}
+
+impl ParsePlugin for ConnExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        // the other plugin runs after this one, so look at the entry it added for the previous event
+        let key = event.event_number() as u64;
+        let Some(prev_key) = self.last_key.replace(key) else {
+            return Ok(());
+        };
+
+        // entries created over the plugin API use the defaults for the skipped fields too
+        let mut entry = self
+            .conns
+            .lookup(&prev_key)
+            .ok_or_else(|| anyhow::anyhow!("no entry"))?;
+        anyhow::ensure!(entry.cache.seen.is_empty());
+        anyhow::ensure!(entry.generation == 7);
+        anyhow::ensure!(*entry.bytes_sent == prev_key);
+
+        entry.cache.seen.push(prev_key);
+        entry.generation += 1;
+
+        Ok(())
+    }
+}
+
+static_plugin!(CONN_EXPORT_API = ConnExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    #[name(c"bytes.sent")]
+    bytes_sent: Field<u64, ImportedConn>,
+    #[name(c"bytes.received")]
+    bytes_received: Field<u64, ImportedConn>,
+    mtu: Field<u64, ImportedConn>,
+}
+
+struct ConnImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for ConnImportPlugin {
+    const NAME: &'static CStr = c"conn_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns: ImportedConnTable = input.get_table(c"conns")?;
+
+        // neither the Rust names of renamed fields nor the skipped fields are exposed
+        let mut fields = conns.list_table_fields(input);
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        let expected = [
+            (c"bytes.received", FieldTypeId::U64, false),
+            (c"bytes.sent", FieldTypeId::U64, false),
+            (c"mtu", FieldTypeId::U64, true),
+        ]
+        .map(|(name, field_type, read_only)| TableFieldInfo {
+            name: name.to_owned(),
+            field_type,
+            read_only,
+        });
+        anyhow::ensure!(fields == expected, "unexpected fields {fields:?}");
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for ConnImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.conns.create_entry(w)?;
+        anyhow::ensure!(entry.get_bytes_sent(r)? == 0);
+        anyhow::ensure!(entry.get_bytes_received(r)? == 1);
+        anyhow::ensure!(entry.get_mtu(r)? == 1500);
+        anyhow::ensure!(entry.set_mtu(w, &9000).is_err());
+
+        entry.set_bytes_sent(w, &key)?;
+        self.conns.insert(r, w, &key, entry)?;
+
+        Ok(())
+    }
+}
+
+static_plugin!(CONN_IMPORT_API = ConnImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_entry_attributes<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::CONN_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::CONN_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_entry_attributes);
+}
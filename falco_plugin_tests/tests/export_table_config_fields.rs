@@ -0,0 +1,221 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Json, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::schemars::JsonSchema;
+use falco_plugin::serde::Deserialize;
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export::{DynamicField, DynamicFieldValue};
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TablesInput};
+use std::collections::BTreeMap;
+use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ExportedEntry {
+    events: export::Readonly<u64>,
+}
+
+type ExportedTable = export::Table<u64, ExportedEntry>;
+
+#[derive(JsonSchema, Deserialize)]
+#[schemars(crate = "falco_plugin::schemars")]
+#[serde(crate = "falco_plugin::serde")]
+struct ExportConfig {
+    extra_fields: Vec<export::DynamicFieldSpec>,
+}
+
+static CHECKED_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct ConfigFieldsExportPlugin {
+    table: Box<ExportedTable>,
+    fields: BTreeMap<String, DynamicField>,
+}
+
+impl Plugin for ConfigFieldsExportPlugin {
+    const NAME: &'static CStr = c"config_fields_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = Json<ExportConfig>;
+
+    fn new(input: Option<&TablesInput>, Json(config): Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let mut table = ExportedTable::new(c"config_fields")?;
+        let fields = table.add_dynamic_fields(&config.extra_fields)?;
+        let table = input.add_table(table)?;
+
+        Ok(Self { table, fields })
+    }
+}
+
+impl ParsePlugin for ConfigFieldsExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let key = event.event_number() as u64;
+        let score = &self.fields["score"];
+        let owner = &self.fields["owner"];
+
+        let mut entry = self.table.entry(key).or_default()?;
+        *entry.events = key;
+        anyhow::ensure!(score.get(&entry).is_none());
+        anyhow::ensure!(score
+            .set(&mut entry, DynamicFieldValue::String(CString::from(c"bad")))
+            .is_err());
+
+        score.set(&mut entry, DynamicFieldValue::U64(key * 10))?;
+        owner.set(
+            &mut entry,
+            DynamicFieldValue::String(CString::new(format!("owner{key}"))?),
+        )?;
+        anyhow::ensure!(
+            matches!(score.get(&entry), Some(DynamicFieldValue::U64(v)) if *v == key * 10)
+        );
+
+        Ok(())
+    }
+}
+
+static_plugin!(CONFIG_FIELDS_EXPORT_API = ConfigFieldsExportPlugin);
+
+type ImportedEntry = Entry<Arc<ImportedEntryMetadata>>;
+type ImportedTable = Table<u64, ImportedEntry>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedEntry)]
+struct ImportedEntryMetadata {
+    score: Field<u64, ImportedEntry>,
+    owner: Field<CStr, ImportedEntry>,
+}
+
+struct ConfigFieldsImportPlugin {
+    table: ImportedTable,
+}
+
+impl Plugin for ConfigFieldsImportPlugin {
+    const NAME: &'static CStr = c"config_fields_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let table = input.get_table(c"config_fields")?;
+
+        Ok(Self { table })
+    }
+}
+
+impl ParsePlugin for ConfigFieldsImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.table.get_entry(r, &key)?;
+        anyhow::ensure!(entry.get_score(r)? == key * 10);
+        anyhow::ensure!(entry.get_owner(r)?.to_str()? == format!("owner{key}"));
+
+        // the owner field is read-only for other plugins
+        anyhow::ensure!(entry.set_owner(w, c"someone else").is_err());
+        entry.set_score(w, &0)?;
+
+        CHECKED_COUNT.fetch_add(1, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+static_plugin!(CONFIG_FIELDS_IMPORT_API = ConfigFieldsImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_config_fields<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(
+                &super::CONFIG_FIELDS_EXPORT_API,
+                cr#"{"extra_fields": [
+                    {"name": "score", "type": "u64"},
+                    {"name": "owner", "type": "string", "readonly": true}
+                ]}"#,
+            )
+            .unwrap();
+        driver
+            .register_plugin(&super::CONFIG_FIELDS_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+        assert_eq!(super::CHECKED_COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    instantiate_native_tests!(test_config_fields);
+}
+
+#[test]
+fn test_conflicting_fields() {
+    use falco_plugin::tables::export::{DynamicFieldSpec, DynamicFieldType};
+
+    let spec = |name: &str, field_type| DynamicFieldSpec {
+        name: name.to_string(),
+        field_type,
+        readonly: false,
+    };
+
+    let mut table = ExportedTable::new(c"conflicts").unwrap();
+    let fields = table
+        .add_dynamic_fields(&[
+            spec("score", DynamicFieldType::U64),
+            spec("score", DynamicFieldType::U64),
+        ])
+        .unwrap();
+    assert_eq!(fields.len(), 1);
+    assert_eq!(fields["score"].field_type(), DynamicFieldType::U64);
+
+    assert!(table
+        .add_dynamic_fields(&[spec("score", DynamicFieldType::Bool)])
+        .is_err());
+    assert!(table
+        .add_dynamic_fields(&[spec("events", DynamicFieldType::U64)])
+        .is_err());
+    assert!(table
+        .add_dynamic_fields(&[spec("bad\0name", DynamicFieldType::U64)])
+        .is_err());
+}
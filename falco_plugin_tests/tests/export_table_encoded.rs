@@ -0,0 +1,166 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, Encoded, TablesInput};
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    ports: export::Public<Encoded<Vec<u64>>>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+static CHECKED_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct EncodedExportPlugin {
+    conns: Box<ExportedConnTable>,
+}
+
+impl Plugin for EncodedExportPlugin {
+    const NAME: &'static CStr = c"encoded_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.add_table(ExportedConnTable::new(c"conns")?)?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for EncodedExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let key = event.event_number() as u64;
+
+        // the import plugin replaced the ports of the previous entry
+        if key > 1 {
+            let prev = key - 1;
+            let entry = self
+                .conns
+                .lookup(&prev)
+                .ok_or_else(|| anyhow::anyhow!("entry {prev} not found"))?;
+            anyhow::ensure!(**entry.ports == [prev, prev + 1]);
+            CHECKED_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let mut entry = self.conns.create_entry()?;
+        entry.ports.push(key);
+        self.conns.insert(&key, entry);
+
+        Ok(())
+    }
+}
+
+static_plugin!(ENCODED_EXPORT_API = EncodedExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    ports: Field<CStr, ImportedConn>,
+}
+
+struct EncodedImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for EncodedImportPlugin {
+    const NAME: &'static CStr = c"encoded_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for EncodedImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.conns.get_entry(r, &key)?;
+        let ports = entry.get_ports(r)?;
+        anyhow::ensure!(*Encoded::<Vec<u64>>::decode(ports)? == [key]);
+
+        entry.set_ports(w, Encoded::new(vec![key, key + 1]).encoded()?)?;
+
+        // writes that do not decode as the exported type are rejected
+        anyhow::ensure!(entry.set_ports(w, c"not json").is_err());
+
+        Ok(())
+    }
+}
+
+static_plugin!(ENCODED_IMPORT_API = EncodedImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_encoded_field<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::ENCODED_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::ENCODED_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+        assert_eq!(super::CHECKED_COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    instantiate_native_tests!(test_encoded_field);
+}
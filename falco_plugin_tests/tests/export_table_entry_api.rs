@@ -0,0 +1,51 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Counter {
+    count: export::Public<u64>,
+}
+
+#[test]
+fn test_entry_api() {
+    let mut table = export::Table::<u64, Counter>::new(c"counters").unwrap();
+    for (key, expected) in [(1, 1), (2, 1), (1, 2), (1, 3)] {
+        let counter = table
+            .entry(key)
+            .and_modify(|counter| *counter.count += 1)
+            .or_insert_with(|counter| *counter.count = 1)
+            .unwrap();
+        assert_eq!(*counter.count, expected);
+    }
+    assert_eq!(*table.get(&1).unwrap().count, 3);
+    assert_eq!(*table.get(&2).unwrap().count, 1);
+
+    assert!(matches!(table.entry(3), export::TableEntry::Vacant(_)));
+    assert_eq!(*table.entry(3).key(), 3);
+    assert_eq!(*table.entry(3).or_default().unwrap().count, 0);
+    assert_eq!(table.size(), 3);
+
+    match table.entry(3) {
+        export::TableEntry::Occupied(mut entry) => *entry.get_mut().count = 1,
+        export::TableEntry::Vacant(_) => panic!("entry 3 not found"),
+    }
+    assert_eq!(*table.get_mut(&3).unwrap().count, 1);
+}
+
+#[test]
+fn test_iter_and_retain() {
+    let mut table = export::Table::<u64, Counter>::new(c"counters").unwrap();
+    for key in 0..6 {
+        let mut counter = table.entry(key).or_default().unwrap();
+        *counter.count = key * 10;
+    }
+
+    let seen: Vec<_> = table.iter().map(|(key, e)| (key, *e.count)).collect();
+    assert_eq!(seen, (0..6).map(|key| (key, key * 10)).collect::<Vec<_>>());
+
+    table.retain(|key, counter| {
+        *counter.count += 1;
+        key % 2 == 0
+    });
+    let seen: Vec<_> = table.iter().map(|(key, e)| (key, *e.count)).collect();
+    assert_eq!(seen, [(0, 1), (2, 21), (4, 41)]);
+}
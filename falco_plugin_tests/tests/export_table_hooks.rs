@@ -0,0 +1,169 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TablesInput};
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    bytes: export::Public<u64>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+static CREATED_KEYS: AtomicU64 = AtomicU64::new(0);
+static ERASED_KEYS: AtomicU64 = AtomicU64::new(0);
+static CLEAR_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct HooksExportPlugin {
+    #[allow(dead_code)]
+    conns: Box<ExportedConnTable>,
+}
+
+impl Plugin for HooksExportPlugin {
+    const NAME: &'static CStr = c"hooks_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = ExportedConnTable::new(c"conns")?
+            .with_create_callback(|key, _entry| {
+                CREATED_KEYS.fetch_add(*key, Ordering::Relaxed);
+            })
+            .with_erase_callback(|key, entry| {
+                // the importer sets the field after inserting the entry
+                assert_eq!(entry.map(|entry| *entry.bytes), Some(*key * 10));
+                ERASED_KEYS.fetch_add(*key, Ordering::Relaxed);
+            })
+            .with_clear_callback(|| {
+                CLEAR_COUNT.fetch_add(1, Ordering::Relaxed);
+            });
+        let conns = input.add_table(conns)?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for HooksExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(HOOKS_EXPORT_API = HooksExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    bytes: Field<u64, ImportedConn>,
+}
+
+struct HooksImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for HooksImportPlugin {
+    const NAME: &'static CStr = c"hooks_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for HooksImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.conns.create_entry(w)?;
+        let entry = self.conns.insert(r, w, &key, entry)?;
+        entry.set_bytes(w, &(key * 10))?;
+
+        match key {
+            2 => self.conns.erase(w, &1)?,
+            4 => self.conns.clear(w)?,
+            _ => {}
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(HOOKS_IMPORT_API = HooksImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_lifecycle_hooks<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::HOOKS_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::HOOKS_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+
+        // entries 1 through 4 were created, 1 was erased and the rest got cleared
+        assert_eq!(super::CREATED_KEYS.load(Ordering::Relaxed), 1 + 2 + 3 + 4);
+        assert_eq!(super::ERASED_KEYS.load(Ordering::Relaxed), 1);
+        assert_eq!(super::CLEAR_COUNT.load(Ordering::Relaxed), 1);
+    }
+
+    instantiate_native_tests!(test_lifecycle_hooks);
+}
@@ -0,0 +1,210 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, IterationResult, Table, TableMetadata};
+use falco_plugin::tables::{export, TableReader, TableWriter, TablesInput};
+use std::ffi::CStr;
+use std::ops::ControlFlow;
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ItemEntry {
+    id: export::Public<u64>,
+}
+
+type ExportedItemTable = export::Table<u64, ItemEntry>;
+
+struct ItemExportPlugin {
+    #[allow(dead_code)]
+    items: Box<ExportedItemTable>,
+}
+
+impl Plugin for ItemExportPlugin {
+    const NAME: &'static CStr = c"item_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let items = input.add_table(ExportedItemTable::new(c"items")?)?;
+
+        Ok(Self { items })
+    }
+}
+
+impl ParsePlugin for ItemExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(ITEM_EXPORT_API = ItemExportPlugin);
+
+type ImportedItem = Entry<Arc<ImportedItemMetadata>>;
+type ImportedItemTable = Table<u64, ImportedItem>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedItem)]
+struct ImportedItemMetadata {
+    id: Field<u64, ImportedItem>,
+}
+
+struct ItemIteratePlugin {
+    items: ImportedItemTable,
+}
+
+impl ItemIteratePlugin {
+    fn add_item(&self, r: &impl TableReader, w: &impl TableWriter, id: u64) -> anyhow::Result<()> {
+        let entry = self.items.create_entry(w)?;
+        entry.set_id(w, &id)?;
+        self.items.insert(r, w, &id, entry)?;
+        Ok(())
+    }
+
+    fn collect_ids(
+        &self,
+        r: &impl TableReader,
+        mut func: impl FnMut(u64) -> anyhow::Result<ControlFlow<()>>,
+    ) -> anyhow::Result<(Vec<u64>, IterationResult)> {
+        let mut ids = Vec::new();
+        let mut err = None;
+        let result = self.items.iter_entries_mut(r, |entry| {
+            let id = match entry.get_id(r) {
+                Ok(id) => id,
+                Err(e) => {
+                    err = Some(e);
+                    return ControlFlow::Break(());
+                }
+            };
+            ids.push(id);
+            func(id).unwrap_or_else(|e| {
+                err = Some(e);
+                ControlFlow::Break(())
+            })
+        })?;
+
+        match err {
+            Some(e) => Err(e),
+            None => Ok((ids, result)),
+        }
+    }
+}
+
+impl Plugin for ItemIteratePlugin {
+    const NAME: &'static CStr = c"item_iterate";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let items = input.get_table(c"items")?;
+
+        Ok(Self { items })
+    }
+}
+
+impl ParsePlugin for ItemIteratePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+
+        self.items.clear(w)?;
+        for id in [4, 2, 5, 1, 3] {
+            self.add_item(r, w, id)?;
+        }
+
+        // entries are visited in key order
+        let (ids, result) = self.collect_ids(r, |_| Ok(ControlFlow::Continue(())))?;
+        anyhow::ensure!(ids == [1, 2, 3, 4, 5], "got {ids:?}");
+        anyhow::ensure!(result == IterationResult::Finished);
+
+        // stopping early is reported back
+        let (ids, result) = self.collect_ids(r, |id| {
+            Ok(match id {
+                2 => ControlFlow::Break(()),
+                _ => ControlFlow::Continue(()),
+            })
+        })?;
+        anyhow::ensure!(ids == [1, 2], "got {ids:?}");
+        anyhow::ensure!(result == IterationResult::Exited);
+
+        // the table can be modified while iterating over it
+        let (ids, result) = self.collect_ids(r, |id| {
+            if id == 1 {
+                let other = self.items.get_entry(r, &3)?;
+                anyhow::ensure!(other.get_id(r)? == 3);
+                drop(other);
+
+                self.items.erase(w, &4)?;
+                self.add_item(r, w, 6)?;
+            }
+            Ok(ControlFlow::Continue(()))
+        })?;
+        anyhow::ensure!(ids == [1, 2, 3, 5], "got {ids:?}");
+        anyhow::ensure!(result == IterationResult::Finished);
+
+        let (ids, _) = self.collect_ids(r, |_| Ok(ControlFlow::Continue(())))?;
+        anyhow::ensure!(ids == [1, 2, 3, 5, 6], "got {ids:?}");
+
+        Ok(())
+    }
+}
+
+static_plugin!(ITEM_ITERATE_API = ItemIteratePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_export_iterate<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::ITEM_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::ITEM_ITERATE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_export_iterate);
+}
@@ -0,0 +1,54 @@
+use falco_plugin::serde_json::json;
+use falco_plugin::tables::export;
+use std::ffi::CString;
+
+#[derive(export::Entry, serde::Serialize, serde::Deserialize)]
+struct Process {
+    comm: export::Public<CString>,
+    events: export::Readonly<u64>,
+}
+
+#[derive(export::Entry, serde::Serialize, serde::Deserialize)]
+struct Container {
+    pid: export::Public<u64>,
+}
+
+fn insert_process(table: &mut export::Table<u64, Process>, key: u64, comm: &str) {
+    let mut entry = table.create_entry().unwrap();
+    *entry.comm = CString::new(comm).unwrap();
+    *entry.events = key * 10;
+    table.insert(&key, entry);
+}
+
+#[test]
+fn test_to_json() {
+    let mut table = export::Table::<u64, Process>::new(c"processes").unwrap();
+    insert_process(&mut table, 1, "init");
+    insert_process(&mut table, 2, "bash");
+
+    let json = table.to_json().unwrap();
+    assert_eq!(
+        json,
+        json!({
+            "1": { "comm": b"init", "events": 10 },
+            "2": { "comm": b"bash", "events": 20 },
+        })
+    );
+
+    let restored = export::Table::<u64, Process>::from_json(c"processes", json).unwrap();
+    assert_eq!(restored.to_json().unwrap(), table.to_json().unwrap());
+    let entry = restored.lookup(&2).unwrap();
+    assert_eq!(entry.comm.as_c_str(), c"bash");
+}
+
+#[test]
+fn test_string_keys() {
+    let json = json!({
+        "abc": { "pid": 1 },
+        "def": { "pid": 2 },
+    });
+    let table =
+        export::Table::<CString, Container>::from_json(c"containers", json.clone()).unwrap();
+    assert_eq!(*table.lookup(c"def").unwrap().pid, 2);
+    assert_eq!(table.to_json().unwrap(), json);
+}
@@ -0,0 +1,162 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export::EvictionPolicy;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TablesInput};
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    bytes: export::Public<u64>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+static EVICTED_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct BoundedExportPlugin {
+    #[allow(dead_code)]
+    conns: Box<ExportedConnTable>,
+}
+
+impl Plugin for BoundedExportPlugin {
+    const NAME: &'static CStr = c"bounded_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = ExportedConnTable::new(c"conns")?
+            .with_max_entries(2, EvictionPolicy::Fifo)
+            .with_eviction_callback(|key, entry| {
+                assert_eq!(*key, *entry.bytes);
+                EVICTED_COUNT.fetch_add(1, Ordering::Relaxed);
+            });
+        let conns = input.add_table(conns)?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for BoundedExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(BOUNDED_EXPORT_API = BoundedExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    bytes: Field<u64, ImportedConn>,
+}
+
+struct BoundedImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for BoundedImportPlugin {
+    const NAME: &'static CStr = c"bounded_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for BoundedImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.conns.create_entry(w)?;
+        entry.set_bytes(w, &key)?;
+        self.conns.insert(r, w, &key, entry)?;
+
+        // only the two most recently inserted entries are kept
+        anyhow::ensure!(self.conns.get_size(r)? <= 2);
+        anyhow::ensure!(self.conns.get_entry(r, &key).is_ok());
+        if key > 2 {
+            anyhow::ensure!(self.conns.get_entry(r, &(key - 1)).is_ok());
+            anyhow::ensure!(self.conns.get_entry(r, &(key - 2)).is_err());
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(BOUNDED_IMPORT_API = BoundedImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_max_entries<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::BOUNDED_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::BOUNDED_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+
+        // the entries for the first two events got evicted
+        assert_eq!(super::EVICTED_COUNT.load(Ordering::Relaxed), 2);
+    }
+
+    instantiate_native_tests!(test_max_entries);
+}
@@ -0,0 +1,193 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export::EvictionPolicy;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TablesInput};
+use std::ffi::CStr;
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    bytes: export::Public<u64>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+struct MetricsExportPlugin {
+    conns: Box<ExportedConnTable>,
+}
+
+impl Plugin for MetricsExportPlugin {
+    const NAME: &'static CStr = c"table_metrics";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = ExportedConnTable::new(c"conns")?
+            .with_max_entries(2, EvictionPolicy::Fifo)
+            .with_metrics();
+        let conns = input.add_table(conns)?;
+
+        Ok(Self { conns })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        self.conns.metrics()
+    }
+}
+
+impl ParsePlugin for MetricsExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(METRICS_EXPORT_API = MetricsExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    bytes: Field<u64, ImportedConn>,
+    #[custom]
+    packets: Field<u64, ImportedConn>,
+}
+
+struct MetricsImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for MetricsImportPlugin {
+    const NAME: &'static CStr = c"table_metrics_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for MetricsImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let entry = self.conns.create_entry(w)?;
+        entry.set_bytes(w, &key)?;
+        entry.set_packets(w, &1)?;
+        self.conns.insert(r, w, &key, entry)?;
+
+        if key.is_multiple_of(2) {
+            self.conns.erase(w, &(key - 1))?;
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(METRICS_IMPORT_API = MetricsImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    #[track_caller]
+    fn check_metrics<D: CapturingTestDriver>(driver: &mut D, expected: &[(&str, u64)]) {
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.name.starts_with("table_metrics."))
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(metrics, expected);
+    }
+
+    fn test_table_metrics<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 5, "batch_size": 5}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::METRICS_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::METRICS_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        check_metrics(
+            &mut driver,
+            &[
+                ("table_metrics.conns.entries", 0),
+                ("table_metrics.conns.dynamic_fields", 1),
+                ("table_metrics.conns.inserts", 0),
+                ("table_metrics.conns.erases", 0),
+                ("table_metrics.conns.evictions", 0),
+            ],
+        );
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 5);
+
+        // every other entry gets erased right away, so only one entry gets evicted
+        check_metrics(
+            &mut driver,
+            &[
+                ("table_metrics.conns.entries", 2),
+                ("table_metrics.conns.dynamic_fields", 1),
+                ("table_metrics.conns.inserts", 5),
+                ("table_metrics.conns.erases", 2),
+                ("table_metrics.conns.evictions", 1),
+            ],
+        );
+    }
+
+    instantiate_tests!(test_table_metrics);
+}
@@ -0,0 +1,113 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::{export, TablesInput};
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+#[derive(export::Entry)]
+struct ItemEntry {
+    id: export::Public<u64>,
+}
+
+type ExportedItemTable = export::Table<u64, ItemEntry>;
+
+static CHECKED_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct ReadViewPlugin {
+    items: Box<ExportedItemTable>,
+}
+
+impl Plugin for ReadViewPlugin {
+    const NAME: &'static CStr = c"read_view";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let items = input.add_table(ExportedItemTable::new(c"items")?)?;
+
+        Ok(Self { items })
+    }
+}
+
+impl ParsePlugin for ReadViewPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let key = event.event_number() as u64;
+        let mut entry = self.items.create_entry()?;
+        *entry.id = key;
+        self.items.insert(&key, entry);
+
+        // the view can be moved into a background thread, unlike the table itself
+        let view = self.items.read_view();
+        std::thread::spawn(move || -> anyhow::Result<()> {
+            anyhow::ensure!(view.len() as u64 == key);
+            let entry = view
+                .get(&key)
+                .ok_or_else(|| anyhow::anyhow!("entry {key} not found"))?;
+            anyhow::ensure!(*entry.id == key);
+
+            let mut sum = 0;
+            view.for_each(|key, entry| {
+                assert_eq!(*key, *entry.id);
+                sum += *key;
+                true
+            });
+            anyhow::ensure!(sum == key * (key + 1) / 2);
+
+            CHECKED_COUNT.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        })
+        .join()
+        .unwrap()
+    }
+}
+
+static_plugin!(READ_VIEW_API = ReadViewPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_read_view<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver.register_plugin(&super::READ_VIEW_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+        assert_eq!(super::CHECKED_COUNT.load(Ordering::Relaxed), 4);
+    }
+
+    instantiate_native_tests!(test_read_view);
+}
@@ -0,0 +1,188 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::api::ss_plugin_state_data;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export::FieldRef;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, FieldTypeId, TablesInput};
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    bytes: export::Public<u64>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+static CHECKED_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct RuntimeNestedExportPlugin {
+    conns: Box<ExportedConnTable>,
+    ports: FieldRef,
+}
+
+impl Plugin for RuntimeNestedExportPlugin {
+    const NAME: &'static CStr = c"runtime_nested_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let mut conns = ExportedConnTable::new(c"conns")?;
+        let ports = conns.add_table_field::<u64>(c"ports", &[(c"hits", FieldTypeId::U64)])?;
+        let conns = input.add_table(conns)?;
+
+        Ok(Self { conns, ports })
+    }
+}
+
+impl ParsePlugin for RuntimeNestedExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let key = event.event_number() as u64;
+
+        // the import plugin added a port to the nested table of the previous entry
+        if key > 1 {
+            let prev = key - 1;
+            let entry = self
+                .conns
+                .lookup(&prev)
+                .ok_or_else(|| anyhow::anyhow!("entry {prev} not found"))?;
+            let ports = self.conns.get_table_field::<u64>(&entry, &self.ports)?;
+            anyhow::ensure!(ports.size() == 1);
+
+            let hits = ports
+                .get_field(c"hits", FieldTypeId::U64)
+                .ok_or_else(|| anyhow::anyhow!("hits field not found"))?;
+            let port = ports
+                .lookup(&80)
+                .ok_or_else(|| anyhow::anyhow!("port 80 not found"))?;
+            let mut out = ss_plugin_state_data { u64_: 0 };
+            ports.get_field_value(&port, hits.as_ref(), &mut out)?;
+            anyhow::ensure!(unsafe { out.u64_ } == prev);
+            CHECKED_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+
+        let entry = self.conns.create_entry()?;
+        self.conns.insert(&key, entry);
+
+        Ok(())
+    }
+}
+
+static_plugin!(RUNTIME_NESTED_EXPORT_API = RuntimeNestedExportPlugin);
+
+type ImportedPort = Entry<Arc<ImportedPortMetadata>>;
+type ImportedPortTable = Table<u64, ImportedPort>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedPort)]
+struct ImportedPortMetadata {
+    hits: Field<u64, ImportedPort>,
+}
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    ports: Field<ImportedPortTable, ImportedConn>,
+}
+
+struct RuntimeNestedImportPlugin {
+    conns: ImportedConnTable,
+}
+
+impl Plugin for RuntimeNestedImportPlugin {
+    const NAME: &'static CStr = c"runtime_nested_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for RuntimeNestedImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        let ports = self.conns.get_entry(r, &key)?.get_ports(r)?;
+        anyhow::ensure!(ports.get_size(r)? == 0);
+
+        let port = ports.create_entry(w)?;
+        port.set_hits(w, &key)?;
+        ports.insert(r, w, &80, port)?;
+
+        Ok(())
+    }
+}
+
+static_plugin!(RUNTIME_NESTED_IMPORT_API = RuntimeNestedImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_runtime_nested_table<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::RUNTIME_NESTED_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::RUNTIME_NESTED_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+        assert_eq!(super::CHECKED_COUNT.load(Ordering::Relaxed), 3);
+    }
+
+    instantiate_native_tests!(test_runtime_nested_table);
+}
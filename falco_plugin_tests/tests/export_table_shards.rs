@@ -0,0 +1,188 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TablesInput};
+use std::ffi::CStr;
+use std::sync::Arc;
+
+const THREADS: u64 = 4;
+const KEYS_PER_THREAD: u64 = 25;
+
+#[derive(export::Entry)]
+struct ItemEntry {
+    id: export::Public<u64>,
+}
+
+type ExportedItemTable = export::Table<u64, ItemEntry>;
+
+struct ShardedExportPlugin {
+    items: Box<ExportedItemTable>,
+}
+
+impl Plugin for ShardedExportPlugin {
+    const NAME: &'static CStr = c"sharded_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let items =
+            input.add_table(ExportedItemTable::new(c"items")?.with_shards(THREADS as usize))?;
+
+        Ok(Self { items })
+    }
+}
+
+impl ParsePlugin for ShardedExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let base = event.event_number() as u64 * THREADS * KEYS_PER_THREAD;
+
+        // fill the table from several threads at once
+        std::thread::scope(|s| {
+            let threads = (0..THREADS)
+                .map(|thread| {
+                    let shards = self.items.shards();
+                    let create_entry = self.items.create_entry_fn();
+                    s.spawn(move || -> anyhow::Result<()> {
+                        for i in 0..KEYS_PER_THREAD {
+                            let key = base + thread * KEYS_PER_THREAD + i;
+                            let entry = create_entry()?;
+                            *entry.write().id = key;
+                            shards.shard(&key).write().insert(key, entry);
+                        }
+                        Ok(())
+                    })
+                })
+                .collect::<Vec<_>>();
+
+            threads
+                .into_iter()
+                .try_for_each(|thread| thread.join().unwrap())
+        })
+    }
+}
+
+static_plugin!(SHARDED_EXPORT_API = ShardedExportPlugin);
+
+type ImportedItem = Entry<Arc<ImportedItemMetadata>>;
+type ImportedItemTable = Table<u64, ImportedItem>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedItem)]
+struct ImportedItemMetadata {
+    id: Field<u64, ImportedItem>,
+}
+
+struct ShardedImportPlugin {
+    items: ImportedItemTable,
+    events: u64,
+}
+
+impl Plugin for ShardedImportPlugin {
+    const NAME: &'static CStr = c"sharded_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let items = input.get_table(c"items")?;
+
+        Ok(Self { items, events: 0 })
+    }
+}
+
+impl ParsePlugin for ShardedImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        self.events += 1;
+
+        let size = self.items.get_size(r)? as u64;
+        anyhow::ensure!(
+            size == self.events * THREADS * KEYS_PER_THREAD,
+            "got {size} entries"
+        );
+
+        let base = event.event_number() as u64 * THREADS * KEYS_PER_THREAD;
+        for key in base..base + THREADS * KEYS_PER_THREAD {
+            let entry = self.items.get_entry(r, &key)?;
+            anyhow::ensure!(entry.get_id(r)? == key);
+        }
+
+        // the other plugins see a single table
+        let mut ids = Vec::new();
+        self.items.iter_entries_mut(r, |entry| {
+            ids.push(entry.get_id(r).unwrap());
+            std::ops::ControlFlow::Continue(())
+        })?;
+        anyhow::ensure!(ids.len() as u64 == size);
+
+        self.items.erase(w, &base)?;
+        anyhow::ensure!(self.items.get_entry(r, &base).is_err());
+        let entry = self.items.create_entry(w)?;
+        entry.set_id(w, &base)?;
+        self.items.insert(r, w, &base, entry)?;
+
+        Ok(())
+    }
+}
+
+static_plugin!(SHARDED_IMPORT_API = ShardedImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_sharded_table<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::SHARDED_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::SHARDED_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_sharded_table);
+}
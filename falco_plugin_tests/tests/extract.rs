@@ -1,4 +1,5 @@
 use falco_plugin::base::Plugin;
+use falco_plugin::source::SourcePlugin;
 use falco_plugin_tests::plugin_collection::extract::remaining_from_payload::EXTRACT_REMAINING_FROM_PAYLOAD;
 use falco_plugin_tests::plugin_collection::source::countdown::{
     check_metrics, CountdownPlugin, COUNTDOWN_PLUGIN_API,
@@ -36,6 +37,13 @@ fn test_extract<D: TestDriver>() {
             .unwrap(),
         "3 events remaining"
     );
+    assert_eq!(
+        driver
+            .event_field_as_string(c"dummy.payload_uppercase", &event)
+            .unwrap()
+            .unwrap(),
+        "3 EVENTS REMAINING"
+    );
     assert_eq!(
         driver
             .event_field_as_string(c"dummy.payload_repeated[2]", &event)
@@ -74,6 +82,31 @@ fn test_extract<D: TestDriver>() {
             .unwrap(),
         "(3,3,3,3,3)"
     );
+    assert_eq!(
+        driver
+            .event_field_as_string(c"dummy.source", &event)
+            .unwrap()
+            .unwrap(),
+        "countdown"
+    );
+    assert_eq!(
+        driver
+            .event_field_as_string(c"dummy.plugin_id", &event)
+            .unwrap()
+            .unwrap(),
+        CountdownPlugin::PLUGIN_ID.to_string()
+    );
+    assert_eq!(
+        driver
+            .event_field_as_string(c"dummy.num_fields", &event)
+            .unwrap()
+            .unwrap(),
+        "1"
+    );
+    assert!(driver.event_field_is_none(c"dummy.not_applicable", &event));
+    assert!(driver
+        .event_field_as_string(c"dummy.unavailable", &event)
+        .is_err());
     check_metrics(&mut driver, 1, 4);
 
     assert_eq!(
@@ -99,3 +132,41 @@ fn test_extract<D: TestDriver>() {
 }
 
 instantiate_tests!(test_extract);
+
+#[cfg(test)]
+mod native_tests {
+    use falco_plugin::api::ss_plugin_rc_SS_PLUGIN_TIMEOUT;
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::native::ExtractError;
+    use falco_plugin_tests::plugin_collection::extract::remaining_from_payload::EXTRACT_REMAINING_FROM_PAYLOAD;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_extract_unavailable<D: TestDriver>() {
+        let (mut driver, _) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        let plugin = driver
+            .register_plugin(&EXTRACT_REMAINING_FROM_PAYLOAD, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"countdown").unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let event = driver.next_event().unwrap();
+        let err = driver
+            .event_field_as_string(c"dummy.unavailable", &event)
+            .unwrap_err();
+        let ExtractError(rc) = err.downcast_ref::<ExtractError>().unwrap();
+        assert_eq!(*rc, ss_plugin_rc_SS_PLUGIN_TIMEOUT);
+    }
+
+    instantiate_native_tests!(test_extract_unavailable);
+}
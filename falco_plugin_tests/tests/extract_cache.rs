@@ -0,0 +1,128 @@
+use falco_event_schema::events::PPME_PLUGINEVENT_E;
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::{CStr, CString};
+
+struct CachingPlugin {
+    calls: u64,
+}
+
+impl Plugin for CachingPlugin {
+    const NAME: &'static CStr = c"caching";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self { calls: 0 })
+    }
+}
+
+impl CachingPlugin {
+    fn extract_calls(&mut self, _req: ExtractRequest<Self>) -> Result<u64, Error> {
+        self.calls += 1;
+        Ok(self.calls)
+    }
+
+    fn extract_calls_with_arg(
+        &mut self,
+        _req: ExtractRequest<Self>,
+        arg: &CStr,
+    ) -> Result<CString, Error> {
+        self.calls += 1;
+        Ok(CString::new(format!("{}:{}", arg.to_str()?, self.calls))?)
+    }
+}
+
+impl ExtractPlugin for CachingPlugin {
+    type Event<'a> = Event<PPME_PLUGINEVENT_E<'a>>;
+    type ExtractContext = ();
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("caching.calls", &Self::extract_calls),
+        field("caching.calls_with_arg", &Self::extract_calls_with_arg),
+    ];
+    const CACHE_EXTRACTED_VALUES: bool = true;
+}
+
+static_plugin!(CACHING_PLUGIN_API = CachingPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn start<D: TestDriver>() -> D::Capturing {
+        let (mut driver, _) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        let plugin = driver
+            .register_plugin(&super::CACHING_PLUGIN_API, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"countdown").unwrap();
+        driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap()
+    }
+
+    fn test_cached_per_event<D: TestDriver>() {
+        let mut driver = start::<D>();
+
+        let event = driver.next_event().unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"caching.calls", &event)
+                    .unwrap()
+                    .unwrap(),
+                "1"
+            );
+        }
+
+        let event = driver.next_event().unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"caching.calls", &event)
+                    .unwrap()
+                    .unwrap(),
+                "2"
+            );
+        }
+    }
+
+    fn test_cached_per_arg<D: TestDriver>() {
+        let mut driver = start::<D>();
+
+        let event = driver.next_event().unwrap();
+        for _ in 0..3 {
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"caching.calls_with_arg[foo]", &event)
+                    .unwrap()
+                    .unwrap(),
+                "foo:1"
+            );
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"caching.calls_with_arg[bar]", &event)
+                    .unwrap()
+                    .unwrap(),
+                "bar:2"
+            );
+        }
+    }
+
+    instantiate_tests!(test_cached_per_event; test_cached_per_arg);
+}
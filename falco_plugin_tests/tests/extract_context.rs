@@ -0,0 +1,123 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::extract::{field, EventInput, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::{LazyTableReader, TablesInput};
+use falco_plugin_tests::plugin_collection::events::countdown::Countdown;
+use std::ffi::CStr;
+
+struct ContextPlugin {
+    contexts_made: u64,
+}
+
+#[derive(Default)]
+struct RemainingContext {
+    remaining: u64,
+    serial: u64,
+}
+
+impl Plugin for ContextPlugin {
+    const NAME: &'static CStr = c"context";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self { contexts_made: 0 })
+    }
+}
+
+impl ContextPlugin {
+    fn extract_remaining(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(req.context.remaining)
+    }
+
+    fn extract_serial(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(req.context.serial)
+    }
+}
+
+impl ExtractPlugin for ContextPlugin {
+    type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
+    type ExtractContext = RemainingContext;
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("context.remaining", &Self::extract_remaining),
+        field("context.serial", &Self::extract_serial),
+    ];
+
+    fn make_context<'a>(
+        &mut self,
+        event_input: &EventInput<'a, Self::Event<'a>>,
+        _table_reader: &LazyTableReader,
+    ) -> Result<Self::ExtractContext, Error> {
+        let event = event_input.event()?;
+        self.contexts_made += 1;
+
+        Ok(RemainingContext {
+            remaining: event.params.event_data.remaining() as u64,
+            serial: self.contexts_made,
+        })
+    }
+}
+
+static_plugin!(CONTEXT_PLUGIN_API = ContextPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_make_context<D: TestDriver>() {
+        let (mut driver, _) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        let plugin = driver
+            .register_plugin(&super::CONTEXT_PLUGIN_API, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"countdown").unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let event = driver.next_event().unwrap();
+        assert_eq!(
+            driver
+                .event_field_as_string(c"context.remaining", &event)
+                .unwrap()
+                .unwrap(),
+            "3"
+        );
+        let first = driver
+            .event_field_as_string(c"context.serial", &event)
+            .unwrap()
+            .unwrap();
+
+        let event = driver.next_event().unwrap();
+        assert_eq!(
+            driver
+                .event_field_as_string(c"context.remaining", &event)
+                .unwrap()
+                .unwrap(),
+            "2"
+        );
+        let second = driver
+            .event_field_as_string(c"context.serial", &event)
+            .unwrap()
+            .unwrap();
+
+        // every extraction request gets a freshly made context
+        assert!(second.parse::<u64>().unwrap() > first.parse::<u64>().unwrap());
+    }
+
+    instantiate_tests!(test_make_context);
+}
@@ -0,0 +1,103 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+
+struct PrefixedExtractPlugin;
+
+impl Plugin for PrefixedExtractPlugin {
+    const NAME: &'static CStr = c"prefixed_extract";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl PrefixedExtractPlugin {
+    fn extract_answer(&mut self, _req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(42)
+    }
+}
+
+impl ExtractPlugin for PrefixedExtractPlugin {
+    type Event<'a> = RawEvent<'a>;
+    type ExtractContext = ();
+    const FIELD_PREFIX: &'static str = "prefixed.";
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] =
+        &[field("answer", &Self::extract_answer)];
+}
+
+static_plugin!(PREFIXED_EXTRACT_API = PrefixedExtractPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_runner::PluginRunner;
+    use falco_plugin_tests::plugin_collection::extract::remaining_from_table::EXTRACT_REMAINING_FROM_TABLE_API;
+    use falco_plugin_tests::plugin_collection::extract::remaining_from_table_runtime::EXTRACT_REMAINING_FROM_TABLE_RUNTIME_API;
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_field_prefix<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 1, "batch_size": 1}"#,
+        )
+        .unwrap();
+        let plugin = driver
+            .register_plugin(&super::PREFIXED_EXTRACT_API, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"countdown").unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let event = driver.next_event().unwrap();
+        assert_eq!(
+            driver
+                .event_field_as_string(c"prefixed.answer", &event)
+                .unwrap()
+                .unwrap(),
+            "42"
+        );
+    }
+
+    instantiate_tests!(test_field_prefix);
+
+    #[test]
+    fn test_field_collision() {
+        let mut runner = PluginRunner::new();
+        runner
+            .register_plugin(
+                &COUNTDOWN_PLUGIN_API,
+                cr#"{"remaining": 1, "batch_size": 1}"#,
+            )
+            .unwrap();
+        runner
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        runner
+            .register_plugin(&EXTRACT_REMAINING_FROM_TABLE_API, c"")
+            .unwrap();
+
+        let err = runner
+            .register_plugin(&EXTRACT_REMAINING_FROM_TABLE_RUNTIME_API, c"")
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            r#"Field countdown.remaining from plugin "extract_remaining_from_table_runtime" is already provided by plugin "extract_remaining_from_table""#
+        );
+    }
+}
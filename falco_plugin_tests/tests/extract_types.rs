@@ -4,6 +4,7 @@ use falco_plugin::anyhow::Error;
 use falco_plugin::base::Plugin;
 use falco_plugin::event::events::Event;
 use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::smallvec::SmallVec;
 use falco_plugin::static_plugin;
 use falco_plugin::tables::TablesInput;
 use std::ffi::{CStr, CString};
@@ -68,6 +69,20 @@ macro_rules! gen_dummy_extractor_fn_impls {
             ) -> Result<Option<Vec<$ty>>, Error> {
                 Ok(None)
             }
+
+            fn [<extract_smallvec_ $field_name>](
+                &mut self,
+                _req: ExtractRequest<Self>,
+            ) -> Result<SmallVec<[$ty; 4]>, Error> {
+                Ok($vec_expr.into_iter().collect())
+            }
+
+            fn [<extract_smallvec_ $field_name _opt>](
+                &mut self,
+                _req: ExtractRequest<Self>,
+            ) -> Result<Option<SmallVec<[$ty; 2]>>, Error> {
+                Ok(Some($vec_expr.into_iter().collect()))
+            }
         }
     };
 }
@@ -172,7 +187,9 @@ macro_rules! gen_fields_variants {
             field(concat!("dummy.", stringify!($field_name), "_opt_none"), paste::paste!(&Self::[<extract_ $field_name _opt_none>])),
             field(concat!("dummy.vec_", stringify!($field_name)), paste::paste!(&Self::[<extract_vec_ $field_name>])),
             field(concat!("dummy.vec_", stringify!($field_name), "_opt"), paste::paste!(&Self::[<extract_vec_ $field_name _opt>])),
-            field(concat!("dummy.vec_", stringify!($field_name), "_opt_none"), paste::paste!(&Self::[<extract_vec_ $field_name _opt_none>]))
+            field(concat!("dummy.vec_", stringify!($field_name), "_opt_none"), paste::paste!(&Self::[<extract_vec_ $field_name _opt_none>])),
+            field(concat!("dummy.smallvec_", stringify!($field_name)), paste::paste!(&Self::[<extract_smallvec_ $field_name>])),
+            field(concat!("dummy.smallvec_", stringify!($field_name), "_opt"), paste::paste!(&Self::[<extract_smallvec_ $field_name _opt>]))
             ),*
         ]
     };
@@ -242,6 +259,18 @@ macro_rules! assert_field_eq {
             c_str_macro::c_str!(concat!("dummy.vec_", stringify!($field_name), "_opt_none")),
             &$event
         ));
+        assert_field_variant_eq!(
+            $driver,
+            $event,
+            concat!("dummy.smallvec_", stringify!($field_name)),
+            $expected_vec_expr
+        );
+        assert_field_variant_eq!(
+            $driver,
+            $event,
+            concat!("dummy.smallvec_", stringify!($field_name), "_opt"),
+            $expected_vec_expr
+        );
     };
 }
 
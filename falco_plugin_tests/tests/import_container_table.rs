@@ -0,0 +1,154 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export;
+use falco_plugin::tables::import::sinsp::container_accessors::*;
+use falco_plugin::tables::import::sinsp::{ContainerTable, CONTAINER_TABLE};
+use falco_plugin::tables::TablesInput;
+use std::ffi::{CStr, CString};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+// a stand-in for the table exported by the container plugin
+#[derive(export::Entry)]
+struct ContainerEntry {
+    id: export::Public<CString>,
+    name: export::Public<CString>,
+    image: export::Public<CString>,
+}
+
+type ExportedContainerTable = export::Table<CString, ContainerEntry>;
+
+struct ContainerExportPlugin {
+    #[allow(dead_code)]
+    containers: Box<ExportedContainerTable>,
+}
+
+impl Plugin for ContainerExportPlugin {
+    const NAME: &'static CStr = c"container_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+
+        let containers = input.add_table(ExportedContainerTable::new(CONTAINER_TABLE)?)?;
+
+        let mut entry = containers.create_entry()?;
+        *entry.id = CString::from(c"0123456789ab");
+        *entry.name = CString::from(c"nginx-1");
+        *entry.image = CString::from(c"docker.io/library/nginx:latest");
+        containers.insert(c"0123456789ab", entry);
+
+        Ok(Self { containers })
+    }
+}
+
+impl ParsePlugin for ContainerExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(CONTAINER_EXPORT_API = ContainerExportPlugin);
+
+static TEST_DONE: AtomicBool = AtomicBool::new(false);
+
+struct ContainerImportPlugin {
+    containers: ContainerTable,
+}
+
+impl Plugin for ContainerImportPlugin {
+    const NAME: &'static CStr = c"container_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let containers = input.get_table(CONTAINER_TABLE)?;
+
+        Ok(Self { containers })
+    }
+}
+
+impl ParsePlugin for ContainerImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+
+        let container = self
+            .containers
+            .get_entry(r, &CString::from(c"0123456789ab"))?;
+
+        let id = container.get_id(r)?;
+        if id != c"0123456789ab" {
+            anyhow::bail!("id mismatch, got {:?}", id);
+        }
+
+        let name = container.get_name(r)?;
+        if name != c"nginx-1" {
+            anyhow::bail!("name mismatch, got {:?}", name);
+        }
+
+        let image = container.get_image(r)?;
+        if image != c"docker.io/library/nginx:latest" {
+            anyhow::bail!("image mismatch, got {:?}", image);
+        }
+
+        TEST_DONE.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+}
+
+static_plugin!(CONTAINER_IMPORT_API = ContainerImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_import_container_table<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::CONTAINER_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::CONTAINER_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        driver.next_event().unwrap();
+        assert!(super::TEST_DONE.load(Ordering::Relaxed));
+    }
+
+    instantiate_tests!(test_import_container_table);
+}
@@ -0,0 +1,153 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{DynamicTable, DynamicValue};
+use falco_plugin::tables::{FieldTypeId, TablesInput};
+use std::ffi::CStr;
+
+struct DynamicTablePlugin {
+    remaining: DynamicTable,
+}
+
+impl Plugin for DynamicTablePlugin {
+    const NAME: &'static CStr = c"dynamic_table";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        anyhow::ensure!(input.get_dynamic_table(c"no_such_table").is_err());
+
+        let remaining = input.get_dynamic_table(c"remaining")?;
+        anyhow::ensure!(remaining.name() == c"remaining");
+        anyhow::ensure!(remaining.key_type() == FieldTypeId::U64);
+
+        let mut fields = remaining
+            .fields()
+            .iter()
+            .map(|f| (f.name(), f.type_id(), f.is_read_only()))
+            .collect::<Vec<_>>();
+        fields.sort_by_key(|(name, _, _)| *name);
+        anyhow::ensure!(
+            fields
+                == [
+                    (c"countdown", FieldTypeId::Table, true),
+                    (c"readonly", FieldTypeId::U64, true),
+                    (c"remaining", FieldTypeId::U64, false),
+                ],
+            "unexpected fields {fields:?}"
+        );
+
+        Ok(Self { remaining })
+    }
+}
+
+impl ParsePlugin for DynamicTablePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let writer = &parse_input.writer;
+        let table = &self.remaining;
+        let remaining_field = table.field(c"remaining").unwrap();
+        let readonly_field = table.field(c"readonly").unwrap();
+        let countdown_field = table.field(c"countdown").unwrap();
+
+        let key = DynamicValue::from(event.event_number() as u64);
+        let entry = table.get_entry(reader, &key)?;
+        let DynamicValue::U64(remaining) = entry.read_field(reader, remaining_field)? else {
+            anyhow::bail!("bad value type");
+        };
+
+        entry.write_field(writer, remaining_field, &DynamicValue::U64(remaining + 100))?;
+        anyhow::ensure!(
+            entry.read_field(reader, remaining_field)? == DynamicValue::U64(remaining + 100)
+        );
+
+        // type and access checks
+        anyhow::ensure!(table
+            .get_entry(reader, &DynamicValue::U32(event.event_number() as u32))
+            .is_err());
+        anyhow::ensure!(entry
+            .write_field(writer, remaining_field, &DynamicValue::U32(1))
+            .is_err());
+        anyhow::ensure!(entry
+            .write_field(writer, readonly_field, &DynamicValue::U64(1))
+            .is_err());
+        anyhow::ensure!(entry.read_field(reader, countdown_field).is_err());
+        // the exporting plugin locks entries while iterating
+        drop(entry);
+
+        // a full lifecycle of a new entry
+        let new_key = DynamicValue::U64(1000);
+        let new_entry = table.create_entry(writer)?;
+        new_entry.write_field(writer, remaining_field, &DynamicValue::U64(7))?;
+        table.insert(reader, writer, &new_key, new_entry)?;
+        let new_entry = table.get_entry(reader, &new_key)?;
+        anyhow::ensure!(new_entry.read_field(reader, remaining_field)? == DynamicValue::U64(7));
+        drop(new_entry);
+        table.erase(writer, &new_key)?;
+        anyhow::ensure!(table.get_entry(reader, &new_key).is_err());
+
+        let mut total = 0;
+        table.iter_entries_mut(reader, |entry| {
+            if let Ok(DynamicValue::U64(value)) = entry.read_field(reader, remaining_field) {
+                total += value;
+            }
+            std::ops::ControlFlow::Continue(())
+        })?;
+        anyhow::ensure!(total >= 100 * table.get_size(reader)? as u64);
+
+        Ok(())
+    }
+}
+
+static_plugin!(DYNAMIC_TABLE_API = DynamicTablePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_dynamic_table<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::DYNAMIC_TABLE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_dynamic_table);
+}
@@ -0,0 +1,115 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::accessors::countdown::get_countdown;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::accessors::remaining::get_remaining;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::nested_accessors::count::{
+    get_count, set_count,
+};
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::RemainingCounterImportTableWithExtraFields;
+use std::ffi::CStr;
+
+struct NestedWritePlugin {
+    remaining_table: RemainingCounterImportTableWithExtraFields,
+}
+
+impl Plugin for NestedWritePlugin {
+    const NAME: &'static CStr = c"nested_write";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining_table })
+    }
+}
+
+impl ParsePlugin for NestedWritePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let writer = &parse_input.writer;
+        let event_num = event.event_number() as u64;
+
+        let entry = self.remaining_table.get_entry(reader, &event_num)?;
+        let remaining = entry.get_remaining(reader)?;
+        let countdown = entry.get_countdown(reader)?;
+        anyhow::ensure!(countdown.get_size(reader)? == remaining as usize + 1);
+
+        // add a new entry
+        let new_entry = countdown.create_entry(writer)?;
+        new_entry.set_count(writer, &100)?;
+        countdown.insert(reader, writer, &100, new_entry)?;
+
+        // modify an existing one
+        countdown
+            .get_entry(reader, &remaining)?
+            .set_count(writer, &200)?;
+
+        // and remove another one
+        if remaining > 0 {
+            countdown.erase(writer, &0)?;
+            anyhow::ensure!(countdown.get_entry(reader, &0).is_err());
+        }
+
+        anyhow::ensure!(countdown.get_size(reader)? == remaining.max(1) as usize + 1);
+        anyhow::ensure!(countdown.get_entry(reader, &100)?.get_count(reader)? == 100);
+        anyhow::ensure!(countdown.get_entry(reader, &remaining)?.get_count(reader)? == 200);
+
+        Ok(())
+    }
+}
+
+static_plugin!(NESTED_WRITE_API = NestedWritePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_nested_table::PARSE_INTO_NESTED_TABLE_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_nested_write<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_INTO_NESTED_TABLE_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::NESTED_WRITE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_nested_write);
+}
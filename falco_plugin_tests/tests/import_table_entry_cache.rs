@@ -0,0 +1,110 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::accessors::remaining::{
+    get_remaining, set_remaining,
+};
+use falco_plugin_tests::plugin_collection::tables::remaining_import::RemainingCounterImportTable;
+use std::ffi::CStr;
+
+struct EntryCachePlugin {
+    remaining_table: RemainingCounterImportTable,
+}
+
+impl Plugin for EntryCachePlugin {
+    const NAME: &'static CStr = c"entry_cache";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table: RemainingCounterImportTable = input.get_table(c"remaining")?;
+
+        Ok(Self {
+            remaining_table: remaining_table.with_entry_cache(2),
+        })
+    }
+}
+
+impl ParsePlugin for EntryCachePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let writer = &parse_input.writer;
+        let event_num = event.event_number() as u64;
+
+        // the second lookup comes from the cache and sees the write done via the first one
+        let entry = self.remaining_table.get_entry(reader, &event_num)?;
+        let remaining = entry.get_remaining(reader)?;
+        entry.set_remaining(writer, &(remaining + 100))?;
+        drop(entry);
+
+        let entry = self.remaining_table.get_entry(reader, &event_num)?;
+        anyhow::ensure!(entry.get_remaining(reader)? == remaining + 100);
+        drop(entry);
+
+        // erasing an entry invalidates the cache
+        if event_num > 1 {
+            self.remaining_table.get_entry(reader, &(event_num - 1))?;
+            self.remaining_table.erase(writer, &(event_num - 1))?;
+            anyhow::ensure!(self
+                .remaining_table
+                .get_entry(reader, &(event_num - 1))
+                .is_err());
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(ENTRY_CACHE_API = EntryCachePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_entry_cache<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::ENTRY_CACHE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_entry_cache);
+}
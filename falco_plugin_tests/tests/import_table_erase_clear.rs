@@ -0,0 +1,142 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::accessors::*;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::RemainingCounterImportTable;
+use std::ffi::CStr;
+
+struct CleanupPlugin {
+    remaining: RemainingCounterImportTable,
+    erased: u64,
+    max_size: usize,
+    final_size: usize,
+}
+
+impl Plugin for CleanupPlugin {
+    const NAME: &'static CStr = c"cleanup";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining = input.get_table(c"remaining")?;
+
+        Ok(Self {
+            remaining,
+            erased: 0,
+            max_size: 0,
+            final_size: usize::MAX,
+        })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [
+            Metric::new(
+                MetricLabel::new(c"erased", MetricType::Monotonic),
+                MetricValue::U64(self.erased),
+            ),
+            Metric::new(
+                MetricLabel::new(c"max_size", MetricType::NonMonotonic),
+                MetricValue::U64(self.max_size as u64),
+            ),
+            Metric::new(
+                MetricLabel::new(c"final_size", MetricType::NonMonotonic),
+                MetricValue::U64(self.final_size as u64),
+            ),
+        ]
+    }
+}
+
+impl ParsePlugin for CleanupPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let event_num = event.event_number() as u64;
+        let entry = self.remaining.get_entry(&parse_input.reader, &event_num)?;
+        let remaining = entry.get_remaining(&parse_input.reader)?;
+
+        // drop the entries for even counts right away
+        if remaining % 2 == 0 {
+            self.remaining.erase(&parse_input.writer, &event_num)?;
+            self.erased += 1;
+            anyhow::ensure!(
+                self.remaining
+                    .get_entry(&parse_input.reader, &event_num)
+                    .is_err(),
+                "erased entry still present"
+            );
+        }
+
+        let size = self.remaining.get_size(&parse_input.reader)?;
+        self.max_size = self.max_size.max(size);
+
+        // and everything else at the end of the capture
+        if remaining == 0 {
+            self.remaining.clear(&parse_input.writer)?;
+            self.final_size = self.remaining.get_size(&parse_input.reader)?;
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(CLEANUP_API = CleanupPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_erase_clear<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver.register_plugin(&super::CLEANUP_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            match driver.next_event() {
+                Ok(_) => continue,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+
+        let metrics = driver.get_metrics().unwrap();
+        let metric = |name: &str| {
+            metrics
+                .iter()
+                .find(|m| m.name == name)
+                .unwrap_or_else(|| panic!("metric {name} not found"))
+                .value
+        };
+
+        // events with 3, 2, 1, 0 remaining: 2 and 0 get erased, 3 and 1 stay until the end
+        assert_eq!(metric("cleanup.erased"), 2);
+        assert_eq!(metric("cleanup.max_size"), 2);
+        assert_eq!(metric("cleanup.final_size"), 0);
+    }
+
+    instantiate_tests!(test_erase_clear);
+}
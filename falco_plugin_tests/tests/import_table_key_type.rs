@@ -0,0 +1,83 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, KeyedTable, TableMetadata};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+type RemainingCounter = Entry<Arc<RemainingCounterMetadata>>;
+type RemainingCounterTable = KeyedTable<RemainingCounter>;
+
+// the actual key type is u64, so we expect an error
+#[derive(TableMetadata)]
+#[entry_type(RemainingCounter)]
+#[key_type(u32)]
+struct RemainingCounterMetadata {
+    remaining: Field<u64, RemainingCounter>,
+}
+
+struct BadKeyTypePlugin {
+    #[allow(dead_code)]
+    remaining: RemainingCounterTable,
+}
+
+impl Plugin for BadKeyTypePlugin {
+    const NAME: &'static CStr = c"bad_key_type";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining })
+    }
+}
+
+impl ParsePlugin for BadKeyTypePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(BAD_KEY_TYPE_API = BadKeyTypePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{init_plugin, instantiate_tests, TestDriver};
+
+    fn test_bad_key_type<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        let err = driver
+            .register_plugin(&super::BAD_KEY_TYPE_API, c"")
+            .unwrap_err();
+        let err = format!("{err:#}");
+        assert!(
+            err.contains("requested u32 (U32), table has Some(U64)"),
+            "{err}"
+        );
+    }
+
+    instantiate_tests!(test_bad_key_type);
+}
@@ -0,0 +1,103 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Field, RuntimeEntry, Table, TableFieldInfo, TableInfo};
+use falco_plugin::tables::{FieldTypeId, TablesInput};
+use std::ffi::CStr;
+
+struct RemainingTag;
+type RemainingEntry = RuntimeEntry<RemainingTag>;
+type RemainingTable = Table<u64, RemainingEntry>;
+
+struct ListFieldsPlugin {
+    #[allow(dead_code)]
+    remaining: RemainingTable,
+    #[allow(dead_code)]
+    optional_field: Option<Field<u64, RemainingEntry>>,
+}
+
+impl Plugin for ListFieldsPlugin {
+    const NAME: &'static CStr = c"list_fields";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+
+        let tables = input.list_table_info();
+        let expected = TableInfo {
+            name: c"remaining".to_owned(),
+            key_type: FieldTypeId::U64,
+        };
+        anyhow::ensure!(tables.contains(&expected), "unexpected tables {tables:?}");
+
+        let remaining: RemainingTable = input.get_table(c"remaining")?;
+
+        let mut fields = remaining.list_table_fields(input);
+        fields.sort_by(|a, b| a.name.cmp(&b.name));
+        let expected = [
+            (c"countdown", FieldTypeId::Table, true),
+            (c"readonly", FieldTypeId::U64, true),
+            (c"remaining", FieldTypeId::U64, false),
+        ]
+        .map(|(name, field_type, read_only)| TableFieldInfo {
+            name: name.to_owned(),
+            field_type,
+            read_only,
+        });
+        anyhow::ensure!(fields == expected, "unexpected fields {fields:?}");
+
+        // only look up the field if the table has it
+        let optional_field = match fields.iter().any(|f| f.name.as_c_str() == c"optional") {
+            true => Some(remaining.get_field(input, c"optional")?),
+            false => None,
+        };
+
+        Ok(Self {
+            remaining,
+            optional_field,
+        })
+    }
+}
+
+impl ParsePlugin for ListFieldsPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+static_plugin!(LIST_FIELDS_API = ListFieldsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{init_plugin, instantiate_tests, TestDriver};
+
+    fn test_list_fields<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::LIST_FIELDS_API, c"")
+            .unwrap();
+    }
+
+    instantiate_tests!(test_list_fields);
+}
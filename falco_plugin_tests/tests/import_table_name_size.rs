@@ -0,0 +1,112 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, KeyedTable, TableMetadata};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+type RemainingCounter = Entry<Arc<RemainingCounterMetadata>>;
+type RemainingCounterTable = KeyedTable<RemainingCounter>;
+
+#[derive(TableMetadata)]
+#[entry_type(RemainingCounter)]
+#[key_type(u64)]
+struct RemainingCounterMetadata {
+    #[allow(dead_code)]
+    remaining: Field<u64, RemainingCounter>,
+}
+
+struct TableSizePlugin {
+    remaining: RemainingCounterTable,
+    size: usize,
+}
+
+impl Plugin for TableSizePlugin {
+    const NAME: &'static CStr = c"table_size";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining, size: 0 })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [Metric::new(
+            MetricLabel::new(c"remaining_table_size", MetricType::NonMonotonic),
+            MetricValue::U64(self.size as u64),
+        )]
+    }
+}
+
+impl ParsePlugin for TableSizePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let name = self.remaining.get_name(&parse_input.reader)?;
+        anyhow::ensure!(name == "remaining", "imported the wrong table: {name}");
+
+        self.size = self.remaining.get_size(&parse_input.reader)?;
+        Ok(())
+    }
+}
+
+static_plugin!(TABLE_SIZE_API = TableSizePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_table_name_size<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver.register_plugin(&super::TABLE_SIZE_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert!(count > 0);
+
+        let size = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .find(|m| m.name == "table_size.remaining_table_size")
+            .unwrap()
+            .value;
+        assert_eq!(size, count);
+    }
+
+    instantiate_tests!(test_table_name_size);
+}
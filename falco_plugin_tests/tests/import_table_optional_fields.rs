@@ -0,0 +1,108 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::traits::Entry as _;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+type RemainingEntry = Entry<Arc<RemainingMetadata>>;
+type RemainingTable = Table<u64, RemainingEntry>;
+
+#[derive(TableMetadata)]
+#[entry_type(RemainingEntry)]
+struct RemainingMetadata {
+    remaining: Field<u64, RemainingEntry>,
+    readonly: Option<Field<u64, RemainingEntry>>,
+    not_there: Option<Field<u64, RemainingEntry>>,
+}
+
+struct OptionalFieldsPlugin {
+    remaining_table: RemainingTable,
+}
+
+impl Plugin for OptionalFieldsPlugin {
+    const NAME: &'static CStr = c"optional_fields";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining_table })
+    }
+}
+
+impl ParsePlugin for OptionalFieldsPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let writer = &parse_input.writer;
+        let event_num = event.event_number() as u64;
+
+        let entry = self.remaining_table.get_entry(reader, &event_num)?;
+        let metadata = entry.get_metadata();
+        anyhow::ensure!(metadata.readonly.is_some());
+        anyhow::ensure!(metadata.not_there.is_none());
+
+        entry.get_remaining(reader)?;
+        anyhow::ensure!(entry.get_readonly(reader)? == 0);
+        anyhow::ensure!(entry.get_not_there(reader).is_err());
+        anyhow::ensure!(entry.set_not_there(writer, &1).is_err());
+
+        Ok(())
+    }
+}
+
+static_plugin!(OPTIONAL_FIELDS_API = OptionalFieldsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_optional_fields<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::OPTIONAL_FIELDS_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_optional_fields);
+}
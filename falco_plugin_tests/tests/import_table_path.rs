@@ -0,0 +1,125 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::table_path;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::accessors::as_string::{
+    get_as_string, set_as_string,
+};
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::accessors::countdown::get_countdown_by_key;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::accessors::remaining::get_remaining;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::nested_accessors::count::get_count;
+use falco_plugin_tests::plugin_collection::tables::remaining_import_extra_fields::RemainingCounterImportTableWithExtraFields;
+use std::ffi::{CStr, CString};
+
+struct TablePathPlugin {
+    remaining_table: RemainingCounterImportTableWithExtraFields,
+}
+
+impl Plugin for TablePathPlugin {
+    const NAME: &'static CStr = c"table_path";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining_table })
+    }
+}
+
+impl ParsePlugin for TablePathPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let event_num = event.event_number() as u64;
+
+        let remaining = table_path!(reader, self.remaining_table => [event_num].remaining)
+            .ok_or_else(|| anyhow::anyhow!("no entry for event {event_num}"))?;
+
+        // the nested entry at key `i` counts down from `remaining`
+        anyhow::ensure!(
+            table_path!(reader, self.remaining_table => [event_num].countdown[0].count)
+                == Some(remaining)
+        );
+        anyhow::ensure!(
+            table_path!(reader, self.remaining_table => [event_num].countdown[remaining].count)
+                == Some(0)
+        );
+
+        // the path can also end at an entry
+        let nested = table_path!(reader, self.remaining_table => [event_num].countdown[0]);
+        anyhow::ensure!(nested.is_some());
+        drop(nested);
+
+        // borrowed values are returned as owned ones
+        self.remaining_table
+            .get_entry(reader, &event_num)?
+            .set_as_string(&parse_input.writer, c"hello")?;
+        let as_string: Option<CString> =
+            table_path!(reader, self.remaining_table => [event_num].as_string);
+        anyhow::ensure!(as_string.as_deref() == Some(c"hello"));
+
+        // any missing level results in None
+        anyhow::ensure!(
+            table_path!(reader, self.remaining_table => [event_num + 100].countdown[0].count)
+                .is_none()
+        );
+        anyhow::ensure!(table_path!(
+            reader, self.remaining_table => [event_num].countdown[remaining + 1].count
+        )
+        .is_none());
+
+        Ok(())
+    }
+}
+
+static_plugin!(TABLE_PATH_API = TablePathPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_nested_table::PARSE_INTO_NESTED_TABLE_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_table_path<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_INTO_NESTED_TABLE_API, c"")
+            .unwrap();
+        driver.register_plugin(&super::TABLE_PATH_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_table_path);
+}
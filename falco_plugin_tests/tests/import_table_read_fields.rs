@@ -0,0 +1,122 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::traits::Entry as _;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::Arc;
+
+type RemainingEntry = Entry<Arc<RemainingMetadata>>;
+type RemainingTable = Table<u64, RemainingEntry>;
+
+#[derive(TableMetadata)]
+#[entry_type(RemainingEntry)]
+struct RemainingMetadata {
+    remaining: Field<u64, RemainingEntry>,
+    readonly: Field<u64, RemainingEntry>,
+    countdown: Field<CountdownTable, RemainingEntry>,
+}
+
+type CountdownEntry = Entry<Arc<CountdownMetadata>>;
+type CountdownTable = Table<u64, CountdownEntry>;
+
+#[derive(TableMetadata)]
+#[entry_type(CountdownEntry)]
+struct CountdownMetadata {
+    count: Field<u64, CountdownEntry>,
+}
+
+struct ReadFieldsPlugin {
+    remaining_table: RemainingTable,
+}
+
+impl Plugin for ReadFieldsPlugin {
+    const NAME: &'static CStr = c"read_fields";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table = input.get_table(c"remaining")?;
+
+        Ok(Self { remaining_table })
+    }
+}
+
+impl ParsePlugin for ReadFieldsPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let reader = &parse_input.reader;
+        let event_num = event.event_number() as u64;
+
+        let entry = self.remaining_table.get_entry(reader, &event_num)?;
+        let metadata = entry.get_metadata();
+
+        let (remaining, readonly, countdown) = entry.read_fields(
+            reader,
+            (&metadata.remaining, &metadata.readonly, &metadata.countdown),
+        )?;
+        anyhow::ensure!(remaining == entry.get_remaining(reader)?);
+        anyhow::ensure!(readonly == entry.get_readonly(reader)?);
+        anyhow::ensure!(countdown.get_size(reader)? == remaining as usize + 1);
+
+        // the entry at `remaining` counts down to zero
+        let nested_entry = countdown.get_entry(reader, &remaining)?;
+        let (count,) = nested_entry.read_fields(reader, (&nested_entry.get_metadata().count,))?;
+        anyhow::ensure!(count == 0);
+
+        Ok(())
+    }
+}
+
+static_plugin!(READ_FIELDS_API = ReadFieldsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_nested_table::PARSE_INTO_NESTED_TABLE_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_read_fields<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_INTO_NESTED_TABLE_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::READ_FIELDS_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_read_fields);
+}
@@ -0,0 +1,111 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::ThreadSafeTables;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::accessors::remaining::{
+    get_remaining, set_remaining,
+};
+use falco_plugin_tests::plugin_collection::tables::remaining_import::RemainingCounterImportTable;
+use std::ffi::CStr;
+
+struct ThreadSafeImportPlugin {
+    tables: ThreadSafeTables<RemainingCounterImportTable>,
+}
+
+impl Plugin for ThreadSafeImportPlugin {
+    const NAME: &'static CStr = c"thread_safe_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let remaining_table: RemainingCounterImportTable = input.get_table(c"remaining")?;
+
+        // the table is exported by a Rust plugin built with `thread-safe-tables`
+        let tables = unsafe { ThreadSafeTables::new(input, remaining_table)? };
+        Ok(Self { tables })
+    }
+}
+
+impl ParsePlugin for ThreadSafeImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let event_num = event.event_number() as u64;
+
+        // double the counter from a background thread
+        let tables = &self.tables;
+        let remaining = std::thread::scope(|s| {
+            s.spawn(|| {
+                tables.with(|table, reader, writer| {
+                    let entry = table.get_entry(reader, &event_num)?;
+                    let remaining = entry.get_remaining(reader)?;
+                    entry.set_remaining(writer, &(remaining * 2))?;
+                    Ok::<_, Error>(remaining)
+                })
+            })
+            .join()
+            .unwrap()
+        })?;
+
+        let doubled = self.tables.with(|table, reader, _| {
+            table
+                .get_entry(reader, &event_num)
+                .and_then(|entry| entry.get_remaining(reader))
+        })?;
+        anyhow::ensure!(doubled == remaining * 2);
+
+        Ok(())
+    }
+}
+
+static_plugin!(THREAD_SAFE_IMPORT_API = ThreadSafeImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_thread_safe_import<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::THREAD_SAFE_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_thread_safe_import);
+}
@@ -0,0 +1,107 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::accessors::remaining::get_remaining;
+use falco_plugin_tests::plugin_collection::tables::remaining_import::RemainingCounterImportTable;
+use std::ffi::CStr;
+
+struct TryGetTablePlugin {
+    remaining_table: Option<RemainingCounterImportTable>,
+}
+
+impl Plugin for TryGetTablePlugin {
+    const NAME: &'static CStr = c"try_get_table";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+
+        anyhow::ensure!(!input.table_exists(c"not_there"));
+        let missing: Option<RemainingCounterImportTable> = input.try_get_table(c"not_there")?;
+        anyhow::ensure!(missing.is_none());
+
+        // the table may or may not exist, depending on the other plugins loaded
+        let remaining_table = input.try_get_table(c"remaining")?;
+        anyhow::ensure!(remaining_table.is_some() == input.table_exists(c"remaining"));
+
+        Ok(Self { remaining_table })
+    }
+}
+
+impl ParsePlugin for TryGetTablePlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let Some(table) = &self.remaining_table else {
+            return Ok(());
+        };
+
+        let reader = &parse_input.reader;
+        let entry = table.get_entry(reader, &(event.event_number() as u64))?;
+        entry.get_remaining(reader)?;
+
+        Ok(())
+    }
+}
+
+static_plugin!(TRY_GET_TABLE_API = TryGetTablePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn run<D: TestDriver>(with_table: bool) {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        if with_table {
+            driver
+                .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+                .unwrap();
+        }
+        driver
+            .register_plugin(&super::TRY_GET_TABLE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    fn test_try_get_table_present<D: TestDriver>() {
+        run::<D>(true)
+    }
+
+    fn test_try_get_table_missing<D: TestDriver>() {
+        run::<D>(false)
+    }
+
+    instantiate_tests!(test_try_get_table_present; test_try_get_table_missing);
+}
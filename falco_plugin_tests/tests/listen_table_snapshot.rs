@@ -0,0 +1,169 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::event::PayloadFormat;
+use falco_plugin::extract::EventInput;
+use falco_plugin::listen::{CaptureListenInput, CaptureListenPlugin, TableSnapshot};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::export;
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+#[derive(export::Entry, serde::Serialize, serde::Deserialize)]
+struct Process {
+    comm: export::Public<CString>,
+    events: export::Readonly<u64>,
+}
+
+type ProcessTable = export::Table<u64, Process>;
+
+// (restored entries, [(tid, comm, events)]) for every capture_open call
+type OpenLog = Vec<(usize, Vec<(u64, CString, u64)>)>;
+
+static OPENED: Mutex<OpenLog> = Mutex::new(Vec::new());
+
+struct SnapshotPlugin {
+    processes: Box<ProcessTable>,
+    snapshot: TableSnapshot,
+}
+
+impl Plugin for SnapshotPlugin {
+    const NAME: &'static CStr = c"snapshot";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = String;
+
+    fn new(input: Option<&TablesInput>, config: Self::ConfigType) -> Result<Self, Error> {
+        let Some(input) = input else {
+            anyhow::bail!("Did not get tables input");
+        };
+
+        let processes = input.add_table(ProcessTable::new(c"processes")?)?;
+        let snapshot = TableSnapshot::new(config, PayloadFormat::Json);
+        Ok(Self {
+            processes,
+            snapshot,
+        })
+    }
+}
+
+struct SnapshotInstance;
+
+impl SourcePluginInstance for SnapshotInstance {
+    type Plugin = SnapshotPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Eof))
+    }
+}
+
+impl SourcePlugin for SnapshotPlugin {
+    type Instance = SnapshotInstance;
+    const EVENT_SOURCE: &'static CStr = c"snapshot";
+    const PLUGIN_ID: u32 = 1123;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(SnapshotInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl CaptureListenPlugin for SnapshotPlugin {
+    fn capture_open(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+        let restored = self.snapshot.restore(&mut self.processes)?;
+
+        // nothing saved yet, start from scratch
+        if restored == 0 {
+            for (tid, comm) in [(1u64, c"init"), (10, c"bash"), (20, c"cat")] {
+                let mut entry = self.processes.create_entry()?;
+                *entry.comm = comm.to_owned();
+                *entry.events = tid * 2;
+                self.processes.insert(&tid, entry);
+            }
+        }
+
+        let contents = self
+            .processes
+            .data()
+            .unwrap()
+            .read()
+            .iter()
+            .map(|(tid, entry)| {
+                let entry = entry.read();
+                (*tid, (*entry.comm).clone(), *entry.events)
+            })
+            .collect();
+        OPENED.lock().unwrap().push((restored, contents));
+
+        Ok(())
+    }
+
+    fn capture_close(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+        self.snapshot.save(&self.processes)
+    }
+}
+
+static_plugin!(SNAPSHOT_API = SnapshotPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::ffi::CString;
+
+    fn run_capture<D: TestDriver>(config: &CString) {
+        let (driver, _plugin) = init_plugin::<D>(&super::SNAPSHOT_API, config).unwrap();
+        let mut driver = driver
+            .start_capture(super::SnapshotPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            match driver.next_event() {
+                Ok(_) | Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+    }
+
+    fn test_table_snapshot<D: TestDriver>() {
+        let path = std::env::temp_dir().join(format!(
+            "falco_plugin_table_snapshot_{}.json",
+            std::process::id()
+        ));
+        let _ = std::fs::remove_file(&path);
+        let config = CString::new(path.to_str().unwrap()).unwrap();
+
+        run_capture::<D>(&config);
+        let saved: serde_json::Value =
+            serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+        assert_eq!(saved.as_array().unwrap().len(), 3);
+
+        run_capture::<D>(&config);
+        std::fs::remove_file(&path).unwrap();
+
+        let expected = vec![
+            (1, c"init".to_owned(), 2),
+            (10, c"bash".to_owned(), 20),
+            (20, c"cat".to_owned(), 40),
+        ];
+        let opened = super::OPENED.lock().unwrap();
+        assert_eq!(*opened, [(0, expected.clone()), (3, expected)]);
+    }
+
+    instantiate_native_tests!(test_table_snapshot);
+}
@@ -0,0 +1,136 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::parse::{EventInput, ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::events::countdown::Countdown;
+use std::ffi::CStr;
+
+struct BatchParsePlugin {
+    batches: u64,
+    events: u64,
+    last_remaining: Option<usize>,
+}
+
+impl Plugin for BatchParsePlugin {
+    const NAME: &'static CStr = c"batch_parse";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self {
+            batches: 0,
+            events: 0,
+            last_remaining: None,
+        })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [
+            Metric::new(
+                MetricLabel::new(c"batches", MetricType::Monotonic),
+                MetricValue::U64(self.batches),
+            ),
+            Metric::new(
+                MetricLabel::new(c"events", MetricType::Monotonic),
+                MetricValue::U64(self.events),
+            ),
+        ]
+    }
+}
+
+impl ParsePlugin for BatchParsePlugin {
+    type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        anyhow::bail!("parse_event called with parse_events overridden");
+    }
+
+    fn parse_events(
+        &mut self,
+        events: &[EventInput<Self::Event<'_>>],
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        anyhow::ensure!(events.len() == 3, "got a batch of {} events", events.len());
+
+        for event in events {
+            anyhow::ensure!(event.source() == Some(c"countdown"));
+
+            // the copied events must be intact and in order
+            let remaining = event.event()?.params.event_data.remaining();
+            if let Some(last) = self.last_remaining {
+                anyhow::ensure!(
+                    remaining + 1 == last,
+                    "expected {}, got {remaining}",
+                    last - 1
+                );
+            }
+            self.last_remaining = Some(remaining);
+        }
+
+        self.batches += 1;
+        self.events += events.len() as u64;
+        Ok(())
+    }
+
+    const PARSE_BATCH_SIZE: usize = 3;
+}
+
+static_plugin!(BATCH_PARSE_API = BatchParsePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_parse_batch<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 7, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::BATCH_PARSE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        for _ in 0..7 {
+            driver.next_event().unwrap();
+        }
+        assert!(matches!(driver.next_event(), Err(ScapStatus::Eof)));
+
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .filter(|m| m.name.starts_with("batch_parse."))
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+
+        // the last event is still waiting for a full batch
+        assert_eq!(
+            metrics,
+            [
+                ("batch_parse.batches".to_string(), 2),
+                ("batch_parse.events".to_string(), 6),
+            ]
+        );
+    }
+
+    instantiate_tests!(test_parse_batch);
+}
@@ -0,0 +1,156 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::parse::{EventInput, ParseInput, ParsePlugin, UnparsableEventPolicy};
+use falco_plugin::source::{EventBatch, SourceError, SourcePlugin, SourcePluginInstance};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::events::countdown::Countdown;
+use std::ffi::CStr;
+
+struct MixedSourcePlugin;
+
+impl Plugin for MixedSourcePlugin {
+    const NAME: &'static CStr = c"mixed_source";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct MixedSourcePluginInstance(std::vec::IntoIter<&'static [u8]>);
+
+impl SourcePluginInstance for MixedSourcePluginInstance {
+    type Plugin = MixedSourcePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        let Some(data) = self.0.next() else {
+            Err(SourceError::eof("all events produced"))?
+        };
+
+        batch.add(Self::plugin_event(data))?;
+        Ok(())
+    }
+}
+
+impl SourcePlugin for MixedSourcePlugin {
+    type Instance = MixedSourcePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"countdown";
+    const PLUGIN_ID: u32 = 1119;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        let events: Vec<&'static [u8]> =
+            vec![b"2 remaining", b"garbage", b"1 remaining", b"0 remaining"];
+        Ok(MixedSourcePluginInstance(events.into_iter()))
+    }
+}
+
+static_plugin!(MIXED_SOURCE_API = MixedSourcePlugin);
+
+struct ParseMetricsPlugin;
+
+impl Plugin for ParseMetricsPlugin {
+    const NAME: &'static CStr = c"parse_metrics";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl ParsePlugin for ParseMetricsPlugin {
+    type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let event = event.event()?;
+        if event.params.event_data.remaining() == 0 {
+            anyhow::bail!("countdown finished");
+        }
+        Ok(())
+    }
+
+    const UNPARSABLE_EVENTS: UnparsableEventPolicy = UnparsableEventPolicy::Skip;
+    const PARSE_METRICS: bool = true;
+}
+
+static_plugin!(PARSE_METRICS_API = ParseMetricsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    #[track_caller]
+    fn check_metrics<D: CapturingTestDriver>(driver: &mut D, expected: &[(&str, u64)]) {
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(metrics, expected);
+    }
+
+    fn test_parse_metrics<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(&super::MIXED_SOURCE_API, c"").unwrap();
+        driver
+            .register_plugin(&super::PARSE_METRICS_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(super::MixedSourcePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        // no events parsed yet
+        check_metrics(&mut driver, &[]);
+
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+        check_metrics(
+            &mut driver,
+            &[
+                ("parse_metrics.parsed_events", 3),
+                ("parse_metrics.parse_errors", 0),
+                ("parse_metrics.unparsable_events", 1),
+                ("parse_metrics.parsed_events.type_322", 3),
+            ],
+        );
+
+        assert!(driver.next_event().is_err());
+        check_metrics(
+            &mut driver,
+            &[
+                ("parse_metrics.parsed_events", 4),
+                ("parse_metrics.parse_errors", 1),
+                ("parse_metrics.unparsable_events", 1),
+                ("parse_metrics.parsed_events.type_322", 4),
+            ],
+        );
+    }
+
+    instantiate_tests!(test_parse_metrics);
+}
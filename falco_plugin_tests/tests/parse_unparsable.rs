@@ -0,0 +1,197 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::{Event, RawEvent};
+use falco_plugin::event::PluginEvent;
+use falco_plugin::parse::{EventInput, ParseInput, ParsePlugin, UnparsableEventPolicy};
+use falco_plugin::source::{EventBatch, SourceError, SourcePlugin, SourcePluginInstance};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use falco_plugin_tests::plugin_collection::events::countdown::Countdown;
+use std::ffi::CStr;
+
+struct MixedSourcePlugin;
+
+impl Plugin for MixedSourcePlugin {
+    const NAME: &'static CStr = c"mixed_source";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct MixedSourcePluginInstance(std::vec::IntoIter<&'static [u8]>);
+
+impl SourcePluginInstance for MixedSourcePluginInstance {
+    type Plugin = MixedSourcePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        let Some(data) = self.0.next() else {
+            Err(SourceError::eof("all events produced"))?
+        };
+
+        batch.add(Self::plugin_event(data))?;
+        Ok(())
+    }
+}
+
+impl SourcePlugin for MixedSourcePlugin {
+    type Instance = MixedSourcePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"countdown";
+    const PLUGIN_ID: u32 = 1119;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        let events: Vec<&'static [u8]> = vec![b"1 remaining", b"garbage", b"0 remaining"];
+        Ok(MixedSourcePluginInstance(events.into_iter()))
+    }
+}
+
+static_plugin!(MIXED_SOURCE_API = MixedSourcePlugin);
+
+struct SkipUnparsablePlugin {
+    parsed: u64,
+    unparsable: u64,
+}
+
+impl Plugin for SkipUnparsablePlugin {
+    const NAME: &'static CStr = c"skip_unparsable";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self {
+            parsed: 0,
+            unparsable: 0,
+        })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [
+            Metric::new(
+                MetricLabel::new(c"parsed", MetricType::Monotonic),
+                MetricValue::U64(self.parsed),
+            ),
+            Metric::new(
+                MetricLabel::new(c"unparsable", MetricType::Monotonic),
+                MetricValue::U64(self.unparsable),
+            ),
+        ]
+    }
+}
+
+impl ParsePlugin for SkipUnparsablePlugin {
+    type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        event.event()?;
+        self.parsed += 1;
+        Ok(())
+    }
+
+    const UNPARSABLE_EVENTS: UnparsableEventPolicy = UnparsableEventPolicy::Skip;
+
+    fn on_unparsable(&mut self, _raw: &RawEvent, _err: &Error) {
+        self.unparsable += 1;
+    }
+}
+
+static_plugin!(SKIP_UNPARSABLE_API = SkipUnparsablePlugin);
+
+struct FailUnparsablePlugin;
+
+impl Plugin for FailUnparsablePlugin {
+    const NAME: &'static CStr = c"fail_unparsable";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl ParsePlugin for FailUnparsablePlugin {
+    type Event<'a> = Event<PluginEvent<Countdown<'a>>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        event.event()?;
+        Ok(())
+    }
+
+    fn on_unparsable(&mut self, _raw: &RawEvent, _err: &Error) {
+        panic!("on_unparsable called with the Fail policy");
+    }
+}
+
+static_plugin!(FAIL_UNPARSABLE_API = FailUnparsablePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_skip_unparsable<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(&super::MIXED_SOURCE_API, c"").unwrap();
+        driver
+            .register_plugin(&super::SKIP_UNPARSABLE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(super::MixedSourcePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+        assert!(matches!(driver.next_event(), Err(ScapStatus::Eof)));
+
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metrics,
+            [
+                ("skip_unparsable.parsed".to_string(), 2),
+                ("skip_unparsable.unparsable".to_string(), 1),
+            ]
+        );
+    }
+
+    fn test_fail_unparsable<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(&super::MIXED_SOURCE_API, c"").unwrap();
+        driver
+            .register_plugin(&super::FAIL_UNPARSABLE_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(super::MixedSourcePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        driver.next_event().unwrap();
+        assert!(driver.next_event().is_err());
+    }
+
+    instantiate_tests!(test_skip_unparsable; test_fail_unparsable);
+}
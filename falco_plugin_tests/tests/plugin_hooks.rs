@@ -0,0 +1,136 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::base::{LifecycleEvent, Plugin, PluginHooks};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::listen::{CaptureListenInput, CaptureListenPlugin};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+
+static EVENTS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+fn log_event(event: impl Into<String>) {
+    EVENTS.lock().unwrap().push(event.into());
+}
+
+struct HooksPlugin {
+    captures: usize,
+}
+
+impl Plugin for HooksPlugin {
+    const NAME: &'static CStr = c"hooks";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"lifecycle hooks plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self { captures: 0 })
+    }
+
+    fn register_hooks(hooks: &mut PluginHooks<Self>) {
+        hooks
+            .on_capture_open(|plugin| {
+                plugin.captures += 1;
+                log_event(format!("hook: open #{}", plugin.captures));
+                Ok(())
+            })
+            .on_capture_close(|_| {
+                log_event("hook: close");
+                Ok(())
+            })
+            .on(LifecycleEvent::CaptureClose, |_| {
+                log_event("hook: close again");
+                Ok(())
+            })
+            .on_shutdown(|plugin| {
+                log_event(format!("hook: shutdown after {}", plugin.captures));
+                Ok(())
+            });
+    }
+}
+
+struct HooksPluginInstance;
+
+impl SourcePluginInstance for HooksPluginInstance {
+    type Plugin = HooksPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Eof))
+    }
+}
+
+impl SourcePlugin for HooksPlugin {
+    type Instance = HooksPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"hooks";
+    const PLUGIN_ID: u32 = 1124;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(HooksPluginInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl CaptureListenPlugin for HooksPlugin {
+    fn capture_open(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+        log_event("capture_open");
+        Ok(())
+    }
+
+    fn capture_close(&mut self, _listen_input: &CaptureListenInput) -> Result<(), Error> {
+        log_event("capture_close");
+        Ok(())
+    }
+}
+
+static_plugin!(HOOKS_PLUGIN_API = HooksPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+
+    fn test_hooks<D: TestDriver>() {
+        let (driver, plugin) = init_plugin::<D>(&super::HOOKS_PLUGIN_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::HooksPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            match driver.next_event() {
+                Ok(_) | Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        drop(driver);
+        drop(plugin);
+
+        assert_eq!(
+            *super::EVENTS.lock().unwrap(),
+            [
+                "capture_open",
+                "hook: open #1",
+                "hook: close",
+                "hook: close again",
+                "capture_close",
+                "hook: shutdown after 1",
+            ]
+        );
+    }
+
+    instantiate_native_tests!(test_hooks);
+}
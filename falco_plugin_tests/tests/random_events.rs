@@ -0,0 +1,134 @@
+#[cfg(test)]
+mod tests {
+    use falco_event_schema::events::{
+        AnyEvent, PPME_SOCKET_CONNECT_X, PPME_SOCKET_SOCKET_X, PPME_SYSCALL_OPEN_E,
+        PPME_SYSCALL_OPEN_X,
+    };
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::{EventPayload, RawEvent};
+    use falco_plugin_tests::plugin_collection::source::random_events::{
+        RandomEventsConfig, RandomEventsPlugin, RANDOM_EVENTS_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, AsPtr, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::ffi::CString;
+
+    fn config(seed: u64, count: usize, event_types: &[u16]) -> CString {
+        let config = RandomEventsConfig {
+            seed,
+            count,
+            event_types: event_types.to_vec(),
+        };
+        CString::new(serde_json::to_string(&config).unwrap()).unwrap()
+    }
+
+    fn collect_events<D: TestDriver>(seed: u64, count: usize, event_types: &[u16]) -> Vec<String> {
+        let config = config(seed, count, event_types);
+        let (driver, _plugin) = init_plugin::<D>(&RANDOM_EVENTS_PLUGIN_API, &config).unwrap();
+        let mut driver = driver
+            .start_capture(RandomEventsPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut events = Vec::new();
+        loop {
+            let event = driver.next_event();
+            match event {
+                Ok(event) => {
+                    let raw = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+                    assert!(event_types.contains(&raw.event_type));
+                    let parsed = raw.load::<AnyEvent>().unwrap();
+                    events.push(format!("{parsed:?}"));
+                }
+                Err(ScapStatus::Eof) => break,
+                Err(ScapStatus::Timeout) => continue,
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+
+        events
+    }
+
+    fn test_random_events<D: TestDriver>() {
+        let event_types = [
+            <PPME_SYSCALL_OPEN_E as EventPayload>::ID,
+            <PPME_SYSCALL_OPEN_X as EventPayload>::ID,
+            <PPME_SOCKET_SOCKET_X as EventPayload>::ID,
+            <PPME_SOCKET_CONNECT_X as EventPayload>::ID,
+        ];
+        let events = collect_events::<D>(1, 100, &event_types);
+        assert_eq!(events.len(), 100);
+
+        let same_seed = collect_events::<D>(1, 100, &event_types);
+        assert_eq!(events, same_seed);
+
+        let other_seed = collect_events::<D>(2, 100, &event_types);
+        assert_ne!(events, other_seed);
+    }
+
+    fn test_random_events_bad_type<D: TestDriver>() {
+        let config = config(0, 1, &[u16::MAX]);
+        let err = init_plugin::<D>(&RANDOM_EVENTS_PLUGIN_API, &config).unwrap_err();
+        assert!(err.to_string().contains("Unknown event type"));
+    }
+
+    instantiate_tests!(test_random_events; test_random_events_bad_type);
+}
+
+#[cfg(all(test, have_libsinsp))]
+mod sinsp_tests {
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::plugin_collection::source::random_events::{
+        write_random_capture, RandomEventsConfig, RANDOM_EVENTS_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_sinsp_tests, AsPtr, CapturingTestDriver, SavefileTestDriver,
+        SavefileWriterTestDriver, ScapStatus,
+    };
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn test_write_random_capture<D: SavefileTestDriver>()
+    where
+        D::Capturing: SavefileWriterTestDriver,
+    {
+        let path = std::env::temp_dir().join(format!("random_{}.scap", std::process::id()));
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+
+        let config = RandomEventsConfig {
+            seed: 1,
+            count: 100,
+            event_types: vec![2, 3],
+        };
+        let written = write_random_capture::<D>(&path, &config).unwrap();
+        assert_eq!(written, 100);
+
+        let (driver, _plugin) = init_plugin::<D>(
+            &RANDOM_EVENTS_PLUGIN_API,
+            c"{\"seed\": 0, \"count\": 0, \"event_types\": [2]}",
+        )
+        .unwrap();
+        let mut driver = driver.load_capture_file(&path).unwrap();
+
+        let mut num_events = 0;
+        loop {
+            match driver.next_event() {
+                Ok(event) => {
+                    let event = unsafe { RawEvent::from_ptr(event.as_ptr()) }.unwrap();
+                    if config.event_types.contains(&event.event_type) {
+                        num_events += 1;
+                    }
+                }
+                Err(ScapStatus::Eof) => break,
+                Err(ScapStatus::Timeout) => continue,
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+        std::fs::remove_file(path.to_str().unwrap()).ok();
+
+        assert_eq!(num_events, 100);
+    }
+
+    instantiate_sinsp_tests!(test_write_random_capture);
+}
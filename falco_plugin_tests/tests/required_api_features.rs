@@ -0,0 +1,84 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{ApiFeature, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{anyhow, static_plugin};
+use std::ffi::CStr;
+
+struct RequiresTables;
+
+impl Plugin for RequiresTables {
+    const NAME: &'static CStr = c"requires_tables";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    const REQUIRED_API_FEATURES: &'static [ApiFeature] =
+        &[ApiFeature::Tables, ApiFeature::CaptureListen];
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        anyhow::ensure!(input.is_some(), "no tables input");
+        Ok(Self)
+    }
+}
+
+impl ParsePlugin for RequiresTables {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+}
+
+// advertise the oldest version providing all the required features
+static_plugin!(REQUIRES_TABLES_API @ unsafe { 3; 9; 0 } = RequiresTables);
+
+#[cfg(test)]
+mod tests {
+    use super::REQUIRES_TABLES_API;
+    use falco_plugin::api::{ss_plugin_init_input, ss_plugin_rc_SS_PLUGIN_SUCCESS};
+    use falco_plugin_tests::{init_plugin, instantiate_tests, TestDriver};
+    use std::ffi::CStr;
+
+    fn test_required_features_present<D: TestDriver>() {
+        init_plugin::<D>(&REQUIRES_TABLES_API, c"").unwrap();
+    }
+
+    instantiate_tests!(test_required_features_present);
+
+    #[test]
+    fn test_advertised_version() {
+        let version =
+            unsafe { CStr::from_ptr(REQUIRES_TABLES_API.get_required_api_version.unwrap()()) };
+        assert_eq!(version, c"3.9.0");
+    }
+
+    #[test]
+    fn test_required_feature_missing() {
+        let input = ss_plugin_init_input {
+            config: c"".as_ptr(),
+            owner: std::ptr::null_mut(),
+            get_owner_last_error: None,
+            tables: std::ptr::null(),
+            log_fn: None,
+        };
+
+        let mut rc = ss_plugin_rc_SS_PLUGIN_SUCCESS;
+        let plugin = unsafe { REQUIRES_TABLES_API.init.unwrap()(&input, &mut rc) };
+        assert_ne!(rc, ss_plugin_rc_SS_PLUGIN_SUCCESS);
+
+        let err = unsafe { CStr::from_ptr(REQUIRES_TABLES_API.get_last_error.unwrap()(plugin)) };
+        assert_eq!(
+            err.to_str().unwrap(),
+            "Plugin requires the table API (plugin API 3.0.0), which is not provided by the plugin framework"
+        );
+
+        unsafe { REQUIRES_TABLES_API.destroy.unwrap()(plugin) };
+    }
+}
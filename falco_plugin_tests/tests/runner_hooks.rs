@@ -0,0 +1,89 @@
+use falco_plugin_runner::{HookEvent, PluginRunner};
+use falco_plugin_tests::plugin_collection::extract::remaining_from_table::EXTRACT_REMAINING_FROM_TABLE_API;
+use falco_plugin_tests::plugin_collection::parse::remaining_into_table_direct::PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API;
+use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+use std::cell::RefCell;
+use std::rc::Rc;
+
+fn describe(event: &HookEvent) -> String {
+    match event {
+        HookEvent::NextEvent { plugin, result } => match result {
+            Ok(event) => format!("next_event {plugin:?} -> #{}", event.evt_num.unwrap()),
+            Err(e) => format!("next_event {plugin:?} -> {e}"),
+        },
+        HookEvent::BeforeParse { plugin, event } => {
+            format!("before_parse {plugin:?} #{}", event.evt_num.unwrap())
+        }
+        HookEvent::AfterParse {
+            plugin,
+            event,
+            result,
+        } => format!(
+            "after_parse {plugin:?} #{} -> {}",
+            event.evt_num.unwrap(),
+            result.is_ok()
+        ),
+        HookEvent::Extract {
+            plugin,
+            event,
+            field,
+            result,
+            range,
+        } => format!(
+            "extract {plugin:?} #{} {field} -> {:?} {:?}",
+            event.evt_num.unwrap(),
+            result,
+            range
+        ),
+    }
+}
+
+#[test]
+fn test_runner_hooks() {
+    let calls = Rc::new(RefCell::new(Vec::new()));
+
+    let mut runner = PluginRunner::new();
+    runner
+        .register_plugin(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 2, "batch_size": 2}"#,
+        )
+        .unwrap();
+    runner
+        .register_plugin(&PARSE_REMAINING_INTO_TABLE_DIRECT_PLUGIN_API, c"")
+        .unwrap();
+    runner
+        .register_plugin(&EXTRACT_REMAINING_FROM_TABLE_API, c"")
+        .unwrap();
+
+    let recorder = Rc::clone(&calls);
+    runner.add_hook(move |event| recorder.borrow_mut().push(describe(event)));
+
+    let mut runner = runner.start_capture(c"").unwrap();
+    let event = runner.next_event().unwrap();
+    runner
+        .extract_field(&event, "countdown.remaining")
+        .unwrap()
+        .unwrap();
+    runner
+        .extract_field_with_range(&event, "countdown.remaining")
+        .unwrap()
+        .unwrap();
+    runner.next_event().unwrap();
+    runner.next_event().unwrap_err();
+
+    assert_eq!(
+        *calls.borrow(),
+        [
+            r#"next_event "countdown" -> #1"#,
+            r#"before_parse "test_parse" #1"#,
+            r#"after_parse "test_parse" #1 -> true"#,
+            r#"extract "extract_remaining_from_table" #1 countdown.remaining -> Ok(U64(1)) None"#,
+            r#"extract "extract_remaining_from_table" #1 countdown.remaining -> Ok(U64(1)) Some(1..0)"#,
+            r#"next_event "countdown" -> #2"#,
+            r#"before_parse "test_parse" #2"#,
+            r#"after_parse "test_parse" #2 -> true"#,
+            r#"next_event "countdown" -> Eof"#,
+        ]
+    );
+}
@@ -0,0 +1,143 @@
+use falco_event_schema::events::PPME_SYSCALL_OPEN_E;
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::Event;
+use falco_plugin::parse::{EventInput, ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+
+struct ReplayMetricsPlugin {
+    named_opens: u64,
+}
+
+impl Plugin for ReplayMetricsPlugin {
+    const NAME: &'static CStr = c"replay_metrics";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self { named_opens: 0 })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [Metric::new(
+            MetricLabel::new(c"named_opens", MetricType::Monotonic),
+            MetricValue::U64(self.named_opens),
+        )]
+    }
+}
+
+impl ParsePlugin for ReplayMetricsPlugin {
+    type Event<'a> = Event<PPME_SYSCALL_OPEN_E<'a>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let event = event.event()?;
+        if event.params.name.is_some() {
+            self.named_opens += 1;
+        }
+        Ok(())
+    }
+
+    const PARSE_METRICS: bool = true;
+}
+
+static_plugin!(REPLAY_METRICS_API = ReplayMetricsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::random_events::{
+        RandomEventsConfig, RandomEventsPlugin, RANDOM_EVENTS_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, SinspMetric, TestDriver,
+    };
+    use std::ffi::CString;
+
+    pub fn config() -> RandomEventsConfig {
+        RandomEventsConfig {
+            seed: 1,
+            count: 50,
+            event_types: vec![2],
+        }
+    }
+
+    pub fn live_history<D: TestDriver>() -> Vec<Vec<SinspMetric>> {
+        let config = CString::new(serde_json::to_string(&config()).unwrap()).unwrap();
+        let (mut driver, _plugin) = init_plugin::<D>(&RANDOM_EVENTS_PLUGIN_API, &config).unwrap();
+        driver
+            .register_plugin(&super::REPLAY_METRICS_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(RandomEventsPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        driver.metrics_history("replay_metrics.").unwrap()
+    }
+
+    fn test_live_metrics_history<D: TestDriver>() {
+        let history = live_history::<D>();
+
+        // every event changes the parse metrics
+        assert_eq!(history.len(), 50);
+        let last = history.last().unwrap();
+        assert!(last.contains(&SinspMetric {
+            name: "replay_metrics.parsed_events".to_string(),
+            value: 50,
+        }));
+        assert!(last.contains(&SinspMetric {
+            name: "replay_metrics.parsed_events.type_2".to_string(),
+            value: 50,
+        }));
+    }
+
+    instantiate_tests!(test_live_metrics_history);
+}
+
+#[cfg(all(test, have_libsinsp))]
+mod sinsp_tests {
+    use super::tests::{config, live_history};
+    use falco_plugin_tests::plugin_collection::source::random_events::{
+        write_random_capture, RANDOM_EVENTS_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_sinsp_tests, CapturingTestDriver, SavefileTestDriver,
+        SavefileWriterTestDriver,
+    };
+    use std::ffi::CString;
+    use std::os::unix::ffi::OsStrExt;
+
+    fn test_replay_metrics_history<D: SavefileTestDriver>()
+    where
+        D::Capturing: SavefileWriterTestDriver,
+    {
+        let path = std::env::temp_dir().join(format!("replay_metrics_{}.scap", std::process::id()));
+        let path = CString::new(path.as_os_str().as_bytes()).unwrap();
+
+        let written = write_random_capture::<D>(&path, &config()).unwrap();
+        assert_eq!(written, 50);
+
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &RANDOM_EVENTS_PLUGIN_API,
+            c"{\"seed\": 0, \"count\": 0, \"event_types\": [2]}",
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::REPLAY_METRICS_API, c"")
+            .unwrap();
+        let mut driver = driver.load_capture_file(&path).unwrap();
+        let replay = driver.metrics_history("replay_metrics.").unwrap();
+        std::fs::remove_file(path.to_str().unwrap()).ok();
+
+        assert_eq!(replay, live_history::<D>());
+    }
+
+    instantiate_sinsp_tests!(test_replay_metrics_history);
+}
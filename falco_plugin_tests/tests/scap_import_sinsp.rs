@@ -0,0 +1,143 @@
+use falco_event_schema::events::PPME_SYSCALL_READ_X;
+use falco_event_schema::fields::types::PT_FD;
+use falco_plugin::anyhow;
+use falco_plugin::anyhow::{Context, Error};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{Event, RawEvent};
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::sinsp::fd_accessors::*;
+use falco_plugin::tables::import::sinsp::thread_accessors::*;
+use falco_plugin::tables::import::sinsp::{ThreadTable, THREAD_TABLE};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+thread_local! {
+static TEST_DONE: AtomicBool = const { AtomicBool::new(false) };
+}
+
+struct DummyPlugin {
+    threads: ThreadTable,
+    event_num: usize,
+}
+
+impl Plugin for DummyPlugin {
+    const NAME: &'static CStr = c"dummy";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        TEST_DONE.with(|flag| flag.store(false, Ordering::Relaxed));
+
+        let Some(input) = input else {
+            anyhow::bail!("Did not get tables input")
+        };
+
+        let threads = input.get_table(THREAD_TABLE)?;
+
+        Ok(Self {
+            threads,
+            event_num: 0,
+        })
+    }
+}
+
+impl ParsePlugin for DummyPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        self.event_num += 1;
+
+        // the same event as in scap_import_table.rs
+        if self.event_num == 1088 {
+            let event = event
+                .event()
+                .context(format!("loading raw event {})", self.event_num))?;
+            let event: Event<PPME_SYSCALL_READ_X> = event
+                .load()
+                .context(format!("parsing event #{} {event:?}", self.event_num))?;
+
+            let Some(PT_FD(event_fd)) = event.params.fd else {
+                anyhow::bail!("event did not have the fd param set");
+            };
+
+            let r = &parse_input.reader;
+
+            let tid = event.metadata.tid;
+            let thread = self.threads.get_entry(r, &tid)?;
+
+            let thread_tid = thread.get_tid(r)?;
+            if thread_tid != tid {
+                anyhow::bail!("tid mismatch, expected {}, got {}", tid, thread_tid);
+            }
+
+            let comm = thread.get_comm(r)?;
+            if comm.to_bytes() != b"node" {
+                anyhow::bail!("comm mismatch, expected \"node\", got {:?}", comm);
+            }
+
+            let fd = thread.get_file_descriptors_by_key(r, &event_fd)?;
+            let fd_num = fd.get_fd(r)?;
+            if fd_num != event_fd {
+                anyhow::bail!("fd mismatch, expected {}, got {}", event_fd, fd_num);
+            }
+
+            let fd_type = fd.get_fd_type(r)?;
+            if fd_type != 9 {
+                anyhow::bail!("fd type mismatch, got {}", fd_type);
+            }
+
+            TEST_DONE.with(|flag| flag.store(true, Ordering::Relaxed));
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(PARSE_API = DummyPlugin);
+
+#[cfg(test)]
+#[cfg_attr(not(have_libsinsp), allow(dead_code))]
+mod tests {
+    use crate::TEST_DONE;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_sinsp_tests, CapturingTestDriver, SavefileTestDriver, ScapStatus,
+    };
+    use std::ffi::CString;
+    use std::sync::atomic::Ordering;
+    use typed_path::UnixPathBuf;
+
+    fn open_capture_file<D: SavefileTestDriver>(driver: D) -> anyhow::Result<D::Capturing> {
+        let manifest_dir = env!("CARGO_MANIFEST_DIR");
+        let scap_file = UnixPathBuf::from(manifest_dir).join("tests/scap/kexec_x86.scap");
+        let scap_file = CString::new(scap_file.as_bytes())?;
+
+        driver.load_capture_file(scap_file.as_c_str())
+    }
+
+    fn test_with_plugin<D: SavefileTestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::PARSE_API, c"").unwrap();
+        let mut driver = open_capture_file(driver).unwrap();
+
+        loop {
+            match driver.next_event() {
+                Ok(_) => continue,
+                Err(ScapStatus::Filtered) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("{e:?}"),
+            }
+        }
+
+        assert!(TEST_DONE.with(|flag| flag.load(Ordering::Relaxed)));
+    }
+
+    instantiate_sinsp_tests!(test_with_plugin);
+}
@@ -29,6 +29,67 @@ fn test_source_batch_sized<D: TestDriver>(size: usize) {
     assert!(matches!(event, Err(ScapStatus::Eof)))
 }
 
+fn test_source_progress<D: TestDriver>() {
+    let (driver, _plugin) = init_plugin::<D>(
+        &COUNTDOWN_PLUGIN_API,
+        cr#"{"remaining": 4, "batch_size": 4}"#,
+    )
+    .unwrap();
+    let mut driver = driver
+        .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+        .unwrap();
+
+    let progress = driver.get_progress().unwrap();
+    assert_eq!(progress.value, 0.0);
+    assert_eq!(progress.detail.as_deref(), Some("0/4 events"));
+
+    driver.next_event().unwrap();
+    let progress = driver.get_progress().unwrap();
+    assert_eq!(progress.value, 100.0);
+    assert_eq!(progress.detail.as_deref(), Some("4/4 events"));
+}
+
+fn test_source_open_params<D: TestDriver>() {
+    let (driver, _plugin) = init_plugin::<D>(
+        &COUNTDOWN_PLUGIN_API,
+        cr#"{"remaining": 4, "batch_size": 4}"#,
+    )
+    .unwrap();
+    let mut driver = driver
+        .start_capture(
+            CountdownPlugin::NAME,
+            cr#"{"remaining": 2}"#,
+            PlatformData::Disabled,
+        )
+        .unwrap();
+
+    assert_eq!(
+        driver.next_event_as_str().unwrap().unwrap(),
+        "1 events remaining"
+    );
+    assert_eq!(
+        driver.next_event_as_str().unwrap().unwrap(),
+        "0 events remaining"
+    );
+
+    let event = driver.next_event();
+    assert!(matches!(event, Err(ScapStatus::Eof)))
+}
+
+fn test_source_bad_open_params<D: TestDriver>() {
+    let (driver, _plugin) = init_plugin::<D>(
+        &COUNTDOWN_PLUGIN_API,
+        cr#"{"remaining": 4, "batch_size": 4}"#,
+    )
+    .unwrap();
+    let res = driver.start_capture(
+        CountdownPlugin::NAME,
+        cr#"{"remaining": "many"}"#,
+        PlatformData::Disabled,
+    );
+    assert!(res.is_err());
+}
+
 fn test_source_batch_1<D: TestDriver>() {
     test_source_batch_sized::<D>(100)
 }
@@ -45,4 +106,4 @@ fn test_source_batch_1000<D: TestDriver>() {
     test_source_batch_sized::<D>(100)
 }
 
-instantiate_tests!(test_source_batch_1;test_source_batch_10;test_source_batch_100;test_source_batch_1000);
+instantiate_tests!(test_source_batch_1;test_source_batch_10;test_source_batch_100;test_source_batch_1000;test_source_progress;test_source_open_params;test_source_bad_open_params);
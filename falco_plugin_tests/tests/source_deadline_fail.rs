@@ -0,0 +1,123 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::deadline;
+use falco_plugin::base::deadline::DeadlinePolicy;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::{EventBatch, SourceError, SourcePlugin, SourcePluginInstance};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::time::Duration;
+
+struct DeadlinePlugin;
+
+impl Plugin for DeadlinePlugin {
+    const NAME: &'static CStr = c"deadline_fail";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct DeadlinePluginInstance {
+    batch_num: usize,
+}
+
+impl SourcePluginInstance for DeadlinePluginInstance {
+    type Plugin = DeadlinePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        assert!(deadline::time_remaining().is_some());
+
+        self.batch_num += 1;
+        match self.batch_num {
+            1 => {
+                batch.add(Self::plugin_event(b"slow"))?;
+                while !deadline::deadline_exceeded() {
+                    std::thread::sleep(Duration::from_millis(1));
+                }
+                Ok(())
+            }
+            2 => {
+                batch.add(Self::plugin_event(b"fast"))?;
+                Ok(())
+            }
+            _ => Err(SourceError::eof("all events produced"))?,
+        }
+    }
+}
+
+impl SourcePlugin for DeadlinePlugin {
+    type Instance = DeadlinePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"deadline_fail";
+    const PLUGIN_ID: u32 = 1126;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(DeadlinePluginInstance { batch_num: 0 })
+    }
+
+    const NEXT_BATCH_DEADLINE: Option<Duration> = Some(Duration::from_millis(20));
+    const NEXT_BATCH_DEADLINE_POLICY: DeadlinePolicy = DeadlinePolicy::Fail;
+}
+
+static_plugin!(DEADLINE_API = DeadlinePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_next_batch_deadline_fail<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::DEADLINE_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::DeadlinePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut outcomes = Vec::new();
+        loop {
+            let event = match driver.next_event() {
+                Ok(event) => event,
+                Err(ScapStatus::Timeout) => {
+                    outcomes.push("<timeout>".to_string());
+                    continue;
+                }
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("{e:?}"),
+            };
+            outcomes.push(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+
+        // the overrun is reported as a timeout, but the partial batch is still delivered
+        assert_eq!(outcomes, ["slow", "<timeout>", "fast"]);
+
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            metrics,
+            [("deadline_fail.deadline_overruns.next_batch".to_string(), 1)]
+        );
+    }
+
+    instantiate_tests!(test_next_batch_deadline_fail);
+}
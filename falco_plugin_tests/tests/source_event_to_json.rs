@@ -0,0 +1,145 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{Event, EventMetadata};
+use falco_plugin::event::fields::{FromBytes, FromBytesError, NoDefault, ToBytes};
+use falco_plugin::event::{EventSource, PluginEvent};
+use falco_plugin::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{anyhow, static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::io::Write;
+
+/// A binary payload, which does not render as text
+#[derive(Debug, serde::Serialize)]
+struct Reading {
+    sensor: u16,
+    value: i32,
+}
+
+impl EventSource for Reading {
+    const SOURCE: Option<&'static str> = Some("readings");
+}
+
+impl ToBytes for Reading {
+    fn binary_size(&self) -> usize {
+        6
+    }
+
+    fn write<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(&self.sensor.to_be_bytes())?;
+        writer.write_all(&self.value.to_be_bytes())
+    }
+
+    fn default_repr() -> impl ToBytes {
+        NoDefault
+    }
+}
+
+impl FromBytes<'_> for Reading {
+    fn from_bytes(buf: &mut &[u8]) -> Result<Self, FromBytesError> {
+        let Some((sensor, value)) = buf.split_first_chunk::<2>() else {
+            return Err(FromBytesError::Other(anyhow::anyhow!("truncated reading")));
+        };
+        let Some((value, rest)) = value.split_first_chunk::<4>() else {
+            return Err(FromBytesError::Other(anyhow::anyhow!("truncated reading")));
+        };
+        let reading = Reading {
+            sensor: u16::from_be_bytes(*sensor),
+            value: i32::from_be_bytes(*value),
+        };
+        *buf = rest;
+        Ok(reading)
+    }
+}
+
+struct ReadingsPlugin;
+
+impl Plugin for ReadingsPlugin {
+    const NAME: &'static CStr = c"readings";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct ReadingsPluginInstance {
+    next: u16,
+}
+
+impl SourcePluginInstance for ReadingsPluginInstance {
+    type Plugin = ReadingsPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        if self.next == 2 {
+            return Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof));
+        }
+
+        batch.add(Event {
+            metadata: EventMetadata::default(),
+            params: PluginEvent {
+                plugin_id: ReadingsPlugin::PLUGIN_ID,
+                event_data: Reading {
+                    sensor: self.next,
+                    value: -5 * i32::from(self.next),
+                },
+            },
+        })?;
+        self.next += 1;
+        Ok(())
+    }
+}
+
+impl SourcePlugin for ReadingsPlugin {
+    type Instance = ReadingsPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"readings";
+    const PLUGIN_ID: u32 = 1125;
+    type Event<'a> = Event<PluginEvent<Reading>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(ReadingsPluginInstance { next: 0 })
+    }
+
+    fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+        event.to_json_cstring()
+    }
+}
+
+static_plugin!(READINGS_API = ReadingsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_event_to_json<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::READINGS_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::ReadingsPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        for expected in [r#"{"sensor":0,"value":0}"#, r#"{"sensor":1,"value":-5}"#] {
+            let event = driver.next_event().unwrap();
+            assert_eq!(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+                expected
+            );
+        }
+
+        assert!(matches!(driver.next_event(), Err(ScapStatus::Eof)));
+    }
+
+    instantiate_tests!(test_event_to_json);
+}
@@ -0,0 +1,145 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{Metric, MetricLabel, MetricType, MetricValue, Plugin};
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::{EventBatch, SourceError, SourcePlugin, SourcePluginInstance};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+
+struct InstanceMetricsPlugin {
+    num_opens: usize,
+}
+
+impl Plugin for InstanceMetricsPlugin {
+    const NAME: &'static CStr = c"instance_metrics";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self { num_opens: 0 })
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [Metric::new(
+            MetricLabel::new(c"opens", MetricType::Monotonic),
+            MetricValue::U64(self.num_opens as u64),
+        )]
+    }
+}
+
+struct InstanceMetricsPluginInstance {
+    events_read: usize,
+    bytes_read: usize,
+}
+
+impl SourcePluginInstance for InstanceMetricsPluginInstance {
+    type Plugin = InstanceMetricsPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        if self.events_read == 2 {
+            Err(SourceError::eof("all events produced"))?
+        }
+
+        let data = b"event";
+        batch.add(Self::plugin_event(data))?;
+        self.events_read += 1;
+        self.bytes_read += data.len();
+        Ok(())
+    }
+
+    fn get_metrics(&mut self) -> impl IntoIterator<Item = Metric> {
+        [
+            Metric::new(
+                MetricLabel::new(c"events_read", MetricType::Monotonic),
+                MetricValue::U64(self.events_read as u64),
+            ),
+            Metric::new(
+                MetricLabel::new(c"bytes_read", MetricType::Monotonic),
+                MetricValue::U64(self.bytes_read as u64),
+            ),
+        ]
+    }
+}
+
+impl SourcePlugin for InstanceMetricsPlugin {
+    type Instance = InstanceMetricsPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"instance_metrics";
+    const PLUGIN_ID: u32 = 1118;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        self.num_opens += 1;
+        Ok(InstanceMetricsPluginInstance {
+            events_read: 0,
+            bytes_read: 0,
+        })
+    }
+}
+
+static_plugin!(INSTANCE_METRICS_API = InstanceMetricsPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    #[track_caller]
+    fn check_metrics<D: CapturingTestDriver>(driver: &mut D, expected: &[(&str, u64)]) {
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+        let expected = expected
+            .iter()
+            .map(|(name, value)| (name.to_string(), *value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(metrics, expected);
+    }
+
+    fn test_instance_metrics<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::INSTANCE_METRICS_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(
+                super::InstanceMetricsPlugin::NAME,
+                c"",
+                PlatformData::Disabled,
+            )
+            .unwrap();
+
+        check_metrics(
+            &mut driver,
+            &[
+                ("instance_metrics.opens", 1),
+                ("instance_metrics.events_read.instance_0", 0),
+                ("instance_metrics.bytes_read.instance_0", 0),
+            ],
+        );
+
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+        check_metrics(
+            &mut driver,
+            &[
+                ("instance_metrics.opens", 1),
+                ("instance_metrics.events_read.instance_0", 2),
+                ("instance_metrics.bytes_read.instance_0", 10),
+            ],
+        );
+
+        assert!(matches!(driver.next_event(), Err(ScapStatus::Eof)));
+    }
+
+    instantiate_tests!(test_instance_metrics);
+}
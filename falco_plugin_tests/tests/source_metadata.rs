@@ -0,0 +1,139 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{Event, EventMetadata};
+use falco_plugin::event::PluginEvent;
+use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::source::{EventBatch, EventInput, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+
+struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    const NAME: &'static CStr = c"replay";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct ReplayPluginInstance {
+    done: bool,
+}
+
+impl SourcePluginInstance for ReplayPluginInstance {
+    type Plugin = ReplayPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        if self.done {
+            return Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof));
+        }
+
+        self.done = true;
+        let metadata = EventMetadata {
+            ts: 1_500_000_000_000_000_000,
+            tid: 42,
+        };
+        batch.add(Self::plugin_event_with_metadata(metadata, b"replayed"))?;
+        Ok(())
+    }
+}
+
+impl SourcePlugin for ReplayPlugin {
+    type Instance = ReplayPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"replay";
+    const PLUGIN_ID: u32 = 1112;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(ReplayPluginInstance { done: false })
+    }
+
+    fn event_to_string(&mut self, event: &EventInput<Self::Event<'_>>) -> Result<CString, Error> {
+        let event = event.event()?;
+        Ok(CString::new(event.params.event_data)?)
+    }
+}
+
+struct MetadataExtractPlugin;
+
+impl Plugin for MetadataExtractPlugin {
+    const NAME: &'static CStr = c"metadata";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl MetadataExtractPlugin {
+    fn extract_ts(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(req.event.event()?.metadata.ts)
+    }
+
+    fn extract_tid(&mut self, req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(req.event.event()?.metadata.tid as u64)
+    }
+}
+
+impl ExtractPlugin for MetadataExtractPlugin {
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+    type ExtractContext = ();
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("metadata.ts", &Self::extract_ts),
+        field("metadata.tid", &Self::extract_tid),
+    ];
+}
+
+static_plugin!(REPLAY_PLUGIN_API = ReplayPlugin);
+static_plugin!(METADATA_PLUGIN_API = MetadataExtractPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_event_metadata<D: TestDriver>(platform_data: PlatformData) {
+        let (mut driver, _plugin) = init_plugin::<D>(&super::REPLAY_PLUGIN_API, c"").unwrap();
+        let plugin = driver
+            .register_plugin(&super::METADATA_PLUGIN_API, c"")
+            .unwrap();
+        driver.add_filterchecks(&plugin, c"replay").unwrap();
+
+        let mut driver = driver
+            .start_capture(super::ReplayPlugin::NAME, c"", platform_data)
+            .unwrap();
+
+        let event = driver.next_event().unwrap();
+        assert_eq!(
+            driver
+                .event_field_as_string(c"metadata.ts", &event)
+                .unwrap()
+                .unwrap(),
+            "1500000000000000000"
+        );
+        assert_eq!(
+            driver
+                .event_field_as_string(c"metadata.tid", &event)
+                .unwrap()
+                .unwrap(),
+            "42"
+        );
+    }
+
+    instantiate_tests!(platform_data: test_event_metadata);
+}
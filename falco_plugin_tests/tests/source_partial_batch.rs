@@ -0,0 +1,105 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::CStr;
+
+struct PartialBatchPlugin;
+
+impl Plugin for PartialBatchPlugin {
+    const NAME: &'static CStr = c"partial_batch";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+struct PartialBatchPluginInstance {
+    batch_num: usize,
+}
+
+impl SourcePluginInstance for PartialBatchPluginInstance {
+    type Plugin = PartialBatchPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        self.batch_num += 1;
+        match self.batch_num {
+            1 => {
+                batch.add(Self::plugin_event(b"first"))?;
+                batch.add(Self::plugin_event(b"second"))?;
+                Err(anyhow::anyhow!("no more data for now").context(FailureReason::Timeout))
+            }
+            2 => Err(anyhow::anyhow!("still no data").context(FailureReason::Timeout)),
+            3 => {
+                batch.add(Self::plugin_event(b"last"))?;
+                Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof))
+            }
+            _ => Err(anyhow::anyhow!("all events produced").context(FailureReason::Eof)),
+        }
+    }
+}
+
+impl SourcePlugin for PartialBatchPlugin {
+    type Instance = PartialBatchPluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"partial_batch";
+    const PLUGIN_ID: u32 = 1115;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(PartialBatchPluginInstance { batch_num: 0 })
+    }
+}
+
+static_plugin!(PARTIAL_BATCH_API = PartialBatchPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_partial_batch<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::PARTIAL_BATCH_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::PartialBatchPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut outcomes = Vec::new();
+        loop {
+            let event = match driver.next_event() {
+                Ok(event) => event,
+                Err(ScapStatus::Timeout) => {
+                    outcomes.push("<timeout>".to_string());
+                    continue;
+                }
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("{e:?}"),
+            };
+            outcomes.push(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(
+            outcomes,
+            ["first", "second", "<timeout>", "<timeout>", "last"]
+        );
+    }
+
+    instantiate_tests!(test_partial_batch);
+}
@@ -0,0 +1,107 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::{EventBatch, PauseHandle, SourcePlugin, SourcePluginInstance};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+
+thread_local! {
+    // lets the test toggle the state, standing in for e.g. a control thread
+    static PAUSE: PauseHandle = PauseHandle::default();
+}
+
+struct PausablePlugin {
+    pause: PauseHandle,
+}
+
+impl Plugin for PausablePlugin {
+    const NAME: &'static CStr = c"pausable";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = String;
+
+    fn new(_input: Option<&TablesInput>, config: Self::ConfigType) -> Result<Self, Error> {
+        let pause = PAUSE.with(PauseHandle::clone);
+        pause.set_paused(config == "paused");
+        Ok(Self { pause })
+    }
+}
+
+struct PausablePluginInstance {
+    pause: PauseHandle,
+}
+
+impl SourcePluginInstance for PausablePluginInstance {
+    type Plugin = PausablePlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        batch.add(Self::plugin_event(b"event"))?;
+        Ok(())
+    }
+
+    fn pause_handle(&self) -> Option<&PauseHandle> {
+        Some(&self.pause)
+    }
+}
+
+impl SourcePlugin for PausablePlugin {
+    type Instance = PausablePluginInstance;
+    const EVENT_SOURCE: &'static CStr = c"pausable";
+    const PLUGIN_ID: u32 = 1120;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(PausablePluginInstance {
+            pause: self.pause.clone(),
+        })
+    }
+}
+
+static_plugin!(PAUSABLE_API = PausablePlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    #[track_caller]
+    fn check_paused<D: CapturingTestDriver>(driver: &mut D, paused: u64) {
+        let metrics = driver
+            .get_metrics()
+            .unwrap()
+            .into_iter()
+            .map(|m| (m.name, m.value))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            metrics,
+            [("pausable.paused.instance_0".to_string(), paused)]
+        );
+    }
+
+    fn test_source_pause<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::PAUSABLE_API, c"paused").unwrap();
+        let mut driver = driver
+            .start_capture(super::PausablePlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        check_paused(&mut driver, 1);
+        assert!(matches!(driver.next_event(), Err(ScapStatus::Timeout)));
+
+        super::PAUSE.with(|pause| pause.resume());
+        check_paused(&mut driver, 0);
+        driver.next_event().unwrap();
+        driver.next_event().unwrap();
+    }
+
+    instantiate_tests!(test_source_pause);
+}
@@ -0,0 +1,76 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::Event;
+use falco_plugin::event::PluginEvent;
+use falco_plugin::source::readers::{lines, LineSource};
+use falco_plugin::source::SourcePlugin;
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::io::Cursor;
+
+struct LinesPlugin;
+
+impl Plugin for LinesPlugin {
+    const NAME: &'static CStr = c"lines";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl SourcePlugin for LinesPlugin {
+    type Instance = LineSource<Self, Cursor<String>>;
+    const EVENT_SOURCE: &'static CStr = c"lines";
+    const PLUGIN_ID: u32 = 1117;
+    type Event<'a> = Event<PluginEvent<&'a [u8]>>;
+
+    fn open(&mut self, params: Option<&str>) -> Result<Self::Instance, Error> {
+        let input = Cursor::new(params.unwrap_or_default().to_string());
+        Ok(LineSource::new(lines(input)).with_max_batch_size(2))
+    }
+}
+
+static_plugin!(LINES_API = LinesPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_line_source<D: TestDriver>() {
+        let (driver, _plugin) = init_plugin::<D>(&super::LINES_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(
+                super::LinesPlugin::NAME,
+                c"first\nsecond\r\n\nlast",
+                PlatformData::Disabled,
+            )
+            .unwrap();
+
+        let mut lines = Vec::new();
+        loop {
+            let event = match driver.next_event() {
+                Ok(event) => event,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("{e:?}"),
+            };
+            lines.push(
+                driver
+                    .event_field_as_string(c"evt.plugininfo", &event)
+                    .unwrap()
+                    .unwrap(),
+            );
+        }
+
+        assert_eq!(lines, ["first", "second", "", "last"]);
+    }
+
+    instantiate_tests!(test_line_source);
+}
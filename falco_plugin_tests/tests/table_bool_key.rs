@@ -0,0 +1,164 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::export;
+use falco_plugin::tables::import::{Bool, Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+// count events with odd and even event numbers
+#[derive(export::Entry)]
+struct ParityEntry {
+    count: export::Public<u64>,
+}
+
+type ExportedParityTable = export::Table<Bool, ParityEntry>;
+
+struct ParityExportPlugin {
+    parity: Box<ExportedParityTable>,
+}
+
+impl Plugin for ParityExportPlugin {
+    const NAME: &'static CStr = c"parity_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let parity = input.add_table(ExportedParityTable::new(c"parity")?)?;
+
+        Ok(Self { parity })
+    }
+}
+
+impl ParsePlugin for ParityExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let is_even = Bool::from(event.event_number().is_multiple_of(2));
+        match self.parity.lookup(&is_even) {
+            Some(mut entry) => *entry.count += 1,
+            None => {
+                let mut entry = self.parity.create_entry()?;
+                *entry.count = 1;
+                self.parity.insert(&is_even, entry);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(PARITY_EXPORT_API = ParityExportPlugin);
+
+type ImportedParity = Entry<Arc<ImportedParityMetadata>>;
+type ImportedParityTable = Table<Bool, ImportedParity>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedParity)]
+#[key_type(Bool)]
+struct ImportedParityMetadata {
+    count: Field<u64, ImportedParity>,
+}
+
+static EVEN_COUNT: AtomicU64 = AtomicU64::new(0);
+static ODD_COUNT: AtomicU64 = AtomicU64::new(0);
+
+struct ParityImportPlugin {
+    parity: ImportedParityTable,
+}
+
+impl Plugin for ParityImportPlugin {
+    const NAME: &'static CStr = c"parity_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let parity = input.get_table(c"parity")?;
+
+        Ok(Self { parity })
+    }
+}
+
+impl ParsePlugin for ParityImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        _event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+
+        for (key, count) in [(true, &EVEN_COUNT), (false, &ODD_COUNT)] {
+            let Ok(entry) = self.parity.get_entry(r, &Bool::from(key)) else {
+                continue;
+            };
+            count.store(entry.get_count(r)?, Ordering::Relaxed);
+        }
+
+        Ok(())
+    }
+}
+
+static_plugin!(PARITY_IMPORT_API = ParityImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::plugin_collection::source::countdown::{
+        CountdownPlugin, COUNTDOWN_PLUGIN_API,
+    };
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, CapturingTestDriver, PlatformData, ScapStatus,
+        TestDriver,
+    };
+    use std::sync::atomic::Ordering;
+
+    fn test_bool_key<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 5, "batch_size": 5}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::PARITY_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::PARITY_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(CountdownPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        loop {
+            match driver.next_event() {
+                Ok(_) => continue,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+
+        let even = super::EVEN_COUNT.load(Ordering::Relaxed);
+        let odd = super::ODD_COUNT.load(Ordering::Relaxed);
+        assert_eq!(even + odd, 5);
+        assert!(even.abs_diff(odd) == 1, "even={even}, odd={odd}");
+    }
+
+    instantiate_native_tests!(test_bool_key);
+}
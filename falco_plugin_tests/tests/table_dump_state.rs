@@ -0,0 +1,251 @@
+use falco_plugin::anyhow::{self, Error};
+use falco_plugin::async_event::{AsyncEvent, AsyncEventPlugin, AsyncHandler};
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::{Event, RawEvent};
+use falco_plugin::event::PayloadFormat;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::source::{EventBatch, SourcePlugin, SourcePluginInstance};
+use falco_plugin::tables::export;
+use falco_plugin::tables::TablesInput;
+use falco_plugin::{static_plugin, FailureReason};
+use std::ffi::{CStr, CString};
+use std::sync::Mutex;
+use std::time::Duration;
+
+#[derive(export::Entry, serde::Serialize, serde::Deserialize)]
+struct Process {
+    comm: export::Public<CString>,
+    events: export::Readonly<u64>,
+    secret: export::Private<String>,
+}
+
+type ProcessTable = export::Table<u64, Process>;
+
+const STATE_EVENT: &CStr = c"process_state";
+
+struct StateWriterPlugin {
+    processes: Box<ProcessTable>,
+}
+
+impl Plugin for StateWriterPlugin {
+    const NAME: &'static CStr = c"state_writer";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let Some(input) = input else {
+            anyhow::bail!("Did not get tables input");
+        };
+
+        let processes = input.add_table(ProcessTable::new(c"processes")?)?;
+        for (tid, comm) in [(1u64, c"init"), (10, c"bash"), (20, c"cat")] {
+            let mut entry = processes.create_entry()?;
+            *entry.comm = comm.to_owned();
+            *entry.events = tid * 2;
+            *entry.secret = format!("secret of {tid}");
+            processes.insert(&tid, entry);
+        }
+
+        Ok(Self { processes })
+    }
+}
+
+struct StateWriterInstance;
+
+impl SourcePluginInstance for StateWriterInstance {
+    type Plugin = StateWriterPlugin;
+
+    fn next_batch(
+        &mut self,
+        _plugin: &mut Self::Plugin,
+        _batch: &mut EventBatch,
+    ) -> Result<(), Error> {
+        std::thread::sleep(Duration::from_millis(1));
+        Err(anyhow::anyhow!("this plugin does nothing").context(FailureReason::Timeout))
+    }
+}
+
+impl SourcePlugin for StateWriterPlugin {
+    type Instance = StateWriterInstance;
+    const EVENT_SOURCE: &'static CStr = c"state";
+    const PLUGIN_ID: u32 = 1115;
+    type Event<'a> = RawEvent<'a>;
+
+    fn open(&mut self, _params: Option<&str>) -> Result<Self::Instance, Error> {
+        Ok(StateWriterInstance)
+    }
+
+    fn event_to_string(&mut self, _event: &EventInput<RawEvent>) -> Result<CString, Error> {
+        Ok(CString::from(c"what event?"))
+    }
+}
+
+impl AsyncEventPlugin for StateWriterPlugin {
+    const ASYNC_EVENTS: &'static [&'static str] = &["process_state"];
+    const EVENT_SOURCES: &'static [&'static str] = &["state"];
+
+    // pretend we're loading a capture file: replay the state right away
+    fn start_async(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        self.dump_state(handler)
+    }
+
+    fn stop_async(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn dump_state(&mut self, handler: AsyncHandler) -> Result<(), Error> {
+        self.processes
+            .dump_state(&handler, STATE_EVENT, PayloadFormat::Json, 2)
+    }
+}
+
+static_plugin!(STATE_WRITER_API = StateWriterPlugin);
+
+// (tid, comm, events, secret)
+type Snapshot = Vec<(u64, CString, u64, String)>;
+
+static RESTORED: Mutex<Snapshot> = Mutex::new(Vec::new());
+
+struct StateReaderPlugin {
+    processes: Box<ProcessTable>,
+}
+
+impl Plugin for StateReaderPlugin {
+    const NAME: &'static CStr = c"state_reader";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let Some(input) = input else {
+            anyhow::bail!("Did not get tables input");
+        };
+
+        let processes = input.add_table(ProcessTable::new(c"restored_processes")?)?;
+        Ok(Self { processes })
+    }
+}
+
+impl ParsePlugin for StateReaderPlugin {
+    type Event<'a> = Event<AsyncEvent<'a, &'a [u8]>>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<Self::Event<'_>>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let restored = self
+            .processes
+            .restore_state(event, STATE_EVENT, PayloadFormat::Json)?;
+        anyhow::ensure!(restored <= 2, "chunk of {restored} entries");
+
+        let snapshot = self
+            .processes
+            .data()
+            .unwrap()
+            .read()
+            .iter()
+            .map(|(tid, entry)| {
+                let entry = entry.read();
+                (
+                    *tid,
+                    (*entry.comm).clone(),
+                    *entry.events,
+                    (*entry.secret).clone(),
+                )
+            })
+            .collect();
+        *RESTORED.lock().unwrap() = snapshot;
+
+        Ok(())
+    }
+}
+
+static_plugin!(STATE_READER_API = StateReaderPlugin);
+
+#[cfg(test)]
+mod dump_tests {
+    use falco_plugin::async_event::AsyncEvent;
+    use falco_plugin::base::Plugin;
+    use falco_plugin::event::events::RawEvent;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_native_tests, DumpStateTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_dump_format<D: TestDriver>()
+    where
+        D::Capturing: DumpStateTestDriver,
+    {
+        let (driver, _plugin) = init_plugin::<D>(&super::STATE_WRITER_API, c"").unwrap();
+        let mut driver = driver
+            .start_capture(super::StateWriterPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        let payloads = driver
+            .dump_state()
+            .unwrap()
+            .iter()
+            .map(|buf| {
+                let event = RawEvent::from(buf).unwrap();
+                let event = event.load::<AsyncEvent<&[u8]>>().unwrap();
+                assert_eq!(event.params.name, super::STATE_EVENT);
+                serde_json::from_slice::<serde_json::Value>(event.params.data).unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // private fields are part of the plugin state too
+        assert_eq!(
+            payloads,
+            [
+                serde_json::json!([
+                    [1, {"comm": b"init", "events": 2, "secret": "secret of 1"}],
+                    [10, {"comm": b"bash", "events": 20, "secret": "secret of 10"}],
+                ]),
+                serde_json::json!([
+                    [20, {"comm": b"cat", "events": 40, "secret": "secret of 20"}],
+                ]),
+            ]
+        );
+    }
+
+    instantiate_native_tests!(test_dump_format);
+}
+
+#[cfg(test)]
+mod restore_tests {
+    use falco_plugin::base::Plugin;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, TestDriver,
+    };
+
+    fn test_restore_state<D: TestDriver>() {
+        super::RESTORED.lock().unwrap().clear();
+
+        let (mut driver, _plugin) = init_plugin::<D>(&super::STATE_WRITER_API, c"").unwrap();
+        driver
+            .register_plugin(&super::STATE_READER_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(super::StateWriterPlugin::NAME, c"", PlatformData::Disabled)
+            .unwrap();
+
+        while super::RESTORED.lock().unwrap().len() < 3 {
+            let _ = driver.next_event();
+        }
+
+        assert_eq!(
+            *super::RESTORED.lock().unwrap(),
+            [
+                (1, c"init".to_owned(), 2, "secret of 1".to_string()),
+                (10, c"bash".to_owned(), 20, "secret of 10".to_string()),
+                (20, c"cat".to_owned(), 40, "secret of 20".to_string()),
+            ]
+        );
+    }
+
+    instantiate_tests!(test_restore_state);
+}
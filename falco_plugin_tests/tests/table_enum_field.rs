@@ -0,0 +1,191 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::import::{Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::{export, TableEnum, TablesInput};
+use std::ffi::CStr;
+use std::sync::Arc;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+enum L4Proto {
+    #[default]
+    Unknown = 0,
+    Tcp = 3,
+    Udp = 4,
+}
+
+impl TableEnum for L4Proto {
+    type Repr = u8;
+
+    fn to_repr(self) -> u8 {
+        self as u8
+    }
+
+    fn from_repr(repr: u8) -> Option<Self> {
+        match repr {
+            0 => Some(Self::Unknown),
+            3 => Some(Self::Tcp),
+            4 => Some(Self::Udp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(export::Entry)]
+struct ConnEntry {
+    proto: export::Public<L4Proto>,
+}
+
+type ExportedConnTable = export::Table<u64, ConnEntry>;
+
+struct ConnExportPlugin {
+    conns: Box<ExportedConnTable>,
+}
+
+impl Plugin for ConnExportPlugin {
+    const NAME: &'static CStr = c"conn_export";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.add_table(ExportedConnTable::new(c"conns")?)?;
+
+        Ok(Self { conns })
+    }
+}
+
+impl ParsePlugin for ConnExportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        _parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let mut entry = self.conns.create_entry()?;
+        *entry.proto = L4Proto::Tcp;
+        self.conns.insert(&(event.event_number() as u64), entry);
+
+        Ok(())
+    }
+}
+
+static_plugin!(CONN_EXPORT_API = ConnExportPlugin);
+
+type ImportedConn = Entry<Arc<ImportedConnMetadata>>;
+type ImportedConnTable = Table<u64, ImportedConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(ImportedConn)]
+struct ImportedConnMetadata {
+    proto: Field<L4Proto, ImportedConn>,
+}
+
+// the same table, seen as plain integers
+type RawConn = Entry<Arc<RawConnMetadata>>;
+type RawConnTable = Table<u64, RawConn>;
+
+#[derive(TableMetadata)]
+#[entry_type(RawConn)]
+struct RawConnMetadata {
+    proto: Field<u8, RawConn>,
+}
+
+struct ConnImportPlugin {
+    conns: ImportedConnTable,
+    raw_conns: RawConnTable,
+}
+
+impl Plugin for ConnImportPlugin {
+    const NAME: &'static CStr = c"conn_import";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        let input = input.ok_or_else(|| anyhow::anyhow!("did not get table input"))?;
+        let conns = input.get_table(c"conns")?;
+        let raw_conns = input.get_table(c"conns")?;
+
+        Ok(Self { conns, raw_conns })
+    }
+}
+
+impl ParsePlugin for ConnImportPlugin {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(
+        &mut self,
+        event: &EventInput<RawEvent>,
+        parse_input: &ParseInput,
+    ) -> anyhow::Result<()> {
+        let r = &parse_input.reader;
+        let w = &parse_input.writer;
+        let key = event.event_number() as u64;
+
+        {
+            let entry = self.conns.get_entry(r, &key)?;
+            anyhow::ensure!(entry.get_proto(r)? == L4Proto::Tcp);
+
+            entry.set_proto(w, &L4Proto::Udp)?;
+            anyhow::ensure!(entry.get_proto(r)? == L4Proto::Udp);
+        }
+
+        let raw_entry = self.raw_conns.get_entry(r, &key)?;
+        anyhow::ensure!(raw_entry.get_proto(r)? == 4);
+
+        // the exporting plugin rejects values that do not map to a variant
+        anyhow::ensure!(raw_entry.set_proto(w, &42).is_err());
+        anyhow::ensure!(raw_entry.get_proto(r)? == 4);
+
+        Ok(())
+    }
+}
+
+static_plugin!(CONN_IMPORT_API = ConnImportPlugin);
+
+#[cfg(test)]
+mod tests {
+    use falco_plugin_tests::plugin_collection::source::countdown::COUNTDOWN_PLUGIN_API;
+    use falco_plugin_tests::{
+        init_plugin, instantiate_tests, CapturingTestDriver, PlatformData, ScapStatus, TestDriver,
+    };
+
+    fn test_enum_field<D: TestDriver>() {
+        let (mut driver, _plugin) = init_plugin::<D>(
+            &COUNTDOWN_PLUGIN_API,
+            cr#"{"remaining": 4, "batch_size": 4}"#,
+        )
+        .unwrap();
+        driver
+            .register_plugin(&super::CONN_EXPORT_API, c"")
+            .unwrap();
+        driver
+            .register_plugin(&super::CONN_IMPORT_API, c"")
+            .unwrap();
+        let mut driver = driver
+            .start_capture(c"countdown", c"", PlatformData::Disabled)
+            .unwrap();
+
+        let mut count = 0;
+        loop {
+            match driver.next_event() {
+                Ok(_) => count += 1,
+                Err(ScapStatus::Timeout) => continue,
+                Err(ScapStatus::Eof) => break,
+                Err(e) => panic!("Got {e:?}"),
+            }
+        }
+        assert_eq!(count, 4);
+    }
+
+    instantiate_tests!(test_enum_field);
+}
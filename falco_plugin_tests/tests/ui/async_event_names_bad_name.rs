@@ -0,0 +1,10 @@
+use falco_plugin::async_event::AsyncEventNames;
+
+#[derive(Clone, Copy, AsyncEventNames)]
+enum MyAsyncEvents {
+    #[name = "container_added"]
+    ContainerAdded,
+    ContainerRemoved,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use falco_plugin::async_event::AsyncEventNames;
+
+#[derive(Clone, Copy, AsyncEventNames)]
+enum MyAsyncEvents {
+    ContainerAdded,
+    ContainerRemoved(u64),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Entry {
+    count: u64,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Conn {
+    bytes: export::Public<u64>,
+    #[name = "bytes"]
+    byte_count: export::Public<u64>,
+}
+
+fn main() {}
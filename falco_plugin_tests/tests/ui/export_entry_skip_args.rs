@@ -0,0 +1,9 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Conn {
+    #[skip(true)]
+    cache: Vec<u64>,
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Conn {
+    #[skip]
+    #[name = "cache"]
+    cache: Vec<u64>,
+}
+
+fn main() {}
@@ -0,0 +1,6 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Entry(export::Public<u64>);
+
+fn main() {}
@@ -0,0 +1,8 @@
+use falco_plugin::tables::export;
+
+#[derive(export::Entry)]
+struct Entry {
+    name: export::Public<String>,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::Plugin;
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::{field, ExtractFieldInfo, ExtractPlugin, ExtractRequest};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::{CStr, CString};
+
+struct DummyPlugin;
+
+impl Plugin for DummyPlugin {
+    const NAME: &'static CStr = c"dummy";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl DummyPlugin {
+    fn extract_name(&mut self, _req: ExtractRequest<Self>) -> Result<CString, Error> {
+        Ok(c"dummy".to_owned())
+    }
+
+    fn extract_num(&mut self, _req: ExtractRequest<Self>) -> Result<u64, Error> {
+        Ok(1)
+    }
+}
+
+impl ExtractPlugin for DummyPlugin {
+    type Event<'a> = RawEvent<'a>;
+    type ExtractContext = ();
+    const EXTRACT_FIELDS: &'static [ExtractFieldInfo<Self>] = &[
+        field("dummy.name", &Self::extract_name),
+        field("dummy.num", &Self::extract_num),
+        field("dummy.name", &Self::extract_num),
+    ];
+}
+
+static_plugin!(DUMMY_PLUGIN_API = DummyPlugin);
+
+fn main() {}
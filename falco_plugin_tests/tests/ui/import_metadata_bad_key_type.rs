@@ -0,0 +1,13 @@
+use falco_plugin::tables::import::{Entry, Field, TableMetadata};
+use std::sync::Arc;
+
+type Thread = Entry<Arc<ThreadMetadata>>;
+
+#[derive(TableMetadata)]
+#[entry_type(Thread)]
+#[key_type(i64, u64)]
+struct ThreadMetadata {
+    comm: Field<std::ffi::CStr, Thread>,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use falco_plugin::tables::import::{Entry, Field, TableMetadata};
+use std::sync::Arc;
+
+type Thread = Entry<Arc<ThreadMetadata>>;
+
+#[derive(TableMetadata)]
+#[entry_type(Thread)]
+struct ThreadMetadata {
+    #[custom]
+    counter: Option<Field<u64, Thread>>,
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+use falco_plugin::anyhow::Error;
+use falco_plugin::base::{ApiFeature, Plugin};
+use falco_plugin::event::events::RawEvent;
+use falco_plugin::extract::EventInput;
+use falco_plugin::parse::{ParseInput, ParsePlugin};
+use falco_plugin::static_plugin;
+use falco_plugin::tables::TablesInput;
+use std::ffi::CStr;
+
+struct NeedsValueOffsets;
+
+impl Plugin for NeedsValueOffsets {
+    const NAME: &'static CStr = c"needs_value_offsets";
+    const PLUGIN_VERSION: &'static CStr = c"0.0.0";
+    const DESCRIPTION: &'static CStr = c"test plugin";
+    const CONTACT: &'static CStr = c"rust@localdomain.pl";
+    const REQUIRED_API_FEATURES: &'static [ApiFeature] = &[ApiFeature::ValueOffsets];
+    type ConfigType = ();
+
+    fn new(_input: Option<&TablesInput>, _config: Self::ConfigType) -> Result<Self, Error> {
+        Ok(Self)
+    }
+}
+
+impl ParsePlugin for NeedsValueOffsets {
+    type Event<'a> = RawEvent<'a>;
+
+    fn parse_event(&mut self, _event: &EventInput<RawEvent>, _input: &ParseInput) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+static_plugin!(NEEDS_VALUE_OFFSETS_API @ unsafe { 3; 3; 0 } = NeedsValueOffsets);
+
+fn main() {}
@@ -0,0 +1,17 @@
+use falco_plugin::tables::import::{table_path, Entry, Field, Table, TableMetadata};
+use falco_plugin::tables::LazyTableReader;
+use std::sync::Arc;
+
+type Thread = Entry<Arc<ThreadMetadata>>;
+
+#[derive(TableMetadata)]
+#[entry_type(Thread)]
+struct ThreadMetadata {
+    pid: Field<u64, Thread>,
+}
+
+fn get_pid(reader: &LazyTableReader, threads: &Table<i64, Thread>) -> Option<u64> {
+    table_path!(reader, threads => [1].file_descriptors.pid)
+}
+
+fn main() {}
@@ -1,4 +1,4 @@
-use crate::format::{display_wrapper_for, formatter_for};
+use crate::format::{debug_wrapper_for, display_wrapper_for, formatter_for};
 use proc_macro::TokenStream;
 use proc_macro2::Ident;
 use quote::quote;
@@ -241,6 +241,17 @@ impl EventInfo {
                 #format_val?;
             )
         });
+        let field_table = self.args().map(|field| {
+            let name = &field.name;
+            let ident = field.ident();
+
+            let display_wrapper =
+                display_wrapper_for(&field.field_type, quote!(self.#ident.as_ref()));
+            let display_val = quote!(falco_event::types::format::OptionFormatter(#display_wrapper));
+            let debug_val = debug_wrapper_for(&field.field_type, &field.field_format, display_val);
+
+            quote!((#name, &#debug_val as &dyn ::std::fmt::Debug))
+        });
         let dirfd_methods = self.args().map(|a| a.dirfd_method(self));
 
         let name = &self.name;
@@ -271,6 +282,7 @@ impl EventInfo {
             #[derive(falco_event_derive::EventPayload)]
             #[falco_event_crate(falco_event)]
             #[event_payload(length_type = #length_type, code = #raw_ident, source = #source)]
+            #[cfg_attr(feature = "small_binary", event_payload(out_of_line))]
             #[cfg_attr(all(not(docsrs), feature = "derive_deftly"), derive(derive_deftly::Deftly))]
             #[cfg_attr(all(not(docsrs), feature = "derive_deftly"), derive_deftly_adhoc(export))]
             #[cfg_attr(all(not(docsrs), feature = "derive_deftly"), deftly(length_type = #length_type_str))]
@@ -282,6 +294,16 @@ impl EventInfo {
                 #(#dirfd_methods)*
             }
 
+            #[cfg(feature = "small_binary")]
+            impl #lifetime ::std::fmt::Debug for #event_code #lifetime {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    falco_event::types::format::format_event_fields(f, #raw_ident, #name, &[
+                        #(#field_table,)*
+                    ])
+                }
+            }
+
+            #[cfg(not(feature = "small_binary"))]
             impl #lifetime ::std::fmt::Debug for #event_code #lifetime {
                 fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
                     use std::fmt::Write;
@@ -298,6 +320,27 @@ impl EventInfo {
         )
     }
 
+    fn type_info(&self) -> proc_macro2::TokenStream {
+        let name = &self.name;
+        let raw_ident = Ident::new(
+            &format!("ppm_event_code_{}", self.event_code),
+            self.event_code.span(),
+        );
+        let flags = self.flags.iter().map(|flag| {
+            let flag = Ident::new(&format!("ppm_event_flags_{flag}"), flag.span());
+            quote!(crate::ffi::#flag)
+        });
+        let nparams = self.args().count() as u32;
+
+        quote!(
+            table[crate::ffi::#raw_ident as usize] = crate::events::event_type::EventTypeInfo {
+                name: #name,
+                flags: #(#flags)|*,
+                nparams: #nparams,
+            };
+        )
+    }
+
     fn derive_deftly(&self) -> proc_macro2::TokenStream {
         let event_code = &self.event_code;
         quote!(
@@ -328,6 +371,26 @@ impl EventInfo {
 
         quote!(#event_type(#event_code #lifetime))
     }
+
+    fn event_set_member(&self) -> proc_macro2::TokenStream {
+        let event_code = &self.event_code;
+        let wants_lifetime = !self.args().all(|arg| {
+            matches!(
+                lifetime_type(&arg.final_field_type_name().to_string()),
+                LifetimeType::None
+            )
+        });
+
+        let lifetime = wants_lifetime.then_some(quote!(<'a>));
+
+        quote!(
+            pub enum #event_code {}
+
+            impl<'a> EventSetMember<'a> for #event_code {
+                type Event = super::#event_code #lifetime;
+            }
+        )
+    }
 }
 
 struct Events {
@@ -366,6 +429,38 @@ impl Events {
         )
     }
 
+    fn event_set_members(&self) -> proc_macro2::TokenStream {
+        let members = self.events.iter().map(|e| e.event_set_member());
+        quote!(
+            /// Support for the `event_set!` macro
+            ///
+            /// Each event type has a marker type here, mapping it to the actual event type
+            /// with the lifetime applied, if the event type has one.
+            #[doc(hidden)]
+            #[allow(non_camel_case_types)]
+            pub mod event_set {
+                pub trait EventSetMember<'a> {
+                    type Event;
+                }
+
+                #(#members)*
+            }
+        )
+    }
+
+    fn type_info_table(&self) -> proc_macro2::TokenStream {
+        let entries = self.events.iter().map(|e| e.type_info());
+        quote!(
+            pub(crate) const EVENT_TYPE_INFO: [crate::events::event_type::EventTypeInfo;
+                crate::ffi::ppm_event_code_PPM_EVENT_MAX as usize] = {
+                let mut table = [crate::events::event_type::EventTypeInfo::UNKNOWN;
+                    crate::ffi::ppm_event_code_PPM_EVENT_MAX as usize];
+                #(#entries)*
+                table
+            };
+        )
+    }
+
     fn enum_variants(&self) -> impl Iterator<Item = proc_macro2::TokenStream> + '_ {
         self.events.iter().map(move |e| e.enum_variant())
     }
@@ -374,12 +469,16 @@ impl Events {
 fn event_info_variant(events: &Events) -> proc_macro2::TokenStream {
     let typedefs = events.typedefs();
     let derive_deftly = events.derive_deftly();
+    let type_info_table = events.type_info_table();
+    let event_set_members = events.event_set_members();
     let variants = events.enum_variants();
     let lifetime = quote!(<'a>);
 
     quote!(
         #(#typedefs)*
         #derive_deftly
+        #type_info_table
+        #event_set_members
 
         #[allow(non_camel_case_types)]
         #[derive(falco_event_derive::AnyEvent)]
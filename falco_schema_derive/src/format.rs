@@ -27,3 +27,17 @@ pub fn formatter_for(
         _ => quote!(::std::fmt::Debug::fmt(#val_tt, #formatter_tt)),
     }
 }
+
+pub fn debug_wrapper_for(
+    pt_type: &Ident,
+    pf_type: &Ident,
+    val_tt: proc_macro2::TokenStream,
+) -> proc_macro2::TokenStream {
+    match (pt_type.to_string().as_str(), pf_type.to_string().as_str()) {
+        ("PT_FSPATH", _) => quote!(falco_event::types::format::DisplayAsDebug(#val_tt)),
+        ("PT_BYTEBUF", "PF_HEX") => quote!(falco_event::types::format::HexDebug(#val_tt)),
+        (_, "PF_HEX") => quote!(falco_event::types::format::HexAsDebug(#val_tt)),
+        (_, "PF_OCT") => quote!(falco_event::types::format::OctAsDebug(#val_tt)),
+        _ => val_tt,
+    }
+}